@@ -0,0 +1,160 @@
+use anchor_lang::prelude::Pubkey;
+use group_6_payment_protocol::state::{Multisig, Reputation, Transaction};
+use group_6_payment_protocol_client::accounts::Project;
+use group_6_payment_protocol_client::decode::{decode_transaction, ProposalWarning};
+use group_6_payment_protocol_client::ProjectAddresses;
+
+const LAMPORTS_PER_SOL: u64 = 1_000_000_000;
+
+pub fn sol(lamports: u64) -> String {
+    format!(
+        "{}.{:09} SOL",
+        lamports / LAMPORTS_PER_SOL,
+        lamports % LAMPORTS_PER_SOL
+    )
+}
+
+pub fn print_project(project: &Project) {
+    let info = &project.info;
+    println!("project:        {}", project.addresses.project_info_account);
+    println!("status:         {:?}", info.status);
+    println!("nonce:          {}", info.nonce);
+    println!("client:         {}", info.client);
+    println!("freelancer:     {}", info.freelancer);
+    println!("total funds:    {}", sol(info.total_project_funds));
+    println!("escrow balance: {}", sol(project.escrow_balance));
+    if info.paused_duration > 0 {
+        println!("paused for:     {}s", info.paused_duration);
+    }
+
+    println!(
+        "milestones:     {}/{} reached, {} withdrawn",
+        info.milestones_reached, info.milestones, info.milestone_funds_withdrawn
+    );
+    for (index, amount) in info.milestone_amounts.iter().enumerate() {
+        let state = if index < info.milestone_funds_withdrawn as usize {
+            "withdrawn"
+        } else if index < info.milestones_reached as usize {
+            "reached"
+        } else {
+            "pending"
+        };
+        match info.milestone_funds_streamed.get(index) {
+            Some(streamed) if *streamed > 0 => println!(
+                "  #{:<3} {:>24}  {}, {} streamed",
+                index + 1,
+                sol(*amount),
+                state,
+                sol(*streamed)
+            ),
+            _ => println!("  #{:<3} {:>24}  {}", index + 1, sol(*amount), state),
+        }
+    }
+
+    let multisig = &project.multisig;
+    println!(
+        "multisig:       {} ({} of {}, seqno {})",
+        project.addresses.multisig,
+        multisig.threshold,
+        multisig.owners.len(),
+        multisig.seqno
+    );
+    match &project.pending_transaction {
+        Some((address, transaction)) => {
+            print_proposal(&project.addresses, &project.multisig, address, transaction)
+        }
+        None => println!("pending proposal: none"),
+    }
+}
+
+pub fn print_reputation(wallet: &Pubkey, reputation: &Reputation) {
+    println!("wallet:         {wallet}");
+    println!("opened:         {}", reputation.opened_projects);
+    println!("completed:      {}", reputation.completed_projects);
+    println!("cancelled:      {}", reputation.cancelled_projects);
+    println!("total volume:   {}", sol(reputation.total_volume));
+    println!(
+        "disputes:       {} won, {} lost",
+        reputation.disputes_won, reputation.disputes_lost
+    );
+    match reputation.on_time_bps() {
+        Some(bps) => println!(
+            "on time:        {}.{:02}% ({} of {} deadlines)",
+            bps / 100,
+            bps % 100,
+            reputation.deadlines_met,
+            reputation.deadlines_met + reputation.deadlines_missed
+        ),
+        None => println!("on time:        no deadlines yet"),
+    }
+    match reputation.ratings {
+        0 => println!("rating:         no ratings yet"),
+        ratings => println!(
+            "rating:         {:.2} stars from {} ratings",
+            reputation.rating_total as f64 / ratings as f64,
+            ratings
+        ),
+    }
+}
+
+pub fn print_proposal(
+    project: &ProjectAddresses,
+    multisig: &Multisig,
+    address: &Pubkey,
+    transaction: &Transaction,
+) {
+    let decoded = decode_transaction(project, transaction);
+    println!("proposal:       {address}");
+    println!("  proposer:     {}", transaction.proposer);
+    println!("  program:      {}", transaction.program_id);
+    match &decoded.instruction {
+        Some(instruction) => println!("  instruction:  {instruction:?}"),
+        None => println!("  instruction:  unknown ({} bytes)", transaction.data.len()),
+    }
+    println!("  executed:     {}", transaction.did_execute);
+    println!("  accounts:");
+    for named in &decoded.accounts {
+        let flags = match (named.account.is_signer, named.account.is_writable) {
+            (true, true) => "signer, writable",
+            (true, false) => "signer",
+            (false, true) => "writable",
+            (false, false) => "",
+        };
+        println!(
+            "    {:<22} {}  {flags}",
+            named.name.unwrap_or("(extra)"),
+            named.account.pubkey
+        );
+    }
+    println!("  approvals:");
+    for (owner, signed) in multisig.owners.iter().zip(&transaction.signers) {
+        let vote = if *signed { "approved" } else { "waiting" };
+        println!("    {owner}  {vote}");
+    }
+    for warning in &decoded.warnings {
+        println!("  WARNING: {}", describe_warning(warning));
+    }
+}
+
+fn describe_warning(warning: &ProposalWarning) -> String {
+    match warning {
+        ProposalWarning::ForeignProgram(program_id) => {
+            format!("calls program {program_id}, not the payment protocol")
+        }
+        ProposalWarning::UnknownInstruction => {
+            "data doesn't match any payment protocol instruction".to_string()
+        }
+        ProposalWarning::InvalidArguments => "instruction arguments can't be decoded".to_string(),
+        ProposalWarning::MissingAccounts { expected, found } => {
+            format!("expects {expected} accounts, found {found}")
+        }
+        ProposalWarning::ForeignAccount {
+            name,
+            expected,
+            found,
+        } => format!("{name} is {found}, this project's is {expected}"),
+        ProposalWarning::NestedProposal => {
+            "executing it would create or approve another proposal".to_string()
+        }
+    }
+}
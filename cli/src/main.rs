@@ -0,0 +1,871 @@
+use std::process::exit;
+
+use anchor_lang::prelude::Pubkey;
+use anchor_lang::solana_program::hash::hash;
+use clap::{Parser, Subcommand};
+use group_6_payment_protocol::state::Ruling;
+use group_6_payment_protocol_client::accounts::{
+    fetch_bond, fetch_project, fetch_project_info, fetch_rating, fetch_reputation,
+    fetch_subcontract, fetch_transaction,
+};
+use group_6_payment_protocol_client::multisig::{self, MultisigAction};
+use group_6_payment_protocol_client::rpc::{keypair_pubkey, read_keypair_file, RpcClient};
+use group_6_payment_protocol_client::{instructions, pda, ClientError, ProjectAddresses};
+
+mod display;
+
+#[derive(Parser)]
+#[command(
+    name = "payment-protocol",
+    about = "Operate freelance escrow projects from the command line"
+)]
+struct Cli {
+    ///RPC endpoint of the cluster
+    #[arg(
+        long,
+        short = 'u',
+        global = true,
+        default_value = "http://localhost:8899"
+    )]
+    url: String,
+    ///keypair that signs and pays for the transaction
+    #[arg(long, short = 'k', global = true, default_value_t = default_keypair())]
+    keypair: String,
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    ///create a project and lock the funds, signed by the client
+    Init {
+        #[arg(long)]
+        freelancer: Pubkey,
+        #[arg(long)]
+        observer: Pubkey,
+        ///lamports locked in the escrow
+        #[arg(long)]
+        funds: u64,
+        #[arg(long)]
+        milestones: u8,
+    },
+    ///create a retainer paid one period per interval, funded upfront by the client
+    InitRetainer {
+        #[arg(long)]
+        freelancer: Pubkey,
+        #[arg(long)]
+        observer: Pubkey,
+        ///lamports paid for each period
+        #[arg(long)]
+        period_amount: u64,
+        #[arg(long)]
+        periods: u8,
+        ///unix timestamp the first period starts at
+        #[arg(long)]
+        start: i64,
+        ///length of a period in seconds
+        #[arg(long)]
+        interval: i64,
+        ///seconds between giving notice and the retainer ending
+        #[arg(long, default_value_t = 0)]
+        notice_period: i64,
+    },
+    ///mark the retainer periods that are due as reached
+    Unlock {
+        #[arg(long)]
+        project: Pubkey,
+    },
+    ///end a retainer once its notice period is over, signed by the client or the freelancer
+    Notice {
+        #[arg(long)]
+        project: Pubkey,
+    },
+    ///refund the periods left once the notice period of a retainer is over
+    Settle {
+        #[arg(long)]
+        project: Pubkey,
+    },
+    ///pause a subcontract while its parent is paused or cancelled
+    Freeze {
+        ///project_info_account of the child
+        #[arg(long)]
+        project: Pubkey,
+    },
+    ///resume a frozen subcontract once its parent runs again
+    Thaw {
+        ///project_info_account of the child
+        #[arg(long)]
+        project: Pubkey,
+    },
+    ///refund a subcontract that wasn't started or got stopped, signed by its client
+    CancelSubcontract {
+        ///project_info_account of the child
+        #[arg(long)]
+        project: Pubkey,
+    },
+    ///ask the freelancer for a performance bond before the project starts, signed by the client
+    RequireBond {
+        #[arg(long)]
+        project: Pubkey,
+        ///lamports the freelancer has to post
+        #[arg(long)]
+        amount: u64,
+        ///unix timestamp the project has to be completed by, 0 for none
+        #[arg(long, default_value_t = 0)]
+        deadline: i64,
+    },
+    ///post the performance bond, signed by the freelancer
+    PostBond {
+        #[arg(long)]
+        project: Pubkey,
+    },
+    ///give the bond back to the freelancer once the project is completed or stopped
+    ReleaseBond {
+        #[arg(long)]
+        project: Pubkey,
+    },
+    ///forfeit the bond to the client once the project missed its deadline
+    ClaimBond {
+        #[arg(long)]
+        project: Pubkey,
+    },
+    ///propose an action to the project multisig
+    Propose {
+        ///project_info_account address
+        #[arg(long)]
+        project: Pubkey,
+        #[command(subcommand)]
+        action: ProposeAction,
+    },
+    ///approve the pending proposal, executing it once the threshold is met
+    Approve {
+        #[arg(long)]
+        project: Pubkey,
+    },
+    ///withdraw the funds of the reached milestones, signed by the freelancer
+    Withdraw {
+        #[arg(long)]
+        project: Pubkey,
+    },
+    ///withdraw the signer's share of the reached milestones of a project with several payees
+    WithdrawShare {
+        #[arg(long)]
+        project: Pubkey,
+    },
+    ///withdraw the vested share of a streamed milestone, signed by the freelancer
+    WithdrawStream {
+        #[arg(long)]
+        project: Pubkey,
+        ///index of the streamed milestone, starting at 0
+        #[arg(long)]
+        milestone: u8,
+    },
+    ///bill the hours worked during a week, signed by the freelancer
+    SubmitTimesheet {
+        #[arg(long)]
+        project: Pubkey,
+        ///week of the hourly terms, starting at 0
+        #[arg(long)]
+        week: u32,
+        #[arg(long)]
+        hours: u32,
+        ///description of the work, only its hash is stored
+        #[arg(long)]
+        memo: String,
+    },
+    ///approve a timesheet, signed by the client
+    ApproveTimesheet {
+        #[arg(long)]
+        project: Pubkey,
+        #[arg(long)]
+        week: u32,
+    },
+    ///dispute a timesheet before its approval window is over, signed by the client
+    DisputeTimesheet {
+        #[arg(long)]
+        project: Pubkey,
+        #[arg(long)]
+        week: u32,
+    },
+    ///withdraw an approved timesheet, signed by the freelancer
+    WithdrawTimesheet {
+        #[arg(long)]
+        project: Pubkey,
+        #[arg(long)]
+        week: u32,
+    },
+    ///accept the terms of an offered project and start it, signed by the freelancer
+    Accept {
+        #[arg(long)]
+        project: Pubkey,
+    },
+    ///turn an offered project down and refund the client, signed by the freelancer
+    Decline {
+        #[arg(long)]
+        project: Pubkey,
+    },
+    ///publish a job freelancers can bid on, signed by the client
+    PostJob {
+        ///picked by the client, tells its postings apart
+        #[arg(long)]
+        job_id: u64,
+        ///lamports, bids must ask for a price between the budget bounds
+        #[arg(long)]
+        budget_min: u64,
+        #[arg(long)]
+        budget_max: u64,
+        ///basis points of the price released by each milestone, e.g. 3000,7000
+        #[arg(long, value_delimiter = ',')]
+        milestones: Vec<u16>,
+        ///off-chain description of the job
+        #[arg(long)]
+        uri: String,
+    },
+    ///bid on a job posting, signed by the freelancer
+    Bid {
+        #[arg(long)]
+        job_posting: Pubkey,
+        ///lamports the project would be funded with
+        #[arg(long)]
+        price: u64,
+        ///seconds needed to deliver
+        #[arg(long)]
+        timeline: i64,
+    },
+    ///hire a bidder: offers them a project funded with the bid price, signed by the client
+    AcceptBid {
+        #[arg(long)]
+        job_posting: Pubkey,
+        ///the freelancer whose bid is accepted
+        #[arg(long)]
+        freelancer: Pubkey,
+        #[arg(long)]
+        observer: Pubkey,
+    },
+    ///withdraw a bid that wasn't accepted and get its rent back, signed by the freelancer
+    CloseBid {
+        #[arg(long)]
+        job_posting: Pubkey,
+    },
+    ///cancel a project that hasn't started, or withdraw an offer, and refund the client
+    Cancel {
+        #[arg(long)]
+        project: Pubkey,
+    },
+    ///upgrade a project, multisig or proposal account written with an older layout
+    Migrate {
+        ///address of the account to migrate
+        #[arg(long)]
+        account: Pubkey,
+    },
+    ///decode a proposal and flag anything that doesn't belong to the project
+    Inspect {
+        #[arg(long)]
+        project: Pubkey,
+        ///proposal address, defaults to the pending proposal
+        #[arg(long)]
+        transaction: Option<Pubkey>,
+    },
+    ///print the project status, milestones, escrow balance and pending proposal
+    Show {
+        #[arg(long)]
+        project: Pubkey,
+    },
+    ///start tracking the reputation of a wallet, paid by the signer
+    CreateReputation {
+        ///defaults to the signer
+        #[arg(long)]
+        wallet: Option<Pubkey>,
+    },
+    ///rate the other party of a completed or cancelled project that ran, once.
+    ///works after it's closed
+    Rate {
+        #[arg(long)]
+        project: Pubkey,
+        ///1 to 5
+        #[arg(long)]
+        stars: u8,
+        ///nonce `show` printed for the project, needed once it's closed
+        #[arg(long)]
+        nonce: Option<u32>,
+    },
+    ///tip the freelancer on top of the project funds, signed by the client
+    Tip {
+        #[arg(long)]
+        project: Pubkey,
+        ///lamports
+        #[arg(long)]
+        amount: u64,
+        ///pay out of what the escrow holds beyond the milestones left
+        #[arg(long)]
+        from_escrow: bool,
+    },
+    ///print the track record of a wallet
+    Reputation {
+        #[arg(long)]
+        wallet: Pubkey,
+    },
+}
+
+#[derive(Subcommand)]
+enum ProposeAction {
+    Start,
+    ///mark the current milestone as completed
+    Milestone,
+    Stop {
+        ///who the multisig sides with if the parties are in dispute: none, client or freelancer
+        #[arg(long, value_parser = parse_ruling, default_value = "none")]
+        ruling: Ruling,
+    },
+    Pause,
+    Resume,
+    ///rule against the freelancer, the project is stopped and the bond goes to the client
+    ForfeitBond,
+    ///top up the budget and/or replace the milestone schedule
+    ChangeOrder {
+        ///lamports the client adds to the escrow
+        #[arg(long, default_value_t = 0)]
+        additional_funds: u64,
+        ///comma separated amount of every milestone, in lamports
+        #[arg(long, value_delimiter = ',', required = true)]
+        milestone_amounts: Vec<u64>,
+    },
+    ///share the milestone payouts between several payees, the client signs the executing approval
+    Splits {
+        ///comma separated payee wallets
+        #[arg(long, value_delimiter = ',', required = true)]
+        payees: Vec<Pubkey>,
+        ///comma separated basis points of one milestone per payee, repeated per milestone,
+        ///later milestones use the last one
+        #[arg(long = "split", value_parser = parse_splits, required = true)]
+        splits: Vec<Vec<u16>>,
+    },
+    ///bill the project by the hour, the client signs the executing approval
+    Hourly {
+        ///lamports paid per hour
+        #[arg(long)]
+        rate: u64,
+        ///most hours billed in a week
+        #[arg(long)]
+        weekly_cap: u32,
+        ///seconds the client has to answer a timesheet
+        #[arg(long)]
+        approval_window: i64,
+        ///unix timestamp week 0 starts at
+        #[arg(long)]
+        start: i64,
+    },
+    ///open a child project funded from the unreached milestones, the project's freelancer
+    ///is its client. the client signs the executing approval
+    Subcontract {
+        ///the subcontractor
+        #[arg(long)]
+        freelancer: Pubkey,
+        #[arg(long)]
+        observer: Pubkey,
+        ///lamports drawn from the project
+        #[arg(long)]
+        funds: u64,
+        #[arg(long)]
+        milestones: u8,
+    },
+    ///pay a milestone out over time, the client signs the executing approval
+    Stream {
+        ///index of the milestone, starting at 0
+        #[arg(long)]
+        milestone: u8,
+        ///unix timestamps of the schedule
+        #[arg(long)]
+        start: i64,
+        #[arg(long)]
+        cliff: i64,
+        #[arg(long)]
+        end: i64,
+    },
+}
+
+impl From<ProposeAction> for MultisigAction {
+    fn from(action: ProposeAction) -> MultisigAction {
+        match action {
+            ProposeAction::Start => MultisigAction::StartProject,
+            ProposeAction::Milestone => MultisigAction::MarkCurrentMilestoneCompleted,
+            ProposeAction::Stop { ruling } => MultisigAction::StopProject { ruling },
+            ProposeAction::Pause => MultisigAction::PauseProject,
+            ProposeAction::Resume => MultisigAction::ResumeProject,
+            ProposeAction::ForfeitBond => MultisigAction::ForfeitBond,
+            ProposeAction::ChangeOrder {
+                additional_funds,
+                milestone_amounts,
+            } => MultisigAction::ChangeOrder {
+                additional_funds,
+                milestone_amounts,
+            },
+            ProposeAction::Splits { payees, splits } => MultisigAction::SetPayeeSplits {
+                payees,
+                milestone_splits: splits,
+            },
+            ProposeAction::Hourly {
+                rate,
+                weekly_cap,
+                approval_window,
+                start,
+            } => MultisigAction::SetHourlyTerms {
+                hourly_rate: rate,
+                weekly_cap,
+                approval_window,
+                start,
+            },
+            ProposeAction::Subcontract {
+                freelancer,
+                observer,
+                funds,
+                milestones,
+            } => MultisigAction::CreateSubcontract {
+                freelancer,
+                observer,
+                total_project_funds: funds,
+                milestones,
+            },
+            ProposeAction::Stream {
+                milestone,
+                start,
+                cliff,
+                end,
+            } => MultisigAction::SetMilestoneStream {
+                milestone_index: milestone,
+                start,
+                cliff,
+                end,
+            },
+        }
+    }
+}
+
+fn default_keypair() -> String {
+    let home = std::env::var("HOME").unwrap_or_else(|_| ".".to_string());
+    format!("{home}/.config/solana/id.json")
+}
+
+fn main() {
+    let cli = Cli::parse();
+    if let Err(err) = run(cli) {
+        eprintln!("error: {err}");
+        exit(1);
+    }
+}
+
+fn run(cli: Cli) -> Result<(), ClientError> {
+    let rpc = RpcClient::new(cli.url);
+    match cli.command {
+        Command::Show { project } => {
+            let project = fetch_project(&rpc, load_addresses(&rpc, &project)?)?;
+            display::print_project(&project);
+            return Ok(());
+        }
+        Command::Reputation { wallet } => {
+            display::print_reputation(&wallet, &fetch_reputation(&rpc, &wallet)?);
+            return Ok(());
+        }
+        Command::Inspect {
+            project,
+            transaction,
+        } => {
+            let project = fetch_project(&rpc, load_addresses(&rpc, &project)?)?;
+            let (address, transaction) = match transaction {
+                Some(address) => (address, fetch_transaction(&rpc, &address)?),
+                None => project
+                    .pending_transaction
+                    .ok_or(ClientError::NoPendingProposal(project.addresses.multisig))?,
+            };
+            display::print_proposal(
+                &project.addresses,
+                &project.multisig,
+                &address,
+                &transaction,
+            );
+            return Ok(());
+        }
+        _ => {}
+    }
+
+    let signer = read_keypair_file(&cli.keypair)?;
+    let signer_key = keypair_pubkey(&signer);
+
+    let ix = match cli.command {
+        Command::Init {
+            freelancer,
+            observer,
+            funds,
+            milestones,
+        } => {
+            let project = ProjectAddresses::new(signer_key, freelancer);
+            println!("project: {}", project.project_info_account);
+            instructions::initialize_project(&project, &observer, funds, milestones)
+        }
+        Command::InitRetainer {
+            freelancer,
+            observer,
+            period_amount,
+            periods,
+            start,
+            interval,
+            notice_period,
+        } => {
+            let project = ProjectAddresses::new(signer_key, freelancer);
+            println!("project: {}", project.project_info_account);
+            instructions::initialize_retainer(
+                &project,
+                &observer,
+                period_amount,
+                periods,
+                start,
+                interval,
+                notice_period,
+            )
+        }
+        Command::Unlock { project } => {
+            instructions::unlock_retainer_periods(&load_addresses(&rpc, &project)?, &signer_key)
+        }
+        Command::Notice { project } => {
+            instructions::give_retainer_notice(&load_addresses(&rpc, &project)?, &signer_key)
+        }
+        Command::Settle { project } => {
+            instructions::settle_retainer(&load_addresses(&rpc, &project)?, &signer_key)
+        }
+        Command::Freeze { project } => {
+            let parent = fetch_subcontract(&rpc, &project)?.parent;
+            instructions::freeze_subcontract(&load_addresses(&rpc, &project)?, &parent, &signer_key)
+        }
+        Command::Thaw { project } => {
+            let parent = fetch_subcontract(&rpc, &project)?.parent;
+            instructions::thaw_subcontract(&load_addresses(&rpc, &project)?, &parent, &signer_key)
+        }
+        Command::CancelSubcontract { project } => {
+            let child = load_addresses(&rpc, &project)?;
+            //the parent may be closed already, its addresses come from the subcontract
+            let subcontract = fetch_subcontract(&rpc, &project)?;
+            let parent = ProjectAddresses::new(subcontract.parent_client, child.client);
+            instructions::cancel_subcontract(&child, &parent)
+        }
+        Command::RequireBond {
+            project,
+            amount,
+            deadline,
+        } => instructions::require_bond(&load_addresses(&rpc, &project)?, amount, deadline),
+        Command::PostBond { project } => instructions::post_bond(&load_addresses(&rpc, &project)?),
+        Command::ReleaseBond { project } => {
+            //the project may be closed already, its addresses come from the bond
+            let bond = fetch_bond(&rpc, &project)?;
+            let project = ProjectAddresses::new(bond.client, bond.freelancer);
+            instructions::release_bond(&project, &signer_key)
+        }
+        Command::ClaimBond { project } => {
+            instructions::claim_bond(&load_addresses(&rpc, &project)?, &signer_key)
+        }
+        Command::Propose { project, action } => {
+            let project = load_addresses(&rpc, &project)?;
+            let seqno = fetch_project(&rpc, project)?.multisig.seqno;
+            let action = MultisigAction::from(action);
+            if let MultisigAction::CreateSubcontract { freelancer, .. } = &action {
+                let child = ProjectAddresses::new(project.freelancer, *freelancer);
+                println!("project: {}", child.project_info_account);
+            }
+            multisig::propose(&project, &signer_key, seqno, &action)
+        }
+        Command::Approve { project } => {
+            let project = fetch_project(&rpc, load_addresses(&rpc, &project)?)?;
+            let (address, transaction) = project
+                .pending_transaction
+                .ok_or(ClientError::NoPendingProposal(project.addresses.multisig))?;
+            multisig::approve_transaction(&project.addresses, &signer_key, &address, &transaction)
+        }
+        Command::Withdraw { project } => {
+            instructions::withdraw_milestone_funds(&load_addresses(&rpc, &project)?)
+        }
+        Command::WithdrawShare { project } => {
+            instructions::withdraw_payee_share(&load_addresses(&rpc, &project)?, &signer_key)
+        }
+        Command::WithdrawStream { project, milestone } => {
+            instructions::withdraw_streamed_funds(&load_addresses(&rpc, &project)?, milestone)
+        }
+        Command::SubmitTimesheet {
+            project,
+            week,
+            hours,
+            memo,
+        } => instructions::submit_timesheet(
+            &load_addresses(&rpc, &project)?,
+            week,
+            hours,
+            hash(memo.as_bytes()).to_bytes(),
+        ),
+        Command::ApproveTimesheet { project, week } => {
+            instructions::approve_timesheet(&load_addresses(&rpc, &project)?, week)
+        }
+        Command::DisputeTimesheet { project, week } => {
+            instructions::dispute_timesheet(&load_addresses(&rpc, &project)?, week)
+        }
+        Command::WithdrawTimesheet { project, week } => {
+            instructions::withdraw_timesheet_funds(&load_addresses(&rpc, &project)?, week)
+        }
+        Command::Accept { project } => instructions::accept_offer(&load_addresses(&rpc, &project)?),
+        Command::Decline { project } => {
+            instructions::decline_offer(&load_addresses(&rpc, &project)?)
+        }
+        Command::PostJob {
+            job_id,
+            budget_min,
+            budget_max,
+            milestones,
+            uri,
+        } => {
+            let (job_posting, _) = pda::job_posting(&signer_key, job_id);
+            println!("job posting: {job_posting}");
+            instructions::create_job_posting(
+                &signer_key,
+                job_id,
+                budget_min,
+                budget_max,
+                milestones,
+                uri,
+            )
+        }
+        Command::Bid {
+            job_posting,
+            price,
+            timeline,
+        } => instructions::submit_bid(&job_posting, &signer_key, price, timeline),
+        Command::AcceptBid {
+            job_posting,
+            freelancer,
+            observer,
+        } => {
+            let project = ProjectAddresses::new(signer_key, freelancer);
+            println!("project: {}", project.project_info_account);
+            instructions::accept_bid(&job_posting, &project, &observer)
+        }
+        Command::CloseBid { job_posting } => instructions::close_bid(&job_posting, &signer_key),
+        Command::Cancel { project } => {
+            let info = fetch_project_info(&rpc, &project)?;
+            let addresses = ProjectAddresses::new(info.client, info.freelancer);
+            instructions::cancel_project(&addresses, info.nonce)
+        }
+        Command::Migrate { account } => instructions::migrate_account(&account, &signer_key),
+        Command::CreateReputation { wallet } => {
+            instructions::create_reputation(&wallet.unwrap_or(signer_key), &signer_key)
+        }
+        Command::Rate {
+            project,
+            stars,
+            nonce,
+        } => {
+            let (addresses, nonce) = load_rated_addresses(&rpc, &project, nonce, &signer_key)?;
+            instructions::rate_party(&addresses, nonce, &signer_key, stars)
+        }
+        Command::Tip {
+            project,
+            amount,
+            from_escrow,
+        } => instructions::tip(&load_addresses(&rpc, &project)?, amount, from_escrow),
+        Command::Show { .. } | Command::Inspect { .. } | Command::Reputation { .. } => {
+            unreachable!("handled above")
+        }
+    };
+
+    let signature = rpc.send_and_confirm(&[ix], &[&signer])?;
+    println!("signature: {signature}");
+    Ok(())
+}
+
+///`5000,2500,2500` as basis points
+fn parse_splits(value: &str) -> Result<Vec<u16>, String> {
+    value
+        .split(',')
+        .map(|bps| {
+            bps.trim()
+                .parse::<u16>()
+                .map_err(|err| format!("{bps}: {err}"))
+        })
+        .collect()
+}
+
+fn parse_ruling(value: &str) -> Result<Ruling, String> {
+    match value {
+        "none" => Ok(Ruling::None),
+        "client" => Ok(Ruling::Client),
+        "freelancer" => Ok(Ruling::Freelancer),
+        _ => Err(format!("{value}: expected none, client or freelancer")),
+    }
+}
+
+///every project address, derived from the parties stored in `project`
+fn load_addresses(rpc: &RpcClient, project: &Pubkey) -> Result<ProjectAddresses, ClientError> {
+    let info = fetch_project_info(rpc, project)?;
+    Ok(ProjectAddresses::new(info.client, info.freelancer))
+}
+
+///like `load_addresses` along with the project nonce, `nonce` picks an earlier project
+///at the same address. a closed project is found through the rating it left `rater`
+fn load_rated_addresses(
+    rpc: &RpcClient,
+    project: &Pubkey,
+    nonce: Option<u32>,
+    rater: &Pubkey,
+) -> Result<(ProjectAddresses, u32), ClientError> {
+    match fetch_project_info(rpc, project) {
+        Err(ClientError::AccountNotFound(_)) => {}
+        Err(err) => return Err(err),
+        Ok(info) => {
+            let addresses = ProjectAddresses::new(info.client, info.freelancer);
+            return Ok((addresses, nonce.unwrap_or(info.nonce)));
+        }
+    }
+    let nonce = nonce.ok_or(ClientError::AccountNotFound(*project))?;
+    let rating = fetch_rating(rpc, project, nonce, rater)?;
+    [(rating.rater, rating.ratee), (rating.ratee, rating.rater)]
+        .into_iter()
+        .map(|(client, freelancer)| ProjectAddresses::new(client, freelancer))
+        .find(|addresses| addresses.project_info_account == *project)
+        .map(|addresses| (addresses, nonce))
+        .ok_or(ClientError::AccountNotFound(*project))
+}
+
+#[cfg(test)]
+mod tests {
+    use clap::CommandFactory;
+
+    use super::*;
+
+    fn parse(args: &[&str]) -> Cli {
+        Cli::try_parse_from([&["payment-protocol"], args].concat()).unwrap()
+    }
+
+    #[test]
+    fn arguments_are_consistent() {
+        Cli::command().debug_assert();
+    }
+
+    #[test]
+    fn global_options_and_init_are_parsed() {
+        let freelancer = Pubkey::new_unique();
+        let observer = Pubkey::new_unique();
+        let cli = parse(&[
+            "init",
+            "--freelancer",
+            &freelancer.to_string(),
+            "--observer",
+            &observer.to_string(),
+            "--funds",
+            "1000",
+            "--milestones",
+            "3",
+            "-u",
+            "http://cluster:8899",
+        ]);
+        assert_eq!(cli.url, "http://cluster:8899");
+        assert_eq!(cli.keypair, default_keypair());
+        match cli.command {
+            Command::Init {
+                freelancer: parsed_freelancer,
+                observer: parsed_observer,
+                funds,
+                milestones,
+            } => {
+                assert_eq!((parsed_freelancer, parsed_observer), (freelancer, observer));
+                assert_eq!((funds, milestones), (1000, 3));
+            }
+            _ => panic!("expected init"),
+        }
+    }
+
+    #[test]
+    fn proposals_map_to_multisig_actions() {
+        let project = Pubkey::new_unique();
+        let designer = Pubkey::new_unique();
+        let writer = Pubkey::new_unique();
+        let payees = format!("{designer},{writer}");
+        let cli = parse(&[
+            "propose",
+            "--project",
+            &project.to_string(),
+            "splits",
+            "--payees",
+            &payees,
+            "--split",
+            "5000,5000",
+            "--split",
+            "10000, 0",
+        ]);
+        let Command::Propose {
+            project: parsed,
+            action,
+        } = cli.command
+        else {
+            panic!("expected propose");
+        };
+        assert_eq!(parsed, project);
+        assert_eq!(
+            MultisigAction::from(action),
+            MultisigAction::SetPayeeSplits {
+                payees: vec![designer, writer],
+                milestone_splits: vec![vec![5000, 5000], vec![10000, 0]],
+            }
+        );
+
+        let cli = parse(&[
+            "propose",
+            "--project",
+            &project.to_string(),
+            "change-order",
+            "--milestone-amounts",
+            "1,2,3",
+        ]);
+        let Command::Propose { action, .. } = cli.command else {
+            panic!("expected propose");
+        };
+        assert_eq!(
+            MultisigAction::from(action),
+            MultisigAction::ChangeOrder {
+                additional_funds: 0,
+                milestone_amounts: vec![1, 2, 3],
+            }
+        );
+
+        let cli = parse(&[
+            "propose",
+            "--project",
+            &project.to_string(),
+            "stop",
+            "--ruling",
+            "freelancer",
+        ]);
+        let Command::Propose { action, .. } = cli.command else {
+            panic!("expected propose");
+        };
+        assert_eq!(
+            MultisigAction::from(action),
+            MultisigAction::StopProject {
+                ruling: Ruling::Freelancer,
+            }
+        );
+    }
+
+    #[test]
+    fn invalid_arguments_are_rejected() {
+        assert!(parse_splits("5000,abc").is_err());
+        assert!(parse_splits("70000").is_err());
+        assert!(parse_ruling("observer").is_err());
+        assert!(Cli::try_parse_from(["payment-protocol", "init", "--funds", "1"]).is_err());
+        assert!(
+            Cli::try_parse_from(["payment-protocol", "unlock", "--project", "not-a-pubkey"])
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn lamports_are_shown_in_sol() {
+        assert_eq!(display::sol(0), "0.000000000 SOL");
+        assert_eq!(display::sol(1_500_000_001), "1.500000001 SOL");
+        assert_eq!(display::sol(u64::MAX), "18446744073.709551615 SOL");
+    }
+}
@@ -0,0 +1,161 @@
+use anchor_lang::prelude::Pubkey;
+use anchor_lang::AccountDeserialize;
+use group_6_payment_protocol::state::{
+    Bid, Bond, JobPosting, Multisig, ProjectInfo, Rating, Reputation, Subcontract, Tips,
+    Transaction,
+};
+
+use crate::pda::ProjectAddresses;
+use crate::ClientError;
+
+///source of raw account data, e.g. an RPC node or a ledger snapshot
+pub trait AccountFetcher {
+    ///data and lamports of `address`, `None` if the account doesn't exist
+    fn fetch_account(&self, address: &Pubkey) -> Result<Option<RawAccount>, ClientError>;
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct RawAccount {
+    pub lamports: u64,
+    pub data: Vec<u8>,
+}
+
+///decode an anchor account, checking its discriminator
+pub fn decode<T: AccountDeserialize>(address: &Pubkey, data: &[u8]) -> Result<T, ClientError> {
+    T::try_deserialize(&mut &data[..])
+        .map_err(|err| ClientError::InvalidAccountData(*address, Box::new(err)))
+}
+
+pub fn fetch<T: AccountDeserialize>(
+    fetcher: &impl AccountFetcher,
+    address: &Pubkey,
+) -> Result<T, ClientError> {
+    let account = fetcher
+        .fetch_account(address)?
+        .ok_or(ClientError::AccountNotFound(*address))?;
+    decode(address, &account.data)
+}
+
+pub fn fetch_project_info(
+    fetcher: &impl AccountFetcher,
+    address: &Pubkey,
+) -> Result<ProjectInfo, ClientError> {
+    fetch(fetcher, address)
+}
+
+pub fn fetch_multisig(
+    fetcher: &impl AccountFetcher,
+    address: &Pubkey,
+) -> Result<Multisig, ClientError> {
+    fetch(fetcher, address)
+}
+
+///the subcontract of the child project at `project_info_account`
+pub fn fetch_subcontract(
+    fetcher: &impl AccountFetcher,
+    project_info_account: &Pubkey,
+) -> Result<Subcontract, ClientError> {
+    fetch(fetcher, &crate::pda::subcontract(project_info_account).0)
+}
+
+///the performance bond of the project at `project_info_account`
+pub fn fetch_bond(
+    fetcher: &impl AccountFetcher,
+    project_info_account: &Pubkey,
+) -> Result<Bond, ClientError> {
+    fetch(fetcher, &crate::pda::bond(project_info_account).0)
+}
+
+pub fn fetch_job_posting(
+    fetcher: &impl AccountFetcher,
+    address: &Pubkey,
+) -> Result<JobPosting, ClientError> {
+    fetch(fetcher, address)
+}
+
+pub fn fetch_bid(fetcher: &impl AccountFetcher, address: &Pubkey) -> Result<Bid, ClientError> {
+    fetch(fetcher, address)
+}
+
+///the reputation of `wallet`
+pub fn fetch_reputation(
+    fetcher: &impl AccountFetcher,
+    wallet: &Pubkey,
+) -> Result<Reputation, ClientError> {
+    fetch(fetcher, &crate::pda::reputation(wallet).0)
+}
+
+///the rating `rater` gives on the project at `project_info_account` with `nonce`,
+///closing a project that ran leaves one unrated for each party
+pub fn fetch_rating(
+    fetcher: &impl AccountFetcher,
+    project_info_account: &Pubkey,
+    nonce: u32,
+    rater: &Pubkey,
+) -> Result<Rating, ClientError> {
+    fetch(
+        fetcher,
+        &crate::pda::rating(project_info_account, nonce, rater).0,
+    )
+}
+
+pub fn fetch_tips(
+    fetcher: &impl AccountFetcher,
+    project_info_account: &Pubkey,
+) -> Result<Tips, ClientError> {
+    fetch(fetcher, &crate::pda::tips(project_info_account).0)
+}
+
+pub fn fetch_transaction(
+    fetcher: &impl AccountFetcher,
+    address: &Pubkey,
+) -> Result<Transaction, ClientError> {
+    fetch(fetcher, address)
+}
+
+///lamports held by the escrow, 0 once it has been emptied
+pub fn fetch_escrow_balance(
+    fetcher: &impl AccountFetcher,
+    address: &Pubkey,
+) -> Result<u64, ClientError> {
+    Ok(fetcher
+        .fetch_account(address)?
+        .map(|account| account.lamports)
+        .unwrap_or_default())
+}
+
+///everything stored on-chain for a single project
+pub struct Project {
+    pub addresses: ProjectAddresses,
+    pub info: ProjectInfo,
+    pub multisig: Multisig,
+    pub escrow_balance: u64,
+    ///the proposal at the current multisig seqno, if any
+    pub pending_transaction: Option<(Pubkey, Transaction)>,
+}
+
+pub fn fetch_project(
+    fetcher: &impl AccountFetcher,
+    addresses: ProjectAddresses,
+) -> Result<Project, ClientError> {
+    let info = fetch_project_info(fetcher, &addresses.project_info_account)?;
+    let multisig = fetch_multisig(fetcher, &addresses.multisig)?;
+    let escrow_balance = fetch_escrow_balance(fetcher, &addresses.token_escrow)?;
+
+    let transaction_address = addresses.multisig_transaction(multisig.seqno);
+    let pending_transaction = match fetcher.fetch_account(&transaction_address)? {
+        Some(account) => {
+            let transaction: Transaction = decode(&transaction_address, &account.data)?;
+            (!transaction.did_execute).then_some((transaction_address, transaction))
+        }
+        None => None,
+    };
+
+    Ok(Project {
+        addresses,
+        info,
+        multisig,
+        escrow_balance,
+        pending_transaction,
+    })
+}
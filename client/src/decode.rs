@@ -0,0 +1,1155 @@
+use anchor_lang::prelude::Pubkey;
+use anchor_lang::solana_program::hash::hash;
+use anchor_lang::solana_program::system_program;
+use anchor_lang::AnchorDeserialize;
+use group_6_payment_protocol::instruction;
+use group_6_payment_protocol::state::{Ruling, Transaction, TransactionAccount};
+
+use crate::pda::ProjectAddresses;
+use crate::PROGRAM_ID;
+
+///program instruction stored in a proposal, with its arguments
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ProposalInstruction {
+    InitializeProject {
+        total_funds_for_project: u64,
+        milestones: u8,
+    },
+    StartProject,
+    CancelProject,
+    AcceptOffer,
+    DeclineOffer,
+    WithdrawMilestoneFunds,
+    MarkCurrentMilestoneCompleted,
+    PauseProject,
+    ResumeProject,
+    ChangeOrder {
+        additional_funds: u64,
+        milestone_amounts: Vec<u64>,
+    },
+    StopProject {
+        ruling: Ruling,
+    },
+    SetMilestoneStream {
+        milestone_index: u8,
+        start: i64,
+        cliff: i64,
+        end: i64,
+    },
+    WithdrawStreamedFunds,
+    InitializeRetainer {
+        period_amount: u64,
+        periods: u8,
+        start: i64,
+        interval: i64,
+        notice_period: i64,
+    },
+    UnlockRetainerPeriods,
+    GiveRetainerNotice,
+    SettleRetainer,
+    CreateSubcontract {
+        total_project_funds: u64,
+        milestones: u8,
+    },
+    FreezeSubcontract,
+    ThawSubcontract,
+    CancelSubcontract,
+    SetPayeeSplits {
+        payees: Vec<Pubkey>,
+        milestone_splits: Vec<Vec<u16>>,
+    },
+    WithdrawPayeeShare,
+    SetHourlyTerms {
+        hourly_rate: u64,
+        weekly_cap: u32,
+        approval_window: i64,
+        start: i64,
+    },
+    SubmitTimesheet {
+        week: u32,
+        hours: u32,
+        memo_hash: [u8; 32],
+    },
+    ApproveTimesheet,
+    DisputeTimesheet,
+    WithdrawTimesheetFunds,
+    RequireBond {
+        amount: u64,
+        deadline: i64,
+    },
+    PostBond,
+    ReleaseBond,
+    ForfeitBond,
+    ClaimBond,
+    CreateJobPosting {
+        job_id: u64,
+        budget_min: u64,
+        budget_max: u64,
+        milestone_template: Vec<u16>,
+        metadata_uri: String,
+    },
+    SubmitBid {
+        price: u64,
+        timeline: i64,
+    },
+    AcceptBid,
+    CloseBid,
+    CreateReputation,
+    RateParty {
+        stars: u8,
+        nonce: u32,
+    },
+    Tip {
+        amount: u64,
+        from_escrow: bool,
+    },
+    CreateTransaction,
+    AppendTransactionData {
+        data: Vec<u8>,
+    },
+    Approve,
+    MigrateAccount,
+}
+
+///something an owner should double check before approving
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ProposalWarning {
+    ///the proposal doesn't call this program
+    ForeignProgram(Pubkey),
+    ///the data doesn't match any instruction of this program
+    UnknownInstruction,
+    ///the instruction arguments couldn't be decoded
+    InvalidArguments,
+    ///fewer accounts than the instruction needs
+    MissingAccounts { expected: usize, found: usize },
+    ///an account that should belong to this project doesn't
+    ForeignAccount {
+        name: &'static str,
+        expected: Pubkey,
+        found: Pubkey,
+    },
+    ///the multisig would execute a proposal that creates or approves proposals
+    NestedProposal,
+}
+
+#[derive(Clone, Debug)]
+pub struct NamedAccount {
+    ///account name in the instruction context, `None` for extra accounts
+    pub name: Option<&'static str>,
+    pub account: TransactionAccount,
+}
+
+#[derive(Clone, Debug)]
+pub struct DecodedProposal {
+    pub program_id: Pubkey,
+    pub instruction: Option<ProposalInstruction>,
+    pub accounts: Vec<NamedAccount>,
+    pub warnings: Vec<ProposalWarning>,
+}
+
+impl DecodedProposal {
+    pub fn is_safe(&self) -> bool {
+        self.warnings.is_empty()
+    }
+}
+
+///anchor instruction discriminator, `sha256("global:<name>")[..8]`
+pub fn sighash(name: &str) -> [u8; 8] {
+    let mut discriminator = [0u8; 8];
+    discriminator.copy_from_slice(&hash(format!("global:{name}").as_bytes()).to_bytes()[..8]);
+    discriminator
+}
+
+///map a stored `Transaction` back to the program instruction and accounts it executes
+pub fn decode_transaction(
+    project: &ProjectAddresses,
+    transaction: &Transaction,
+) -> DecodedProposal {
+    decode_proposal(
+        project,
+        &transaction.program_id,
+        &transaction.accounts,
+        &transaction.data,
+    )
+}
+
+pub fn decode_proposal(
+    project: &ProjectAddresses,
+    program_id: &Pubkey,
+    accounts: &[TransactionAccount],
+    data: &[u8],
+) -> DecodedProposal {
+    let mut decoded = DecodedProposal {
+        program_id: *program_id,
+        instruction: None,
+        accounts: accounts
+            .iter()
+            .map(|account| NamedAccount {
+                name: None,
+                account: account.clone(),
+            })
+            .collect(),
+        warnings: Vec::new(),
+    };
+    if *program_id != PROGRAM_ID {
+        decoded
+            .warnings
+            .push(ProposalWarning::ForeignProgram(*program_id));
+        return decoded;
+    }
+
+    let (instruction, specs) = match decode_instruction(project, data) {
+        Some(Ok(decoded_instruction)) => decoded_instruction,
+        Some(Err(())) => {
+            decoded.warnings.push(ProposalWarning::InvalidArguments);
+            return decoded;
+        }
+        None => {
+            decoded.warnings.push(ProposalWarning::UnknownInstruction);
+            return decoded;
+        }
+    };
+    if matches!(
+        instruction,
+        ProposalInstruction::CreateTransaction
+            | ProposalInstruction::AppendTransactionData { .. }
+            | ProposalInstruction::Approve
+    ) {
+        decoded.warnings.push(ProposalWarning::NestedProposal);
+    }
+
+    if accounts.len() < specs.len() {
+        decoded.warnings.push(ProposalWarning::MissingAccounts {
+            expected: specs.len(),
+            found: accounts.len(),
+        });
+    }
+    for (named, (name, expected)) in decoded.accounts.iter_mut().zip(specs) {
+        named.name = Some(name);
+        if let Some(expected) = expected {
+            if named.account.pubkey != expected {
+                decoded.warnings.push(ProposalWarning::ForeignAccount {
+                    name,
+                    expected,
+                    found: named.account.pubkey,
+                });
+            }
+        }
+    }
+    decoded.instruction = Some(instruction);
+    decoded
+}
+
+///account name and, when it's fixed by the project, its expected address
+type AccountSpec = (&'static str, Option<Pubkey>);
+
+fn decode_instruction(
+    project: &ProjectAddresses,
+    data: &[u8],
+) -> Option<Result<(ProposalInstruction, Vec<AccountSpec>), ()>> {
+    if data.len() < 8 {
+        return None;
+    }
+    let (discriminator, mut args) = data.split_at(8);
+
+    let project_info_account = ("project_info_account", Some(project.project_info_account));
+    let multisig_signer = ("multisig_signer", Some(project.multisig));
+    let token_escrow = ("token_escrow", Some(project.token_escrow));
+    let client = ("client", Some(project.client));
+    let freelancer = ("freelancer", Some(project.freelancer));
+    let retainer = ("retainer", Some(project.retainer()));
+    let hourly_terms = ("hourly_terms", Some(project.hourly_terms()));
+    let payee_splits = ("payee_splits", Some(project.payee_splits()));
+    let subcontract = ("subcontract", Some(project.subcontract()));
+    let bond = ("bond", Some(project.bond()));
+    let client_reputation = ("client_reputation", Some(project.client_reputation()));
+    let freelancer_reputation = (
+        "freelancer_reputation",
+        Some(project.freelancer_reputation()),
+    );
+    let system = ("system_program", Some(system_program::ID));
+    let multisig_auth = vec![project_info_account, multisig_signer];
+
+    let decoded = if discriminator == sighash("initialize_project") {
+        instruction::InitializeProject::deserialize(&mut args).map(|ix| {
+            (
+                ProposalInstruction::InitializeProject {
+                    total_funds_for_project: ix.total_funds_for_project,
+                    milestones: ix.milestones,
+                },
+                vec![
+                    project_info_account,
+                    ("multisig", Some(project.multisig)),
+                    token_escrow,
+                    freelancer,
+                    ("observer", None),
+                    client,
+                    system,
+                    client_reputation,
+                    freelancer_reputation,
+                ],
+            )
+        })
+    } else if discriminator == sighash("start_project") {
+        Ok((
+            ProposalInstruction::StartProject,
+            vec![project_info_account, multisig_signer, bond],
+        ))
+    } else if discriminator == sighash("cancel_project") {
+        Ok((
+            ProposalInstruction::CancelProject,
+            vec![
+                project_info_account,
+                token_escrow,
+                freelancer,
+                client,
+                system,
+                subcontract,
+                client_reputation,
+                freelancer_reputation,
+                payee_splits,
+                //seeded by the project nonce, which the proposal doesn't carry
+                ("client_rating", None),
+                ("freelancer_rating", None),
+            ],
+        ))
+    } else if discriminator == sighash("accept_offer") {
+        Ok((
+            ProposalInstruction::AcceptOffer,
+            vec![project_info_account, freelancer, bond],
+        ))
+    } else if discriminator == sighash("decline_offer") {
+        Ok((
+            ProposalInstruction::DeclineOffer,
+            vec![
+                project_info_account,
+                ("multisig", Some(project.multisig)),
+                token_escrow,
+                client,
+                freelancer,
+                system,
+                subcontract,
+            ],
+        ))
+    } else if discriminator == sighash("withdraw_milestone_funds") {
+        Ok((
+            ProposalInstruction::WithdrawMilestoneFunds,
+            vec![
+                project_info_account,
+                token_escrow,
+                freelancer,
+                system,
+                payee_splits,
+                bond,
+                client_reputation,
+                freelancer_reputation,
+            ],
+        ))
+    } else if discriminator == sighash("mark_current_milestone_completed") {
+        Ok((
+            ProposalInstruction::MarkCurrentMilestoneCompleted,
+            multisig_auth,
+        ))
+    } else if discriminator == sighash("pause_project") {
+        Ok((ProposalInstruction::PauseProject, multisig_auth))
+    } else if discriminator == sighash("resume_project") {
+        Ok((
+            ProposalInstruction::ResumeProject,
+            vec![project_info_account, multisig_signer, subcontract],
+        ))
+    } else if discriminator == sighash("change_order") {
+        instruction::ChangeOrder::deserialize(&mut args).map(|ix| {
+            (
+                ProposalInstruction::ChangeOrder {
+                    additional_funds: ix.additional_funds,
+                    milestone_amounts: ix.milestone_amounts,
+                },
+                vec![
+                    project_info_account,
+                    multisig_signer,
+                    token_escrow,
+                    client,
+                    system,
+                ],
+            )
+        })
+    } else if discriminator == sighash("stop_project") {
+        instruction::StopProject::deserialize(&mut args).map(|ix| {
+            (
+                ProposalInstruction::StopProject { ruling: ix.ruling },
+                vec![
+                    project_info_account,
+                    multisig_signer,
+                    client_reputation,
+                    freelancer_reputation,
+                    bond,
+                    client,
+                ],
+            )
+        })
+    } else if discriminator == sighash("set_milestone_stream") {
+        instruction::SetMilestoneStream::deserialize(&mut args).map(|ix| {
+            (
+                ProposalInstruction::SetMilestoneStream {
+                    milestone_index: ix.milestone_index,
+                    start: ix.start,
+                    cliff: ix.cliff,
+                    end: ix.end,
+                },
+                vec![
+                    project_info_account,
+                    multisig_signer,
+                    (
+                        "milestone_stream",
+                        Some(project.milestone_stream(ix.milestone_index)),
+                    ),
+                    client,
+                    system,
+                ],
+            )
+        })
+    } else if discriminator == sighash("withdraw_streamed_funds") {
+        Ok((
+            ProposalInstruction::WithdrawStreamedFunds,
+            vec![
+                project_info_account,
+                ("milestone_stream", None),
+                token_escrow,
+                freelancer,
+                system,
+                payee_splits,
+            ],
+        ))
+    } else if discriminator == sighash("initialize_retainer") {
+        instruction::InitializeRetainer::deserialize(&mut args).map(|ix| {
+            (
+                ProposalInstruction::InitializeRetainer {
+                    period_amount: ix.period_amount,
+                    periods: ix.periods,
+                    start: ix.start,
+                    interval: ix.interval,
+                    notice_period: ix.notice_period,
+                },
+                vec![
+                    project_info_account,
+                    ("multisig", Some(project.multisig)),
+                    retainer,
+                    token_escrow,
+                    freelancer,
+                    ("observer", None),
+                    client,
+                    system,
+                    client_reputation,
+                    freelancer_reputation,
+                ],
+            )
+        })
+    } else if discriminator == sighash("unlock_retainer_periods") {
+        Ok((
+            ProposalInstruction::UnlockRetainerPeriods,
+            vec![project_info_account, retainer, ("caller", None)],
+        ))
+    } else if discriminator == sighash("give_retainer_notice") {
+        Ok((
+            ProposalInstruction::GiveRetainerNotice,
+            vec![project_info_account, retainer, ("party", None)],
+        ))
+    } else if discriminator == sighash("settle_retainer") {
+        Ok((
+            ProposalInstruction::SettleRetainer,
+            vec![
+                project_info_account,
+                retainer,
+                token_escrow,
+                client,
+                ("caller", None),
+                system,
+                bond,
+                client_reputation,
+                freelancer_reputation,
+            ],
+        ))
+    } else if discriminator == sighash("set_payee_splits") {
+        instruction::SetPayeeSplits::deserialize(&mut args).map(|ix| {
+            (
+                ProposalInstruction::SetPayeeSplits {
+                    payees: ix.payees,
+                    milestone_splits: ix.milestone_splits,
+                },
+                vec![
+                    project_info_account,
+                    multisig_signer,
+                    payee_splits,
+                    client,
+                    system,
+                ],
+            )
+        })
+    } else if discriminator == sighash("withdraw_payee_share") {
+        Ok((
+            ProposalInstruction::WithdrawPayeeShare,
+            vec![
+                project_info_account,
+                payee_splits,
+                token_escrow,
+                ("payee", None),
+                system,
+                bond,
+                client_reputation,
+                freelancer_reputation,
+            ],
+        ))
+    } else if discriminator == sighash("create_subcontract") {
+        //proposals only ever target their own project, so it can only be the parent
+        instruction::CreateSubcontract::deserialize(&mut args).map(|ix| {
+            (
+                ProposalInstruction::CreateSubcontract {
+                    total_project_funds: ix.total_project_funds,
+                    milestones: ix.milestones,
+                },
+                vec![
+                    ("parent", Some(project.project_info_account)),
+                    ("parent_escrow", Some(project.token_escrow)),
+                    ("parent_payee_splits", Some(project.payee_splits())),
+                    ("parent_multisig", Some(project.multisig)),
+                    ("parent_client", Some(project.client)),
+                    ("project_info_account", None),
+                    ("multisig", None),
+                    ("subcontract", None),
+                    ("token_escrow", None),
+                    ("freelancer", None),
+                    ("observer", None),
+                    ("client", Some(project.freelancer)),
+                    system,
+                    ("client_reputation", Some(project.freelancer_reputation())),
+                    ("freelancer_reputation", None),
+                ],
+            )
+        })
+    } else if discriminator == sighash("freeze_subcontract") {
+        Ok((
+            ProposalInstruction::FreezeSubcontract,
+            vec![
+                project_info_account,
+                subcontract,
+                ("parent", None),
+                ("caller", None),
+            ],
+        ))
+    } else if discriminator == sighash("thaw_subcontract") {
+        Ok((
+            ProposalInstruction::ThawSubcontract,
+            vec![
+                project_info_account,
+                subcontract,
+                ("parent", None),
+                ("caller", None),
+            ],
+        ))
+    } else if discriminator == sighash("cancel_subcontract") {
+        Ok((
+            ProposalInstruction::CancelSubcontract,
+            vec![
+                project_info_account,
+                token_escrow,
+                subcontract,
+                ("parent", None),
+                ("parent_escrow", None),
+                ("parent_client", None),
+                client,
+                system,
+                client_reputation,
+                freelancer_reputation,
+                freelancer,
+                payee_splits,
+            ],
+        ))
+    } else if discriminator == sighash("set_hourly_terms") {
+        instruction::SetHourlyTerms::deserialize(&mut args).map(|ix| {
+            (
+                ProposalInstruction::SetHourlyTerms {
+                    hourly_rate: ix.hourly_rate,
+                    weekly_cap: ix.weekly_cap,
+                    approval_window: ix.approval_window,
+                    start: ix.start,
+                },
+                vec![
+                    project_info_account,
+                    multisig_signer,
+                    hourly_terms,
+                    client,
+                    system,
+                ],
+            )
+        })
+    } else if discriminator == sighash("submit_timesheet") {
+        instruction::SubmitTimesheet::deserialize(&mut args).map(|ix| {
+            (
+                ProposalInstruction::SubmitTimesheet {
+                    week: ix.week,
+                    hours: ix.hours,
+                    memo_hash: ix.memo_hash,
+                },
+                vec![
+                    project_info_account,
+                    hourly_terms,
+                    ("timesheet", Some(project.timesheet(ix.week))),
+                    freelancer,
+                    system,
+                ],
+            )
+        })
+    } else if discriminator == sighash("approve_timesheet") {
+        Ok((
+            ProposalInstruction::ApproveTimesheet,
+            vec![
+                project_info_account,
+                hourly_terms,
+                ("timesheet", None),
+                client,
+            ],
+        ))
+    } else if discriminator == sighash("dispute_timesheet") {
+        Ok((
+            ProposalInstruction::DisputeTimesheet,
+            vec![
+                project_info_account,
+                hourly_terms,
+                ("timesheet", None),
+                client,
+            ],
+        ))
+    } else if discriminator == sighash("withdraw_timesheet_funds") {
+        Ok((
+            ProposalInstruction::WithdrawTimesheetFunds,
+            vec![
+                project_info_account,
+                hourly_terms,
+                ("timesheet", None),
+                token_escrow,
+                freelancer,
+                system,
+                payee_splits,
+            ],
+        ))
+    } else if discriminator == sighash("require_bond") {
+        instruction::RequireBond::deserialize(&mut args).map(|ix| {
+            (
+                ProposalInstruction::RequireBond {
+                    amount: ix.amount,
+                    deadline: ix.deadline,
+                },
+                vec![project_info_account, bond, client, system],
+            )
+        })
+    } else if discriminator == sighash("post_bond") {
+        Ok((
+            ProposalInstruction::PostBond,
+            vec![project_info_account, bond, freelancer, system],
+        ))
+    } else if discriminator == sighash("release_bond") {
+        Ok((
+            ProposalInstruction::ReleaseBond,
+            vec![
+                project_info_account,
+                bond,
+                freelancer,
+                client,
+                ("caller", None),
+            ],
+        ))
+    } else if discriminator == sighash("forfeit_bond") {
+        Ok((
+            ProposalInstruction::ForfeitBond,
+            vec![
+                project_info_account,
+                multisig_signer,
+                bond,
+                client,
+                client_reputation,
+                freelancer_reputation,
+            ],
+        ))
+    } else if discriminator == sighash("claim_bond") {
+        Ok((
+            ProposalInstruction::ClaimBond,
+            vec![
+                project_info_account,
+                bond,
+                client,
+                ("caller", None),
+                freelancer_reputation,
+            ],
+        ))
+    } else if discriminator == sighash("create_job_posting") {
+        instruction::CreateJobPosting::deserialize(&mut args).map(|ix| {
+            (
+                ProposalInstruction::CreateJobPosting {
+                    job_id: ix.job_id,
+                    budget_min: ix.budget_min,
+                    budget_max: ix.budget_max,
+                    milestone_template: ix.milestone_template,
+                    metadata_uri: ix.metadata_uri,
+                },
+                vec![("job_posting", None), ("client", None), system],
+            )
+        })
+    } else if discriminator == sighash("submit_bid") {
+        instruction::SubmitBid::deserialize(&mut args).map(|ix| {
+            (
+                ProposalInstruction::SubmitBid {
+                    price: ix.price,
+                    timeline: ix.timeline,
+                },
+                vec![
+                    ("job_posting", None),
+                    ("bid", None),
+                    ("freelancer", None),
+                    system,
+                ],
+            )
+        })
+    } else if discriminator == sighash("accept_bid") {
+        Ok((
+            ProposalInstruction::AcceptBid,
+            vec![
+                ("job_posting", None),
+                ("bid", None),
+                project_info_account,
+                ("multisig", Some(project.multisig)),
+                token_escrow,
+                freelancer,
+                ("observer", None),
+                client,
+                system,
+                client_reputation,
+                freelancer_reputation,
+            ],
+        ))
+    } else if discriminator == sighash("close_bid") {
+        Ok((
+            ProposalInstruction::CloseBid,
+            vec![("bid", None), ("freelancer", None)],
+        ))
+    } else if discriminator == sighash("create_reputation") {
+        Ok((
+            ProposalInstruction::CreateReputation,
+            vec![
+                ("reputation", None),
+                ("wallet", None),
+                ("payer", None),
+                system,
+            ],
+        ))
+    } else if discriminator == sighash("rate_party") {
+        instruction::RateParty::deserialize(&mut args).map(|ix| {
+            (
+                ProposalInstruction::RateParty {
+                    stars: ix.stars,
+                    nonce: ix.nonce,
+                },
+                vec![
+                    project_info_account,
+                    ("rating", None),
+                    ("rater", None),
+                    ("ratee", None),
+                    ("ratee_reputation", None),
+                    system,
+                ],
+            )
+        })
+    } else if discriminator == sighash("tip") {
+        instruction::Tip::deserialize(&mut args).map(|ix| {
+            (
+                ProposalInstruction::Tip {
+                    amount: ix.amount,
+                    from_escrow: ix.from_escrow,
+                },
+                vec![
+                    project_info_account,
+                    ("tips", Some(project.tips())),
+                    token_escrow,
+                    freelancer,
+                    client,
+                    system,
+                ],
+            )
+        })
+    } else if discriminator == sighash("create_transaction") {
+        Ok((
+            ProposalInstruction::CreateTransaction,
+            vec![
+                project_info_account,
+                ("multisig", Some(project.multisig)),
+                ("transaction", None),
+                ("proposer", None),
+                system,
+            ],
+        ))
+    } else if discriminator == sighash("append_transaction_data") {
+        instruction::AppendTransactionData::deserialize(&mut args).map(|ix| {
+            (
+                ProposalInstruction::AppendTransactionData { data: ix.data },
+                vec![
+                    project_info_account,
+                    ("multisig", Some(project.multisig)),
+                    ("transaction", None),
+                    ("proposer", None),
+                    system,
+                ],
+            )
+        })
+    } else if discriminator == sighash("approve") {
+        Ok((
+            ProposalInstruction::Approve,
+            vec![
+                project_info_account,
+                ("multisig", Some(project.multisig)),
+                multisig_signer,
+                ("transaction", None),
+                ("owner", None),
+            ],
+        ))
+    } else if discriminator == sighash("migrate_account") {
+        Ok((
+            ProposalInstruction::MigrateAccount,
+            vec![("account", None), ("payer", None), system],
+        ))
+    } else {
+        return None;
+    };
+    Some(decoded.map_err(|_| ()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::instructions;
+    use anchor_lang::solana_program::instruction::Instruction;
+
+    fn project() -> ProjectAddresses {
+        ProjectAddresses::new(Pubkey::new_unique(), Pubkey::new_unique())
+    }
+
+    fn decode(project: &ProjectAddresses, ix: &Instruction) -> DecodedProposal {
+        let accounts: Vec<TransactionAccount> = ix.accounts.iter().map(Into::into).collect();
+        decode_proposal(project, &ix.program_id, &accounts, &ix.data)
+    }
+
+    ///every builder of the client, decoded against the project it acts on
+    fn builders(project: &ProjectAddresses) -> Vec<(&'static str, Instruction)> {
+        //`project` is the parent of `child` and the child of `parent`
+        let child = ProjectAddresses::new(project.freelancer, Pubkey::new_unique());
+        let parent = ProjectAddresses::new(Pubkey::new_unique(), project.client);
+        let observer = Pubkey::new_unique();
+        let job_posting = Pubkey::new_unique();
+        let payer = Pubkey::new_unique();
+        let start_project = instructions::start_project(project);
+        vec![
+            (
+                "initialize_project",
+                instructions::initialize_project(project, &observer, 1000, 2),
+            ),
+            (
+                "initialize_retainer",
+                instructions::initialize_retainer(project, &observer, 100, 3, 0, 60, 60),
+            ),
+            (
+                "unlock_retainer_periods",
+                instructions::unlock_retainer_periods(project, &payer),
+            ),
+            (
+                "give_retainer_notice",
+                instructions::give_retainer_notice(project, &project.client),
+            ),
+            (
+                "settle_retainer",
+                instructions::settle_retainer(project, &payer),
+            ),
+            (
+                "create_subcontract",
+                instructions::create_subcontract(project, &child, &observer, 500, 1),
+            ),
+            (
+                "freeze_subcontract",
+                instructions::freeze_subcontract(project, &parent.project_info_account, &payer),
+            ),
+            (
+                "thaw_subcontract",
+                instructions::thaw_subcontract(project, &parent.project_info_account, &payer),
+            ),
+            (
+                "cancel_subcontract",
+                instructions::cancel_subcontract(project, &parent),
+            ),
+            ("require_bond", instructions::require_bond(project, 10, 100)),
+            ("post_bond", instructions::post_bond(project)),
+            ("release_bond", instructions::release_bond(project, &payer)),
+            ("claim_bond", instructions::claim_bond(project, &payer)),
+            ("cancel_project", instructions::cancel_project(project, 0)),
+            ("accept_offer", instructions::accept_offer(project)),
+            ("decline_offer", instructions::decline_offer(project)),
+            (
+                "create_job_posting",
+                instructions::create_job_posting(
+                    &project.client,
+                    7,
+                    100,
+                    200,
+                    vec![5000, 5000],
+                    "ipfs://job".to_string(),
+                ),
+            ),
+            (
+                "submit_bid",
+                instructions::submit_bid(&job_posting, &project.freelancer, 150, 86400),
+            ),
+            (
+                "accept_bid",
+                instructions::accept_bid(&job_posting, project, &observer),
+            ),
+            (
+                "close_bid",
+                instructions::close_bid(&job_posting, &project.freelancer),
+            ),
+            (
+                "create_reputation",
+                instructions::create_reputation(&project.client, &payer),
+            ),
+            (
+                "rate_party",
+                instructions::rate_party(project, 0, &project.client, 5),
+            ),
+            ("tip", instructions::tip(project, 10, true)),
+            (
+                "withdraw_milestone_funds",
+                instructions::withdraw_milestone_funds(project),
+            ),
+            ("start_project", start_project.clone()),
+            (
+                "mark_current_milestone_completed",
+                instructions::mark_current_milestone_completed(project),
+            ),
+            (
+                "stop_project",
+                instructions::stop_project(project, Ruling::Client),
+            ),
+            ("forfeit_bond", instructions::forfeit_bond(project)),
+            ("pause_project", instructions::pause_project(project)),
+            ("resume_project", instructions::resume_project(project)),
+            (
+                "change_order",
+                instructions::change_order(project, 100, vec![500, 600]),
+            ),
+            (
+                "set_milestone_stream",
+                instructions::set_milestone_stream(project, 0, 0, 10, 100),
+            ),
+            (
+                "withdraw_streamed_funds",
+                instructions::withdraw_streamed_funds(project, 0),
+            ),
+            (
+                "set_payee_splits",
+                instructions::set_payee_splits(project, vec![payer], vec![vec![10000]]),
+            ),
+            (
+                "withdraw_payee_share",
+                instructions::withdraw_payee_share(project, &payer),
+            ),
+            (
+                "set_hourly_terms",
+                instructions::set_hourly_terms(project, 50, 40, 3600, 0),
+            ),
+            (
+                "submit_timesheet",
+                instructions::submit_timesheet(project, 0, 10, [1; 32]),
+            ),
+            (
+                "approve_timesheet",
+                instructions::approve_timesheet(project, 0),
+            ),
+            (
+                "dispute_timesheet",
+                instructions::dispute_timesheet(project, 0),
+            ),
+            (
+                "withdraw_timesheet_funds",
+                instructions::withdraw_timesheet_funds(project, 0),
+            ),
+            (
+                "create_transaction",
+                instructions::create_transaction(project, &project.client, 0, &start_project),
+            ),
+            (
+                "append_transaction_data",
+                instructions::append_transaction_data(project, &project.client, 0, vec![1, 2]),
+            ),
+            (
+                "approve",
+                instructions::approve(
+                    project,
+                    &project.freelancer,
+                    &project.multisig_transaction(0),
+                    &PROGRAM_ID,
+                    &[],
+                ),
+            ),
+            (
+                "migrate_account",
+                instructions::migrate_account(&project.project_info_account, &payer),
+            ),
+        ]
+    }
+
+    #[test]
+    fn every_client_instruction_is_decoded() {
+        let project = project();
+        for (name, ix) in builders(&project) {
+            let decoded = decode(&project, &ix);
+            let expected = match name {
+                "create_transaction" | "append_transaction_data" | "approve" => {
+                    vec![ProposalWarning::NestedProposal]
+                }
+                _ => vec![],
+            };
+            assert_eq!(decoded.warnings, expected, "{name}");
+            assert!(decoded.instruction.is_some(), "{name}");
+            //no account is missing, and only the accounts `approve` passes on
+            //to the proposal are left unnamed
+            if name != "approve" {
+                assert!(
+                    decoded
+                        .accounts
+                        .iter()
+                        .all(|account| account.name.is_some()),
+                    "{name}"
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn every_program_instruction_is_known() {
+        let project = project();
+        let lib = include_str!("../../programs/group_6_payment_protocol/src/lib.rs");
+        let names: Vec<&str> = lib
+            .lines()
+            .filter_map(|line| line.trim().strip_prefix("pub fn "))
+            .filter_map(|rest| rest.split(['(', '<']).next())
+            .collect();
+        assert!(names.len() > 40);
+        for name in names {
+            assert!(
+                decode_instruction(&project, &sighash(name)).is_some(),
+                "{name} isn't decoded"
+            );
+        }
+    }
+
+    #[test]
+    fn decoded_arguments_match_the_proposal() {
+        let project = project();
+        let decoded = decode(
+            &project,
+            &instructions::change_order(&project, 100, vec![500, 600]),
+        );
+        assert_eq!(
+            decoded.instruction,
+            Some(ProposalInstruction::ChangeOrder {
+                additional_funds: 100,
+                milestone_amounts: vec![500, 600],
+            })
+        );
+        assert!(decoded.is_safe());
+        assert_eq!(decoded.accounts[0].name, Some("project_info_account"));
+    }
+
+    #[test]
+    fn foreign_programs_are_flagged() {
+        let project = project();
+        let mut ix = instructions::start_project(&project);
+        ix.program_id = Pubkey::new_unique();
+        let decoded = decode(&project, &ix);
+        assert_eq!(
+            decoded.warnings,
+            vec![ProposalWarning::ForeignProgram(ix.program_id)]
+        );
+        assert_eq!(decoded.instruction, None);
+    }
+
+    #[test]
+    fn unknown_instructions_are_flagged() {
+        let project = project();
+        let mut ix = instructions::start_project(&project);
+        ix.data = sighash("drain_escrow").to_vec();
+        let decoded = decode(&project, &ix);
+        assert_eq!(decoded.warnings, vec![ProposalWarning::UnknownInstruction]);
+    }
+
+    #[test]
+    fn bad_arguments_are_flagged() {
+        let project = project();
+        let mut ix = instructions::change_order(&project, 100, vec![500, 600]);
+        ix.data.truncate(12);
+        let decoded = decode(&project, &ix);
+        assert_eq!(decoded.warnings, vec![ProposalWarning::InvalidArguments]);
+        assert_eq!(decoded.instruction, None);
+    }
+
+    #[test]
+    fn missing_accounts_are_flagged() {
+        let project = project();
+        let mut ix = instructions::withdraw_milestone_funds(&project);
+        let expected = ix.accounts.len();
+        ix.accounts.pop();
+        let decoded = decode(&project, &ix);
+        assert_eq!(
+            decoded.warnings,
+            vec![ProposalWarning::MissingAccounts {
+                expected,
+                found: expected - 1,
+            }]
+        );
+    }
+
+    #[test]
+    fn foreign_accounts_are_flagged() {
+        let project = project();
+        let other = self::project();
+        let mut ix = instructions::withdraw_milestone_funds(&project);
+        let index = ix
+            .accounts
+            .iter()
+            .position(|meta| meta.pubkey == project.token_escrow)
+            .unwrap();
+        ix.accounts[index].pubkey = other.token_escrow;
+        let decoded = decode(&project, &ix);
+        assert_eq!(
+            decoded.warnings,
+            vec![ProposalWarning::ForeignAccount {
+                name: "token_escrow",
+                expected: project.token_escrow,
+                found: other.token_escrow,
+            }]
+        );
+        assert!(!decoded.is_safe());
+    }
+
+    #[test]
+    fn nested_proposals_are_flagged() {
+        let project = project();
+        let proposal = instructions::create_transaction(
+            &project,
+            &project.client,
+            0,
+            &instructions::start_project(&project),
+        );
+        let decoded = decode(&project, &proposal);
+        assert_eq!(
+            decoded.instruction,
+            Some(ProposalInstruction::CreateTransaction)
+        );
+        assert_eq!(decoded.warnings, vec![ProposalWarning::NestedProposal]);
+    }
+}
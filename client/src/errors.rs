@@ -0,0 +1,15 @@
+use anchor_lang::prelude::Pubkey;
+
+#[derive(Debug, thiserror::Error)]
+pub enum ClientError {
+    #[error("account {0} not found")]
+    AccountNotFound(Pubkey),
+    #[error("account {0} could not be decoded: {1}")]
+    InvalidAccountData(Pubkey, Box<anchor_lang::error::Error>),
+    #[error("rpc request failed: {0}")]
+    Rpc(String),
+    #[error("multisig {0} has no pending proposal")]
+    NoPendingProposal(Pubkey),
+    #[error("invalid keypair: {0}")]
+    Keypair(String),
+}
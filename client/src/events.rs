@@ -0,0 +1,141 @@
+use anchor_lang::{AnchorDeserialize, Discriminator};
+use group_6_payment_protocol::events::*;
+
+use crate::PROGRAM_ID;
+
+///any event emitted by the program
+#[derive(Debug)]
+pub enum ProgramEvent {
+    ProjectInitialized(ProjectInitialized),
+    ProjectStarted(ProjectStarted),
+    MilestoneReached(MilestoneReached),
+    FundsWithdrawn(FundsWithdrawn),
+    MilestoneStreamSet(MilestoneStreamSet),
+    StreamedFundsWithdrawn(StreamedFundsWithdrawn),
+    RetainerInitialized(RetainerInitialized),
+    RetainerNoticeGiven(RetainerNoticeGiven),
+    RetainerSettled(RetainerSettled),
+    HourlyTermsSet(HourlyTermsSet),
+    TimesheetSubmitted(TimesheetSubmitted),
+    TimesheetApproved(TimesheetApproved),
+    TimesheetDisputed(TimesheetDisputed),
+    TimesheetPaid(TimesheetPaid),
+    PayeeSplitsSet(PayeeSplitsSet),
+    PayeeShareWithdrawn(PayeeShareWithdrawn),
+    SubcontractCreated(SubcontractCreated),
+    SubcontractFrozen(SubcontractFrozen),
+    SubcontractThawed(SubcontractThawed),
+    SubcontractCancelled(SubcontractCancelled),
+    BondRequired(BondRequired),
+    BondPosted(BondPosted),
+    BondReleased(BondReleased),
+    BondForfeited(BondForfeited),
+    OfferAccepted(OfferAccepted),
+    OfferDeclined(OfferDeclined),
+    JobPosted(JobPosted),
+    BidSubmitted(BidSubmitted),
+    BidAccepted(BidAccepted),
+    BidClosed(BidClosed),
+    ReputationCreated(ReputationCreated),
+    PartyRated(PartyRated),
+    TipPaid(TipPaid),
+    ProjectPaused(ProjectPaused),
+    ProjectResumed(ProjectResumed),
+    ChangeOrderApplied(ChangeOrderApplied),
+    ProjectStopped(ProjectStopped),
+    ProjectCancelled(ProjectCancelled),
+    ProposalCreated(ProposalCreated),
+    ProposalDataAppended(ProposalDataAppended),
+    ProposalApproved(ProposalApproved),
+    ProposalExecuted(ProposalExecuted),
+}
+
+fn decode_as<T: Discriminator + AnchorDeserialize>(
+    data: &[u8],
+    wrap: fn(T) -> ProgramEvent,
+) -> Option<Option<ProgramEvent>> {
+    if data.len() < 8 || data[..8] != T::discriminator() {
+        return None;
+    }
+    Some(T::try_from_slice(&data[8..]).ok().map(wrap))
+}
+
+///decode the data of a `Program data:` log line, `None` if it isn't one of our events
+pub fn decode_event(data: &[u8]) -> Option<ProgramEvent> {
+    decode_as(data, ProgramEvent::ProjectInitialized)
+        .or_else(|| decode_as(data, ProgramEvent::ProjectStarted))
+        .or_else(|| decode_as(data, ProgramEvent::MilestoneReached))
+        .or_else(|| decode_as(data, ProgramEvent::FundsWithdrawn))
+        .or_else(|| decode_as(data, ProgramEvent::MilestoneStreamSet))
+        .or_else(|| decode_as(data, ProgramEvent::StreamedFundsWithdrawn))
+        .or_else(|| decode_as(data, ProgramEvent::RetainerInitialized))
+        .or_else(|| decode_as(data, ProgramEvent::RetainerNoticeGiven))
+        .or_else(|| decode_as(data, ProgramEvent::RetainerSettled))
+        .or_else(|| decode_as(data, ProgramEvent::HourlyTermsSet))
+        .or_else(|| decode_as(data, ProgramEvent::TimesheetSubmitted))
+        .or_else(|| decode_as(data, ProgramEvent::TimesheetApproved))
+        .or_else(|| decode_as(data, ProgramEvent::TimesheetDisputed))
+        .or_else(|| decode_as(data, ProgramEvent::TimesheetPaid))
+        .or_else(|| decode_as(data, ProgramEvent::PayeeSplitsSet))
+        .or_else(|| decode_as(data, ProgramEvent::PayeeShareWithdrawn))
+        .or_else(|| decode_as(data, ProgramEvent::SubcontractCreated))
+        .or_else(|| decode_as(data, ProgramEvent::SubcontractFrozen))
+        .or_else(|| decode_as(data, ProgramEvent::SubcontractThawed))
+        .or_else(|| decode_as(data, ProgramEvent::SubcontractCancelled))
+        .or_else(|| decode_as(data, ProgramEvent::BondRequired))
+        .or_else(|| decode_as(data, ProgramEvent::BondPosted))
+        .or_else(|| decode_as(data, ProgramEvent::BondReleased))
+        .or_else(|| decode_as(data, ProgramEvent::BondForfeited))
+        .or_else(|| decode_as(data, ProgramEvent::OfferAccepted))
+        .or_else(|| decode_as(data, ProgramEvent::OfferDeclined))
+        .or_else(|| decode_as(data, ProgramEvent::JobPosted))
+        .or_else(|| decode_as(data, ProgramEvent::BidSubmitted))
+        .or_else(|| decode_as(data, ProgramEvent::BidAccepted))
+        .or_else(|| decode_as(data, ProgramEvent::BidClosed))
+        .or_else(|| decode_as(data, ProgramEvent::ReputationCreated))
+        .or_else(|| decode_as(data, ProgramEvent::PartyRated))
+        .or_else(|| decode_as(data, ProgramEvent::TipPaid))
+        .or_else(|| decode_as(data, ProgramEvent::ProjectPaused))
+        .or_else(|| decode_as(data, ProgramEvent::ProjectResumed))
+        .or_else(|| decode_as(data, ProgramEvent::ChangeOrderApplied))
+        .or_else(|| decode_as(data, ProgramEvent::ProjectStopped))
+        .or_else(|| decode_as(data, ProgramEvent::ProjectCancelled))
+        .or_else(|| decode_as(data, ProgramEvent::ProposalCreated))
+        .or_else(|| decode_as(data, ProgramEvent::ProposalDataAppended))
+        .or_else(|| decode_as(data, ProgramEvent::ProposalApproved))
+        .or_else(|| decode_as(data, ProgramEvent::ProposalExecuted))
+        .flatten()
+}
+
+///every event emitted by this program in a transaction's log messages,
+///`Program data:` lines logged by other programs are skipped
+pub fn parse_logs<S: AsRef<str>>(logs: &[S]) -> Vec<ProgramEvent> {
+    let program_id = PROGRAM_ID.to_string();
+    let mut invocations: Vec<String> = Vec::new();
+    let mut events = Vec::new();
+
+    for log in logs.iter().map(AsRef::as_ref) {
+        if let Some(data) = log.strip_prefix("Program data: ") {
+            if invocations.last() == Some(&program_id) {
+                if let Some(event) = base64::decode(data).ok().as_deref().and_then(decode_event) {
+                    events.push(event);
+                }
+            }
+        } else if let Some(rest) = log.strip_prefix("Program ") {
+            let mut words = rest.split(' ');
+            let program = words.next().unwrap_or_default();
+            //`Program log:`, `Program return:`...
+            if program.ends_with(':') {
+                continue;
+            }
+            match words.next() {
+                Some("invoke") => invocations.push(program.to_string()),
+                Some("success") | Some("failed:") => {
+                    invocations.pop();
+                }
+                _ => {}
+            }
+        }
+    }
+    events
+}
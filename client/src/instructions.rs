@@ -0,0 +1,803 @@
+use anchor_lang::prelude::{AccountMeta, Pubkey};
+use anchor_lang::solana_program::instruction::Instruction;
+use anchor_lang::solana_program::system_program;
+use anchor_lang::{InstructionData, ToAccountMetas};
+use group_6_payment_protocol::state::{Ruling, TransactionAccount};
+use group_6_payment_protocol::{accounts, instruction};
+
+use crate::pda::{self, ProjectAddresses};
+use crate::PROGRAM_ID;
+
+fn build(accounts: impl ToAccountMetas, data: impl InstructionData) -> Instruction {
+    Instruction {
+        program_id: PROGRAM_ID,
+        accounts: accounts.to_account_metas(None),
+        data: data.data(),
+    }
+}
+
+pub fn initialize_project(
+    project: &ProjectAddresses,
+    observer: &Pubkey,
+    total_funds_for_project: u64,
+    milestones: u8,
+) -> Instruction {
+    build(
+        accounts::InitializeProjectContext {
+            project_info_account: project.project_info_account,
+            multisig: project.multisig,
+            token_escrow: project.token_escrow,
+            freelancer: project.freelancer,
+            observer: *observer,
+            client: project.client,
+            system_program: system_program::ID,
+            client_reputation: project.client_reputation(),
+            freelancer_reputation: project.freelancer_reputation(),
+        },
+        instruction::InitializeProject {
+            total_funds_for_project,
+            milestones,
+        },
+    )
+}
+
+///`start`, `interval` and `notice_period` are in unix seconds
+pub fn initialize_retainer(
+    project: &ProjectAddresses,
+    observer: &Pubkey,
+    period_amount: u64,
+    periods: u8,
+    start: i64,
+    interval: i64,
+    notice_period: i64,
+) -> Instruction {
+    build(
+        accounts::InitializeRetainerContext {
+            project_info_account: project.project_info_account,
+            multisig: project.multisig,
+            retainer: project.retainer(),
+            token_escrow: project.token_escrow,
+            freelancer: project.freelancer,
+            observer: *observer,
+            client: project.client,
+            system_program: system_program::ID,
+            client_reputation: project.client_reputation(),
+            freelancer_reputation: project.freelancer_reputation(),
+        },
+        instruction::InitializeRetainer {
+            period_amount,
+            periods,
+            start,
+            interval,
+            notice_period,
+        },
+    )
+}
+
+///anyone can unlock the periods that are due, `caller` signs
+pub fn unlock_retainer_periods(project: &ProjectAddresses, caller: &Pubkey) -> Instruction {
+    build(
+        accounts::UnlockRetainerPeriodsContext {
+            project_info_account: project.project_info_account,
+            retainer: project.retainer(),
+            caller: *caller,
+        },
+        instruction::UnlockRetainerPeriods {},
+    )
+}
+
+///`party` is the client or the freelancer
+pub fn give_retainer_notice(project: &ProjectAddresses, party: &Pubkey) -> Instruction {
+    build(
+        accounts::GiveRetainerNoticeContext {
+            project_info_account: project.project_info_account,
+            retainer: project.retainer(),
+            party: *party,
+        },
+        instruction::GiveRetainerNotice {},
+    )
+}
+
+///anyone can settle, the refund always goes to the client
+pub fn settle_retainer(project: &ProjectAddresses, caller: &Pubkey) -> Instruction {
+    build(
+        accounts::SettleRetainerContext {
+            project_info_account: project.project_info_account,
+            retainer: project.retainer(),
+            token_escrow: project.token_escrow,
+            client: project.client,
+            caller: *caller,
+            system_program: system_program::ID,
+            bond: project.bond(),
+            client_reputation: project.client_reputation(),
+            freelancer_reputation: project.freelancer_reputation(),
+        },
+        instruction::SettleRetainer {},
+    )
+}
+
+///open `child` as a subcontract of `parent`, `child.client` is the parent's freelancer.
+///the child's funds are drawn from the parent's unreached milestones. executed through
+///the parent multisig, the parent's client signs the executing transaction
+pub fn create_subcontract(
+    parent: &ProjectAddresses,
+    child: &ProjectAddresses,
+    observer: &Pubkey,
+    total_project_funds: u64,
+    milestones: u8,
+) -> Instruction {
+    build(
+        accounts::CreateSubcontractContext {
+            parent: parent.project_info_account,
+            parent_escrow: parent.token_escrow,
+            parent_payee_splits: parent.payee_splits(),
+            parent_multisig: parent.multisig,
+            parent_client: parent.client,
+            project_info_account: child.project_info_account,
+            multisig: child.multisig,
+            subcontract: child.subcontract(),
+            token_escrow: child.token_escrow,
+            freelancer: child.freelancer,
+            observer: *observer,
+            client: child.client,
+            system_program: system_program::ID,
+            client_reputation: child.client_reputation(),
+            freelancer_reputation: child.freelancer_reputation(),
+        },
+        instruction::CreateSubcontract {
+            total_project_funds,
+            milestones,
+        },
+    )
+}
+
+///anyone can freeze a child whose parent is paused or cancelled, `caller` signs
+pub fn freeze_subcontract(
+    child: &ProjectAddresses,
+    parent: &Pubkey,
+    caller: &Pubkey,
+) -> Instruction {
+    build(
+        accounts::FreezeSubcontractContext {
+            project_info_account: child.project_info_account,
+            subcontract: child.subcontract(),
+            parent: *parent,
+            caller: *caller,
+        },
+        instruction::FreezeSubcontract {},
+    )
+}
+
+///anyone can thaw a frozen child once its parent is resolved, `caller` signs
+pub fn thaw_subcontract(child: &ProjectAddresses, parent: &Pubkey, caller: &Pubkey) -> Instruction {
+    build(
+        accounts::ThawSubcontractContext {
+            project_info_account: child.project_info_account,
+            subcontract: child.subcontract(),
+            parent: *parent,
+            caller: *caller,
+        },
+        instruction::ThawSubcontract {},
+    )
+}
+
+///cancel_project for a child, signed by its client
+pub fn cancel_subcontract(child: &ProjectAddresses, parent: &ProjectAddresses) -> Instruction {
+    build(
+        accounts::CancelSubcontractContext {
+            project_info_account: child.project_info_account,
+            token_escrow: child.token_escrow,
+            subcontract: child.subcontract(),
+            parent: parent.project_info_account,
+            parent_escrow: parent.token_escrow,
+            parent_client: parent.client,
+            client: child.client,
+            system_program: system_program::ID,
+            client_reputation: child.client_reputation(),
+            freelancer_reputation: child.freelancer_reputation(),
+            freelancer: child.freelancer,
+            payee_splits: child.payee_splits(),
+        },
+        instruction::CancelSubcontract {},
+    )
+}
+
+///ask the freelancer for a performance bond, `deadline` is a unix timestamp or 0 for none
+pub fn require_bond(project: &ProjectAddresses, amount: u64, deadline: i64) -> Instruction {
+    build(
+        accounts::RequireBondContext {
+            project_info_account: project.project_info_account,
+            bond: project.bond(),
+            client: project.client,
+            system_program: system_program::ID,
+        },
+        instruction::RequireBond { amount, deadline },
+    )
+}
+
+pub fn post_bond(project: &ProjectAddresses) -> Instruction {
+    build(
+        accounts::PostBondContext {
+            project_info_account: project.project_info_account,
+            bond: project.bond(),
+            freelancer: project.freelancer,
+            system_program: system_program::ID,
+        },
+        instruction::PostBond {},
+    )
+}
+
+///anyone can hand the bond back once the project is completed or stopped, `caller` signs
+pub fn release_bond(project: &ProjectAddresses, caller: &Pubkey) -> Instruction {
+    build(
+        accounts::ReleaseBondContext {
+            project_info_account: project.project_info_account,
+            bond: project.bond(),
+            freelancer: project.freelancer,
+            client: project.client,
+            caller: *caller,
+        },
+        instruction::ReleaseBond {},
+    )
+}
+
+///anyone can forfeit the bond to the client once the deadline is missed, `caller` signs
+pub fn claim_bond(project: &ProjectAddresses, caller: &Pubkey) -> Instruction {
+    build(
+        accounts::ClaimBondContext {
+            project_info_account: project.project_info_account,
+            bond: project.bond(),
+            client: project.client,
+            caller: *caller,
+            freelancer_reputation: project.freelancer_reputation(),
+        },
+        instruction::ClaimBond {},
+    )
+}
+
+///the client signs, the ratings opened for a project that ran are seeded by its `nonce`
+pub fn cancel_project(project: &ProjectAddresses, nonce: u32) -> Instruction {
+    build(
+        accounts::StopProjectContext {
+            project_info_account: project.project_info_account,
+            token_escrow: project.token_escrow,
+            freelancer: project.freelancer,
+            client: project.client,
+            system_program: system_program::ID,
+            subcontract: project.subcontract(),
+            client_reputation: project.client_reputation(),
+            freelancer_reputation: project.freelancer_reputation(),
+            payee_splits: project.payee_splits(),
+            client_rating: project.client_rating(nonce),
+            freelancer_rating: project.freelancer_rating(nonce),
+        },
+        instruction::CancelProject {},
+    )
+}
+
+///the freelancer signs, starting the offered project
+pub fn accept_offer(project: &ProjectAddresses) -> Instruction {
+    build(
+        accounts::AcceptOfferContext {
+            project_info_account: project.project_info_account,
+            freelancer: project.freelancer,
+            bond: project.bond(),
+        },
+        instruction::AcceptOffer {},
+    )
+}
+
+///the freelancer signs, refunding the client and closing the project and its multisig
+pub fn decline_offer(project: &ProjectAddresses) -> Instruction {
+    build(
+        accounts::DeclineOfferContext {
+            project_info_account: project.project_info_account,
+            multisig: project.multisig,
+            token_escrow: project.token_escrow,
+            client: project.client,
+            freelancer: project.freelancer,
+            system_program: system_program::ID,
+            subcontract: project.subcontract(),
+        },
+        instruction::DeclineOffer {},
+    )
+}
+
+///the client signs and pays for the posting, `milestone_template` is in basis points
+pub fn create_job_posting(
+    client: &Pubkey,
+    job_id: u64,
+    budget_min: u64,
+    budget_max: u64,
+    milestone_template: Vec<u16>,
+    metadata_uri: String,
+) -> Instruction {
+    build(
+        accounts::CreateJobPostingContext {
+            job_posting: pda::job_posting(client, job_id).0,
+            client: *client,
+            system_program: system_program::ID,
+        },
+        instruction::CreateJobPosting {
+            job_id,
+            budget_min,
+            budget_max,
+            milestone_template,
+            metadata_uri,
+        },
+    )
+}
+
+///the freelancer signs and pays for the bid, `timeline` is in seconds
+pub fn submit_bid(
+    job_posting: &Pubkey,
+    freelancer: &Pubkey,
+    price: u64,
+    timeline: i64,
+) -> Instruction {
+    build(
+        accounts::SubmitBidContext {
+            job_posting: *job_posting,
+            bid: pda::bid(job_posting, freelancer).0,
+            freelancer: *freelancer,
+            system_program: system_program::ID,
+        },
+        instruction::SubmitBid { price, timeline },
+    )
+}
+
+///the client signs, funding the project offered to the bidder
+pub fn accept_bid(
+    job_posting: &Pubkey,
+    project: &ProjectAddresses,
+    observer: &Pubkey,
+) -> Instruction {
+    build(
+        accounts::AcceptBidContext {
+            job_posting: *job_posting,
+            bid: pda::bid(job_posting, &project.freelancer).0,
+            project_info_account: project.project_info_account,
+            multisig: project.multisig,
+            token_escrow: project.token_escrow,
+            freelancer: project.freelancer,
+            observer: *observer,
+            client: project.client,
+            system_program: system_program::ID,
+            client_reputation: project.client_reputation(),
+            freelancer_reputation: project.freelancer_reputation(),
+        },
+        instruction::AcceptBid {},
+    )
+}
+
+///the freelancer signs, getting the rent of the bid back
+pub fn close_bid(job_posting: &Pubkey, freelancer: &Pubkey) -> Instruction {
+    build(
+        accounts::CloseBidContext {
+            bid: pda::bid(job_posting, freelancer).0,
+            freelancer: *freelancer,
+        },
+        instruction::CloseBid {},
+    )
+}
+
+///`payer` signs and pays for the reputation account of `wallet`
+pub fn create_reputation(wallet: &Pubkey, payer: &Pubkey) -> Instruction {
+    build(
+        accounts::CreateReputationContext {
+            reputation: pda::reputation(wallet).0,
+            wallet: *wallet,
+            payer: *payer,
+            system_program: system_program::ID,
+        },
+        instruction::CreateReputation {},
+    )
+}
+
+///`rater` signs and pays for the rating of the other party on the project with `nonce`
+pub fn rate_party(
+    project: &ProjectAddresses,
+    nonce: u32,
+    rater: &Pubkey,
+    stars: u8,
+) -> Instruction {
+    let ratee = if *rater == project.client {
+        project.freelancer
+    } else {
+        project.client
+    };
+    build(
+        accounts::RatePartyContext {
+            project_info_account: project.project_info_account,
+            rating: pda::rating(&project.project_info_account, nonce, rater).0,
+            rater: *rater,
+            ratee,
+            ratee_reputation: pda::reputation(&ratee).0,
+            system_program: system_program::ID,
+        },
+        instruction::RateParty { stars, nonce },
+    )
+}
+
+///the client tips the freelancer, directly or from what the escrow holds beyond the milestones
+pub fn tip(project: &ProjectAddresses, amount: u64, from_escrow: bool) -> Instruction {
+    build(
+        accounts::TipContext {
+            project_info_account: project.project_info_account,
+            tips: project.tips(),
+            token_escrow: project.token_escrow,
+            freelancer: project.freelancer,
+            client: project.client,
+            system_program: system_program::ID,
+        },
+        instruction::Tip {
+            amount,
+            from_escrow,
+        },
+    )
+}
+
+pub fn withdraw_milestone_funds(project: &ProjectAddresses) -> Instruction {
+    build(
+        accounts::WithdrawMilestoneFundsContext {
+            project_info_account: project.project_info_account,
+            token_escrow: project.token_escrow,
+            freelancer: project.freelancer,
+            system_program: system_program::ID,
+            payee_splits: project.payee_splits(),
+            bond: project.bond(),
+            client_reputation: project.client_reputation(),
+            freelancer_reputation: project.freelancer_reputation(),
+        },
+        instruction::WithdrawMilestoneFunds {},
+    )
+}
+
+fn multisig_auth(project: &ProjectAddresses) -> accounts::MultisigAuth {
+    accounts::MultisigAuth {
+        project_info_account: project.project_info_account,
+        multisig_signer: project.multisig,
+    }
+}
+
+//the instructions below need the multisig PDA as signer,
+//they are only meant to be wrapped in a proposal (see `crate::multisig`)
+
+pub fn start_project(project: &ProjectAddresses) -> Instruction {
+    build(
+        accounts::StartProjectContext {
+            project_info_account: project.project_info_account,
+            multisig_signer: project.multisig,
+            bond: project.bond(),
+        },
+        instruction::StartProject {},
+    )
+}
+
+pub fn mark_current_milestone_completed(project: &ProjectAddresses) -> Instruction {
+    build(
+        multisig_auth(project),
+        instruction::MarkCurrentMilestoneCompleted {},
+    )
+}
+
+///`ruling` is who the multisig sides with if the parties are in dispute,
+///ruling for the client forfeits the freelancer's bond
+pub fn stop_project(project: &ProjectAddresses, ruling: Ruling) -> Instruction {
+    build(
+        accounts::MultisigRulingAuth {
+            project_info_account: project.project_info_account,
+            multisig_signer: project.multisig,
+            client_reputation: project.client_reputation(),
+            freelancer_reputation: project.freelancer_reputation(),
+            bond: project.bond(),
+            client: project.client,
+        },
+        instruction::StopProject { ruling },
+    )
+}
+
+///rule against the freelancer, the project is stopped and the bond goes to the client
+pub fn forfeit_bond(project: &ProjectAddresses) -> Instruction {
+    build(
+        accounts::ForfeitBondContext {
+            project_info_account: project.project_info_account,
+            multisig_signer: project.multisig,
+            bond: project.bond(),
+            client: project.client,
+            client_reputation: project.client_reputation(),
+            freelancer_reputation: project.freelancer_reputation(),
+        },
+        instruction::ForfeitBond {},
+    )
+}
+
+pub fn pause_project(project: &ProjectAddresses) -> Instruction {
+    build(multisig_auth(project), instruction::PauseProject {})
+}
+
+pub fn resume_project(project: &ProjectAddresses) -> Instruction {
+    build(
+        accounts::ResumeProjectContext {
+            project_info_account: project.project_info_account,
+            multisig_signer: project.multisig,
+            subcontract: project.subcontract(),
+        },
+        instruction::ResumeProject {},
+    )
+}
+
+pub fn change_order(
+    project: &ProjectAddresses,
+    additional_funds: u64,
+    milestone_amounts: Vec<u64>,
+) -> Instruction {
+    build(
+        accounts::ChangeOrderContext {
+            project_info_account: project.project_info_account,
+            multisig_signer: project.multisig,
+            token_escrow: project.token_escrow,
+            client: project.client,
+            system_program: system_program::ID,
+        },
+        instruction::ChangeOrder {
+            additional_funds,
+            milestone_amounts,
+        },
+    )
+}
+
+///stream `milestone_index` between `start` and `end` (unix timestamps),
+///the client pays for the stream account and has to sign the executing transaction
+pub fn set_milestone_stream(
+    project: &ProjectAddresses,
+    milestone_index: u8,
+    start: i64,
+    cliff: i64,
+    end: i64,
+) -> Instruction {
+    build(
+        accounts::SetMilestoneStreamContext {
+            project_info_account: project.project_info_account,
+            multisig_signer: project.multisig,
+            milestone_stream: project.milestone_stream(milestone_index),
+            client: project.client,
+            system_program: system_program::ID,
+        },
+        instruction::SetMilestoneStream {
+            milestone_index,
+            start,
+            cliff,
+            end,
+        },
+    )
+}
+
+pub fn withdraw_streamed_funds(project: &ProjectAddresses, milestone_index: u8) -> Instruction {
+    build(
+        accounts::WithdrawStreamedFundsContext {
+            project_info_account: project.project_info_account,
+            milestone_stream: project.milestone_stream(milestone_index),
+            token_escrow: project.token_escrow,
+            freelancer: project.freelancer,
+            system_program: system_program::ID,
+            payee_splits: project.payee_splits(),
+        },
+        instruction::WithdrawStreamedFunds {},
+    )
+}
+
+///share the milestone payouts between `payees`, one row of basis points per milestone,
+///the client pays for the splits account and has to sign the executing transaction
+pub fn set_payee_splits(
+    project: &ProjectAddresses,
+    payees: Vec<Pubkey>,
+    milestone_splits: Vec<Vec<u16>>,
+) -> Instruction {
+    build(
+        accounts::SetPayeeSplitsContext {
+            project_info_account: project.project_info_account,
+            multisig_signer: project.multisig,
+            payee_splits: project.payee_splits(),
+            client: project.client,
+            system_program: system_program::ID,
+        },
+        instruction::SetPayeeSplits {
+            payees,
+            milestone_splits,
+        },
+    )
+}
+
+pub fn withdraw_payee_share(project: &ProjectAddresses, payee: &Pubkey) -> Instruction {
+    build(
+        accounts::WithdrawPayeeShareContext {
+            project_info_account: project.project_info_account,
+            payee_splits: project.payee_splits(),
+            token_escrow: project.token_escrow,
+            payee: *payee,
+            system_program: system_program::ID,
+            bond: project.bond(),
+            client_reputation: project.client_reputation(),
+            freelancer_reputation: project.freelancer_reputation(),
+        },
+        instruction::WithdrawPayeeShare {},
+    )
+}
+
+///bill the project by the hour, the client pays for the terms account
+///and has to sign the executing transaction
+pub fn set_hourly_terms(
+    project: &ProjectAddresses,
+    hourly_rate: u64,
+    weekly_cap: u32,
+    approval_window: i64,
+    start: i64,
+) -> Instruction {
+    build(
+        accounts::SetHourlyTermsContext {
+            project_info_account: project.project_info_account,
+            multisig_signer: project.multisig,
+            hourly_terms: project.hourly_terms(),
+            client: project.client,
+            system_program: system_program::ID,
+        },
+        instruction::SetHourlyTerms {
+            hourly_rate,
+            weekly_cap,
+            approval_window,
+            start,
+        },
+    )
+}
+
+pub fn submit_timesheet(
+    project: &ProjectAddresses,
+    week: u32,
+    hours: u32,
+    memo_hash: [u8; 32],
+) -> Instruction {
+    build(
+        accounts::SubmitTimesheetContext {
+            project_info_account: project.project_info_account,
+            hourly_terms: project.hourly_terms(),
+            timesheet: project.timesheet(week),
+            freelancer: project.freelancer,
+            system_program: system_program::ID,
+        },
+        instruction::SubmitTimesheet {
+            week,
+            hours,
+            memo_hash,
+        },
+    )
+}
+
+fn answer_timesheet(
+    project: &ProjectAddresses,
+    week: u32,
+    data: impl InstructionData,
+) -> Instruction {
+    build(
+        accounts::AnswerTimesheetContext {
+            project_info_account: project.project_info_account,
+            hourly_terms: project.hourly_terms(),
+            timesheet: project.timesheet(week),
+            client: project.client,
+        },
+        data,
+    )
+}
+
+pub fn approve_timesheet(project: &ProjectAddresses, week: u32) -> Instruction {
+    answer_timesheet(project, week, instruction::ApproveTimesheet {})
+}
+
+pub fn dispute_timesheet(project: &ProjectAddresses, week: u32) -> Instruction {
+    answer_timesheet(project, week, instruction::DisputeTimesheet {})
+}
+
+pub fn withdraw_timesheet_funds(project: &ProjectAddresses, week: u32) -> Instruction {
+    build(
+        accounts::WithdrawTimesheetFundsContext {
+            project_info_account: project.project_info_account,
+            hourly_terms: project.hourly_terms(),
+            timesheet: project.timesheet(week),
+            token_escrow: project.token_escrow,
+            freelancer: project.freelancer,
+            system_program: system_program::ID,
+            payee_splits: project.payee_splits(),
+        },
+        instruction::WithdrawTimesheetFunds {},
+    )
+}
+
+///propose `proposal` at `seqno`, the proposer approves it implicitly
+pub fn create_transaction(
+    project: &ProjectAddresses,
+    proposer: &Pubkey,
+    seqno: u32,
+    proposal: &Instruction,
+) -> Instruction {
+    build(
+        accounts::CreateTransaction {
+            project_info_account: project.project_info_account,
+            multisig: project.multisig,
+            transaction: project.multisig_transaction(seqno),
+            proposer: *proposer,
+            system_program: system_program::ID,
+        },
+        instruction::CreateTransaction {
+            pid: proposal.program_id,
+            transaction_accounts: proposal.accounts.iter().map(Into::into).collect(),
+            data: proposal.data.clone(),
+        },
+    )
+}
+
+///append `data` to the instruction data of the proposal at `seqno`,
+///for payloads that don't fit in the `create_transaction` transaction
+pub fn append_transaction_data(
+    project: &ProjectAddresses,
+    proposer: &Pubkey,
+    seqno: u32,
+    data: Vec<u8>,
+) -> Instruction {
+    build(
+        accounts::AppendTransactionData {
+            project_info_account: project.project_info_account,
+            multisig: project.multisig,
+            transaction: project.multisig_transaction(seqno),
+            proposer: *proposer,
+            system_program: system_program::ID,
+        },
+        instruction::AppendTransactionData { data },
+    )
+}
+
+///approve the proposal stored at `transaction`, executing it once the threshold is met.
+///`program_id` and `transaction_accounts` are the ones the proposal was created with,
+///they're passed along as remaining accounts for the execution
+pub fn approve(
+    project: &ProjectAddresses,
+    owner: &Pubkey,
+    transaction: &Pubkey,
+    program_id: &Pubkey,
+    transaction_accounts: &[TransactionAccount],
+) -> Instruction {
+    let mut ix = build(
+        accounts::Approve {
+            project_info_account: project.project_info_account,
+            multisig: project.multisig,
+            multisig_signer: project.multisig,
+            transaction: *transaction,
+            owner: *owner,
+        },
+        instruction::Approve {},
+    );
+    //the multisig PDA signs through invoke_signed, not the transaction
+    ix.accounts
+        .extend(transaction_accounts.iter().map(|account| {
+            let mut meta = AccountMeta::from(account);
+            if meta.pubkey == project.multisig {
+                meta.is_signer = false;
+            }
+            meta
+        }));
+    ix.accounts
+        .push(AccountMeta::new_readonly(*program_id, false));
+    ix
+}
+
+///rewrite `account` (a project, multisig or proposal) in the current layout,
+///`payer` covers the rent of any space it grows by
+pub fn migrate_account(account: &Pubkey, payer: &Pubkey) -> Instruction {
+    build(
+        accounts::MigrateAccount {
+            account: *account,
+            payer: *payer,
+            system_program: system_program::ID,
+        },
+        instruction::MigrateAccount {},
+    )
+}
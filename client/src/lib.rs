@@ -0,0 +1,19 @@
+//! Rust client for the freelance escrow payment protocol.
+//!
+//! Derives the program addresses, builds every program instruction,
+//! decodes the program accounts and builds the payloads for the
+//! actions that go through the project multisig.
+
+pub mod accounts;
+pub mod decode;
+pub mod errors;
+pub mod events;
+pub mod instructions;
+pub mod multisig;
+pub mod pda;
+#[cfg(feature = "rpc")]
+pub mod rpc;
+
+pub use errors::ClientError;
+pub use group_6_payment_protocol::ID as PROGRAM_ID;
+pub use pda::ProjectAddresses;
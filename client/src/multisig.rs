@@ -0,0 +1,162 @@
+use anchor_lang::prelude::Pubkey;
+use anchor_lang::solana_program::instruction::Instruction;
+use group_6_payment_protocol::state::{Ruling, Transaction, TransactionAccount};
+
+use crate::instructions;
+use crate::pda::ProjectAddresses;
+
+///every action that has to be approved by the project multisig
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum MultisigAction {
+    StartProject,
+    MarkCurrentMilestoneCompleted,
+    ///`ruling` is who the multisig sides with if the parties are in dispute
+    StopProject {
+        ruling: Ruling,
+    },
+    PauseProject,
+    ResumeProject,
+    ///rule against the freelancer, the project is stopped and the bond goes to the client
+    ForfeitBond,
+    ///the client has to sign the transaction that executes it
+    ChangeOrder {
+        additional_funds: u64,
+        milestone_amounts: Vec<u64>,
+    },
+    ///the client has to sign the transaction that executes it
+    SetMilestoneStream {
+        milestone_index: u8,
+        start: i64,
+        cliff: i64,
+        end: i64,
+    },
+    ///the client has to sign the transaction that executes it
+    SetPayeeSplits {
+        payees: Vec<Pubkey>,
+        milestone_splits: Vec<Vec<u16>>,
+    },
+    ///the client has to sign the transaction that executes it
+    SetHourlyTerms {
+        hourly_rate: u64,
+        weekly_cap: u32,
+        approval_window: i64,
+        start: i64,
+    },
+    ///open a child project for `freelancer`, its client is the project's freelancer.
+    ///the client has to sign the transaction that executes it
+    CreateSubcontract {
+        freelancer: Pubkey,
+        observer: Pubkey,
+        total_project_funds: u64,
+        milestones: u8,
+    },
+}
+
+impl MultisigAction {
+    ///the program instruction executed once the proposal is approved
+    pub fn instruction(&self, project: &ProjectAddresses) -> Instruction {
+        match self {
+            MultisigAction::StartProject => instructions::start_project(project),
+            MultisigAction::MarkCurrentMilestoneCompleted => {
+                instructions::mark_current_milestone_completed(project)
+            }
+            MultisigAction::StopProject { ruling } => instructions::stop_project(project, *ruling),
+            MultisigAction::PauseProject => instructions::pause_project(project),
+            MultisigAction::ResumeProject => instructions::resume_project(project),
+            MultisigAction::ForfeitBond => instructions::forfeit_bond(project),
+            MultisigAction::ChangeOrder {
+                additional_funds,
+                milestone_amounts,
+            } => instructions::change_order(project, *additional_funds, milestone_amounts.clone()),
+            MultisigAction::SetMilestoneStream {
+                milestone_index,
+                start,
+                cliff,
+                end,
+            } => {
+                instructions::set_milestone_stream(project, *milestone_index, *start, *cliff, *end)
+            }
+            MultisigAction::SetPayeeSplits {
+                payees,
+                milestone_splits,
+            } => instructions::set_payee_splits(project, payees.clone(), milestone_splits.clone()),
+            MultisigAction::SetHourlyTerms {
+                hourly_rate,
+                weekly_cap,
+                approval_window,
+                start,
+            } => instructions::set_hourly_terms(
+                project,
+                *hourly_rate,
+                *weekly_cap,
+                *approval_window,
+                *start,
+            ),
+            MultisigAction::CreateSubcontract {
+                freelancer,
+                observer,
+                total_project_funds,
+                milestones,
+            } => instructions::create_subcontract(
+                project,
+                &ProjectAddresses::new(project.freelancer, *freelancer),
+                observer,
+                *total_project_funds,
+                *milestones,
+            ),
+        }
+    }
+
+    ///`(program_id, accounts, data)` as stored in the `Transaction` account
+    pub fn payload(
+        &self,
+        project: &ProjectAddresses,
+    ) -> (Pubkey, Vec<TransactionAccount>, Vec<u8>) {
+        let ix = self.instruction(project);
+        let accounts = ix.accounts.iter().map(Into::into).collect();
+        (ix.program_id, accounts, ix.data)
+    }
+}
+
+///`create_transaction` instruction proposing `action` at `seqno`
+pub fn propose(
+    project: &ProjectAddresses,
+    proposer: &Pubkey,
+    seqno: u32,
+    action: &MultisigAction,
+) -> Instruction {
+    instructions::create_transaction(project, proposer, seqno, &action.instruction(project))
+}
+
+///`approve` instruction for a proposal of `action` at `seqno`
+pub fn approve_action(
+    project: &ProjectAddresses,
+    owner: &Pubkey,
+    seqno: u32,
+    action: &MultisigAction,
+) -> Instruction {
+    let (program_id, accounts, _) = action.payload(project);
+    instructions::approve(
+        project,
+        owner,
+        &project.multisig_transaction(seqno),
+        &program_id,
+        &accounts,
+    )
+}
+
+///`approve` instruction for a proposal fetched from chain
+pub fn approve_transaction(
+    project: &ProjectAddresses,
+    owner: &Pubkey,
+    address: &Pubkey,
+    transaction: &Transaction,
+) -> Instruction {
+    instructions::approve(
+        project,
+        owner,
+        address,
+        &transaction.program_id,
+        &transaction.accounts,
+    )
+}
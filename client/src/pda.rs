@@ -0,0 +1,217 @@
+use anchor_lang::prelude::Pubkey;
+
+use crate::PROGRAM_ID;
+
+///`[b"project_info_account", client, freelancer]`
+pub fn project_info_account(client: &Pubkey, freelancer: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[
+            b"project_info_account",
+            client.as_ref(),
+            freelancer.as_ref(),
+        ],
+        &PROGRAM_ID,
+    )
+}
+
+///`[b"multisig", project_info_account]`, also the signer of executed proposals
+pub fn multisig(project_info_account: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[b"multisig", project_info_account.as_ref()], &PROGRAM_ID)
+}
+
+///`[b"token_escrow", project_info_account]`
+pub fn token_escrow(project_info_account: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[b"token_escrow", project_info_account.as_ref()],
+        &PROGRAM_ID,
+    )
+}
+
+///`[b"multisig_transaction", multisig, seqno (le bytes)]`
+pub fn multisig_transaction(multisig: &Pubkey, seqno: u32) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[
+            b"multisig_transaction",
+            multisig.as_ref(),
+            seqno.to_le_bytes().as_ref(),
+        ],
+        &PROGRAM_ID,
+    )
+}
+
+///`[b"milestone_stream", project_info_account, milestone_index]`
+pub fn milestone_stream(project_info_account: &Pubkey, milestone_index: u8) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[
+            b"milestone_stream",
+            project_info_account.as_ref(),
+            &[milestone_index],
+        ],
+        &PROGRAM_ID,
+    )
+}
+
+///`[b"retainer", project_info_account]`
+pub fn retainer(project_info_account: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[b"retainer", project_info_account.as_ref()], &PROGRAM_ID)
+}
+
+///`[b"hourly_terms", project_info_account]`
+pub fn hourly_terms(project_info_account: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[b"hourly_terms", project_info_account.as_ref()],
+        &PROGRAM_ID,
+    )
+}
+
+///`[b"timesheet", project_info_account, week]`
+pub fn timesheet(project_info_account: &Pubkey, week: u32) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[
+            b"timesheet",
+            project_info_account.as_ref(),
+            week.to_le_bytes().as_ref(),
+        ],
+        &PROGRAM_ID,
+    )
+}
+
+///`[b"payee_splits", project_info_account]`
+pub fn payee_splits(project_info_account: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[b"payee_splits", project_info_account.as_ref()],
+        &PROGRAM_ID,
+    )
+}
+
+///`[b"subcontract", project_info_account]` of the child project
+pub fn subcontract(project_info_account: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[b"subcontract", project_info_account.as_ref()],
+        &PROGRAM_ID,
+    )
+}
+
+///`[b"bond", project_info_account]`
+pub fn bond(project_info_account: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[b"bond", project_info_account.as_ref()], &PROGRAM_ID)
+}
+
+///`[b"job_posting", client, job_id (le bytes)]`
+pub fn job_posting(client: &Pubkey, job_id: u64) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[
+            b"job_posting",
+            client.as_ref(),
+            job_id.to_le_bytes().as_ref(),
+        ],
+        &PROGRAM_ID,
+    )
+}
+
+///`[b"bid", job_posting, freelancer]`
+pub fn bid(job_posting: &Pubkey, freelancer: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[b"bid", job_posting.as_ref(), freelancer.as_ref()],
+        &PROGRAM_ID,
+    )
+}
+
+///`[b"reputation", wallet]`
+pub fn reputation(wallet: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[b"reputation", wallet.as_ref()], &PROGRAM_ID)
+}
+
+///`[b"rating", project_info_account, nonce, rater]`, `nonce` is the project's
+pub fn rating(project_info_account: &Pubkey, nonce: u32, rater: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[
+            b"rating",
+            project_info_account.as_ref(),
+            &nonce.to_le_bytes(),
+            rater.as_ref(),
+        ],
+        &PROGRAM_ID,
+    )
+}
+
+///`[b"tips", project_info_account]`
+pub fn tips(project_info_account: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[b"tips", project_info_account.as_ref()], &PROGRAM_ID)
+}
+
+///every address owned by a single project
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ProjectAddresses {
+    pub client: Pubkey,
+    pub freelancer: Pubkey,
+    pub project_info_account: Pubkey,
+    pub multisig: Pubkey,
+    pub token_escrow: Pubkey,
+}
+
+impl ProjectAddresses {
+    pub fn new(client: Pubkey, freelancer: Pubkey) -> ProjectAddresses {
+        let (project_info_account, _) = project_info_account(&client, &freelancer);
+        ProjectAddresses {
+            client,
+            freelancer,
+            project_info_account,
+            multisig: multisig(&project_info_account).0,
+            token_escrow: token_escrow(&project_info_account).0,
+        }
+    }
+
+    pub fn multisig_transaction(&self, seqno: u32) -> Pubkey {
+        multisig_transaction(&self.multisig, seqno).0
+    }
+
+    pub fn milestone_stream(&self, milestone_index: u8) -> Pubkey {
+        milestone_stream(&self.project_info_account, milestone_index).0
+    }
+
+    pub fn retainer(&self) -> Pubkey {
+        retainer(&self.project_info_account).0
+    }
+
+    pub fn payee_splits(&self) -> Pubkey {
+        payee_splits(&self.project_info_account).0
+    }
+
+    pub fn subcontract(&self) -> Pubkey {
+        subcontract(&self.project_info_account).0
+    }
+
+    pub fn bond(&self) -> Pubkey {
+        bond(&self.project_info_account).0
+    }
+
+    pub fn client_reputation(&self) -> Pubkey {
+        reputation(&self.client).0
+    }
+
+    pub fn freelancer_reputation(&self) -> Pubkey {
+        reputation(&self.freelancer).0
+    }
+
+    ///ratings are seeded by the project nonce, see `ProjectInfo::nonce`
+    pub fn client_rating(&self, nonce: u32) -> Pubkey {
+        rating(&self.project_info_account, nonce, &self.client).0
+    }
+
+    pub fn freelancer_rating(&self, nonce: u32) -> Pubkey {
+        rating(&self.project_info_account, nonce, &self.freelancer).0
+    }
+
+    pub fn tips(&self) -> Pubkey {
+        tips(&self.project_info_account).0
+    }
+
+    pub fn hourly_terms(&self) -> Pubkey {
+        hourly_terms(&self.project_info_account).0
+    }
+
+    pub fn timesheet(&self, week: u32) -> Pubkey {
+        timesheet(&self.project_info_account, week).0
+    }
+}
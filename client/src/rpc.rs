@@ -0,0 +1,548 @@
+use std::str::FromStr;
+use std::thread::sleep;
+use std::time::{Duration, Instant};
+
+use anchor_lang::prelude::Pubkey;
+use anchor_lang::solana_program::hash::Hash;
+use anchor_lang::solana_program::instruction::Instruction;
+use anchor_lang::solana_program::message::Message;
+use ed25519_dalek::{Keypair, Signer};
+use serde_json::{json, Value};
+
+use crate::accounts::{AccountFetcher, RawAccount};
+use crate::ClientError;
+
+const CONFIRM_TIMEOUT: Duration = Duration::from_secs(60);
+
+///minimal JSON-RPC client, enough to read accounts and send transactions
+pub struct RpcClient {
+    url: String,
+    commitment: String,
+}
+
+impl RpcClient {
+    pub fn new(url: impl Into<String>) -> RpcClient {
+        RpcClient {
+            url: url.into(),
+            commitment: "confirmed".to_string(),
+        }
+    }
+
+    pub fn request(&self, method: &str, params: Value) -> Result<Value, ClientError> {
+        let response: Value = ureq::post(&self.url)
+            .send_json(json!({
+                "jsonrpc": "2.0",
+                "id": 1,
+                "method": method,
+                "params": params,
+            }))
+            .map_err(|err| ClientError::Rpc(err.to_string()))?
+            .into_json()
+            .map_err(|err| ClientError::Rpc(err.to_string()))?;
+
+        if let Some(error) = response.get("error") {
+            return Err(ClientError::Rpc(error.to_string()));
+        }
+        response
+            .get("result")
+            .cloned()
+            .ok_or_else(|| ClientError::Rpc(format!("{method}: missing result")))
+    }
+
+    pub fn get_latest_blockhash(&self) -> Result<Hash, ClientError> {
+        let result = self.request(
+            "getLatestBlockhash",
+            json!([{ "commitment": self.commitment }]),
+        )?;
+        result["value"]["blockhash"]
+            .as_str()
+            .and_then(|blockhash| Hash::from_str(blockhash).ok())
+            .ok_or_else(|| ClientError::Rpc("getLatestBlockhash: invalid blockhash".to_string()))
+    }
+
+    ///addresses of every account owned by `program_id`
+    pub fn get_program_accounts(
+        &self,
+        program_id: &Pubkey,
+    ) -> Result<Vec<(Pubkey, RawAccount)>, ClientError> {
+        let result = self.request(
+            "getProgramAccounts",
+            json!([program_id.to_string(), { "encoding": "base64", "commitment": self.commitment }]),
+        )?;
+        result
+            .as_array()
+            .ok_or_else(|| ClientError::Rpc("getProgramAccounts: expected an array".to_string()))?
+            .iter()
+            .map(|entry| {
+                let address = entry["pubkey"]
+                    .as_str()
+                    .and_then(|address| Pubkey::from_str(address).ok())
+                    .ok_or_else(|| {
+                        ClientError::Rpc("getProgramAccounts: invalid pubkey".to_string())
+                    })?;
+                Ok((address, parse_account(&entry["account"])?))
+            })
+            .collect()
+    }
+
+    ///signatures of the transactions that touched `address`, newest first,
+    ///paging backwards from `before`
+    pub fn get_signatures_for_address(
+        &self,
+        address: &Pubkey,
+        before: Option<&str>,
+    ) -> Result<Vec<String>, ClientError> {
+        let mut config = json!({ "commitment": self.commitment });
+        if let Some(before) = before {
+            config["before"] = json!(before);
+        }
+        let result = self.request(
+            "getSignaturesForAddress",
+            json!([address.to_string(), config]),
+        )?;
+        Ok(result
+            .as_array()
+            .map(|entries| {
+                entries
+                    .iter()
+                    .filter_map(|entry| entry["signature"].as_str().map(str::to_string))
+                    .collect()
+            })
+            .unwrap_or_default())
+    }
+
+    ///log messages of a confirmed transaction, `None` if it's unknown to the node
+    pub fn get_transaction_logs(
+        &self,
+        signature: &str,
+    ) -> Result<Option<TransactionLogs>, ClientError> {
+        let result = self.request(
+            "getTransaction",
+            json!([signature, {
+                "encoding": "json",
+                "commitment": self.commitment,
+                "maxSupportedTransactionVersion": 0
+            }]),
+        )?;
+        if result.is_null() {
+            return Ok(None);
+        }
+        Ok(Some(TransactionLogs::from_json(signature, &result)))
+    }
+
+    ///sign `instructions` with `signers` (the first one pays the fees), send and confirm
+    pub fn send_and_confirm(
+        &self,
+        instructions: &[Instruction],
+        signers: &[&Keypair],
+    ) -> Result<String, ClientError> {
+        let payer = signers
+            .first()
+            .map(|payer| keypair_pubkey(payer))
+            .ok_or_else(|| ClientError::Rpc("no fee payer".to_string()))?;
+        let blockhash = self.get_latest_blockhash()?;
+        let message = Message::new_with_blockhash(instructions, Some(&payer), &blockhash);
+        let transaction = sign_message(&message, signers)?;
+
+        let signature = self.request(
+            "sendTransaction",
+            json!([
+                base64::encode(transaction),
+                { "encoding": "base64", "preflightCommitment": self.commitment }
+            ]),
+        )?;
+        let signature = signature
+            .as_str()
+            .ok_or_else(|| ClientError::Rpc("sendTransaction: invalid signature".to_string()))?
+            .to_string();
+        self.confirm(&signature)?;
+        Ok(signature)
+    }
+
+    fn confirm(&self, signature: &str) -> Result<(), ClientError> {
+        let started = Instant::now();
+        while started.elapsed() < CONFIRM_TIMEOUT {
+            let result = self.request("getSignatureStatuses", json!([[signature]]))?;
+            let status = &result["value"][0];
+            if !status.is_null() {
+                if !status["err"].is_null() {
+                    return Err(ClientError::Rpc(format!(
+                        "transaction {signature} failed: {}",
+                        status["err"]
+                    )));
+                }
+                if matches!(
+                    status["confirmationStatus"].as_str(),
+                    Some("confirmed") | Some("finalized")
+                ) {
+                    return Ok(());
+                }
+            }
+            sleep(Duration::from_millis(500));
+        }
+        Err(ClientError::Rpc(format!(
+            "transaction {signature} was not confirmed in time"
+        )))
+    }
+}
+
+pub struct TransactionLogs {
+    pub signature: String,
+    pub slot: u64,
+    pub block_time: Option<i64>,
+    pub failed: bool,
+    pub logs: Vec<String>,
+}
+
+impl TransactionLogs {
+    ///read a `getTransaction` result with the `json` encoding
+    pub fn from_json(signature: &str, result: &Value) -> TransactionLogs {
+        TransactionLogs {
+            signature: signature.to_string(),
+            slot: result["slot"].as_u64().unwrap_or_default(),
+            block_time: result["blockTime"].as_i64(),
+            failed: !result["meta"]["err"].is_null(),
+            logs: result["meta"]["logMessages"]
+                .as_array()
+                .map(|logs| {
+                    logs.iter()
+                        .filter_map(|log| log.as_str().map(str::to_string))
+                        .collect()
+                })
+                .unwrap_or_default(),
+        }
+    }
+}
+
+impl AccountFetcher for RpcClient {
+    fn fetch_account(&self, address: &Pubkey) -> Result<Option<RawAccount>, ClientError> {
+        let result = self.request(
+            "getAccountInfo",
+            json!([address.to_string(), { "encoding": "base64", "commitment": self.commitment }]),
+        )?;
+        match &result["value"] {
+            Value::Null => Ok(None),
+            account => parse_account(account).map(Some),
+        }
+    }
+}
+
+fn parse_account(account: &Value) -> Result<RawAccount, ClientError> {
+    let lamports = account["lamports"]
+        .as_u64()
+        .ok_or_else(|| ClientError::Rpc("account: invalid lamports".to_string()))?;
+    let data = account["data"][0]
+        .as_str()
+        .and_then(|data| base64::decode(data).ok())
+        .ok_or_else(|| ClientError::Rpc("account: invalid data".to_string()))?;
+    Ok(RawAccount { lamports, data })
+}
+
+pub fn keypair_pubkey(keypair: &Keypair) -> Pubkey {
+    Pubkey::new_from_array(keypair.public.to_bytes())
+}
+
+///read a keypair written by `solana-keygen` (a JSON array of 64 bytes)
+pub fn read_keypair_file(path: &str) -> Result<Keypair, ClientError> {
+    let contents = std::fs::read_to_string(path)
+        .map_err(|err| ClientError::Keypair(format!("{path}: {err}")))?;
+    let bytes: Vec<u8> = serde_json::from_str(&contents)
+        .map_err(|err| ClientError::Keypair(format!("{path}: {err}")))?;
+    Keypair::from_bytes(&bytes).map_err(|err| ClientError::Keypair(format!("{path}: {err}")))
+}
+
+///wire format of a signed legacy transaction
+fn sign_message(message: &Message, signers: &[&Keypair]) -> Result<Vec<u8>, ClientError> {
+    let message_data = message.serialize();
+    let required = message.header.num_required_signatures as usize;
+
+    let mut transaction = Vec::new();
+    encode_length(&mut transaction, required);
+    for key in &message.account_keys[..required] {
+        let signer = signers
+            .iter()
+            .find(|signer| keypair_pubkey(signer) == *key)
+            .ok_or_else(|| ClientError::Keypair(format!("missing signer {key}")))?;
+        transaction.extend_from_slice(&signer.sign(&message_data).to_bytes());
+    }
+    transaction.extend_from_slice(&message_data);
+    Ok(transaction)
+}
+
+///compact-u16 length prefix
+fn encode_length(buf: &mut Vec<u8>, mut len: usize) {
+    loop {
+        let mut byte = (len & 0x7f) as u8;
+        len >>= 7;
+        if len == 0 {
+            buf.push(byte);
+            return;
+        }
+        byte |= 0x80;
+        buf.push(byte);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::{BufRead, BufReader, Read, Write};
+    use std::net::TcpListener;
+    use std::sync::mpsc::{channel, Receiver};
+
+    use anchor_lang::solana_program::short_vec::decode_shortu16_len;
+    use anchor_lang::solana_program::system_instruction;
+    use ed25519_dalek::{PublicKey, Signature, Verifier};
+
+    use super::*;
+
+    //RFC 8032, section 7.1, test 1
+    const SECRET: &str = "9d61b19deffd5a60ba844af492ec2cc44449c5697b326919703bac031cae7f60";
+    const PUBLIC: &str = "d75a980182b10ab7d54bfed3c964073a0ee172f3daa62325af021a68f707511a";
+    const EMPTY_SIGNATURE: &str = "e5564300c360ac729086e2cc806e828a84877f1eb8e5d974d873e065\
+        224901555fb8821590a33bacc61e39701cf9b46bd25bf5f0595bbe24655141438e7a100b";
+
+    fn hex(value: &str) -> Vec<u8> {
+        (0..value.len())
+            .step_by(2)
+            .map(|i| u8::from_str_radix(&value[i..i + 2], 16).unwrap())
+            .collect()
+    }
+
+    fn keypair() -> Keypair {
+        Keypair::from_bytes(&[hex(SECRET), hex(PUBLIC)].concat()).unwrap()
+    }
+
+    ///serves `responses` in order, one connection each, and hands back the request bodies
+    fn serve(responses: Vec<Value>) -> (RpcClient, Receiver<Value>) {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+        let (requests, received) = channel();
+        std::thread::spawn(move || {
+            for response in responses {
+                let (stream, _) = listener.accept().unwrap();
+                let mut reader = BufReader::new(stream);
+                let mut length = 0;
+                loop {
+                    let mut line = String::new();
+                    reader.read_line(&mut line).unwrap();
+                    if line == "\r\n" {
+                        break;
+                    }
+                    if let Some(value) = line.to_ascii_lowercase().strip_prefix("content-length:") {
+                        length = value.trim().parse().unwrap();
+                    }
+                }
+                let mut body = vec![0; length];
+                reader.read_exact(&mut body).unwrap();
+                requests
+                    .send(serde_json::from_slice(&body).unwrap())
+                    .unwrap();
+
+                let body = json!({ "jsonrpc": "2.0", "id": 1 })
+                    .as_object()
+                    .unwrap()
+                    .clone()
+                    .into_iter()
+                    .chain(response.as_object().unwrap().clone())
+                    .collect::<serde_json::Map<_, _>>();
+                let body = Value::Object(body).to_string();
+                write!(
+                    reader.get_mut(),
+                    "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\n\
+                     Content-Length: {}\r\nConnection: close\r\n\r\n{body}",
+                    body.len()
+                )
+                .unwrap();
+            }
+        });
+        (RpcClient::new(url), received)
+    }
+
+    ///a transfer of 42 lamports from the RFC key, serialized by hand from the wire format
+    fn transfer_message(blockhash: &Hash, to: &Pubkey) -> Vec<u8> {
+        let payer = keypair_pubkey(&keypair());
+        let mut message = vec![1, 0, 1, 3];
+        message.extend_from_slice(payer.as_ref());
+        message.extend_from_slice(to.as_ref());
+        message.extend_from_slice(&[0; 32]);
+        message.extend_from_slice(blockhash.as_ref());
+        message.extend_from_slice(&[1, 2, 2, 0, 1, 12, 2, 0, 0, 0]);
+        message.extend_from_slice(&42u64.to_le_bytes());
+        message
+    }
+
+    #[test]
+    fn lengths_are_encoded_as_compact_u16() {
+        for (len, expected) in [
+            (0, vec![0x00]),
+            (0x7f, vec![0x7f]),
+            (0x80, vec![0x80, 0x01]),
+            (0xff, vec![0xff, 0x01]),
+            (0x100, vec![0x80, 0x02]),
+            (0x3fff, vec![0xff, 0x7f]),
+            (0x4000, vec![0x80, 0x80, 0x01]),
+            (0xffff, vec![0xff, 0xff, 0x03]),
+        ] {
+            let mut buf = Vec::new();
+            encode_length(&mut buf, len);
+            assert_eq!(buf, expected, "{len:#x}");
+        }
+        for len in 0..=u16::MAX as usize {
+            let mut buf = Vec::new();
+            encode_length(&mut buf, len);
+            assert_eq!(decode_shortu16_len(&buf), Ok((len, buf.len())));
+        }
+    }
+
+    #[test]
+    fn keypair_files_hold_the_secret_and_public_key() {
+        let path = std::env::temp_dir().join(format!("rpc-test-keypair-{}", std::process::id()));
+        let bytes = [hex(SECRET), hex(PUBLIC)].concat();
+        std::fs::write(&path, serde_json::to_string(&bytes).unwrap()).unwrap();
+        let keypair = read_keypair_file(path.to_str().unwrap()).unwrap();
+        std::fs::remove_file(&path).unwrap();
+
+        assert_eq!(keypair_pubkey(&keypair).to_bytes().to_vec(), hex(PUBLIC));
+        assert_eq!(keypair.sign(&[]).to_bytes().to_vec(), hex(EMPTY_SIGNATURE));
+        assert!(matches!(
+            read_keypair_file("/nonexistent/keypair.json"),
+            Err(ClientError::Keypair(_))
+        ));
+    }
+
+    #[test]
+    fn signed_transactions_match_the_wire_format() {
+        let keypair = keypair();
+        let payer = keypair_pubkey(&keypair);
+        let to = Pubkey::new_from_array([2; 32]);
+        let blockhash = Hash::new_from_array([7; 32]);
+        let ix = system_instruction::transfer(&payer, &to, 42);
+        let message = Message::new_with_blockhash(&[ix], Some(&payer), &blockhash);
+
+        let transaction = sign_message(&message, &[&keypair]).unwrap();
+        let expected = transfer_message(&blockhash, &to);
+        assert_eq!(transaction[0], 1);
+        assert_eq!(&transaction[65..], &expected[..]);
+        let signature = Signature::from_bytes(&transaction[1..65]).unwrap();
+        PublicKey::from_bytes(&hex(PUBLIC))
+            .unwrap()
+            .verify(&expected, &signature)
+            .unwrap();
+
+        //a second signer whose key wasn't passed
+        let ix = system_instruction::transfer(&to, &payer, 1);
+        let message = Message::new_with_blockhash(&[ix], Some(&payer), &blockhash);
+        assert!(matches!(
+            sign_message(&message, &[&keypair]),
+            Err(ClientError::Keypair(_))
+        ));
+    }
+
+    #[test]
+    fn send_and_confirm_signs_with_the_latest_blockhash() {
+        let blockhash = Hash::new_from_array([7; 32]);
+        let (rpc, requests) = serve(vec![
+            json!({ "result": { "context": { "slot": 1 }, "value": {
+                "blockhash": blockhash.to_string(), "lastValidBlockHeight": 100 } } }),
+            json!({ "result": "5ig" }),
+            json!({ "result": { "context": { "slot": 2 }, "value": [null] } }),
+            json!({ "result": { "context": { "slot": 3 }, "value": [{
+                "slot": 3, "confirmations": 0, "err": null, "confirmationStatus": "confirmed" }] } }),
+        ]);
+        let keypair = keypair();
+        let to = Pubkey::new_from_array([2; 32]);
+        let ix = system_instruction::transfer(&keypair_pubkey(&keypair), &to, 42);
+
+        assert_eq!(rpc.send_and_confirm(&[ix], &[&keypair]).unwrap(), "5ig");
+
+        let methods: Vec<Value> = requests.try_iter().collect();
+        assert_eq!(
+            methods
+                .iter()
+                .map(|request| request["method"].as_str().unwrap())
+                .collect::<Vec<_>>(),
+            [
+                "getLatestBlockhash",
+                "sendTransaction",
+                "getSignatureStatuses",
+                "getSignatureStatuses"
+            ]
+        );
+        assert_eq!(methods[0]["jsonrpc"], "2.0");
+        let sent = base64::decode(methods[1]["params"][0].as_str().unwrap()).unwrap();
+        assert_eq!(&sent[65..], &transfer_message(&blockhash, &to)[..]);
+        assert_eq!(methods[1]["params"][1]["encoding"], "base64");
+        assert_eq!(methods[2]["params"], json!([["5ig"]]));
+    }
+
+    #[test]
+    fn failed_transactions_and_rpc_errors_are_reported() {
+        let (rpc, _requests) = serve(vec![
+            json!({ "error": { "code": -32602, "message": "invalid params" } }),
+            json!({ "result": { "context": { "slot": 1 }, "value": [{
+                "err": { "InstructionError": [0, { "Custom": 6000 }] } }] } }),
+        ]);
+        match rpc.request("getLatestBlockhash", json!([])) {
+            Err(ClientError::Rpc(message)) => assert!(message.contains("invalid params")),
+            other => panic!("unexpected {other:?}"),
+        }
+        match rpc.confirm("5ig") {
+            Err(ClientError::Rpc(message)) => assert!(message.contains("6000")),
+            other => panic!("unexpected {other:?}"),
+        }
+    }
+
+    #[test]
+    fn accounts_and_logs_are_parsed() {
+        let address = Pubkey::new_from_array([3; 32]);
+        let (rpc, requests) = serve(vec![
+            json!({ "result": { "context": { "slot": 1 }, "value": {
+                "lamports": 5, "data": [base64::encode([1, 2, 3]), "base64"],
+                "owner": address.to_string(), "executable": false, "rentEpoch": 0 } } }),
+            json!({ "result": { "context": { "slot": 1 }, "value": null } }),
+            json!({ "result": [
+                { "pubkey": address.to_string(), "account": {
+                    "lamports": 7, "data": ["", "base64"] } }
+            ] }),
+            json!({ "result": [{ "signature": "a" }, { "signature": "b" }] }),
+            json!({ "result": { "slot": 9, "blockTime": 11, "meta": {
+                "err": null, "logMessages": ["Program log: hi"] } } }),
+            json!({ "result": null }),
+        ]);
+
+        assert_eq!(
+            rpc.fetch_account(&address).unwrap(),
+            Some(RawAccount {
+                lamports: 5,
+                data: vec![1, 2, 3]
+            })
+        );
+        assert_eq!(rpc.fetch_account(&address).unwrap(), None);
+        assert_eq!(
+            rpc.get_program_accounts(&crate::PROGRAM_ID).unwrap(),
+            vec![(
+                address,
+                RawAccount {
+                    lamports: 7,
+                    data: Vec::new()
+                }
+            )]
+        );
+        assert_eq!(
+            rpc.get_signatures_for_address(&address, Some("c")).unwrap(),
+            ["a", "b"]
+        );
+        let logs = rpc.get_transaction_logs("a").unwrap().unwrap();
+        assert_eq!(
+            (logs.slot, logs.block_time, logs.failed),
+            (9, Some(11), false)
+        );
+        assert_eq!(logs.logs, ["Program log: hi"]);
+        assert!(rpc.get_transaction_logs("z").unwrap().is_none());
+
+        let requests: Vec<Value> = requests.try_iter().collect();
+        assert_eq!(requests[0]["params"][0], address.to_string());
+        assert_eq!(requests[0]["params"][1]["encoding"], "base64");
+        assert_eq!(requests[3]["params"][1]["before"], "c");
+    }
+}
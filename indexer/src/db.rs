@@ -0,0 +1,650 @@
+use anchor_lang::prelude::Pubkey;
+use group_6_payment_protocol::state::{Multisig, Transaction};
+use rusqlite::{params, Connection, OptionalExtension};
+
+use crate::layouts::IndexedProject;
+
+const SCHEMA: &str = "
+CREATE TABLE IF NOT EXISTS projects (
+    address TEXT PRIMARY KEY,
+    client TEXT NOT NULL,
+    freelancer TEXT NOT NULL,
+    multisig TEXT NOT NULL,
+    status TEXT NOT NULL,
+    total_project_funds INTEGER NOT NULL,
+    milestones INTEGER NOT NULL,
+    milestones_reached INTEGER NOT NULL,
+    milestone_funds_withdrawn INTEGER NOT NULL,
+    paused_duration INTEGER NOT NULL,
+    escrow_balance INTEGER,
+    layout TEXT NOT NULL
+);
+CREATE INDEX IF NOT EXISTS projects_client ON projects (client);
+CREATE INDEX IF NOT EXISTS projects_freelancer ON projects (freelancer);
+
+CREATE TABLE IF NOT EXISTS milestones (
+    project TEXT NOT NULL,
+    idx INTEGER NOT NULL,
+    amount INTEGER NOT NULL,
+    state TEXT NOT NULL,
+    PRIMARY KEY (project, idx)
+);
+
+CREATE TABLE IF NOT EXISTS multisig_owners (
+    multisig TEXT NOT NULL,
+    position INTEGER NOT NULL,
+    owner TEXT NOT NULL,
+    threshold INTEGER NOT NULL,
+    PRIMARY KEY (multisig, position)
+);
+CREATE INDEX IF NOT EXISTS multisig_owners_owner ON multisig_owners (owner);
+
+CREATE TABLE IF NOT EXISTS proposals (
+    address TEXT PRIMARY KEY,
+    multisig TEXT NOT NULL,
+    program_id TEXT NOT NULL,
+    instruction TEXT NOT NULL,
+    did_execute INTEGER NOT NULL
+);
+
+CREATE TABLE IF NOT EXISTS approvals (
+    proposal TEXT NOT NULL,
+    owner TEXT NOT NULL,
+    PRIMARY KEY (proposal, owner)
+);
+
+CREATE TABLE IF NOT EXISTS payouts (
+    signature TEXT NOT NULL,
+    position INTEGER NOT NULL,
+    project TEXT NOT NULL,
+    recipient TEXT NOT NULL,
+    kind TEXT NOT NULL,
+    amount INTEGER NOT NULL,
+    milestone_index INTEGER,
+    slot INTEGER NOT NULL,
+    timestamp INTEGER NOT NULL,
+    PRIMARY KEY (signature, position, kind)
+);
+
+CREATE TABLE IF NOT EXISTS events (
+    signature TEXT NOT NULL,
+    position INTEGER NOT NULL,
+    slot INTEGER NOT NULL,
+    name TEXT NOT NULL,
+    project TEXT NOT NULL,
+    actor TEXT NOT NULL,
+    timestamp INTEGER NOT NULL,
+    details TEXT NOT NULL,
+    PRIMARY KEY (signature, position)
+);
+CREATE INDEX IF NOT EXISTS events_project ON events (project);
+
+CREATE TABLE IF NOT EXISTS sync_state (
+    key TEXT PRIMARY KEY,
+    value TEXT NOT NULL
+);
+";
+
+pub struct Database {
+    conn: Connection,
+}
+
+pub struct ProjectRow {
+    pub address: String,
+    pub client: String,
+    pub freelancer: String,
+    pub status: String,
+    pub total_project_funds: u64,
+    pub milestones: u8,
+    pub milestones_reached: u8,
+    pub milestone_funds_withdrawn: u8,
+    pub escrow_balance: Option<u64>,
+}
+
+pub struct PendingApproval {
+    pub project: String,
+    pub proposal: String,
+    pub instruction: String,
+    pub approvals: u32,
+    pub threshold: u32,
+}
+
+pub struct PayoutRow {
+    pub signature: String,
+    pub project: String,
+    pub recipient: String,
+    pub kind: String,
+    pub amount: u64,
+    pub milestone_index: Option<u8>,
+    pub timestamp: i64,
+}
+
+pub struct EventRow {
+    pub signature: String,
+    pub name: String,
+    pub actor: String,
+    pub timestamp: i64,
+    pub details: String,
+}
+
+pub struct NewEvent<'a> {
+    pub signature: &'a str,
+    pub position: usize,
+    pub slot: u64,
+    pub name: &'static str,
+    pub project: &'a Pubkey,
+    pub actor: &'a Pubkey,
+    pub timestamp: i64,
+    pub details: String,
+}
+
+pub struct NewPayout<'a> {
+    pub signature: &'a str,
+    ///position of the event paying it out in the transaction
+    pub position: usize,
+    pub project: &'a Pubkey,
+    pub recipient: &'a Pubkey,
+    ///`milestone` for withdrawals, `refund` for cancellations
+    pub kind: &'static str,
+    pub amount: u64,
+    pub milestone_index: Option<u8>,
+    pub slot: u64,
+    pub timestamp: i64,
+}
+
+impl Database {
+    pub fn open(path: &str) -> rusqlite::Result<Database> {
+        let conn = Connection::open(path)?;
+        //payouts used to be keyed by transaction, project and kind, which dropped a
+        //second payout of the same kind, so drop them and replay every transaction
+        let (columns, keyed_by_event): (i64, i64) = conn.query_row(
+            "SELECT COUNT(*), COALESCE(SUM(name = 'position'), 0) FROM pragma_table_info('payouts')",
+            [],
+            |row| Ok((row.get(0)?, row.get(1)?)),
+        )?;
+        if columns > 0 && keyed_by_event == 0 {
+            conn.execute_batch("DROP TABLE payouts; DELETE FROM sync_state;")?;
+        }
+        conn.execute_batch(SCHEMA)?;
+        Ok(Database { conn })
+    }
+
+    pub fn begin(&self) -> rusqlite::Result<()> {
+        self.conn.execute_batch("BEGIN")
+    }
+
+    pub fn commit(&self) -> rusqlite::Result<()> {
+        self.conn.execute_batch("COMMIT")
+    }
+
+    pub fn upsert_project(
+        &self,
+        address: &Pubkey,
+        project: &IndexedProject,
+        escrow_balance: Option<u64>,
+    ) -> rusqlite::Result<()> {
+        let address = address.to_string();
+        self.conn.execute(
+            "INSERT OR REPLACE INTO projects VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12)",
+            params![
+                address,
+                project.client.to_string(),
+                project.freelancer.to_string(),
+                project.multisig.to_string(),
+                project.status,
+                project.total_project_funds as i64,
+                project.milestones,
+                project.milestones_reached,
+                project.milestone_funds_withdrawn,
+                project.paused_duration,
+                escrow_balance.map(|balance| balance as i64),
+                project.layout,
+            ],
+        )?;
+
+        self.conn
+            .execute("DELETE FROM milestones WHERE project = ?1", [&address])?;
+        for (idx, amount) in project.milestone_amounts.iter().enumerate() {
+            let state = if idx < project.milestone_funds_withdrawn as usize {
+                "withdrawn"
+            } else if idx < project.milestones_reached as usize {
+                "reached"
+            } else {
+                "pending"
+            };
+            self.conn.execute(
+                "INSERT INTO milestones VALUES (?1, ?2, ?3, ?4)",
+                params![address, idx, *amount as i64, state],
+            )?;
+        }
+        Ok(())
+    }
+
+    pub fn upsert_multisig(&self, address: &Pubkey, multisig: &Multisig) -> rusqlite::Result<()> {
+        let address = address.to_string();
+        self.conn.execute(
+            "DELETE FROM multisig_owners WHERE multisig = ?1",
+            [&address],
+        )?;
+        for (position, owner) in multisig.owners.iter().enumerate() {
+            self.conn.execute(
+                "INSERT INTO multisig_owners VALUES (?1, ?2, ?3, ?4)",
+                params![
+                    address,
+                    position,
+                    owner.to_string(),
+                    multisig.threshold as i64
+                ],
+            )?;
+        }
+        Ok(())
+    }
+
+    ///store a proposal and who approved it, `owners` being the owners of its multisig
+    pub fn upsert_proposal(
+        &self,
+        address: &Pubkey,
+        transaction: &Transaction,
+        instruction: &str,
+        owners: &[Pubkey],
+    ) -> rusqlite::Result<()> {
+        let address = address.to_string();
+        self.conn.execute(
+            "INSERT OR REPLACE INTO proposals VALUES (?1, ?2, ?3, ?4, ?5)",
+            params![
+                address,
+                transaction.multisig.to_string(),
+                transaction.program_id.to_string(),
+                instruction,
+                transaction.did_execute,
+            ],
+        )?;
+
+        //the PDA is reused for the next proposal, only its current signers count
+        self.conn
+            .execute("DELETE FROM approvals WHERE proposal = ?1", [&address])?;
+        for (owner, signed) in owners.iter().zip(&transaction.signers) {
+            if *signed {
+                self.conn.execute(
+                    "INSERT INTO approvals VALUES (?1, ?2)",
+                    params![address, owner.to_string()],
+                )?;
+            }
+        }
+        Ok(())
+    }
+
+    pub fn multisig_owners(&self, multisig: &Pubkey) -> rusqlite::Result<Vec<Pubkey>> {
+        let mut statement = self
+            .conn
+            .prepare("SELECT owner FROM multisig_owners WHERE multisig = ?1 ORDER BY position")?;
+        let owners = statement
+            .query_map([multisig.to_string()], |row| row.get::<_, String>(0))?
+            .collect::<rusqlite::Result<Vec<_>>>()?;
+        Ok(owners
+            .iter()
+            .filter_map(|owner| owner.parse().ok())
+            .collect())
+    }
+
+    pub fn insert_event(&self, event: &NewEvent) -> rusqlite::Result<()> {
+        self.conn.execute(
+            "INSERT OR IGNORE INTO events VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
+            params![
+                event.signature,
+                event.position,
+                event.slot as i64,
+                event.name,
+                event.project.to_string(),
+                event.actor.to_string(),
+                event.timestamp,
+                event.details,
+            ],
+        )?;
+        Ok(())
+    }
+
+    pub fn insert_payout(&self, payout: &NewPayout) -> rusqlite::Result<()> {
+        self.conn.execute(
+            "INSERT OR IGNORE INTO payouts VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)",
+            params![
+                payout.signature,
+                payout.position,
+                payout.project.to_string(),
+                payout.recipient.to_string(),
+                payout.kind,
+                payout.amount as i64,
+                payout.milestone_index,
+                payout.slot as i64,
+                payout.timestamp,
+            ],
+        )?;
+        Ok(())
+    }
+
+    pub fn sync_state(&self, key: &str) -> rusqlite::Result<Option<String>> {
+        self.conn
+            .query_row(
+                "SELECT value FROM sync_state WHERE key = ?1",
+                [key],
+                |row| row.get(0),
+            )
+            .optional()
+    }
+
+    pub fn set_sync_state(&self, key: &str, value: &str) -> rusqlite::Result<()> {
+        self.conn.execute(
+            "INSERT OR REPLACE INTO sync_state VALUES (?1, ?2)",
+            [key, value],
+        )?;
+        Ok(())
+    }
+
+    ///projects where `wallet` is the client or the freelancer
+    pub fn projects_for(
+        &self,
+        client: Option<&Pubkey>,
+        freelancer: Option<&Pubkey>,
+    ) -> rusqlite::Result<Vec<ProjectRow>> {
+        let mut statement = self.conn.prepare(
+            "SELECT address, client, freelancer, status, total_project_funds, milestones,
+                    milestones_reached, milestone_funds_withdrawn, escrow_balance
+             FROM projects
+             WHERE (?1 IS NULL OR client = ?1) AND (?2 IS NULL OR freelancer = ?2)
+             ORDER BY address",
+        )?;
+        let rows = statement.query_map(
+            params![
+                client.map(Pubkey::to_string),
+                freelancer.map(Pubkey::to_string)
+            ],
+            |row| {
+                Ok(ProjectRow {
+                    address: row.get(0)?,
+                    client: row.get(1)?,
+                    freelancer: row.get(2)?,
+                    status: row.get(3)?,
+                    total_project_funds: row.get::<_, i64>(4)? as u64,
+                    milestones: row.get(5)?,
+                    milestones_reached: row.get(6)?,
+                    milestone_funds_withdrawn: row.get(7)?,
+                    escrow_balance: row.get::<_, Option<i64>>(8)?.map(|balance| balance as u64),
+                })
+            },
+        )?;
+        rows.collect()
+    }
+
+    ///unexecuted proposals of multisigs `owner` belongs to that `owner` hasn't approved yet
+    pub fn pending_approvals(&self, owner: &Pubkey) -> rusqlite::Result<Vec<PendingApproval>> {
+        let mut statement = self.conn.prepare(
+            "SELECT projects.address, proposals.address, proposals.instruction,
+                    (SELECT COUNT(*) FROM approvals WHERE approvals.proposal = proposals.address),
+                    multisig_owners.threshold
+             FROM proposals
+             JOIN multisig_owners
+               ON multisig_owners.multisig = proposals.multisig AND multisig_owners.owner = ?1
+             JOIN projects ON projects.multisig = proposals.multisig
+             WHERE proposals.did_execute = 0
+               AND NOT EXISTS (SELECT 1 FROM approvals
+                               WHERE approvals.proposal = proposals.address AND approvals.owner = ?1)
+             ORDER BY projects.address",
+        )?;
+        let rows = statement.query_map([owner.to_string()], |row| {
+            Ok(PendingApproval {
+                project: row.get(0)?,
+                proposal: row.get(1)?,
+                instruction: row.get(2)?,
+                approvals: row.get(3)?,
+                threshold: row.get(4)?,
+            })
+        })?;
+        rows.collect()
+    }
+
+    ///payouts to `recipient` and/or of `project`, oldest first
+    pub fn payouts(
+        &self,
+        project: Option<&Pubkey>,
+        recipient: Option<&Pubkey>,
+    ) -> rusqlite::Result<Vec<PayoutRow>> {
+        let mut statement = self.conn.prepare(
+            "SELECT signature, project, recipient, kind, amount, milestone_index, timestamp
+             FROM payouts
+             WHERE (?1 IS NULL OR project = ?1) AND (?2 IS NULL OR recipient = ?2)
+             ORDER BY slot, timestamp, position, rowid",
+        )?;
+        let rows = statement.query_map(
+            params![
+                project.map(Pubkey::to_string),
+                recipient.map(Pubkey::to_string)
+            ],
+            |row| {
+                Ok(PayoutRow {
+                    signature: row.get(0)?,
+                    project: row.get(1)?,
+                    recipient: row.get(2)?,
+                    kind: row.get(3)?,
+                    amount: row.get::<_, i64>(4)? as u64,
+                    milestone_index: row.get(5)?,
+                    timestamp: row.get(6)?,
+                })
+            },
+        )?;
+        rows.collect()
+    }
+
+    ///events of `project`, oldest first
+    pub fn history(&self, project: &Pubkey) -> rusqlite::Result<Vec<EventRow>> {
+        let mut statement = self.conn.prepare(
+            "SELECT signature, name, actor, timestamp, details
+             FROM events WHERE project = ?1
+             ORDER BY slot, position",
+        )?;
+        let rows = statement.query_map([project.to_string()], |row| {
+            Ok(EventRow {
+                signature: row.get(0)?,
+                name: row.get(1)?,
+                actor: row.get(2)?,
+                timestamp: row.get(3)?,
+                details: row.get(4)?,
+            })
+        })?;
+        rows.collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use group_6_payment_protocol::state::AccountVersion;
+
+    fn project(client: Pubkey, freelancer: Pubkey, multisig: Pubkey) -> IndexedProject {
+        IndexedProject {
+            total_project_funds: 300,
+            milestones: 3,
+            milestones_reached: 2,
+            milestone_funds_withdrawn: 1,
+            client,
+            freelancer,
+            multisig,
+            status: "Running".to_string(),
+            milestone_amounts: vec![100, 100, 100],
+            paused_duration: 0,
+            layout: "v2",
+        }
+    }
+
+    fn payout<'a>(signature: &'a str, position: usize, project: &'a Pubkey) -> NewPayout<'a> {
+        NewPayout {
+            signature,
+            position,
+            project,
+            recipient: project,
+            kind: "share",
+            amount: 10 + position as u64,
+            milestone_index: Some(0),
+            slot: 1,
+            timestamp: 0,
+        }
+    }
+
+    #[test]
+    fn projects_are_found_by_party() {
+        let db = Database::open(":memory:").unwrap();
+        let (client, freelancer) = (Pubkey::new_unique(), Pubkey::new_unique());
+        let (first, second) = (Pubkey::new_unique(), Pubkey::new_unique());
+        db.upsert_project(
+            &first,
+            &project(client, freelancer, Pubkey::new_unique()),
+            Some(200),
+        )
+        .unwrap();
+        db.upsert_project(
+            &second,
+            &project(client, Pubkey::new_unique(), Pubkey::new_unique()),
+            None,
+        )
+        .unwrap();
+
+        assert_eq!(db.projects_for(Some(&client), None).unwrap().len(), 2);
+        let rows = db.projects_for(None, Some(&freelancer)).unwrap();
+        assert_eq!(rows.len(), 1);
+        assert_eq!(rows[0].address, first.to_string());
+        assert_eq!(rows[0].escrow_balance, Some(200));
+        assert_eq!(
+            (
+                rows[0].milestones_reached,
+                rows[0].milestone_funds_withdrawn
+            ),
+            (2, 1)
+        );
+        assert!(db.projects_for(Some(&freelancer), None).unwrap().is_empty());
+
+        let states = db
+            .conn
+            .prepare("SELECT state FROM milestones WHERE project = ?1 ORDER BY idx")
+            .unwrap()
+            .query_map([first.to_string()], |row| row.get::<_, String>(0))
+            .unwrap()
+            .collect::<rusqlite::Result<Vec<_>>>()
+            .unwrap();
+        assert_eq!(states, ["withdrawn", "reached", "pending"]);
+    }
+
+    #[test]
+    fn pending_approvals_are_those_an_owner_still_has_to_sign() {
+        let db = Database::open(":memory:").unwrap();
+        let owners = vec![
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+        ];
+        let (address, multisig, proposal) = (
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+        );
+        db.upsert_project(&address, &project(owners[0], owners[1], multisig), None)
+            .unwrap();
+        db.upsert_multisig(
+            &multisig,
+            &Multisig {
+                threshold: 2,
+                seqno: 0,
+                owners: owners.clone(),
+                version: AccountVersion::default(),
+            },
+        )
+        .unwrap();
+        assert_eq!(db.multisig_owners(&multisig).unwrap(), owners);
+
+        let mut transaction = Transaction {
+            proposer: owners[0],
+            multisig,
+            program_id: Pubkey::new_unique(),
+            did_execute: false,
+            seqno: 0,
+            accounts: vec![],
+            signers: vec![true, false, false],
+            data: vec![],
+            version: AccountVersion::default(),
+        };
+        db.upsert_proposal(&proposal, &transaction, "StartProject", &owners)
+            .unwrap();
+
+        assert!(db.pending_approvals(&owners[0]).unwrap().is_empty());
+        let pending = db.pending_approvals(&owners[1]).unwrap();
+        assert_eq!(pending.len(), 1);
+        assert_eq!(pending[0].project, address.to_string());
+        assert_eq!(pending[0].proposal, proposal.to_string());
+        assert_eq!(pending[0].instruction, "StartProject");
+        assert_eq!((pending[0].approvals, pending[0].threshold), (1, 2));
+        assert!(db
+            .pending_approvals(&Pubkey::new_unique())
+            .unwrap()
+            .is_empty());
+
+        transaction.did_execute = true;
+        db.upsert_proposal(&proposal, &transaction, "StartProject", &owners)
+            .unwrap();
+        assert!(db.pending_approvals(&owners[1]).unwrap().is_empty());
+    }
+
+    #[test]
+    fn payouts_of_one_kind_in_a_transaction_are_all_kept() {
+        let db = Database::open(":memory:").unwrap();
+        let project = Pubkey::new_unique();
+        db.insert_payout(&payout("tx", 0, &project)).unwrap();
+        db.insert_payout(&payout("tx", 1, &project)).unwrap();
+        //replaying the transaction doesn't count them twice
+        db.insert_payout(&payout("tx", 1, &project)).unwrap();
+
+        let payouts = db.payouts(Some(&project), None).unwrap();
+        let amounts: Vec<u64> = payouts.iter().map(|payout| payout.amount).collect();
+        assert_eq!(amounts, [10, 11]);
+        assert!(db
+            .payouts(None, Some(&Pubkey::new_unique()))
+            .unwrap()
+            .is_empty());
+    }
+
+    #[test]
+    fn payouts_keyed_by_kind_are_replayed() {
+        let path = std::env::temp_dir().join(format!("indexer-{}-payouts.db", std::process::id()));
+        let path = path.to_str().unwrap();
+        let _ = std::fs::remove_file(path);
+        {
+            let conn = Connection::open(path).unwrap();
+            conn.execute_batch(
+                "CREATE TABLE payouts (
+                     signature TEXT NOT NULL, project TEXT NOT NULL, recipient TEXT NOT NULL,
+                     kind TEXT NOT NULL, amount INTEGER NOT NULL, milestone_index INTEGER,
+                     slot INTEGER NOT NULL, timestamp INTEGER NOT NULL,
+                     PRIMARY KEY (signature, project, kind)
+                 );
+                 CREATE TABLE sync_state (key TEXT PRIMARY KEY, value TEXT NOT NULL);
+                 INSERT INTO sync_state VALUES ('last_signature', 'tx');",
+            )
+            .unwrap();
+        }
+
+        let db = Database::open(path).unwrap();
+        assert_eq!(db.sync_state("last_signature").unwrap(), None);
+        let project = Pubkey::new_unique();
+        db.insert_payout(&payout("tx", 0, &project)).unwrap();
+        db.insert_payout(&payout("tx", 1, &project)).unwrap();
+        assert_eq!(db.payouts(Some(&project), None).unwrap().len(), 2);
+
+        //reopening an up to date database keeps everything
+        db.set_sync_state("last_signature", "tx").unwrap();
+        drop(db);
+        let db = Database::open(path).unwrap();
+        assert_eq!(
+            db.sync_state("last_signature").unwrap().as_deref(),
+            Some("tx")
+        );
+        assert_eq!(db.payouts(Some(&project), None).unwrap().len(), 2);
+        std::fs::remove_file(path).unwrap();
+    }
+}
@@ -0,0 +1,85 @@
+use anchor_lang::prelude::*;
+use anchor_lang::{AccountDeserialize, Discriminator};
+use group_6_payment_protocol::state::{
+    AccountVersion, Multisig, ProjectInfo, ProjectInfoV0, Transaction,
+};
+use group_6_payment_protocol_client::accounts::RawAccount;
+
+///project fields the indexer stores, whatever layout the account uses
+pub struct IndexedProject {
+    pub total_project_funds: u64,
+    pub milestones: u8,
+    pub milestones_reached: u8,
+    pub milestone_funds_withdrawn: u8,
+    pub client: Pubkey,
+    pub freelancer: Pubkey,
+    pub multisig: Pubkey,
+    pub status: String,
+    pub milestone_amounts: Vec<u64>,
+    pub paused_duration: i64,
+    pub layout: &'static str,
+}
+
+impl IndexedProject {
+    fn new(info: ProjectInfo, layout: &'static str) -> IndexedProject {
+        IndexedProject {
+            total_project_funds: info.total_project_funds,
+            milestones: info.milestones,
+            milestones_reached: info.milestones_reached,
+            milestone_funds_withdrawn: info.milestone_funds_withdrawn,
+            client: info.client,
+            freelancer: info.freelancer,
+            multisig: info.multisig,
+            status: format!("{:?}", info.status),
+            milestone_amounts: info.milestone_amounts,
+            paused_duration: info.paused_duration,
+            layout,
+        }
+    }
+}
+
+///decode a project written with any layout, older ones wait for `migrate_account`
+fn decode_project(data: &[u8]) -> Option<IndexedProject> {
+    let info = ProjectInfo::decode_any_layout(data).ok()?;
+    //v0 split the funds equally, the last milestone taking the rest
+    let layout = if data.len() == 8 + ProjectInfoV0::SIZE {
+        "v0"
+    } else {
+        match info.version {
+            AccountVersion::Unversioned => "unversioned",
+            AccountVersion::V1 => "v1",
+            AccountVersion::V2 => "current",
+        }
+    };
+    Some(IndexedProject::new(info, layout))
+}
+
+pub enum ProgramAccount {
+    Project(IndexedProject),
+    Multisig(Multisig),
+    Transaction(Transaction),
+}
+
+///decode any account owned by the program, `None` for unknown data
+pub fn decode_account(account: &RawAccount) -> Option<ProgramAccount> {
+    let data = &account.data;
+    if data.len() < 8 {
+        return None;
+    }
+    let discriminator = &data[..8];
+
+    if discriminator == ProjectInfo::discriminator() {
+        return decode_project(data).map(ProgramAccount::Project);
+    }
+    if discriminator == Multisig::discriminator() {
+        return Multisig::try_deserialize(&mut &data[..])
+            .ok()
+            .map(ProgramAccount::Multisig);
+    }
+    if discriminator == Transaction::discriminator() {
+        return Transaction::try_deserialize(&mut &data[..])
+            .ok()
+            .map(ProgramAccount::Transaction);
+    }
+    None
+}
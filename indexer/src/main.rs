@@ -0,0 +1,159 @@
+use std::error::Error;
+use std::path::PathBuf;
+use std::process::exit;
+
+use anchor_lang::prelude::Pubkey;
+use clap::{Parser, Subcommand};
+use group_6_payment_protocol_client::rpc::RpcClient;
+
+mod db;
+mod layouts;
+mod sync;
+
+use db::Database;
+use sync::Stats;
+
+#[derive(Parser)]
+#[command(
+    name = "payment-protocol-indexer",
+    about = "Index freelance escrow projects into a SQLite database"
+)]
+struct Cli {
+    ///SQLite database, created if it doesn't exist
+    #[arg(long, global = true, default_value = "payment-protocol.db")]
+    db: String,
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    ///snapshot the program accounts and replay new transactions from an RPC node
+    Sync {
+        #[arg(long, short = 'u', default_value = "http://localhost:8899")]
+        url: String,
+    },
+    ///index a directory of `solana account --output json` dumps
+    LoadSnapshot {
+        #[arg(long)]
+        dir: PathBuf,
+    },
+    ///projects of a client and/or freelancer
+    Projects {
+        #[arg(long)]
+        client: Option<Pubkey>,
+        #[arg(long)]
+        freelancer: Option<Pubkey>,
+    },
+    ///proposals waiting for the approval of `owner`
+    PendingApprovals {
+        #[arg(long)]
+        owner: Pubkey,
+    },
+    ///milestone payouts and refunds of a project and/or recipient
+    Payouts {
+        #[arg(long)]
+        project: Option<Pubkey>,
+        #[arg(long)]
+        recipient: Option<Pubkey>,
+    },
+    ///every event emitted for a project
+    History {
+        #[arg(long)]
+        project: Pubkey,
+    },
+}
+
+fn main() {
+    let cli = Cli::parse();
+    if let Err(err) = run(cli) {
+        eprintln!("error: {err}");
+        exit(1);
+    }
+}
+
+fn run(cli: Cli) -> Result<(), Box<dyn Error>> {
+    let db = Database::open(&cli.db)?;
+    match cli.command {
+        Command::Sync { url } => {
+            let stats = sync::sync_rpc(&db, &RpcClient::new(url))?;
+            print_stats(&stats);
+        }
+        Command::LoadSnapshot { dir } => {
+            let stats = sync::load_snapshot(&db, &dir)?;
+            print_stats(&stats);
+        }
+        Command::Projects { client, freelancer } => {
+            for project in db.projects_for(client.as_ref(), freelancer.as_ref())? {
+                let escrow = project
+                    .escrow_balance
+                    .map(|balance| balance.to_string())
+                    .unwrap_or_else(|| "?".to_string());
+                println!(
+                    "{} {} client={} freelancer={} funds={} milestones={}/{} withdrawn={} escrow={}",
+                    project.address,
+                    project.status,
+                    project.client,
+                    project.freelancer,
+                    project.total_project_funds,
+                    project.milestones_reached,
+                    project.milestones,
+                    project.milestone_funds_withdrawn,
+                    escrow,
+                );
+            }
+        }
+        Command::PendingApprovals { owner } => {
+            for pending in db.pending_approvals(&owner)? {
+                println!(
+                    "{} proposal={} {} approvals={}/{}",
+                    pending.project,
+                    pending.proposal,
+                    pending.instruction,
+                    pending.approvals,
+                    pending.threshold,
+                );
+            }
+        }
+        Command::Payouts { project, recipient } => {
+            for payout in db.payouts(project.as_ref(), recipient.as_ref())? {
+                let milestone = payout
+                    .milestone_index
+                    .map(|index| format!(" milestone={index}"))
+                    .unwrap_or_default();
+                println!(
+                    "{} {} {} -> {} amount={}{} tx={}",
+                    payout.timestamp,
+                    payout.kind,
+                    payout.project,
+                    payout.recipient,
+                    payout.amount,
+                    milestone,
+                    payout.signature,
+                );
+            }
+        }
+        Command::History { project } => {
+            for event in db.history(&project)? {
+                println!(
+                    "{} {} actor={} {} tx={}",
+                    event.timestamp, event.name, event.actor, event.details, event.signature,
+                );
+            }
+        }
+    }
+    Ok(())
+}
+
+fn print_stats(stats: &Stats) {
+    println!(
+        "indexed {} projects, {} multisigs, {} proposals ({} unknown accounts skipped)",
+        stats.projects, stats.multisigs, stats.proposals, stats.skipped
+    );
+    if stats.transactions > 0 {
+        println!(
+            "replayed {} transactions, {} events",
+            stats.transactions, stats.events
+        );
+    }
+}
@@ -0,0 +1,964 @@
+use std::collections::HashMap;
+use std::error::Error;
+use std::fs;
+use std::path::Path;
+use std::str::FromStr;
+
+use anchor_lang::prelude::Pubkey;
+use anchor_lang::solana_program::hash::Hash;
+use group_6_payment_protocol_client::accounts::{fetch_escrow_balance, RawAccount};
+use group_6_payment_protocol_client::decode::{decode_transaction, DecodedProposal};
+use group_6_payment_protocol_client::events::{parse_logs, ProgramEvent};
+use group_6_payment_protocol_client::rpc::{RpcClient, TransactionLogs};
+use group_6_payment_protocol_client::{ProjectAddresses, PROGRAM_ID};
+use serde_json::Value;
+
+use crate::db::{Database, NewEvent, NewPayout};
+use crate::layouts::{decode_account, ProgramAccount};
+
+///newest transaction already replayed by `sync`
+const LAST_SIGNATURE: &str = "last_signature";
+
+#[derive(Default)]
+pub struct Stats {
+    pub projects: usize,
+    pub multisigs: usize,
+    pub proposals: usize,
+    pub skipped: usize,
+    pub transactions: usize,
+    pub events: usize,
+}
+
+///index program accounts, `escrow_balance` looks up the lamports of an escrow PDA
+pub fn index_accounts(
+    db: &Database,
+    accounts: &[(Pubkey, RawAccount)],
+    escrow_balance: impl Fn(&Pubkey) -> Option<u64>,
+    stats: &mut Stats,
+) -> rusqlite::Result<()> {
+    let mut projects = HashMap::new();
+    let mut multisigs = HashMap::new();
+    let mut transactions = Vec::new();
+    for (address, account) in accounts {
+        match decode_account(account) {
+            Some(ProgramAccount::Project(project)) => {
+                projects.insert(*address, project);
+            }
+            Some(ProgramAccount::Multisig(multisig)) => {
+                multisigs.insert(*address, multisig);
+            }
+            Some(ProgramAccount::Transaction(transaction)) => {
+                transactions.push((*address, transaction))
+            }
+            None => stats.skipped += 1,
+        }
+    }
+
+    //proposals are decoded against their project, so index them last
+    let mut addresses = HashMap::new();
+    for (address, project) in &projects {
+        let project_addresses = ProjectAddresses::new(project.client, project.freelancer);
+        let balance = escrow_balance(&project_addresses.token_escrow);
+        db.upsert_project(address, project, balance)?;
+        addresses.insert(project.multisig, project_addresses);
+        stats.projects += 1;
+    }
+    for (address, multisig) in &multisigs {
+        db.upsert_multisig(address, multisig)?;
+        stats.multisigs += 1;
+    }
+    for (address, transaction) in &transactions {
+        let instruction = match addresses.get(&transaction.multisig) {
+            Some(project) => instruction_name(&decode_transaction(project, transaction)),
+            None => "unknown".to_string(),
+        };
+        let owners = db.multisig_owners(&transaction.multisig)?;
+        db.upsert_proposal(address, transaction, &instruction, &owners)?;
+        stats.proposals += 1;
+    }
+    Ok(())
+}
+
+fn instruction_name(decoded: &DecodedProposal) -> String {
+    match &decoded.instruction {
+        Some(instruction) => {
+            let name = format!("{instruction:?}");
+            //drop the arguments, e.g. `ChangeOrder { .. }`
+            name.split([' ', '{'])
+                .next()
+                .unwrap_or_default()
+                .to_string()
+        }
+        None => "unknown".to_string(),
+    }
+}
+
+///record the events of one transaction and the payouts they imply
+pub fn index_transaction(
+    db: &Database,
+    transaction: &TransactionLogs,
+    stats: &mut Stats,
+) -> rusqlite::Result<()> {
+    stats.transactions += 1;
+    if transaction.failed {
+        return Ok(());
+    }
+    for (position, event) in parse_logs(&transaction.logs).iter().enumerate() {
+        let (name, project, actor, timestamp, details) = describe(event);
+        db.insert_event(&NewEvent {
+            signature: &transaction.signature,
+            position,
+            slot: transaction.slot,
+            name,
+            project: &project,
+            actor: &actor,
+            timestamp,
+            details,
+        })?;
+        stats.events += 1;
+
+        match event {
+            ProgramEvent::FundsWithdrawn(event) => db.insert_payout(&NewPayout {
+                signature: &transaction.signature,
+                position,
+                project: &event.project,
+                recipient: &event.actor,
+                kind: "milestone",
+                amount: event.amount,
+                milestone_index: Some(event.milestone_index),
+                slot: transaction.slot,
+                timestamp: event.timestamp,
+            })?,
+            ProgramEvent::StreamedFundsWithdrawn(event) => db.insert_payout(&NewPayout {
+                signature: &transaction.signature,
+                position,
+                project: &event.project,
+                recipient: &event.actor,
+                kind: "stream",
+                amount: event.amount,
+                milestone_index: Some(event.milestone_index),
+                slot: transaction.slot,
+                timestamp: event.timestamp,
+            })?,
+            ProgramEvent::PayeeShareWithdrawn(event) => db.insert_payout(&NewPayout {
+                signature: &transaction.signature,
+                position,
+                project: &event.project,
+                recipient: &event.actor,
+                kind: "share",
+                amount: event.amount,
+                milestone_index: Some(event.milestone_index),
+                slot: transaction.slot,
+                timestamp: event.timestamp,
+            })?,
+            ProgramEvent::TimesheetPaid(event) => db.insert_payout(&NewPayout {
+                signature: &transaction.signature,
+                position,
+                project: &event.project,
+                recipient: &event.actor,
+                kind: "timesheet",
+                amount: event.amount,
+                milestone_index: None,
+                slot: transaction.slot,
+                timestamp: event.timestamp,
+            })?,
+            ProgramEvent::RetainerSettled(event) if event.refund > 0 => {
+                db.insert_payout(&NewPayout {
+                    signature: &transaction.signature,
+                    position,
+                    project: &event.project,
+                    recipient: &event.client,
+                    kind: "refund",
+                    amount: event.refund,
+                    milestone_index: None,
+                    slot: transaction.slot,
+                    timestamp: event.timestamp,
+                })?
+            }
+            ProgramEvent::SubcontractCancelled(event) => {
+                if event.earned > 0 {
+                    db.insert_payout(&NewPayout {
+                        signature: &transaction.signature,
+                        position,
+                        project: &event.project,
+                        recipient: &event.freelancer,
+                        kind: "milestone",
+                        amount: event.earned,
+                        milestone_index: None,
+                        slot: transaction.slot,
+                        timestamp: event.timestamp,
+                    })?;
+                }
+                if event.refund > 0 {
+                    db.insert_payout(&NewPayout {
+                        signature: &transaction.signature,
+                        position,
+                        project: &event.project,
+                        recipient: &event.recipient,
+                        kind: "refund",
+                        amount: event.refund,
+                        milestone_index: None,
+                        slot: transaction.slot,
+                        timestamp: event.timestamp,
+                    })?;
+                }
+            }
+            ProgramEvent::BondReleased(event) if event.amount > 0 => {
+                db.insert_payout(&NewPayout {
+                    signature: &transaction.signature,
+                    position,
+                    project: &event.project,
+                    recipient: &event.freelancer,
+                    kind: "bond",
+                    amount: event.amount,
+                    milestone_index: None,
+                    slot: transaction.slot,
+                    timestamp: event.timestamp,
+                })?
+            }
+            ProgramEvent::BondForfeited(event) => db.insert_payout(&NewPayout {
+                signature: &transaction.signature,
+                position,
+                project: &event.project,
+                recipient: &event.client,
+                kind: "bond",
+                amount: event.amount,
+                milestone_index: None,
+                slot: transaction.slot,
+                timestamp: event.timestamp,
+            })?,
+            ProgramEvent::OfferDeclined(event) => db.insert_payout(&NewPayout {
+                signature: &transaction.signature,
+                position,
+                project: &event.project,
+                recipient: &event.client,
+                kind: "refund",
+                amount: event.amount,
+                milestone_index: None,
+                slot: transaction.slot,
+                timestamp: event.timestamp,
+            })?,
+            ProgramEvent::TipPaid(event) => db.insert_payout(&NewPayout {
+                signature: &transaction.signature,
+                position,
+                project: &event.project,
+                recipient: &event.freelancer,
+                kind: "tip",
+                amount: event.amount,
+                milestone_index: None,
+                slot: transaction.slot,
+                timestamp: event.timestamp,
+            })?,
+            ProgramEvent::ProjectCancelled(event) => {
+                if event.earned > 0 {
+                    db.insert_payout(&NewPayout {
+                        signature: &transaction.signature,
+                        position,
+                        project: &event.project,
+                        recipient: &event.freelancer,
+                        kind: "milestone",
+                        amount: event.earned,
+                        milestone_index: None,
+                        slot: transaction.slot,
+                        timestamp: event.timestamp,
+                    })?;
+                }
+                db.insert_payout(&NewPayout {
+                    signature: &transaction.signature,
+                    position,
+                    project: &event.project,
+                    recipient: &event.actor,
+                    kind: "refund",
+                    amount: event.amount,
+                    milestone_index: None,
+                    slot: transaction.slot,
+                    timestamp: event.timestamp,
+                })?
+            }
+            _ => {}
+        }
+    }
+    Ok(())
+}
+
+///name, project, actor, timestamp and a short summary of the event payload
+fn describe(event: &ProgramEvent) -> (&'static str, Pubkey, Pubkey, i64, String) {
+    match event {
+        ProgramEvent::ProjectInitialized(e) => (
+            "ProjectInitialized",
+            e.project,
+            e.actor,
+            e.timestamp,
+            format!(
+                "client={} freelancer={} observer={} funds={} milestones={}",
+                e.client, e.freelancer, e.observer, e.total_project_funds, e.milestones
+            ),
+        ),
+        ProgramEvent::ProjectStarted(e) => (
+            "ProjectStarted",
+            e.project,
+            e.actor,
+            e.timestamp,
+            String::new(),
+        ),
+        ProgramEvent::MilestoneReached(e) => (
+            "MilestoneReached",
+            e.project,
+            e.actor,
+            e.timestamp,
+            format!("milestone={} amount={}", e.milestone_index, e.amount),
+        ),
+        ProgramEvent::FundsWithdrawn(e) => (
+            "FundsWithdrawn",
+            e.project,
+            e.actor,
+            e.timestamp,
+            format!(
+                "milestone={} amount={} completed={}",
+                e.milestone_index, e.amount, e.completed
+            ),
+        ),
+        ProgramEvent::MilestoneStreamSet(e) => (
+            "MilestoneStreamSet",
+            e.project,
+            e.actor,
+            e.timestamp,
+            format!(
+                "milestone={} start={} cliff={} end={}",
+                e.milestone_index, e.start, e.cliff, e.end
+            ),
+        ),
+        ProgramEvent::StreamedFundsWithdrawn(e) => (
+            "StreamedFundsWithdrawn",
+            e.project,
+            e.actor,
+            e.timestamp,
+            format!(
+                "milestone={} amount={} streamed={}",
+                e.milestone_index, e.amount, e.total_streamed
+            ),
+        ),
+        ProgramEvent::RetainerInitialized(e) => (
+            "RetainerInitialized",
+            e.project,
+            e.actor,
+            e.timestamp,
+            format!(
+                "period_amount={} periods={} start={} interval={} notice={}",
+                e.period_amount, e.periods, e.start, e.interval, e.notice_period
+            ),
+        ),
+        ProgramEvent::RetainerNoticeGiven(e) => (
+            "RetainerNoticeGiven",
+            e.project,
+            e.actor,
+            e.timestamp,
+            format!("terminates_at={}", e.terminates_at),
+        ),
+        ProgramEvent::RetainerSettled(e) => (
+            "RetainerSettled",
+            e.project,
+            e.actor,
+            e.timestamp,
+            format!("periods={} refund={}", e.periods, e.refund),
+        ),
+        ProgramEvent::HourlyTermsSet(e) => (
+            "HourlyTermsSet",
+            e.project,
+            e.actor,
+            e.timestamp,
+            format!(
+                "rate={} weekly_cap={} window={} start={}",
+                e.hourly_rate, e.weekly_cap, e.approval_window, e.start
+            ),
+        ),
+        ProgramEvent::TimesheetSubmitted(e) => (
+            "TimesheetSubmitted",
+            e.project,
+            e.actor,
+            e.timestamp,
+            format!(
+                "week={} hours={} memo={}",
+                e.week,
+                e.hours,
+                Hash::new_from_array(e.memo_hash)
+            ),
+        ),
+        ProgramEvent::TimesheetApproved(e) => (
+            "TimesheetApproved",
+            e.project,
+            e.actor,
+            e.timestamp,
+            format!("week={}", e.week),
+        ),
+        ProgramEvent::TimesheetDisputed(e) => (
+            "TimesheetDisputed",
+            e.project,
+            e.actor,
+            e.timestamp,
+            format!("week={}", e.week),
+        ),
+        ProgramEvent::TimesheetPaid(e) => (
+            "TimesheetPaid",
+            e.project,
+            e.actor,
+            e.timestamp,
+            format!(
+                "week={} amount={} auto_approved={}",
+                e.week, e.amount, e.auto_approved
+            ),
+        ),
+        ProgramEvent::PayeeSplitsSet(e) => (
+            "PayeeSplitsSet",
+            e.project,
+            e.actor,
+            e.timestamp,
+            format!("payees={} splits={:?}", e.payees.len(), e.milestone_splits),
+        ),
+        ProgramEvent::PayeeShareWithdrawn(e) => (
+            "PayeeShareWithdrawn",
+            e.project,
+            e.actor,
+            e.timestamp,
+            format!(
+                "milestone={} amount={} completed={}",
+                e.milestone_index, e.amount, e.completed
+            ),
+        ),
+        ProgramEvent::SubcontractCreated(e) => (
+            "SubcontractCreated",
+            e.project,
+            e.actor,
+            e.timestamp,
+            format!("parent={} amount={}", e.parent, e.amount),
+        ),
+        ProgramEvent::SubcontractFrozen(e) => (
+            "SubcontractFrozen",
+            e.project,
+            e.actor,
+            e.timestamp,
+            format!("parent={}", e.parent),
+        ),
+        ProgramEvent::SubcontractThawed(e) => (
+            "SubcontractThawed",
+            e.project,
+            e.actor,
+            e.timestamp,
+            format!("parent={} paused_for={}", e.parent, e.paused_for),
+        ),
+        ProgramEvent::SubcontractCancelled(e) => (
+            "SubcontractCancelled",
+            e.project,
+            e.actor,
+            e.timestamp,
+            format!(
+                "parent={} returned_to_parent={} refund={} recipient={} earned={}",
+                e.parent, e.returned_to_parent, e.refund, e.recipient, e.earned
+            ),
+        ),
+        ProgramEvent::BondRequired(e) => (
+            "BondRequired",
+            e.project,
+            e.actor,
+            e.timestamp,
+            format!("amount={} deadline={}", e.amount, e.deadline),
+        ),
+        ProgramEvent::BondPosted(e) => (
+            "BondPosted",
+            e.project,
+            e.actor,
+            e.timestamp,
+            format!("amount={}", e.amount),
+        ),
+        ProgramEvent::BondReleased(e) => (
+            "BondReleased",
+            e.project,
+            e.actor,
+            e.timestamp,
+            format!("amount={} freelancer={}", e.amount, e.freelancer),
+        ),
+        ProgramEvent::BondForfeited(e) => (
+            "BondForfeited",
+            e.project,
+            e.actor,
+            e.timestamp,
+            format!(
+                "amount={} client={} missed_deadline={}",
+                e.amount, e.client, e.missed_deadline
+            ),
+        ),
+        ProgramEvent::OfferAccepted(e) => (
+            "OfferAccepted",
+            e.project,
+            e.actor,
+            e.timestamp,
+            String::new(),
+        ),
+        ProgramEvent::OfferDeclined(e) => (
+            "OfferDeclined",
+            e.project,
+            e.actor,
+            e.timestamp,
+            format!("amount={}", e.amount),
+        ),
+        ProgramEvent::JobPosted(e) => (
+            "JobPosted",
+            e.job_posting,
+            e.actor,
+            e.timestamp,
+            format!(
+                "budget={}..{} milestones={} uri={}",
+                e.budget_min, e.budget_max, e.milestones, e.metadata_uri
+            ),
+        ),
+        ProgramEvent::BidSubmitted(e) => (
+            "BidSubmitted",
+            e.job_posting,
+            e.actor,
+            e.timestamp,
+            format!("price={} timeline={}", e.price, e.timeline),
+        ),
+        ProgramEvent::BidAccepted(e) => (
+            "BidAccepted",
+            e.project,
+            e.actor,
+            e.timestamp,
+            format!(
+                "job_posting={} freelancer={} price={} timeline={} deadline={}",
+                e.job_posting, e.freelancer, e.price, e.timeline, e.deadline
+            ),
+        ),
+        ProgramEvent::BidClosed(e) => (
+            "BidClosed",
+            e.job_posting,
+            e.actor,
+            e.timestamp,
+            String::new(),
+        ),
+        ProgramEvent::ReputationCreated(e) => (
+            "ReputationCreated",
+            e.wallet,
+            e.actor,
+            e.timestamp,
+            String::new(),
+        ),
+        ProgramEvent::PartyRated(e) => (
+            "PartyRated",
+            e.project,
+            e.actor,
+            e.timestamp,
+            format!("ratee={} stars={}", e.ratee, e.stars),
+        ),
+        ProgramEvent::TipPaid(e) => (
+            "TipPaid",
+            e.project,
+            e.actor,
+            e.timestamp,
+            format!(
+                "amount={} from_escrow={} total_tips={}",
+                e.amount, e.from_escrow, e.total_tips
+            ),
+        ),
+        ProgramEvent::ProjectPaused(e) => (
+            "ProjectPaused",
+            e.project,
+            e.actor,
+            e.timestamp,
+            String::new(),
+        ),
+        ProgramEvent::ProjectResumed(e) => (
+            "ProjectResumed",
+            e.project,
+            e.actor,
+            e.timestamp,
+            format!("paused_for={}", e.paused_for),
+        ),
+        ProgramEvent::ChangeOrderApplied(e) => (
+            "ChangeOrderApplied",
+            e.project,
+            e.actor,
+            e.timestamp,
+            format!(
+                "additional_funds={} funds={} milestones={}",
+                e.additional_funds, e.total_project_funds, e.milestones
+            ),
+        ),
+        ProgramEvent::ProjectStopped(e) => (
+            "ProjectStopped",
+            e.project,
+            e.actor,
+            e.timestamp,
+            format!("ruling={:?}", e.ruling),
+        ),
+        ProgramEvent::ProjectCancelled(e) => (
+            "ProjectCancelled",
+            e.project,
+            e.actor,
+            e.timestamp,
+            format!("refund={} earned={}", e.amount, e.earned),
+        ),
+        ProgramEvent::ProposalCreated(e) => (
+            "ProposalCreated",
+            e.project,
+            e.actor,
+            e.timestamp,
+            format!(
+                "transaction={} program={} seqno={}",
+                e.transaction, e.program_id, e.seqno
+            ),
+        ),
+        ProgramEvent::ProposalDataAppended(e) => (
+            "ProposalDataAppended",
+            e.project,
+            e.actor,
+            e.timestamp,
+            format!("transaction={} data_len={}", e.transaction, e.data_len),
+        ),
+        ProgramEvent::ProposalApproved(e) => (
+            "ProposalApproved",
+            e.project,
+            e.actor,
+            e.timestamp,
+            format!("transaction={} approvals={}", e.transaction, e.approvals),
+        ),
+        ProgramEvent::ProposalExecuted(e) => (
+            "ProposalExecuted",
+            e.project,
+            e.actor,
+            e.timestamp,
+            format!("transaction={}", e.transaction),
+        ),
+    }
+}
+
+///snapshot the program accounts and replay every transaction since the last sync
+pub fn sync_rpc(db: &Database, rpc: &RpcClient) -> Result<Stats, Box<dyn Error>> {
+    let mut stats = Stats::default();
+
+    let accounts = rpc.get_program_accounts(&PROGRAM_ID)?;
+    let mut balances = HashMap::new();
+    for (_, account) in &accounts {
+        if let Some(ProgramAccount::Project(project)) = decode_account(account) {
+            let escrow = ProjectAddresses::new(project.client, project.freelancer).token_escrow;
+            let balance = fetch_escrow_balance(rpc, &escrow)?;
+            balances.insert(escrow, balance);
+        }
+    }
+
+    //page back to the last replayed signature, then replay oldest first
+    let last = db.sync_state(LAST_SIGNATURE)?;
+    let mut signatures = Vec::new();
+    let mut before: Option<String> = None;
+    'paging: loop {
+        let page = rpc.get_signatures_for_address(&PROGRAM_ID, before.as_deref())?;
+        if page.is_empty() {
+            break;
+        }
+        for signature in page {
+            if Some(&signature) == last.as_ref() {
+                break 'paging;
+            }
+            before = Some(signature.clone());
+            signatures.push(signature);
+        }
+    }
+
+    db.begin()?;
+    index_accounts(
+        db,
+        &accounts,
+        |escrow| balances.get(escrow).copied(),
+        &mut stats,
+    )?;
+    for signature in signatures.iter().rev() {
+        if let Some(transaction) = rpc.get_transaction_logs(signature)? {
+            index_transaction(db, &transaction, &mut stats)?;
+        }
+    }
+    if let Some(newest) = signatures.first() {
+        db.set_sync_state(LAST_SIGNATURE, newest)?;
+    }
+    db.commit()?;
+    Ok(stats)
+}
+
+///index a ledger snapshot: the accounts dumped with `solana account --output json`
+///into `dir` and the `getTransaction` results saved next to them, replayed oldest first
+pub fn load_snapshot(db: &Database, dir: &Path) -> Result<Stats, Box<dyn Error>> {
+    let mut stats = Stats::default();
+    let mut accounts = Vec::new();
+    let mut balances = HashMap::new();
+    let mut transactions = Vec::new();
+
+    for entry in fs::read_dir(dir)? {
+        let path = entry?.path();
+        if path.extension().and_then(|ext| ext.to_str()) != Some("json") {
+            continue;
+        }
+        let dump: Value = serde_json::from_str(&fs::read_to_string(&path)?)?;
+        if let Some(transaction) = parse_transaction(&dump) {
+            transactions.push(transaction);
+            continue;
+        }
+        let (address, owner, account) = parse_dump(&dump)
+            .ok_or_else(|| format!("{}: neither an account nor a transaction", path.display()))?;
+        if owner == PROGRAM_ID {
+            accounts.push((address, account));
+        } else {
+            balances.insert(address, account.lamports);
+        }
+    }
+
+    db.begin()?;
+    //an escrow missing from the snapshot is unknown, not empty
+    index_accounts(
+        db,
+        &accounts,
+        |escrow| balances.get(escrow).copied(),
+        &mut stats,
+    )?;
+    //a snapshot doesn't record the order within a slot, the signature breaks ties
+    transactions.sort_by(|a, b| (a.slot, &a.signature).cmp(&(b.slot, &b.signature)));
+    for transaction in &transactions {
+        index_transaction(db, transaction, &mut stats)?;
+    }
+    db.commit()?;
+    Ok(stats)
+}
+
+///a `getTransaction` result, bare or still wrapped in its JSON-RPC response
+fn parse_transaction(dump: &Value) -> Option<TransactionLogs> {
+    let result = if dump["result"].is_object() {
+        &dump["result"]
+    } else {
+        dump
+    };
+    let signature = result["transaction"]["signatures"][0].as_str()?;
+    if !result["meta"].is_object() {
+        return None;
+    }
+    Some(TransactionLogs::from_json(signature, result))
+}
+
+fn parse_dump(dump: &Value) -> Option<(Pubkey, Pubkey, RawAccount)> {
+    let address = Pubkey::from_str(dump["pubkey"].as_str()?).ok()?;
+    let account = &dump["account"];
+    let owner = Pubkey::from_str(account["owner"].as_str()?).ok()?;
+    let lamports = account["lamports"].as_u64()?;
+    let data = base64::decode(account["data"][0].as_str()?).ok()?;
+    Some((address, owner, RawAccount { lamports, data }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use anchor_lang::{AnchorSerialize, Discriminator};
+    use group_6_payment_protocol::events::{PayeeShareWithdrawn, ProjectCancelled, ProjectStarted};
+    use serde_json::json;
+
+    ///`Program data:` line of an event emitted by this program
+    fn data<T: Discriminator + AnchorSerialize>(event: T) -> String {
+        let mut data = T::discriminator().to_vec();
+        event.serialize(&mut data).unwrap();
+        format!("Program data: {}", base64::encode(data))
+    }
+
+    fn logs(events: Vec<String>) -> Vec<String> {
+        let mut logs = vec![format!("Program {PROGRAM_ID} invoke [1]")];
+        logs.extend(events);
+        logs.push(format!("Program {PROGRAM_ID} success"));
+        logs
+    }
+
+    fn share(project: Pubkey, payee: Pubkey, amount: u64) -> String {
+        data(PayeeShareWithdrawn {
+            project,
+            actor: payee,
+            milestone_index: 0,
+            amount,
+            completed: false,
+            timestamp: 5,
+        })
+    }
+
+    fn cancelled(project: Pubkey, client: Pubkey, freelancer: Pubkey) -> String {
+        data(ProjectCancelled {
+            project,
+            actor: client,
+            amount: 70,
+            freelancer,
+            earned: 30,
+            timestamp: 6,
+        })
+    }
+
+    #[test]
+    fn events_are_recorded_with_the_payouts_they_imply() {
+        let db = Database::open(":memory:").unwrap();
+        let mut stats = Stats::default();
+        let (project, client, freelancer) = (
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+        );
+        let payees = [Pubkey::new_unique(), Pubkey::new_unique()];
+
+        let transaction = TransactionLogs {
+            signature: "shares".to_string(),
+            slot: 1,
+            block_time: None,
+            failed: false,
+            logs: logs(vec![
+                data(ProjectStarted {
+                    project,
+                    actor: client,
+                    timestamp: 4,
+                }),
+                share(project, payees[0], 10),
+                share(project, payees[1], 20),
+            ]),
+        };
+        index_transaction(&db, &transaction, &mut stats).unwrap();
+        let transaction = TransactionLogs {
+            signature: "cancel".to_string(),
+            slot: 2,
+            block_time: None,
+            failed: false,
+            logs: logs(vec![cancelled(project, client, freelancer)]),
+        };
+        index_transaction(&db, &transaction, &mut stats).unwrap();
+        //replays are ignored
+        index_transaction(&db, &transaction, &mut stats).unwrap();
+        assert_eq!((stats.transactions, stats.events), (3, 5));
+
+        let history = db.history(&project).unwrap();
+        let names: Vec<&str> = history.iter().map(|event| event.name.as_str()).collect();
+        assert_eq!(
+            names,
+            [
+                "ProjectStarted",
+                "PayeeShareWithdrawn",
+                "PayeeShareWithdrawn",
+                "ProjectCancelled"
+            ]
+        );
+        assert_eq!(history[0].actor, client.to_string());
+        assert_eq!(history[3].details, "refund=70 earned=30");
+
+        let payouts: Vec<(String, String, u64)> = db
+            .payouts(Some(&project), None)
+            .unwrap()
+            .into_iter()
+            .map(|payout| (payout.kind, payout.recipient, payout.amount))
+            .collect();
+        assert_eq!(
+            payouts,
+            [
+                ("share".to_string(), payees[0].to_string(), 10),
+                ("share".to_string(), payees[1].to_string(), 20),
+                ("milestone".to_string(), freelancer.to_string(), 30),
+                ("refund".to_string(), client.to_string(), 70),
+            ]
+        );
+        assert_eq!(db.payouts(None, Some(&payees[1])).unwrap().len(), 1);
+    }
+
+    #[test]
+    fn failed_transactions_and_other_programs_are_skipped() {
+        let db = Database::open(":memory:").unwrap();
+        let mut stats = Stats::default();
+        let project = Pubkey::new_unique();
+        let failed = TransactionLogs {
+            signature: "failed".to_string(),
+            slot: 1,
+            block_time: None,
+            failed: true,
+            logs: logs(vec![share(project, Pubkey::new_unique(), 10)]),
+        };
+        index_transaction(&db, &failed, &mut stats).unwrap();
+        let other = TransactionLogs {
+            signature: "other".to_string(),
+            slot: 1,
+            block_time: None,
+            failed: false,
+            logs: vec![
+                format!("Program {} invoke [1]", Pubkey::new_unique()),
+                share(project, Pubkey::new_unique(), 10),
+                format!("Program {} success", Pubkey::new_unique()),
+            ],
+        };
+        index_transaction(&db, &other, &mut stats).unwrap();
+
+        assert_eq!((stats.transactions, stats.events), (2, 0));
+        assert!(db.history(&project).unwrap().is_empty());
+        assert!(db.payouts(Some(&project), None).unwrap().is_empty());
+    }
+
+    #[test]
+    fn snapshots_replay_their_transactions_oldest_first() {
+        let dir = std::env::temp_dir().join(format!("indexer-{}-snapshot", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir(&dir).unwrap();
+        let (project, client, freelancer) = (
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+        );
+        let payee = Pubkey::new_unique();
+
+        //one bare `getTransaction` result and one still wrapped in its response
+        let transaction = |signature: &str, slot: u64, events: Vec<String>| {
+            json!({
+                "slot": slot,
+                "blockTime": 100,
+                "meta": { "err": null, "logMessages": logs(events) },
+                "transaction": { "signatures": [signature] },
+            })
+        };
+        fs::write(
+            dir.join("cancel.json"),
+            transaction("cancel", 9, vec![cancelled(project, client, freelancer)]).to_string(),
+        )
+        .unwrap();
+        fs::write(
+            dir.join("share.json"),
+            json!({
+                "jsonrpc": "2.0",
+                "id": 1,
+                "result": transaction("share", 3, vec![share(project, payee, 10)]),
+            })
+            .to_string(),
+        )
+        .unwrap();
+        //an escrow balance, accounts not owned by the program only give balances
+        fs::write(
+            dir.join("escrow.json"),
+            json!({
+                "pubkey": Pubkey::new_unique().to_string(),
+                "account": {
+                    "lamports": 70,
+                    "owner": Pubkey::default().to_string(),
+                    "data": ["", "base64"],
+                },
+            })
+            .to_string(),
+        )
+        .unwrap();
+        fs::write(dir.join("notes.txt"), "not json").unwrap();
+
+        let db = Database::open(":memory:").unwrap();
+        let stats = load_snapshot(&db, &dir).unwrap();
+        assert_eq!((stats.transactions, stats.events), (2, 2));
+        let kinds: Vec<String> = db
+            .payouts(Some(&project), None)
+            .unwrap()
+            .into_iter()
+            .map(|payout| payout.kind)
+            .collect();
+        assert_eq!(kinds, ["share", "milestone", "refund"]);
+
+        fs::write(dir.join("broken.json"), "{}").unwrap();
+        assert!(load_snapshot(&db, &dir).is_err());
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
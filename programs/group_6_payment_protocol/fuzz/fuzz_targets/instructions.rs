@@ -0,0 +1,13 @@
+//! Random sequences of project and multisig instructions with random signers
+//! and amounts, checked against the escrow invariants in `tests/common/fuzz.rs`.
+
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+
+#[path = "../../tests/common/mod.rs"]
+mod common;
+
+fuzz_target!(|data: &[u8]| {
+    common::fuzz::run(&common::fuzz::actions(data));
+});
@@ -1,9 +1,11 @@
 use anchor_lang::prelude::*;
 
+///error codes start at 6000 and follow declaration order,
+///new variants must be added at the end so existing codes never change
 #[error_code]
 pub enum ErrorCode {
-    #[msg("Owners must be unique")]
-    UniqueOwners,   
+    #[msg("Owners must be unique.")]
+    UniqueOwners,
     #[msg("The given transaction has already been executed.")]
     AlreadyExecuted,
     #[msg("Threshold must be less than or equal to the number of owners.")]
@@ -14,20 +16,116 @@ pub enum ErrorCode {
     InvalidOwner,
     #[msg("Owners length must be non zero.")]
     InvalidOwnersLen,
-    #[msg("Only Client can can this function")]
+    #[msg("Only the client can call this function.")]
     ClientOnly,
-    #[msg("The given owners is empty.")]
+    #[msg("The given owners list is empty.")]
     InvalidOwnerLength,
     #[msg("Not enough owners signed this transaction.")]
     NotEnoughSigners,
     #[msg("Cannot delete a transaction that has been signed by an owner.")]
     TransactionAlreadySigned,
-    #[msg("Overflow when adding.")]
+    #[msg("Arithmetic overflow or underflow.")]
     Overflow,
     #[msg("Cannot delete a transaction the owner did not create.")]
     UnableToDelete,
-    #[msg("The given transaction has already been executed.")]
+    #[msg("A parameter exceeds its maximum length.")]
     ParamLength,
-    #[msg("Wrong Project Status")]
+    #[msg("Wrong project status.")]
     InvalidStatus,
-}
\ No newline at end of file
+    #[msg("Milestones must be between 1 and 20.")]
+    InvalidMilestones,
+    #[msg("Milestones already reached can't be changed.")]
+    MilestoneAlreadyReached,
+    #[msg("Milestone amounts don't add up to the project funds.")]
+    FundsMismatch,
+    #[msg("Project is paused.")]
+    ProjectPaused,
+    #[msg("Project hasn't been started yet.")]
+    ProjectNotStarted,
+    #[msg("Project is already running.")]
+    ProjectAlreadyRunning,
+    #[msg("Project has already been completed.")]
+    ProjectAlreadyCompleted,
+    #[msg("Project has already been cancelled.")]
+    ProjectAlreadyCancelled,
+    #[msg("All milestones have already been reached.")]
+    AllMilestonesReached,
+    #[msg("No reached milestone left to withdraw.")]
+    NothingToWithdraw,
+    #[msg("PDA bump seed not found.")]
+    BumpNotFound,
+    #[msg("Account uses an old layout, migrate it with migrate_account first.")]
+    AccountNotMigrated,
+    #[msg("Account already uses the current layout.")]
+    AlreadyMigrated,
+    #[msg("Stream schedule must satisfy start <= cliff <= end and start < end.")]
+    InvalidStream,
+    #[msg("Retainer needs a positive interval and a notice period that isn't negative.")]
+    InvalidRetainer,
+    #[msg("Only the client or the freelancer can call this function.")]
+    PartiesOnly,
+    #[msg("Notice has already been given for this retainer.")]
+    NoticeAlreadyGiven,
+    #[msg("The retainer hasn't reached the end of its notice period.")]
+    NoticePeriodRunning,
+    #[msg("No retainer period has unlocked since the last one.")]
+    NoPeriodDue,
+    #[msg("Hourly terms need a positive rate, weekly cap and approval window.")]
+    InvalidHourlyTerms,
+    #[msg("Timesheets can only be submitted once their week has started.")]
+    WeekNotStarted,
+    #[msg("Timesheet hours exceed the weekly cap.")]
+    WeeklyCapExceeded,
+    #[msg("A timesheet for this week is already pending or approved.")]
+    TimesheetAlreadySubmitted,
+    #[msg("Timesheet isn't waiting for the client.")]
+    TimesheetNotPending,
+    #[msg("The approval window is over, the timesheet is approved.")]
+    ApprovalWindowOver,
+    #[msg("Timesheet hasn't been approved yet.")]
+    TimesheetNotApproved,
+    #[msg("Not enough unreached milestone funds left to pay this.")]
+    BudgetExceeded,
+    #[msg("Splits need 1 to 8 unique payees and every milestone must split 10000 basis points between them.")]
+    InvalidSplits,
+    #[msg("Only a payee of the project can call this function.")]
+    PayeeOnly,
+    #[msg("Project pays several payees, withdraw with withdraw_payee_share.")]
+    SplitPayoutsOnly,
+    #[msg("Parent project is neither paused nor cancelled.")]
+    ParentNotDisputed,
+    #[msg("Parent project is still paused or cancelled.")]
+    ParentStillDisputed,
+    #[msg("Subcontract is frozen until its parent project is resolved.")]
+    SubcontractFrozen,
+    #[msg("Subcontract isn't frozen.")]
+    SubcontractNotFrozen,
+    #[msg("Project is a subcontract, cancel it with cancel_subcontract.")]
+    SubcontractRefundsToParent,
+    #[msg("Bond needs a positive amount and a deadline that isn't negative.")]
+    InvalidBond,
+    #[msg("The freelancer hasn't posted the bond yet.")]
+    BondNotPosted,
+    #[msg("Bond has already been posted.")]
+    BondAlreadyPosted,
+    #[msg("The bond deadline hasn't been missed.")]
+    DeadlineNotMissed,
+    #[msg("The freelancer hasn't accepted the offer yet.")]
+    OfferNotAccepted,
+    #[msg("Only the freelancer can call this function.")]
+    FreelancerOnly,
+    #[msg("Job postings need a budget range, a milestone template adding up to 10000 basis points and a metadata URI of at most 200 bytes.")]
+    InvalidJobPosting,
+    #[msg("Bids need a price within the budget and a positive timeline.")]
+    InvalidBid,
+    #[msg("Job posting has already been filled.")]
+    JobPostingFilled,
+    #[msg("Ratings are 1 to 5 stars given by one party of the project to the other.")]
+    InvalidRating,
+    #[msg("Tips need a positive amount, escrow tips can't touch the funds of the milestones left.")]
+    InvalidTip,
+    #[msg("Every payee has to withdraw their share of the milestones reached before the project is closed.")]
+    SharesNotWithdrawn,
+    #[msg("This party already rated the other on this project.")]
+    AlreadyRated,
+}
@@ -0,0 +1,470 @@
+use crate::state::Ruling;
+use anchor_lang::prelude::*;
+
+//every state change emits one of these events,
+//`actor` is the signer that triggered the change
+//(the multisig PDA for instructions executed through a proposal)
+
+#[event]
+#[derive(Debug)]
+pub struct ProjectInitialized {
+    pub project: Pubkey,
+    pub actor: Pubkey,
+    pub client: Pubkey,
+    pub freelancer: Pubkey,
+    pub observer: Pubkey,
+    pub total_project_funds: u64,
+    pub milestones: u8,
+    pub timestamp: i64,
+}
+
+#[event]
+#[derive(Debug)]
+pub struct ProjectStarted {
+    pub project: Pubkey,
+    pub actor: Pubkey,
+    pub timestamp: i64,
+}
+
+#[event]
+#[derive(Debug)]
+pub struct MilestoneReached {
+    pub project: Pubkey,
+    pub actor: Pubkey,
+    ///index of the milestone that got reached, starting at 0
+    pub milestone_index: u8,
+    pub amount: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+#[derive(Debug)]
+pub struct FundsWithdrawn {
+    pub project: Pubkey,
+    pub actor: Pubkey,
+    ///index of the last milestone paid by this withdrawal
+    pub milestone_index: u8,
+    pub amount: u64,
+    pub completed: bool,
+    pub timestamp: i64,
+}
+
+#[event]
+#[derive(Debug)]
+pub struct MilestoneStreamSet {
+    pub project: Pubkey,
+    pub actor: Pubkey,
+    pub milestone_index: u8,
+    pub start: i64,
+    pub cliff: i64,
+    pub end: i64,
+    pub timestamp: i64,
+}
+
+#[event]
+#[derive(Debug)]
+pub struct StreamedFundsWithdrawn {
+    pub project: Pubkey,
+    pub actor: Pubkey,
+    pub milestone_index: u8,
+    pub amount: u64,
+    ///streamed out of the milestone so far, this withdrawal included
+    pub total_streamed: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+#[derive(Debug)]
+pub struct RetainerInitialized {
+    pub project: Pubkey,
+    pub actor: Pubkey,
+    pub period_amount: u64,
+    pub periods: u8,
+    pub start: i64,
+    pub interval: i64,
+    pub notice_period: i64,
+    pub timestamp: i64,
+}
+
+#[event]
+#[derive(Debug)]
+pub struct RetainerNoticeGiven {
+    pub project: Pubkey,
+    pub actor: Pubkey,
+    pub terminates_at: i64,
+    pub timestamp: i64,
+}
+
+#[event]
+#[derive(Debug)]
+pub struct RetainerSettled {
+    pub project: Pubkey,
+    pub actor: Pubkey,
+    ///periods the freelancer keeps
+    pub periods: u8,
+    ///unused periods refunded to the client
+    pub refund: u64,
+    pub client: Pubkey,
+    pub timestamp: i64,
+}
+
+#[event]
+#[derive(Debug)]
+pub struct HourlyTermsSet {
+    pub project: Pubkey,
+    pub actor: Pubkey,
+    pub hourly_rate: u64,
+    pub weekly_cap: u32,
+    pub approval_window: i64,
+    pub start: i64,
+    pub timestamp: i64,
+}
+
+#[event]
+#[derive(Debug)]
+pub struct TimesheetSubmitted {
+    pub project: Pubkey,
+    pub actor: Pubkey,
+    pub week: u32,
+    pub hours: u32,
+    pub memo_hash: [u8; 32],
+    pub timestamp: i64,
+}
+
+#[event]
+#[derive(Debug)]
+pub struct TimesheetApproved {
+    pub project: Pubkey,
+    pub actor: Pubkey,
+    pub week: u32,
+    pub timestamp: i64,
+}
+
+#[event]
+#[derive(Debug)]
+pub struct TimesheetDisputed {
+    pub project: Pubkey,
+    pub actor: Pubkey,
+    pub week: u32,
+    pub timestamp: i64,
+}
+
+#[event]
+#[derive(Debug)]
+pub struct TimesheetPaid {
+    pub project: Pubkey,
+    pub actor: Pubkey,
+    pub week: u32,
+    pub amount: u64,
+    ///the client never answered, the approval window approved it
+    pub auto_approved: bool,
+    pub timestamp: i64,
+}
+
+#[event]
+#[derive(Debug)]
+pub struct PayeeSplitsSet {
+    pub project: Pubkey,
+    pub actor: Pubkey,
+    pub payees: Vec<Pubkey>,
+    pub milestone_splits: Vec<Vec<u16>>,
+    pub timestamp: i64,
+}
+
+#[event]
+#[derive(Debug)]
+pub struct PayeeShareWithdrawn {
+    pub project: Pubkey,
+    pub actor: Pubkey,
+    ///index of the last milestone paid by this withdrawal
+    pub milestone_index: u8,
+    pub amount: u64,
+    ///every payee withdrew every milestone, the project is completed
+    pub completed: bool,
+    pub timestamp: i64,
+}
+
+#[event]
+#[derive(Debug)]
+pub struct SubcontractCreated {
+    ///the child project
+    pub project: Pubkey,
+    pub actor: Pubkey,
+    pub parent: Pubkey,
+    ///drawn from the parent's unreached milestones into the child's escrow
+    pub amount: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+#[derive(Debug)]
+pub struct SubcontractFrozen {
+    pub project: Pubkey,
+    pub actor: Pubkey,
+    pub parent: Pubkey,
+    pub timestamp: i64,
+}
+
+#[event]
+#[derive(Debug)]
+pub struct SubcontractThawed {
+    pub project: Pubkey,
+    pub actor: Pubkey,
+    pub parent: Pubkey,
+    ///seconds the child was paused by the freeze, 0 if it wasn't
+    pub paused_for: i64,
+    pub timestamp: i64,
+}
+
+#[event]
+#[derive(Debug)]
+pub struct SubcontractCancelled {
+    pub project: Pubkey,
+    pub actor: Pubkey,
+    pub parent: Pubkey,
+    ///lamports given back to the parent's unreached milestones
+    pub returned_to_parent: u64,
+    ///lamports refunded to `recipient`
+    pub refund: u64,
+    ///the parent's freelancer, or the parent's client once the parent is closed
+    pub recipient: Pubkey,
+    ///the child's freelancer
+    pub freelancer: Pubkey,
+    ///lamports paid to `freelancer` for milestones reached before the child got stopped
+    pub earned: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+#[derive(Debug)]
+pub struct BondRequired {
+    pub project: Pubkey,
+    pub actor: Pubkey,
+    pub amount: u64,
+    ///0 when the project has no deadline
+    pub deadline: i64,
+    pub timestamp: i64,
+}
+
+#[event]
+#[derive(Debug)]
+pub struct BondPosted {
+    pub project: Pubkey,
+    pub actor: Pubkey,
+    pub amount: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+#[derive(Debug)]
+pub struct BondReleased {
+    pub project: Pubkey,
+    pub actor: Pubkey,
+    ///lamports returned to `freelancer`, 0 if the bond was never posted
+    pub amount: u64,
+    pub freelancer: Pubkey,
+    pub timestamp: i64,
+}
+
+#[event]
+#[derive(Debug)]
+pub struct BondForfeited {
+    pub project: Pubkey,
+    pub actor: Pubkey,
+    pub amount: u64,
+    pub client: Pubkey,
+    ///forfeited for the missed deadline rather than by a multisig ruling
+    pub missed_deadline: bool,
+    pub timestamp: i64,
+}
+
+#[event]
+#[derive(Debug)]
+pub struct OfferAccepted {
+    pub project: Pubkey,
+    pub actor: Pubkey,
+    pub timestamp: i64,
+}
+
+#[event]
+#[derive(Debug)]
+pub struct OfferDeclined {
+    pub project: Pubkey,
+    pub actor: Pubkey,
+    ///lamports refunded to the client
+    pub amount: u64,
+    pub client: Pubkey,
+    pub timestamp: i64,
+}
+
+#[event]
+#[derive(Debug)]
+pub struct JobPosted {
+    pub job_posting: Pubkey,
+    pub actor: Pubkey,
+    pub budget_min: u64,
+    pub budget_max: u64,
+    pub milestones: u8,
+    pub metadata_uri: String,
+    pub timestamp: i64,
+}
+
+#[event]
+#[derive(Debug)]
+pub struct BidSubmitted {
+    pub job_posting: Pubkey,
+    pub actor: Pubkey,
+    pub price: u64,
+    ///seconds the freelancer needs to deliver
+    pub timeline: i64,
+    pub timestamp: i64,
+}
+
+#[event]
+#[derive(Debug)]
+pub struct BidAccepted {
+    pub job_posting: Pubkey,
+    pub actor: Pubkey,
+    ///project created from the bid
+    pub project: Pubkey,
+    pub freelancer: Pubkey,
+    pub price: u64,
+    ///seconds the freelancer needs to deliver
+    pub timeline: i64,
+    ///unix timestamp the project is due by
+    pub deadline: i64,
+    pub timestamp: i64,
+}
+
+#[event]
+#[derive(Debug)]
+pub struct BidClosed {
+    pub job_posting: Pubkey,
+    pub actor: Pubkey,
+    pub timestamp: i64,
+}
+
+#[event]
+#[derive(Debug)]
+pub struct ReputationCreated {
+    pub wallet: Pubkey,
+    pub actor: Pubkey,
+    pub timestamp: i64,
+}
+
+#[event]
+#[derive(Debug)]
+pub struct PartyRated {
+    pub project: Pubkey,
+    pub actor: Pubkey,
+    pub ratee: Pubkey,
+    pub stars: u8,
+    pub timestamp: i64,
+}
+
+#[event]
+#[derive(Debug)]
+pub struct TipPaid {
+    pub project: Pubkey,
+    pub actor: Pubkey,
+    pub freelancer: Pubkey,
+    pub amount: u64,
+    ///paid out of the escrow rather than by the client directly
+    pub from_escrow: bool,
+    ///every tip of the project so far, this one included
+    pub total_tips: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+#[derive(Debug)]
+pub struct ProjectPaused {
+    pub project: Pubkey,
+    pub actor: Pubkey,
+    pub timestamp: i64,
+}
+
+#[event]
+#[derive(Debug)]
+pub struct ProjectResumed {
+    pub project: Pubkey,
+    pub actor: Pubkey,
+    ///seconds the project spent paused
+    pub paused_for: i64,
+    pub timestamp: i64,
+}
+
+#[event]
+#[derive(Debug)]
+pub struct ChangeOrderApplied {
+    pub project: Pubkey,
+    pub actor: Pubkey,
+    pub additional_funds: u64,
+    pub total_project_funds: u64,
+    pub milestones: u8,
+    pub timestamp: i64,
+}
+
+#[event]
+#[derive(Debug)]
+pub struct ProjectStopped {
+    pub project: Pubkey,
+    pub actor: Pubkey,
+    pub ruling: Ruling,
+    pub timestamp: i64,
+}
+
+#[event]
+#[derive(Debug)]
+pub struct ProjectCancelled {
+    pub project: Pubkey,
+    pub actor: Pubkey,
+    ///lamports refunded to the client
+    pub amount: u64,
+    pub freelancer: Pubkey,
+    ///lamports paid to the freelancer for milestones reached before the project got stopped
+    pub earned: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+#[derive(Debug)]
+pub struct ProposalCreated {
+    pub project: Pubkey,
+    pub actor: Pubkey,
+    pub transaction: Pubkey,
+    pub program_id: Pubkey,
+    pub seqno: u32,
+    pub timestamp: i64,
+}
+
+#[event]
+#[derive(Debug)]
+pub struct ProposalDataAppended {
+    pub project: Pubkey,
+    pub actor: Pubkey,
+    pub transaction: Pubkey,
+    ///length of the instruction data after the append
+    pub data_len: u32,
+    pub timestamp: i64,
+}
+
+#[event]
+#[derive(Debug)]
+pub struct ProposalApproved {
+    pub project: Pubkey,
+    pub actor: Pubkey,
+    pub transaction: Pubkey,
+    ///number of owners that signed so far
+    pub approvals: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+#[derive(Debug)]
+pub struct ProposalExecuted {
+    pub project: Pubkey,
+    pub actor: Pubkey,
+    pub transaction: Pubkey,
+    pub timestamp: i64,
+}
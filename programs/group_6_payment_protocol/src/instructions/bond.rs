@@ -0,0 +1,273 @@
+use crate::errors::ErrorCode;
+use crate::events;
+use crate::state::{Bond, ProjectInfo, ProjectStatus, Reputation, Ruling, Versioned};
+use anchor_lang::prelude::*;
+use anchor_lang::system_program;
+use anchor_lang::system_program::Transfer;
+
+///ask the freelancer for a performance bond before the project starts,
+///accept_offer and start_project fail until it's posted. `deadline` is 0 for none
+pub fn require_bond(ctx: Context<RequireBondContext>, amount: u64, deadline: i64) -> Result<()> {
+    let project_info = &ctx.accounts.project_info_account;
+    project_info.require_status(&[ProjectStatus::Offered, ProjectStatus::Pending])?;
+    require!(amount > 0 && deadline >= 0, ErrorCode::InvalidBond);
+
+    let bond = &mut ctx.accounts.bond;
+    bond.project = project_info.key();
+    bond.client = project_info.client;
+    bond.freelancer = project_info.freelancer;
+    bond.amount = amount;
+    bond.deadline = deadline;
+    bond.posted = false;
+    bond.version = Bond::VERSION;
+
+    emit!(events::BondRequired {
+        project: project_info.key(),
+        actor: ctx.accounts.client.key(),
+        amount,
+        deadline,
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+    Ok(())
+}
+
+///the freelancer deposits the bond into the bond account
+pub fn post_bond(ctx: Context<PostBondContext>) -> Result<()> {
+    ctx.accounts
+        .project_info_account
+        .require_status(&[ProjectStatus::Offered, ProjectStatus::Pending])?;
+    require!(!ctx.accounts.bond.posted, ErrorCode::BondAlreadyPosted);
+
+    let amount = ctx.accounts.bond.amount;
+    system_program::transfer(ctx.accounts.transfer_bond_from_freelancer(), amount)?;
+    ctx.accounts.bond.posted = true;
+
+    emit!(events::BondPosted {
+        project: ctx.accounts.project_info_account.key(),
+        actor: ctx.accounts.freelancer.key(),
+        amount,
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+    Ok(())
+}
+
+///give the bond back to the freelancer once the project is completed, or once it
+///was stopped or closed by cancel_project without a ruling against the freelancer,
+///which forfeits the bond.
+///anyone can crank it, the client gets the rent back
+pub fn release_bond(ctx: Context<ReleaseBondContext>) -> Result<()> {
+    let project_info = ProjectInfo::load_unless_closed(&ctx.accounts.project_info_account)?;
+    if let Some(project_info) = project_info {
+        project_info.require_status(&[ProjectStatus::Completed, ProjectStatus::Cancelled])?;
+    }
+
+    let bond = &ctx.accounts.bond;
+    let amount = if bond.posted { bond.amount } else { 0 };
+    let bond_info = bond.to_account_info();
+    let freelancer = ctx.accounts.freelancer.to_account_info();
+    **bond_info.try_borrow_mut_lamports()? = bond_info
+        .lamports()
+        .checked_sub(amount)
+        .ok_or(ErrorCode::Overflow)?;
+    **freelancer.try_borrow_mut_lamports()? = freelancer
+        .lamports()
+        .checked_add(amount)
+        .ok_or(ErrorCode::Overflow)?;
+
+    emit!(events::BondReleased {
+        project: bond.project,
+        actor: ctx.accounts.caller.key(),
+        amount,
+        freelancer: freelancer.key(),
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+    Ok(())
+}
+
+// rule against the freelancer: the project is stopped and the bond goes to the client,
+// the ruling shows in both reputations, like stop_project with Ruling::Client.
+//The only way this can be invoked
+// is via a recursive call from execute_transaction -> forfeit_bond.
+pub fn forfeit_bond(ctx: Context<ForfeitBondContext>) -> Result<()> {
+    let project_info = &mut ctx.accounts.project_info_account;
+    project_info.require_status(&[ProjectStatus::Running, ProjectStatus::Paused])?;
+    let bond = &ctx.accounts.bond;
+    require!(bond.posted, ErrorCode::BondNotPosted);
+    project_info.transition(ProjectStatus::Cancelled)?;
+    Reputation::record_ruling(
+        &ctx.accounts.client_reputation,
+        &ctx.accounts.freelancer_reputation,
+    )?;
+
+    let now = Clock::get()?.unix_timestamp;
+    emit!(events::BondForfeited {
+        project: bond.project,
+        actor: ctx.accounts.multisig_signer.key(),
+        amount: bond.amount,
+        client: bond.client,
+        missed_deadline: false,
+        timestamp: now,
+    });
+    emit!(events::ProjectStopped {
+        project: bond.project,
+        actor: ctx.accounts.multisig_signer.key(),
+        ruling: Ruling::Client,
+        timestamp: now,
+    });
+    Ok(())
+}
+
+///forfeit the bond to the client once the project missed its deadline, anyone can crank it
+pub fn claim_bond(ctx: Context<ClaimBondContext>) -> Result<()> {
+    let bond = &ctx.accounts.bond;
+    require!(bond.posted, ErrorCode::BondNotPosted);
+    let now = Clock::get()?.unix_timestamp;
+    require!(
+        bond.deadline_missed(now, &ctx.accounts.project_info_account),
+        ErrorCode::DeadlineNotMissed
+    );
+    Reputation::update(&ctx.accounts.freelancer_reputation, |reputation| {
+        reputation.deadlines_missed = reputation.deadlines_missed.saturating_add(1)
+    })?;
+
+    emit!(events::BondForfeited {
+        project: bond.project,
+        actor: ctx.accounts.caller.key(),
+        amount: bond.amount,
+        client: bond.client,
+        missed_deadline: true,
+        timestamp: now,
+    });
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct RequireBondContext<'info> {
+    #[account(
+        has_one = client @ ErrorCode::ClientOnly,
+        constraint = project_info_account.is_current() @ ErrorCode::AccountNotMigrated,
+    )]
+    project_info_account: Box<Account<'info, ProjectInfo>>,
+    #[account(
+        init,
+        payer = client,
+        space = 8 + Bond::MAX_SIZE,
+        seeds = [b"bond", project_info_account.key().as_ref()],
+        bump,
+    )]
+    bond: Account<'info, Bond>,
+    #[account(mut)]
+    client: Signer<'info>,
+    system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct PostBondContext<'info> {
+    #[account(
+        has_one = freelancer,
+        constraint = project_info_account.is_current() @ ErrorCode::AccountNotMigrated,
+    )]
+    project_info_account: Box<Account<'info, ProjectInfo>>,
+    #[account(
+        mut,
+        seeds = [b"bond", project_info_account.key().as_ref()],
+        bump,
+        constraint = bond.is_current() @ ErrorCode::AccountNotMigrated,
+    )]
+    bond: Account<'info, Bond>,
+    #[account(mut)]
+    freelancer: Signer<'info>,
+    system_program: Program<'info, System>,
+}
+impl<'info> PostBondContext<'info> {
+    pub fn transfer_bond_from_freelancer(&self) -> CpiContext<'_, '_, '_, 'info, Transfer<'info>> {
+        let transfer_acct = Transfer {
+            from: self.freelancer.to_account_info(),
+            to: self.bond.to_account_info(),
+        };
+        CpiContext::new(self.system_program.to_account_info(), transfer_acct)
+    }
+}
+
+#[derive(Accounts)]
+pub struct ReleaseBondContext<'info> {
+    /// CHECK: may have been closed by cancel_project, read in the handler
+    #[account(address = bond.project)]
+    project_info_account: UncheckedAccount<'info>,
+    #[account(
+        mut,
+        close = client,
+        seeds = [b"bond", project_info_account.key().as_ref()],
+        bump,
+        constraint = bond.is_current() @ ErrorCode::AccountNotMigrated,
+    )]
+    bond: Account<'info, Bond>,
+    /// CHECK: gets the bond back, checked against the bond
+    #[account(mut, address = bond.freelancer)]
+    freelancer: AccountInfo<'info>,
+    /// CHECK: gets the rent back, checked against the bond
+    #[account(mut, address = bond.client)]
+    client: AccountInfo<'info>,
+    caller: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct ForfeitBondContext<'info> {
+    #[account(mut, constraint = project_info_account.is_current() @ ErrorCode::AccountNotMigrated)]
+    project_info_account: Box<Account<'info, ProjectInfo>>,
+    #[account(
+        seeds = [b"multisig", project_info_account.key().as_ref()],
+        bump,
+    )]
+    multisig_signer: Signer<'info>,
+    #[account(
+        mut,
+        close = client,
+        seeds = [b"bond", project_info_account.key().as_ref()],
+        bump,
+        constraint = bond.is_current() @ ErrorCode::AccountNotMigrated,
+    )]
+    bond: Account<'info, Bond>,
+    /// CHECK: gets the bond and the rent, checked against the bond
+    #[account(mut, address = bond.client)]
+    client: AccountInfo<'info>,
+    /// CHECK: wins the ruling, updated when the client has a reputation account
+    #[account(
+        mut,
+        seeds = [b"reputation", bond.client.as_ref()],
+        bump
+    )]
+    client_reputation: UncheckedAccount<'info>,
+    /// CHECK: loses the ruling, updated when the freelancer has a reputation account
+    #[account(
+        mut,
+        seeds = [b"reputation", bond.freelancer.as_ref()],
+        bump
+    )]
+    freelancer_reputation: UncheckedAccount<'info>,
+}
+
+#[derive(Accounts)]
+pub struct ClaimBondContext<'info> {
+    #[account(constraint = project_info_account.is_current() @ ErrorCode::AccountNotMigrated)]
+    project_info_account: Box<Account<'info, ProjectInfo>>,
+    #[account(
+        mut,
+        close = client,
+        seeds = [b"bond", project_info_account.key().as_ref()],
+        bump,
+        constraint = bond.is_current() @ ErrorCode::AccountNotMigrated,
+    )]
+    bond: Account<'info, Bond>,
+    /// CHECK: gets the bond and the rent, checked against the bond
+    #[account(mut, address = bond.client)]
+    client: AccountInfo<'info>,
+    caller: Signer<'info>,
+    /// CHECK: missed the deadline, updated when the freelancer has a reputation account
+    #[account(
+        mut,
+        seeds = [b"reputation", bond.freelancer.as_ref()],
+        bump
+    )]
+    freelancer_reputation: UncheckedAccount<'info>,
+}
@@ -0,0 +1,252 @@
+use crate::errors::ErrorCode;
+use crate::events;
+use crate::instructions::project::open_project;
+use crate::state::{Bid, JobPosting, Multisig, ProjectInfo, Reputation, Versioned};
+use anchor_lang::prelude::*;
+use anchor_lang::system_program::Transfer;
+
+///publish a job freelancers can bid on, the client pays for the posting
+pub fn create_job_posting(
+    ctx: Context<CreateJobPostingContext>,
+    job_id: u64,
+    budget_min: u64,
+    budget_max: u64,
+    milestone_template: Vec<u16>,
+    metadata_uri: String,
+) -> Result<()> {
+    JobPosting::check(budget_min, budget_max, &milestone_template, &metadata_uri)?;
+
+    let job_posting = &mut ctx.accounts.job_posting;
+    job_posting.client = ctx.accounts.client.key();
+    job_posting.job_id = job_id;
+    job_posting.budget_min = budget_min;
+    job_posting.budget_max = budget_max;
+    job_posting.milestone_template = milestone_template;
+    job_posting.metadata_uri = metadata_uri;
+    job_posting.project = Pubkey::default();
+    job_posting.deadline = 0;
+    job_posting.version = JobPosting::VERSION;
+
+    emit!(events::JobPosted {
+        job_posting: job_posting.key(),
+        actor: ctx.accounts.client.key(),
+        budget_min,
+        budget_max,
+        milestones: job_posting.milestone_template.len() as u8,
+        metadata_uri: job_posting.metadata_uri.clone(),
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+    Ok(())
+}
+
+///the freelancer offers to do the job for `price` lamports within `timeline` seconds,
+///and pays for the bid account
+pub fn submit_bid(ctx: Context<SubmitBidContext>, price: u64, timeline: i64) -> Result<()> {
+    let job_posting = &ctx.accounts.job_posting;
+    require!(job_posting.is_open(), ErrorCode::JobPostingFilled);
+    require!(
+        price >= job_posting.budget_min && price <= job_posting.budget_max && timeline > 0,
+        ErrorCode::InvalidBid
+    );
+
+    let bid = &mut ctx.accounts.bid;
+    bid.job_posting = job_posting.key();
+    bid.freelancer = ctx.accounts.freelancer.key();
+    bid.price = price;
+    bid.timeline = timeline;
+    bid.version = Bid::VERSION;
+
+    emit!(events::BidSubmitted {
+        job_posting: job_posting.key(),
+        actor: ctx.accounts.freelancer.key(),
+        price,
+        timeline,
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+    Ok(())
+}
+
+///hire the freelancer of `bid`: creates the project, its multisig and the escrow
+///funded with the bid price, the same way initialize_project does.
+///the project is offered on the bid's terms, the freelancer still accepts it,
+///and is due the bid's timeline from now.
+///the accepted bid is closed and its rent goes back to the freelancer
+pub fn accept_bid(ctx: Context<AcceptBidContext>) -> Result<()> {
+    let job_posting = &mut ctx.accounts.job_posting;
+    require!(job_posting.is_open(), ErrorCode::JobPostingFilled);
+    let price = ctx.accounts.bid.price;
+    let timeline = ctx.accounts.bid.timeline;
+    let now = Clock::get()?.unix_timestamp;
+    let deadline = now.checked_add(timeline).ok_or(ErrorCode::Overflow)?;
+    let milestone_amounts = job_posting.milestone_amounts(price)?;
+    job_posting.project = ctx.accounts.project_info_account.key();
+    job_posting.deadline = deadline;
+
+    let funding = ctx.accounts.transfer_funds_from_client();
+    open_project(
+        &mut ctx.accounts.project_info_account,
+        &mut ctx.accounts.multisig,
+        [
+            ctx.accounts.client.key(),
+            ctx.accounts.freelancer.key(),
+            ctx.accounts.observer.key(),
+        ],
+        [
+            &mut ctx.accounts.client_reputation,
+            &mut ctx.accounts.freelancer_reputation,
+        ],
+        milestone_amounts,
+        funding,
+        ctx.accounts.client.key(),
+    )?;
+
+    emit!(events::BidAccepted {
+        job_posting: ctx.accounts.job_posting.key(),
+        actor: ctx.accounts.client.key(),
+        project: ctx.accounts.project_info_account.key(),
+        freelancer: ctx.accounts.freelancer.key(),
+        price,
+        timeline,
+        deadline,
+        timestamp: now,
+    });
+    Ok(())
+}
+
+///the freelancer withdraws a bid that wasn't accepted and gets the rent back
+pub fn close_bid(ctx: Context<CloseBidContext>) -> Result<()> {
+    emit!(events::BidClosed {
+        job_posting: ctx.accounts.bid.job_posting,
+        actor: ctx.accounts.freelancer.key(),
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+    Ok(())
+}
+
+#[derive(Accounts)]
+#[instruction(job_id: u64)]
+pub struct CreateJobPostingContext<'info> {
+    #[account(
+        init,
+        payer = client,
+        space = 8 + JobPosting::MAX_SIZE,
+        seeds = [b"job_posting", client.key().as_ref(), &job_id.to_le_bytes()],
+        bump,
+    )]
+    job_posting: Box<Account<'info, JobPosting>>,
+    #[account(mut)]
+    client: Signer<'info>,
+    system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct SubmitBidContext<'info> {
+    #[account(constraint = job_posting.is_current() @ ErrorCode::AccountNotMigrated)]
+    job_posting: Box<Account<'info, JobPosting>>,
+    #[account(
+        init,
+        payer = freelancer,
+        space = 8 + Bid::MAX_SIZE,
+        seeds = [b"bid", job_posting.key().as_ref(), freelancer.key().as_ref()],
+        bump,
+    )]
+    bid: Account<'info, Bid>,
+    #[account(mut)]
+    freelancer: Signer<'info>,
+    system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct AcceptBidContext<'info> {
+    #[account(
+        mut,
+        has_one = client @ ErrorCode::ClientOnly,
+        constraint = job_posting.is_current() @ ErrorCode::AccountNotMigrated,
+    )]
+    job_posting: Box<Account<'info, JobPosting>>,
+    #[account(
+        mut,
+        close = freelancer,
+        has_one = job_posting,
+        has_one = freelancer,
+        constraint = bid.is_current() @ ErrorCode::AccountNotMigrated,
+    )]
+    bid: Account<'info, Bid>,
+    #[account(
+        init,
+        payer = client,
+        space = 8 + ProjectInfo::MAX_SIZE,
+        seeds = [
+            b"project_info_account",
+            client.key().as_ref(),
+            freelancer.key().as_ref(),
+        ],
+        bump,
+    )]
+    project_info_account: Box<Account<'info, ProjectInfo>>,
+    #[account(
+        init,
+        payer = client,
+        space = 8 + Multisig::MAX_SIZE,
+        seeds = [b"multisig", project_info_account.key().as_ref()],
+        bump,
+    )]
+    multisig: Box<Account<'info, Multisig>>,
+    /// CHECK:
+    #[account(
+        mut,
+        seeds = [
+            b"token_escrow",
+            project_info_account.key().as_ref()
+        ],
+        bump
+    )]
+    token_escrow: AccountInfo<'info>,
+    /// CHECK: gets the rent of the bid back, checked against the bid
+    #[account(mut)]
+    freelancer: AccountInfo<'info>,
+    /// CHECK:
+    observer: AccountInfo<'info>,
+    #[account(mut)]
+    client: Signer<'info>,
+    system_program: Program<'info, System>,
+    #[account(
+        init_if_needed,
+        payer = client,
+        space = 8 + Reputation::MAX_SIZE,
+        seeds = [b"reputation", client.key().as_ref()],
+        bump,
+    )]
+    client_reputation: Box<Account<'info, Reputation>>,
+    #[account(
+        init_if_needed,
+        payer = client,
+        space = 8 + Reputation::MAX_SIZE,
+        seeds = [b"reputation", freelancer.key().as_ref()],
+        bump,
+    )]
+    freelancer_reputation: Box<Account<'info, Reputation>>,
+}
+impl<'info> AcceptBidContext<'info> {
+    pub fn transfer_funds_from_client<'a, 'b, 'c>(
+        &self,
+    ) -> CpiContext<'a, 'b, 'c, 'info, Transfer<'info>> {
+        let transfer_acct = Transfer {
+            from: self.client.to_account_info(),
+            to: self.token_escrow.to_account_info(),
+        };
+        CpiContext::new(self.system_program.to_account_info(), transfer_acct)
+    }
+}
+
+#[derive(Accounts)]
+pub struct CloseBidContext<'info> {
+    #[account(
+        mut,
+        close = freelancer,
+        has_one = freelancer,
+    )]
+    bid: Account<'info, Bid>,
+    #[account(mut)]
+    freelancer: Signer<'info>,
+}
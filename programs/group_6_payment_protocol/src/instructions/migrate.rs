@@ -0,0 +1,64 @@
+use crate::errors::ErrorCode;
+use crate::state::{Multisig, ProjectInfo, Transaction, Versioned};
+use crate::utils::grow_account;
+use anchor_lang::prelude::*;
+use anchor_lang::Discriminator;
+
+///rewrite a `ProjectInfo`, `Multisig` or `Transaction` written with an older layout
+///in the current one, growing the account with realloc when it needs more space.
+///anyone can migrate an account, the payer covers the extra rent
+pub fn migrate_account(ctx: Context<MigrateAccount>) -> Result<()> {
+    let account = ctx.accounts.account.to_account_info();
+    let (migrated, space) = {
+        let data = account.try_borrow_data()?;
+        require!(
+            data.len() >= 8,
+            anchor_lang::error::ErrorCode::AccountDiscriminatorNotFound
+        );
+        let discriminator = &data[..8];
+        if discriminator == ProjectInfo::discriminator() {
+            let info = ProjectInfo::decode_any_layout(&data)?;
+            (upgrade(info)?, 8 + ProjectInfo::MAX_SIZE)
+        } else if discriminator == Multisig::discriminator() {
+            let multisig = Multisig::try_deserialize(&mut &data[..])?;
+            (upgrade(multisig)?, 8 + Multisig::MAX_SIZE)
+        } else if discriminator == Transaction::discriminator() {
+            let tx = Transaction::try_deserialize(&mut &data[..])?;
+            //proposals are sized from their contents
+            let migrated = upgrade(tx)?;
+            let space = migrated.len();
+            (migrated, space)
+        } else {
+            return Err(anchor_lang::error::ErrorCode::AccountDiscriminatorMismatch.into());
+        }
+    };
+
+    grow_account(
+        &account,
+        &ctx.accounts.payer.to_account_info(),
+        &ctx.accounts.system_program.to_account_info(),
+        space,
+    )?;
+    account.try_borrow_mut_data()?[..migrated.len()].copy_from_slice(&migrated);
+    Ok(())
+}
+
+///stamp `account` with the current version and serialize it
+fn upgrade<T: AccountSerialize + Versioned>(mut account: T) -> Result<Vec<u8>> {
+    require!(!account.is_current(), ErrorCode::AlreadyMigrated);
+    *account.version_mut() = T::VERSION;
+
+    let mut data = Vec::new();
+    account.try_serialize(&mut data)?;
+    Ok(data)
+}
+
+#[derive(Accounts)]
+pub struct MigrateAccount<'info> {
+    /// CHECK: any account of the program, the layout is checked in the handler
+    #[account(mut, owner = crate::ID)]
+    account: UncheckedAccount<'info>,
+    #[account(mut)]
+    payer: Signer<'info>,
+    system_program: Program<'info, System>,
+}
@@ -4,3 +4,36 @@ pub use project::*;
 pub mod multisig;
 pub use multisig::*;
 
+
+pub mod migrate;
+pub use migrate::*;
+
+pub mod stream;
+pub use stream::*;
+
+pub mod retainer;
+pub use retainer::*;
+
+pub mod timesheet;
+pub use timesheet::*;
+
+pub mod payee;
+pub use payee::*;
+
+pub mod subcontract;
+pub use subcontract::*;
+
+pub mod bond;
+pub use bond::*;
+
+pub mod offer;
+pub use offer::*;
+
+pub mod job_board;
+pub use job_board::*;
+
+pub mod reputation;
+pub use reputation::*;
+
+pub mod tip;
+pub use tip::*;
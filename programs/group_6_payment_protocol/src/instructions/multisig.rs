@@ -1,5 +1,7 @@
 use crate::errors::ErrorCode;
-use crate::state::{Multisig, Transaction, TransactionAccount};
+use crate::events;
+use crate::state::{Multisig, Transaction, TransactionAccount, Versioned};
+use crate::utils::grow_account;
 use anchor_lang::{
     prelude::*,
     solana_program::{self},
@@ -13,6 +15,11 @@ pub fn create_transaction(
     transaction_accounts: Vec<TransactionAccount>,
     data: Vec<u8>,
 ) -> Result<()> {
+    require!(
+        transaction_accounts.len() <= Transaction::MAX_ACCOUNTS
+            && data.len() <= Transaction::MAX_DATA_LEN,
+        ErrorCode::ParamLength
+    );
     let owner_index = ctx
         .accounts
         .multisig
@@ -25,6 +32,14 @@ pub fn create_transaction(
     signers.resize(ctx.accounts.multisig.owners.len(), false);
     signers[owner_index] = true;
 
+    //the account is reused for every proposal, grow it if this one is larger
+    grow_account(
+        &ctx.accounts.transaction.to_account_info(),
+        &ctx.accounts.proposer.to_account_info(),
+        &ctx.accounts.system_program.to_account_info(),
+        8 + Transaction::space(transaction_accounts.len(), data.len(), signers.len()),
+    )?;
+
     let tx = &mut ctx.accounts.transaction;
     tx.program_id = program_id;
     tx.accounts = transaction_accounts;
@@ -34,9 +49,51 @@ pub fn create_transaction(
     tx.did_execute = false;
     tx.seqno = ctx.accounts.multisig.seqno;
     tx.proposer = ctx.accounts.proposer.key();
+    tx.version = Transaction::VERSION;
 
+    emit!(events::ProposalCreated {
+        project: ctx.accounts.project_info_account.key(),
+        actor: ctx.accounts.proposer.key(),
+        transaction: tx.key(),
+        program_id,
+        seqno: tx.seqno,
+        timestamp: Clock::get()?.unix_timestamp,
+    });
     Ok(())
 }
+
+pub fn append_transaction_data(ctx: Context<AppendTransactionData>, data: Vec<u8>) -> Result<()> {
+    require!(
+        !ctx.accounts.transaction.did_execute,
+        ErrorCode::AlreadyExecuted
+    );
+    let data_len = ctx.accounts.transaction.data.len() + data.len();
+    require!(data_len <= Transaction::MAX_DATA_LEN, ErrorCode::ParamLength);
+
+    let tx = &ctx.accounts.transaction;
+    grow_account(
+        &tx.to_account_info(),
+        &ctx.accounts.proposer.to_account_info(),
+        &ctx.accounts.system_program.to_account_info(),
+        8 + Transaction::space(tx.accounts.len(), data_len, tx.signers.len()),
+    )?;
+
+    //owners approved the shorter data, only the proposer's approval stands
+    let owners = &ctx.accounts.multisig.owners;
+    let tx = &mut ctx.accounts.transaction;
+    tx.data.extend(data);
+    tx.signers = owners.iter().map(|owner| *owner == tx.proposer).collect();
+
+    emit!(events::ProposalDataAppended {
+        project: ctx.accounts.project_info_account.key(),
+        actor: ctx.accounts.proposer.key(),
+        transaction: tx.key(),
+        data_len: data_len as u32,
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+    Ok(())
+}
+
 // Approve and Executes the given transaction if threshold owners have signed it.
 pub fn approve(ctx: Context<Approve>) -> Result<()> {
     let owner_index = ctx
@@ -58,6 +115,15 @@ pub fn approve(ctx: Context<Approve>) -> Result<()> {
         .filter(|&did_sign| *did_sign)
         .count() as u64;
 
+    let timestamp = Clock::get()?.unix_timestamp;
+    emit!(events::ProposalApproved {
+        project: ctx.accounts.project_info_account.key(),
+        actor: ctx.accounts.owner.key(),
+        transaction: ctx.accounts.transaction.key(),
+        approvals: sig_count,
+        timestamp,
+    });
+
     if sig_count < ctx.accounts.multisig.threshold {
         return Ok(());
     }
@@ -81,7 +147,10 @@ pub fn approve(ctx: Context<Approve>) -> Result<()> {
         })
         .collect();
 
-    let bump = *ctx.bumps.get("multisig_signer").unwrap();
+    let bump = *ctx
+        .bumps
+        .get("multisig_signer")
+        .ok_or(ErrorCode::BumpNotFound)?;
     let project_info_key = ctx.accounts.project_info_account.key();
 
     let seeds = &[b"multisig", project_info_key.as_ref(), &[bump]];
@@ -90,28 +159,83 @@ pub fn approve(ctx: Context<Approve>) -> Result<()> {
     solana_program::program::invoke_signed(&ix, accounts, signer)?;
 
     ctx.accounts.transaction.did_execute = true;
+
+    emit!(events::ProposalExecuted {
+        project: project_info_key,
+        actor: ctx.accounts.owner.key(),
+        transaction: ctx.accounts.transaction.key(),
+        timestamp,
+    });
     Ok(())
 }
 
+///space `init_if_needed` expects for the proposal account.
+///a reused account has to keep its size there, `create_transaction` grows it.
+///oversized proposals get an empty one, the handler rejects them with `ParamLength`
+fn proposal_space(
+    transaction: &AccountInfo,
+    transaction_accounts: &[TransactionAccount],
+    data: &[u8],
+    owners: usize,
+) -> usize {
+    if !transaction.data_is_empty() {
+        return transaction.data_len();
+    }
+    if transaction_accounts.len() > Transaction::MAX_ACCOUNTS
+        || data.len() > Transaction::MAX_DATA_LEN
+    {
+        return 8 + Transaction::space(0, 0, owners);
+    }
+    8 + Transaction::space(transaction_accounts.len(), data.len(), owners)
+}
+
 #[derive(Accounts)]
+#[instruction(pid: Pubkey, transaction_accounts: Vec<TransactionAccount>, data: Vec<u8>)]
 pub struct CreateTransaction<'info> {
     /// CHECK: just pubkey needed
     project_info_account: AccountInfo<'info>,
     #[account(
         seeds = [b"multisig", project_info_account.key().as_ref()],
         bump,
+        constraint = multisig.is_current() @ ErrorCode::AccountNotMigrated,
     )]
     multisig: Box<Account<'info, Multisig>>,
+    //a fresh account has no multisig yet, a reused one must be migrated
     #[account(
         init_if_needed,
         payer = proposer,
-        space = 8 + Transaction::MAX_SIZE,
+        space = proposal_space(transaction, &transaction_accounts, &data, multisig.owners.len()),
         seeds = [
             b"multisig_transaction",
             multisig.key().as_ref(),
             multisig.seqno.to_le_bytes().as_ref(),
         ],
-        bump
+        bump,
+        constraint = transaction.multisig == Pubkey::default()
+            || transaction.is_current() @ ErrorCode::AccountNotMigrated,
+    )]
+    transaction: Box<Account<'info, Transaction>>,
+    #[account(mut)]
+    proposer: Signer<'info>,
+    system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct AppendTransactionData<'info> {
+    /// CHECK: just pubkey needed
+    project_info_account: AccountInfo<'info>,
+    #[account(
+        seeds = [b"multisig", project_info_account.key().as_ref()],
+        bump,
+        constraint = multisig.is_current() @ ErrorCode::AccountNotMigrated,
+    )]
+    multisig: Box<Account<'info, Multisig>>,
+    #[account(
+        mut,
+        has_one = multisig,
+        has_one = proposer,
+        constraint = multisig.seqno == transaction.seqno,
+        constraint = transaction.is_current() @ ErrorCode::AccountNotMigrated,
     )]
     transaction: Box<Account<'info, Transaction>>,
     #[account(mut)]
@@ -126,7 +250,8 @@ pub struct Approve<'info> {
     #[account(
             seeds = [b"multisig", project_info_account.key().as_ref()],
             bump,
-        constraint = multisig.seqno == transaction.seqno
+        constraint = multisig.seqno == transaction.seqno,
+        constraint = multisig.is_current() @ ErrorCode::AccountNotMigrated,
     )]
     multisig: Box<Account<'info, Multisig>>,
     /// CHECK
@@ -136,7 +261,11 @@ pub struct Approve<'info> {
     constraint = multisig.seqno == transaction.seqno
 )]
     multisig_signer: AccountInfo<'info>,
-    #[account(mut, has_one = multisig)]
+    #[account(
+        mut,
+        has_one = multisig,
+        constraint = transaction.is_current() @ ErrorCode::AccountNotMigrated,
+    )]
     transaction: Box<Account<'info, Transaction>>,
     // One of the multisig owners. Checked in the handler.
     owner: Signer<'info>,
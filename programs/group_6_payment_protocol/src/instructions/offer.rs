@@ -0,0 +1,121 @@
+use crate::errors::ErrorCode;
+use crate::events;
+use crate::state::{Bond, Multisig, ProjectInfo, ProjectStatus, Versioned};
+use anchor_lang::prelude::*;
+use anchor_lang::system_program;
+use anchor_lang::system_program::Transfer;
+
+///the freelancer agrees to the terms of the offer, which starts the project.
+///a required bond has to be posted first
+pub fn accept_offer(ctx: Context<AcceptOfferContext>) -> Result<()> {
+    Bond::require_posted(&ctx.accounts.bond)?;
+    let project_info = &mut ctx.accounts.project_info_account;
+    project_info.require_status(&[ProjectStatus::Offered])?;
+    project_info.transition(ProjectStatus::Running)?;
+
+    emit!(events::OfferAccepted {
+        project: project_info.key(),
+        actor: ctx.accounts.freelancer.key(),
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+    Ok(())
+}
+
+///the freelancer turns the offer down, the client gets the escrow back
+///and the rent of the project account and its multisig.
+///a subcontract is refunded to its parent with cancel_subcontract instead
+pub fn decline_offer(ctx: Context<DeclineOfferContext>) -> Result<()> {
+    ctx.accounts
+        .project_info_account
+        .require_status(&[ProjectStatus::Offered])?;
+    require!(
+        ctx.accounts.subcontract.data_is_empty(),
+        ErrorCode::SubcontractRefundsToParent
+    );
+    let bump = *ctx
+        .bumps
+        .get("token_escrow")
+        .ok_or(ErrorCode::BumpNotFound)?;
+    let project_info_account = ctx.accounts.project_info_account.key();
+    let signer_seed: &[&[&[u8]]] = &[&[b"token_escrow", project_info_account.as_ref(), &[bump]]];
+    let amount = ctx.accounts.token_escrow.lamports();
+    system_program::transfer(
+        ctx.accounts.refund_client().with_signer(signer_seed),
+        amount,
+    )?;
+
+    emit!(events::OfferDeclined {
+        project: project_info_account,
+        actor: ctx.accounts.freelancer.key(),
+        amount,
+        client: ctx.accounts.client.key(),
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct AcceptOfferContext<'info> {
+    #[account(
+        mut,
+        has_one = freelancer @ ErrorCode::FreelancerOnly,
+        constraint = project_info_account.is_current() @ ErrorCode::AccountNotMigrated,
+    )]
+    project_info_account: Box<Account<'info, ProjectInfo>>,
+    freelancer: Signer<'info>,
+    /// CHECK: only read to check a required bond was posted
+    #[account(
+        seeds = [b"bond", project_info_account.key().as_ref()],
+        bump
+    )]
+    bond: UncheckedAccount<'info>,
+}
+
+#[derive(Accounts)]
+pub struct DeclineOfferContext<'info> {
+    #[account(
+        mut,
+        close = client,
+        has_one = client,
+        has_one = freelancer @ ErrorCode::FreelancerOnly,
+        constraint = project_info_account.is_current() @ ErrorCode::AccountNotMigrated,
+    )]
+    project_info_account: Box<Account<'info, ProjectInfo>>,
+    #[account(
+        mut,
+        close = client,
+        seeds = [b"multisig", project_info_account.key().as_ref()],
+        bump,
+    )]
+    multisig: Box<Account<'info, Multisig>>,
+    /// CHECK:
+    #[account(
+        mut,
+        seeds = [
+            b"token_escrow",
+            project_info_account.key().as_ref()
+        ],
+        bump
+    )]
+    token_escrow: AccountInfo<'info>,
+    /// CHECK: gets the refund, checked against the project
+    #[account(mut)]
+    client: AccountInfo<'info>,
+    freelancer: Signer<'info>,
+    system_program: Program<'info, System>,
+    /// CHECK: only read to make sure the project isn't a subcontract
+    #[account(
+        seeds = [b"subcontract", project_info_account.key().as_ref()],
+        bump
+    )]
+    subcontract: UncheckedAccount<'info>,
+}
+impl<'info> DeclineOfferContext<'info> {
+    pub fn refund_client(&self) -> CpiContext<'_, '_, '_, 'info, Transfer<'info>> {
+        let transfer_acct = Transfer {
+            from: self.token_escrow.to_account_info(),
+            to: self.client.to_account_info(),
+        };
+        CpiContext::new(self.system_program.to_account_info(), transfer_acct)
+    }
+}
@@ -0,0 +1,234 @@
+use crate::errors::ErrorCode;
+use crate::events;
+use crate::payout;
+use crate::state::{Bond, PayeeSplits, ProjectInfo, ProjectStatus, Reputation, Versioned};
+use crate::utils::assert_unique_owners;
+use anchor_lang::prelude::*;
+use anchor_lang::system_program;
+use anchor_lang::system_program::Transfer;
+
+// share the milestone payouts between several payees by basis points,
+// from then on they're withdrawn with withdraw_payee_share only
+//The only way this can be invoked
+// is via a recursive call from execute_transaction -> set_payee_splits.
+// the client has to sign the executing transaction, it pays for the splits account
+pub fn set_payee_splits(
+    ctx: Context<SetPayeeSplitsContext>,
+    payees: Vec<Pubkey>,
+    milestone_splits: Vec<Vec<u16>>,
+) -> Result<()> {
+    let project_info = &ctx.accounts.project_info_account;
+    project_info.require_status(&[
+        ProjectStatus::Offered,
+        ProjectStatus::Pending,
+        ProjectStatus::Running,
+    ])?;
+    require!(
+        !payees.is_empty() && payees.len() <= PayeeSplits::MAX_PAYEES,
+        ErrorCode::InvalidSplits
+    );
+    assert_unique_owners(&payees).map_err(|_| ErrorCode::InvalidSplits)?;
+    payout::check_splits(payees.len(), &milestone_splits, ProjectInfo::MAX_MILESTONES)?;
+
+    let splits = &mut ctx.accounts.payee_splits;
+    splits.project = project_info.key();
+    //milestones already paid to the freelancer aren't shared
+    splits.milestones_withdrawn = vec![project_info.milestone_funds_withdrawn; payees.len()];
+    splits.amounts_withdrawn = vec![0; payees.len()];
+    splits.payees = payees.clone();
+    splits.milestone_splits = milestone_splits.clone();
+    splits.version = PayeeSplits::VERSION;
+
+    emit!(events::PayeeSplitsSet {
+        project: project_info.key(),
+        actor: ctx.accounts.client.key(),
+        payees,
+        milestone_splits,
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+    Ok(())
+}
+
+///withdraw the signer's share of every milestone reached since their last withdrawal,
+///what a stream or a timesheet already paid out of a milestone isn't shared
+pub fn withdraw_payee_share(ctx: Context<WithdrawPayeeShareContext>) -> Result<()> {
+    let project_info = &ctx.accounts.project_info_account;
+    //shares of the milestones reached before a stop can still be withdrawn
+    project_info.require_status(&[ProjectStatus::Running, ProjectStatus::Cancelled])?;
+    let splits = &ctx.accounts.payee_splits;
+    let payee = splits
+        .payees
+        .iter()
+        .position(|payee| *payee == ctx.accounts.payee.key())
+        .ok_or(ErrorCode::PayeeOnly)?;
+    let first = splits.milestones_withdrawn[payee];
+    require!(
+        project_info.milestones_reached > first,
+        ErrorCode::NothingToWithdraw
+    );
+
+    let mut amount = 0u64;
+    for milestone_index in first..project_info.milestones_reached {
+        let index = milestone_index as usize;
+        let (milestone_amount, streamed) = project_info
+            .milestone_amounts
+            .get(index)
+            .zip(project_info.milestone_funds_streamed.get(index))
+            .ok_or(ErrorCode::InvalidMilestones)?;
+        let shares = payout::split_shares(
+            milestone_amount
+                .checked_sub(*streamed)
+                .ok_or(ErrorCode::Overflow)?,
+            splits
+                .splits(milestone_index)
+                .ok_or(ErrorCode::InvalidSplits)?,
+        )?;
+        amount = amount
+            .checked_add(shares[payee])
+            .ok_or(ErrorCode::Overflow)?;
+    }
+
+    //a milestone counts as withdrawn once every payee took their share
+    let milestones_reached = project_info.milestones_reached;
+    let withdrawn = splits
+        .milestones_withdrawn
+        .iter()
+        .enumerate()
+        .map(|(index, first)| if index == payee { milestones_reached } else { *first })
+        .min()
+        .ok_or(ErrorCode::InvalidSplits)?;
+    let completed =
+        withdrawn == project_info.milestones && project_info.status == ProjectStatus::Running;
+    //the payee completing the project sweeps the escrow like a single freelancer would,
+    //so rounding and lamports sent to the escrow don't stay locked in it
+    if completed {
+        amount = ctx.accounts.token_escrow.lamports();
+    }
+
+    if amount > 0 {
+        let bump = *ctx
+            .bumps
+            .get("token_escrow")
+            .ok_or(ErrorCode::BumpNotFound)?;
+        let project_info_key = project_info.key();
+        let signer_seed: &[&[&[u8]]] = &[&[b"token_escrow", project_info_key.as_ref(), &[bump]]];
+        system_program::transfer(
+            ctx.accounts
+                .transfer_share_to_payee()
+                .with_signer(signer_seed),
+            amount,
+        )?;
+    }
+
+    let splits = &mut ctx.accounts.payee_splits;
+    splits.milestones_withdrawn[payee] = milestones_reached;
+    splits.amounts_withdrawn[payee] = splits.amounts_withdrawn[payee]
+        .checked_add(amount)
+        .ok_or(ErrorCode::Overflow)?;
+    let project_info = &mut ctx.accounts.project_info_account;
+    project_info.milestone_funds_withdrawn = withdrawn;
+    if completed {
+        let now = Clock::get()?.unix_timestamp;
+        let on_time = Bond::deadline_met(&ctx.accounts.bond, now, project_info)?;
+        project_info.transition(ProjectStatus::Completed)?;
+        Reputation::record_completion(
+            &ctx.accounts.client_reputation,
+            &ctx.accounts.freelancer_reputation,
+            project_info.total_project_funds,
+            on_time,
+        )?;
+    }
+
+    emit!(events::PayeeShareWithdrawn {
+        project: project_info.key(),
+        actor: ctx.accounts.payee.key(),
+        milestone_index: milestones_reached - 1,
+        amount,
+        completed: project_info.status == ProjectStatus::Completed,
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct SetPayeeSplitsContext<'info> {
+    #[account(
+        has_one = client,
+        constraint = project_info_account.is_current() @ ErrorCode::AccountNotMigrated,
+    )]
+    project_info_account: Box<Account<'info, ProjectInfo>>,
+    #[account(
+        seeds = [b"multisig", project_info_account.key().as_ref()],
+        bump,
+    )]
+    multisig_signer: Signer<'info>,
+    #[account(
+        init,
+        payer = client,
+        space = 8 + PayeeSplits::MAX_SIZE,
+        seeds = [b"payee_splits", project_info_account.key().as_ref()],
+        bump,
+    )]
+    payee_splits: Box<Account<'info, PayeeSplits>>,
+    #[account(mut)]
+    client: Signer<'info>,
+    system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct WithdrawPayeeShareContext<'info> {
+    #[account(
+        mut,
+        constraint = project_info_account.is_current() @ ErrorCode::AccountNotMigrated,
+    )]
+    project_info_account: Box<Account<'info, ProjectInfo>>,
+    #[account(
+        mut,
+        seeds = [b"payee_splits", project_info_account.key().as_ref()],
+        bump,
+        constraint = payee_splits.is_current() @ ErrorCode::AccountNotMigrated,
+    )]
+    payee_splits: Box<Account<'info, PayeeSplits>>,
+    /// CHECK:
+    #[account(
+        mut,
+        seeds = [
+            b"token_escrow",
+            project_info_account.key().as_ref()
+        ],
+        bump
+    )]
+    token_escrow: AccountInfo<'info>,
+    #[account(mut)]
+    payee: Signer<'info>,
+    system_program: Program<'info, System>,
+    /// CHECK: only read to check the bond deadline once the project completes
+    #[account(
+        seeds = [b"bond", project_info_account.key().as_ref()],
+        bump
+    )]
+    bond: UncheckedAccount<'info>,
+    /// CHECK: updated once the project completes, when the client has a reputation account
+    #[account(
+        mut,
+        seeds = [b"reputation", project_info_account.client.as_ref()],
+        bump
+    )]
+    client_reputation: UncheckedAccount<'info>,
+    /// CHECK: updated once the project completes, when the freelancer has a reputation account
+    #[account(
+        mut,
+        seeds = [b"reputation", project_info_account.freelancer.as_ref()],
+        bump
+    )]
+    freelancer_reputation: UncheckedAccount<'info>,
+}
+impl<'info> WithdrawPayeeShareContext<'info> {
+    pub fn transfer_share_to_payee(&self) -> CpiContext<'_, '_, '_, 'info, Transfer<'info>> {
+        let transfer_acct = Transfer {
+            from: self.token_escrow.to_account_info(),
+            to: self.payee.to_account_info(),
+        };
+        CpiContext::new(self.system_program.to_account_info(), transfer_acct)
+    }
+}
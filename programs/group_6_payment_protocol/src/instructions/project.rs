@@ -1,51 +1,103 @@
 use crate::errors::ErrorCode;
+use crate::events;
+use crate::payout;
 use anchor_lang::prelude::*;
 use anchor_lang::system_program;
 use anchor_lang::system_program::Transfer;
+use std::collections::BTreeMap;
 
 use crate::state::project_info::ProjectInfo;
 use crate::state::project_info::ProjectStatus;
-use crate::state::Multisig;
+use crate::state::{Bond, Multisig, Rating, Reputation, Ruling, Subcontract, Versioned};
+use crate::utils::create_pda_account;
 
 pub fn initialize_project(
     ctx: Context<InitializeProjectContext>,
     total_project_funds: u64,
     milestones: u8,
 ) -> Result<()> {
-    //initialize multisig
-    let multisig = &mut ctx.accounts.multisig;
-    let owners = vec![
+    let funding = ctx.accounts.transfer_funds_from_client();
+    open_project(
+        &mut ctx.accounts.project_info_account,
+        &mut ctx.accounts.multisig,
+        [
+            ctx.accounts.client.key(),
+            ctx.accounts.freelancer.key(),
+            ctx.accounts.observer.key(),
+        ],
+        [
+            &mut ctx.accounts.client_reputation,
+            &mut ctx.accounts.freelancer_reputation,
+        ],
+        ProjectInfo::split_funds(total_project_funds, milestones)?,
+        funding,
         ctx.accounts.client.key(),
-        ctx.accounts.freelancer.key(),
-        ctx.accounts.observer.key(),
-    ];
-    Multisig::init(multisig, owners, 2)?;
-
-    //initialize data acct
-    let project_info_account = &mut ctx.accounts.project_info_account;
-    project_info_account.client = ctx.accounts.client.key();
-    project_info_account.freelancer = ctx.accounts.freelancer.key();
-    project_info_account.total_project_funds = total_project_funds;
-    project_info_account.milestones = milestones;
-    project_info_account.multisig = multisig.key();
-    project_info_account.milestones_reached = 0;
-    project_info_account.milestone_funds_withdrawn = 0;
-
-    //transfer funds and lock funds from client
-    system_program::transfer(
-        ctx.accounts.transfer_funds_from_client(),
-        total_project_funds,
-    )?;
+    )
+}
 
+///set up a new project the way every path creating one does: the multisig of its
+///client, freelancer and observer, the project offered on `milestone_amounts`, the
+///escrow funded with their sum through `funding`, and the reputations of the client
+///and the freelancer if they don't have one yet. `actor` opened the project
+pub fn open_project<'info>(
+    project_info_account: &mut Account<'info, ProjectInfo>,
+    multisig: &mut Account<'info, Multisig>,
+    [client, freelancer, observer]: [Pubkey; 3],
+    reputations: [&mut Account<'info, Reputation>; 2],
+    milestone_amounts: Vec<u64>,
+    funding: CpiContext<'_, '_, '_, 'info, Transfer<'info>>,
+    actor: Pubkey,
+) -> Result<()> {
+    let [client_reputation, freelancer_reputation] = reputations;
+    for (reputation, wallet) in [&mut *client_reputation, freelancer_reputation]
+        .into_iter()
+        .zip([client, freelancer])
+    {
+        if reputation.open(wallet) {
+            emit!(events::ReputationCreated {
+                wallet,
+                actor,
+                timestamp: Clock::get()?.unix_timestamp,
+            });
+        }
+    }
+    let nonce = client_reputation.open_project();
+    Multisig::init(multisig, vec![client, freelancer, observer], 2)?;
+    project_info_account.init(client, freelancer, multisig.key(), nonce, milestone_amounts)?;
+
+    //transfer funds and lock funds
+    let total_project_funds = project_info_account.total_project_funds;
+    system_program::transfer(funding, total_project_funds)?;
+
+    emit!(events::ProjectInitialized {
+        project: project_info_account.key(),
+        actor,
+        client,
+        freelancer,
+        observer,
+        total_project_funds,
+        milestones: project_info_account.milestones,
+        timestamp: Clock::get()?.unix_timestamp,
+    });
     Ok(())
 }
 
 // update the state of the project
 //The only way this can be invoked
 // is via a recursive call from execute_transaction -> start_project.
-pub fn start_project(ctx: Context<MultisigAuth>) -> Result<()> {
+// a project asking for a bond can't start before the freelancer posted it
+pub fn start_project(ctx: Context<StartProjectContext>) -> Result<()> {
+    Bond::require_posted(&ctx.accounts.bond)?;
     let project_info = &mut ctx.accounts.project_info_account;
-    project_info.status = ProjectStatus::Running.to_code();
+    //offers are started by the freelancer accepting them
+    project_info.require_status(&[ProjectStatus::Pending])?;
+    project_info.transition(ProjectStatus::Running)?;
+
+    emit!(events::ProjectStarted {
+        project: project_info.key(),
+        actor: ctx.accounts.multisig_signer.key(),
+        timestamp: Clock::get()?.unix_timestamp,
+    });
     Ok(())
 }
 // mark current milestone as completed
@@ -54,7 +106,28 @@ pub fn start_project(ctx: Context<MultisigAuth>) -> Result<()> {
 // is via a recursive call from execute_transaction -> start_project.
 pub fn mark_current_milestone_completed(ctx: Context<MultisigAuth>) -> Result<()> {
     let project_info = &mut ctx.accounts.project_info_account;
-    project_info.milestones_reached += 1;
+    project_info.require_status(&[ProjectStatus::Running])?;
+    require!(
+        project_info.milestones_reached < project_info.milestones,
+        ErrorCode::AllMilestonesReached
+    );
+    let milestone_index = project_info.milestones_reached;
+    let amount = *project_info
+        .milestone_amounts
+        .get(milestone_index as usize)
+        .ok_or(ErrorCode::InvalidMilestones)?;
+    project_info.milestones_reached = project_info
+        .milestones_reached
+        .checked_add(1)
+        .ok_or(ErrorCode::Overflow)?;
+
+    emit!(events::MilestoneReached {
+        project: project_info.key(),
+        actor: ctx.accounts.multisig_signer.key(),
+        milestone_index,
+        amount,
+        timestamp: Clock::get()?.unix_timestamp,
+    });
     Ok(())
 }
 // withdraw funds for completed milestones
@@ -63,29 +136,52 @@ pub fn mark_current_milestone_completed(ctx: Context<MultisigAuth>) -> Result<()
 // is via a recursive call from execute_transaction -> start_project.
 pub fn withdraw_milestone_funds(ctx: Context<WithdrawMilestoneFundsContext>) -> Result<()> {
     let project_info = &mut ctx.accounts.project_info_account;
+    //a stopped project still pays out the milestones reached before it got stopped
+    project_info.require_status(&[ProjectStatus::Running, ProjectStatus::Cancelled])?;
+    require!(
+        ctx.accounts.payee_splits.data_is_empty(),
+        ErrorCode::SplitPayoutsOnly
+    );
 
-    let amount_per_milestone = project_info
-        .total_project_funds
-        .checked_div(project_info.milestones.into())
-        .unwrap();
-
-    let milestone_funds_withdrawn_so_far = project_info.milestone_funds_withdrawn;
-    let milestones_not_withdrawn = project_info
-        .milestones_reached
-        .checked_sub(milestone_funds_withdrawn_so_far)
-        .unwrap();
-
-    let mut amount_to_withdraw = amount_per_milestone
-        .checked_mul(milestones_not_withdrawn.into())
-        .unwrap();
-
-    let escrow_balance = ctx.accounts.token_escrow.lamports();
-    if project_info.milestones_reached == project_info.milestones {
-        amount_to_withdraw = escrow_balance;
-        project_info.status = ProjectStatus::Completed.to_code();
+    let payout = if project_info.status == ProjectStatus::Cancelled {
+        require!(
+            project_info.milestones_reached > project_info.milestone_funds_withdrawn,
+            ErrorCode::NothingToWithdraw
+        );
+        payout::MilestonePayout {
+            amount: payout::earned_funds(
+                &project_info.milestone_amounts,
+                &project_info.milestone_funds_streamed,
+                project_info.milestone_funds_withdrawn,
+                project_info.milestones_reached,
+            )?,
+            completed: false,
+        }
+    } else {
+        payout::milestone_payout(
+            &project_info.milestone_amounts,
+            &project_info.milestone_funds_streamed,
+            project_info.milestone_funds_withdrawn,
+            project_info.milestones_reached,
+            ctx.accounts.token_escrow.lamports(),
+        )?
+    };
+    if payout.completed {
+        let now = Clock::get()?.unix_timestamp;
+        let on_time = Bond::deadline_met(&ctx.accounts.bond, now, project_info)?;
+        project_info.transition(ProjectStatus::Completed)?;
+        Reputation::record_completion(
+            &ctx.accounts.client_reputation,
+            &ctx.accounts.freelancer_reputation,
+            project_info.total_project_funds,
+            on_time,
+        )?;
     }
 
-    let bump = *ctx.bumps.get("token_escrow").unwrap();
+    let bump = *ctx
+        .bumps
+        .get("token_escrow")
+        .ok_or(ErrorCode::BumpNotFound)?;
     let project_info_key = ctx.accounts.project_info_account.clone().key();
 
     let signer_seed: &[&[&[u8]]] = &[&[b"token_escrow", project_info_key.as_ref(), &[bump]]];
@@ -94,19 +190,143 @@ pub fn withdraw_milestone_funds(ctx: Context<WithdrawMilestoneFundsContext>) ->
         ctx.accounts
             .transfer_funds_to_freelancer()
             .with_signer(signer_seed),
-        amount_to_withdraw,
+        payout.amount,
     )?;
 
-    ctx.accounts.project_info_account.milestone_funds_withdrawn += 1;
+    let project_info = &mut ctx.accounts.project_info_account;
+    project_info.milestone_funds_withdrawn = project_info.milestones_reached;
+
+    emit!(events::FundsWithdrawn {
+        project: project_info.key(),
+        actor: ctx.accounts.freelancer.key(),
+        milestone_index: project_info.milestones_reached - 1,
+        amount: payout.amount,
+        completed: project_info.status == ProjectStatus::Completed,
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+    Ok(())
+}
+
+// put a running project on hold
+//The only way this can be invoked
+// is via a recursive call from execute_transaction -> pause_project.
+pub fn pause_project(ctx: Context<MultisigAuth>) -> Result<()> {
+    let now = Clock::get()?.unix_timestamp;
+    ctx.accounts.project_info_account.pause(now)?;
+
+    emit!(events::ProjectPaused {
+        project: ctx.accounts.project_info_account.key(),
+        actor: ctx.accounts.multisig_signer.key(),
+        timestamp: now,
+    });
+    Ok(())
+}
+// resume a paused project
+//The only way this can be invoked
+// is via a recursive call from execute_transaction -> resume_project.
+// a subcontract frozen by its parent can't be resumed until it's thawed
+pub fn resume_project(ctx: Context<ResumeProjectContext>) -> Result<()> {
+    let subcontract = &ctx.accounts.subcontract;
+    if !subcontract.data_is_empty() {
+        let subcontract = Subcontract::try_deserialize(&mut &subcontract.try_borrow_data()?[..])?;
+        require!(!subcontract.frozen, ErrorCode::SubcontractFrozen);
+    }
+    let now = Clock::get()?.unix_timestamp;
+    let paused_for = ctx.accounts.project_info_account.resume(now)?;
+
+    emit!(events::ProjectResumed {
+        project: ctx.accounts.project_info_account.key(),
+        actor: ctx.accounts.multisig_signer.key(),
+        paused_for,
+        timestamp: now,
+    });
     Ok(())
 }
 
-///collective effort to stop the project regardless of the current state
-pub fn stop_project(ctx: Context<MultisigAuth>) -> Result<()> {
-    ctx.accounts.project_info_account.status = ProjectStatus::Cancelled.to_code();
+// change order: add funds to the escrow and/or append or resize milestones
+//The only way this can be invoked
+// is via a recursive call from execute_transaction -> change_order.
+// the client has to sign the executing transaction so the additional funds can be moved
+pub fn change_order(
+    ctx: Context<ChangeOrderContext>,
+    additional_funds: u64,
+    milestone_amounts: Vec<u64>,
+) -> Result<()> {
+    ctx.accounts
+        .project_info_account
+        .require_status(&[
+            ProjectStatus::Offered,
+            ProjectStatus::Pending,
+            ProjectStatus::Running,
+        ])?;
+
+    let total_project_funds = ctx
+        .accounts
+        .project_info_account
+        .total_project_funds
+        .checked_add(additional_funds)
+        .ok_or(ErrorCode::Overflow)?;
+    ctx.accounts
+        .project_info_account
+        .apply_change_order(total_project_funds, milestone_amounts)?;
+
+    if additional_funds > 0 {
+        system_program::transfer(ctx.accounts.transfer_funds_from_client(), additional_funds)?;
+    }
+
+    emit!(events::ChangeOrderApplied {
+        project: ctx.accounts.project_info_account.key(),
+        actor: ctx.accounts.client.key(),
+        additional_funds,
+        total_project_funds,
+        milestones: ctx.accounts.project_info_account.milestones,
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+    Ok(())
+}
+
+///collective effort to stop the project at any point before it gets completed.
+///stopping it over a dispute rules it in favour of one party, a ruling against
+///the freelancer forfeits their bond like forfeit_bond does
+pub fn stop_project(ctx: Context<MultisigRulingAuth>, ruling: Ruling) -> Result<()> {
+    ctx.accounts
+        .project_info_account
+        .transition(ProjectStatus::Cancelled)?;
+    let project = ctx.accounts.project_info_account.key();
+    let actor = ctx.accounts.multisig_signer.key();
+    let now = Clock::get()?.unix_timestamp;
+    let client_reputation = &ctx.accounts.client_reputation;
+    let freelancer_reputation = &ctx.accounts.freelancer_reputation;
+    match ruling {
+        Ruling::None => {}
+        Ruling::Client => {
+            Reputation::record_ruling(client_reputation, freelancer_reputation)?;
+            //otherwise release_bond would hand it back to the freelancer
+            let client = ctx.accounts.client.to_account_info();
+            if let Some(bond) = Bond::forfeit(&ctx.accounts.bond, &client)? {
+                emit!(events::BondForfeited {
+                    project,
+                    actor,
+                    amount: bond.amount,
+                    client: bond.client,
+                    missed_deadline: false,
+                    timestamp: now,
+                });
+            }
+        }
+        Ruling::Freelancer => Reputation::record_ruling(freelancer_reputation, client_reputation)?,
+    }
+
+    emit!(events::ProjectStopped {
+        project,
+        actor,
+        ruling,
+        timestamp: now,
+    });
     Ok(())
 }
-///stop the project before it gets started(project status changes to running)
+///stop the project before it gets started(project status changes to running),
+///also withdraws an offer the freelancer hasn't accepted
 pub fn cancel_project(ctx: Context<StopProjectContext>) -> Result<()> {
     //transfer funds back to client
     require!(
@@ -114,30 +334,77 @@ pub fn cancel_project(ctx: Context<StopProjectContext>) -> Result<()> {
         ErrorCode::ClientOnly
     );
     require!(
-        ProjectStatus::from(ctx.accounts.project_info_account.status).unwrap()
-            == ProjectStatus::Pending
-            || ProjectStatus::from(ctx.accounts.project_info_account.status).unwrap()
-                == ProjectStatus::Cancelled,
-        ErrorCode::InvalidStatus
+        ctx.accounts.subcontract.data_is_empty(),
+        ErrorCode::SubcontractRefundsToParent
     );
+    ctx.accounts
+        .project_info_account
+        .require_status(&[
+            ProjectStatus::Offered,
+            ProjectStatus::Pending,
+            ProjectStatus::Cancelled,
+        ])?;
+
+    //the parties of a project that ran can still rate each other once
+    //the project account is gone, withdrawn offers leave no ratings
+    if ctx.accounts.project_info_account.require_rateable().is_ok() {
+        ctx.accounts.open_ratings(&ctx.bumps)?;
+    }
 
-    let bump = *ctx.bumps.get("token_escrow").unwrap();
-    let project_info_account = ctx.accounts.project_info_account.key().clone();
+    //withdrawn offers and projects that never started don't count
+    let project_info = &ctx.accounts.project_info_account;
+    let mut earned = 0;
+    if project_info.status == ProjectStatus::Cancelled {
+        Reputation::record_cancellation(
+            &ctx.accounts.client_reputation,
+            &ctx.accounts.freelancer_reputation,
+        )?;
+        //milestones reached before the project got stopped still belong to the freelancer
+        earned = payout::earned_funds(
+            &project_info.milestone_amounts,
+            &project_info.milestone_funds_streamed,
+            project_info.milestone_funds_withdrawn,
+            project_info.milestones_reached,
+        )?;
+        require!(
+            earned == 0 || ctx.accounts.payee_splits.data_is_empty(),
+            ErrorCode::SharesNotWithdrawn
+        );
+    }
+
+    let bump = *ctx
+        .bumps
+        .get("token_escrow")
+        .ok_or(ErrorCode::BumpNotFound)?;
+    let project_info_account = ctx.accounts.project_info_account.key();
 
     let signer_seed: &[&[&[u8]]] = &[&[b"token_escrow", project_info_account.as_ref(), &[bump]]];
-    let transfer_accounts = system_program::Transfer {
-        from: ctx.accounts.token_escrow.to_account_info().clone(),
-        to: ctx.accounts.client.to_account_info().clone(),
-    };
-    let transfer_ctx = CpiContext::new(
-        ctx.accounts.system_program.to_account_info(),
-        transfer_accounts,
-    );
+    if earned > 0 {
+        let to_freelancer = ctx.accounts.freelancer.to_account_info();
+        system_program::transfer(
+            ctx.accounts
+                .transfer_from_escrow(to_freelancer)
+                .with_signer(signer_seed),
+            earned,
+        )?;
+    }
+    let to_client = ctx.accounts.client.to_account_info();
+    let amount = ctx.accounts.token_escrow.lamports();
     system_program::transfer(
-        transfer_ctx.with_signer(signer_seed),
-        ctx.accounts.token_escrow.lamports(),
+        ctx.accounts
+            .transfer_from_escrow(to_client)
+            .with_signer(signer_seed),
+        amount,
     )?;
 
+    emit!(events::ProjectCancelled {
+        project: project_info_account,
+        actor: ctx.accounts.client.key(),
+        amount,
+        freelancer: ctx.accounts.freelancer.key(),
+        earned,
+        timestamp: Clock::get()?.unix_timestamp,
+    });
     Ok(())
 }
 #[derive(Accounts)]
@@ -179,10 +446,28 @@ pub struct InitializeProjectContext<'info> {
     #[account(mut)]
     client: Signer<'info>,
     system_program: Program<'info, System>,
+    #[account(
+        init_if_needed,
+        payer = client,
+        space = 8 + Reputation::MAX_SIZE,
+        seeds = [b"reputation", client.key().as_ref()],
+        bump,
+    )]
+    client_reputation: Box<Account<'info, Reputation>>,
+    #[account(
+        init_if_needed,
+        payer = client,
+        space = 8 + Reputation::MAX_SIZE,
+        seeds = [b"reputation", freelancer.key().as_ref()],
+        bump,
+    )]
+    freelancer_reputation: Box<Account<'info, Reputation>>,
 }
 
 impl<'info> InitializeProjectContext<'info> {
-    pub fn transfer_funds_from_client(&self) -> CpiContext<'_, '_, '_, 'info, Transfer<'info>> {
+    pub fn transfer_funds_from_client<'a, 'b, 'c>(
+        &self,
+    ) -> CpiContext<'a, 'b, 'c, 'info, Transfer<'info>> {
         let transfer_acct = Transfer {
             from: self.client.to_account_info().clone(),
             to: self.token_escrow.to_account_info().clone(),
@@ -196,6 +481,7 @@ pub struct StopProjectContext<'info> {
     #[account(
         mut,
         close = client,
+        constraint = project_info_account.is_current() @ ErrorCode::AccountNotMigrated,
         seeds = [
             b"project_info_account",
             client.key().as_ref(),
@@ -214,16 +500,112 @@ pub struct StopProjectContext<'info> {
         bump
     )]
     token_escrow: AccountInfo<'info>,
-    /// CHECK:
+    /// CHECK: paid the milestones reached before the project got stopped
+    #[account(mut)]
     freelancer: AccountInfo<'info>,
     #[account(mut)]
     client: Signer<'info>,
     system_program: Program<'info, System>,
+    /// CHECK: only read to make sure the project isn't a subcontract
+    #[account(
+        seeds = [b"subcontract", project_info_account.key().as_ref()],
+        bump
+    )]
+    subcontract: UncheckedAccount<'info>,
+    /// CHECK: updated when a stopped project is closed, if the client has a reputation account
+    #[account(
+        mut,
+        seeds = [b"reputation", project_info_account.client.as_ref()],
+        bump
+    )]
+    client_reputation: UncheckedAccount<'info>,
+    /// CHECK: updated when a stopped project is closed, if the freelancer has a reputation account
+    #[account(
+        mut,
+        seeds = [b"reputation", project_info_account.freelancer.as_ref()],
+        bump
+    )]
+    freelancer_reputation: UncheckedAccount<'info>,
+    /// CHECK: only read, payees withdraw their shares of a stopped project themselves
+    #[account(
+        seeds = [b"payee_splits", project_info_account.key().as_ref()],
+        bump
+    )]
+    payee_splits: UncheckedAccount<'info>,
+    /// CHECK: created by the handler, only when the project ran
+    #[account(
+        mut,
+        seeds = [
+            b"rating",
+            project_info_account.key().as_ref(),
+            &project_info_account.nonce.to_le_bytes(),
+            project_info_account.client.as_ref(),
+        ],
+        bump,
+    )]
+    client_rating: UncheckedAccount<'info>,
+    /// CHECK: created by the handler, only when the project ran
+    #[account(
+        mut,
+        seeds = [
+            b"rating",
+            project_info_account.key().as_ref(),
+            &project_info_account.nonce.to_le_bytes(),
+            project_info_account.freelancer.as_ref(),
+        ],
+        bump,
+    )]
+    freelancer_rating: UncheckedAccount<'info>,
+}
+impl<'info> StopProjectContext<'info> {
+    ///open both ratings unrated, the client pays for them.
+    ///one a party already gave while the project was open is left as it is
+    pub fn open_ratings(&self, bumps: &BTreeMap<String, u8>) -> Result<()> {
+        let project = self.project_info_account.key();
+        let nonce = self.project_info_account.nonce.to_le_bytes();
+        let [client, freelancer] = [self.client.key(), self.freelancer.key()];
+        for (name, rating, rater, ratee) in [
+            ("client_rating", &self.client_rating, client, freelancer),
+            ("freelancer_rating", &self.freelancer_rating, freelancer, client),
+        ] {
+            if !rating.data_is_empty() {
+                continue;
+            }
+            let bump = *bumps.get(name).ok_or(ErrorCode::BumpNotFound)?;
+            create_pda_account(
+                rating,
+                &self.client.to_account_info(),
+                &self.system_program.to_account_info(),
+                8 + Rating::MAX_SIZE,
+                &[b"rating", project.as_ref(), &nonce, rater.as_ref(), &[bump]],
+            )?;
+            let state = Rating {
+                project,
+                rater,
+                ratee,
+                stars: 0,
+                version: Rating::VERSION,
+            };
+            state.try_serialize(&mut &mut rating.try_borrow_mut_data()?[..])?;
+        }
+        Ok(())
+    }
+
+    pub fn transfer_from_escrow(
+        &self,
+        to: AccountInfo<'info>,
+    ) -> CpiContext<'_, '_, '_, 'info, Transfer<'info>> {
+        let transfer_acct = Transfer {
+            from: self.token_escrow.to_account_info(),
+            to,
+        };
+        CpiContext::new(self.system_program.to_account_info(), transfer_acct)
+    }
 }
 
 #[derive(Accounts)]
 pub struct MultisigAuth<'info> {
-    #[account(mut)]
+    #[account(mut, constraint = project_info_account.is_current() @ ErrorCode::AccountNotMigrated)]
     project_info_account: Box<Account<'info, ProjectInfo>>,
     #[account(
         seeds = [b"multisig", project_info_account.key().as_ref()],
@@ -233,8 +615,110 @@ pub struct MultisigAuth<'info> {
 }
 
 #[derive(Accounts)]
-pub struct WithdrawMilestoneFundsContext<'info> {
+pub struct MultisigRulingAuth<'info> {
+    #[account(mut, constraint = project_info_account.is_current() @ ErrorCode::AccountNotMigrated)]
+    project_info_account: Box<Account<'info, ProjectInfo>>,
+    #[account(
+        seeds = [b"multisig", project_info_account.key().as_ref()],
+        bump,
+    )]
+    multisig_signer: Signer<'info>,
+    /// CHECK: updated by a ruling, if the client has a reputation account
+    #[account(
+        mut,
+        seeds = [b"reputation", project_info_account.client.as_ref()],
+        bump
+    )]
+    client_reputation: UncheckedAccount<'info>,
+    /// CHECK: updated by a ruling, if the freelancer has a reputation account
+    #[account(
+        mut,
+        seeds = [b"reputation", project_info_account.freelancer.as_ref()],
+        bump
+    )]
+    freelancer_reputation: UncheckedAccount<'info>,
+    /// CHECK: forfeited by a ruling against the freelancer, if one was posted
+    #[account(
+        mut,
+        seeds = [b"bond", project_info_account.key().as_ref()],
+        bump
+    )]
+    bond: UncheckedAccount<'info>,
+    /// CHECK: gets a forfeited bond and its rent, checked against the project
+    #[account(mut, address = project_info_account.client)]
+    client: AccountInfo<'info>,
+}
+
+#[derive(Accounts)]
+pub struct StartProjectContext<'info> {
+    #[account(mut, constraint = project_info_account.is_current() @ ErrorCode::AccountNotMigrated)]
+    project_info_account: Box<Account<'info, ProjectInfo>>,
+    #[account(
+        seeds = [b"multisig", project_info_account.key().as_ref()],
+        bump,
+    )]
+    multisig_signer: Signer<'info>,
+    /// CHECK: only read to check a required bond was posted
+    #[account(
+        seeds = [b"bond", project_info_account.key().as_ref()],
+        bump
+    )]
+    bond: UncheckedAccount<'info>,
+}
+
+#[derive(Accounts)]
+pub struct ResumeProjectContext<'info> {
+    #[account(mut, constraint = project_info_account.is_current() @ ErrorCode::AccountNotMigrated)]
+    project_info_account: Box<Account<'info, ProjectInfo>>,
+    #[account(
+        seeds = [b"multisig", project_info_account.key().as_ref()],
+        bump,
+    )]
+    multisig_signer: Signer<'info>,
+    /// CHECK: only read to check a subcontract isn't frozen
+    #[account(
+        seeds = [b"subcontract", project_info_account.key().as_ref()],
+        bump
+    )]
+    subcontract: UncheckedAccount<'info>,
+}
+
+#[derive(Accounts)]
+pub struct ChangeOrderContext<'info> {
+    #[account(mut, has_one = client, constraint = project_info_account.is_current() @ ErrorCode::AccountNotMigrated)]
+    project_info_account: Box<Account<'info, ProjectInfo>>,
+    #[account(
+        seeds = [b"multisig", project_info_account.key().as_ref()],
+        bump,
+    )]
+    multisig_signer: Signer<'info>,
+    /// CHECK:
+    #[account(
+        mut,
+        seeds = [
+            b"token_escrow",
+            project_info_account.key().as_ref()
+        ],
+        bump
+    )]
+    token_escrow: AccountInfo<'info>,
     #[account(mut)]
+    client: Signer<'info>,
+    system_program: Program<'info, System>,
+}
+impl<'info> ChangeOrderContext<'info> {
+    pub fn transfer_funds_from_client(&self) -> CpiContext<'_, '_, '_, 'info, Transfer<'info>> {
+        let transfer_acct = Transfer {
+            from: self.client.to_account_info(),
+            to: self.token_escrow.to_account_info(),
+        };
+        CpiContext::new(self.system_program.to_account_info(), transfer_acct)
+    }
+}
+
+#[derive(Accounts)]
+pub struct WithdrawMilestoneFundsContext<'info> {
+    #[account(mut, constraint = project_info_account.is_current() @ ErrorCode::AccountNotMigrated)]
     project_info_account: Box<Account<'info, ProjectInfo>>,
     /// CHECK:
     #[account(
@@ -253,6 +737,32 @@ pub struct WithdrawMilestoneFundsContext<'info> {
     )]
     freelancer: Signer<'info>,
     system_program: Program<'info, System>,
+    /// CHECK: only read to make sure the project doesn't share its payouts
+    #[account(
+        seeds = [b"payee_splits", project_info_account.key().as_ref()],
+        bump
+    )]
+    payee_splits: UncheckedAccount<'info>,
+    /// CHECK: only read to check the bond deadline once the project completes
+    #[account(
+        seeds = [b"bond", project_info_account.key().as_ref()],
+        bump
+    )]
+    bond: UncheckedAccount<'info>,
+    /// CHECK: updated once the project completes, when the client has a reputation account
+    #[account(
+        mut,
+        seeds = [b"reputation", project_info_account.client.as_ref()],
+        bump
+    )]
+    client_reputation: UncheckedAccount<'info>,
+    /// CHECK: updated once the project completes, when the freelancer has a reputation account
+    #[account(
+        mut,
+        seeds = [b"reputation", project_info_account.freelancer.as_ref()],
+        bump
+    )]
+    freelancer_reputation: UncheckedAccount<'info>,
 }
 impl<'info> WithdrawMilestoneFundsContext<'info> {
     pub fn transfer_funds_to_freelancer(&self) -> CpiContext<'_, '_, '_, 'info, Transfer<'info>> {
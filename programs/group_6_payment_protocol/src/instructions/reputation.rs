@@ -0,0 +1,116 @@
+use crate::errors::ErrorCode;
+use crate::events;
+use crate::state::{ProjectInfo, Rating, Reputation, Versioned};
+use anchor_lang::prelude::*;
+
+///start tracking the reputation of `wallet` before its first project, anyone can pay for it.
+///opening a project creates it for both parties otherwise
+pub fn create_reputation(ctx: Context<CreateReputationContext>) -> Result<()> {
+    let reputation = &mut ctx.accounts.reputation;
+    reputation.wallet = ctx.accounts.wallet.key();
+    reputation.version = Reputation::VERSION;
+
+    emit!(events::ReputationCreated {
+        wallet: ctx.accounts.wallet.key(),
+        actor: ctx.accounts.payer.key(),
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+    Ok(())
+}
+
+///one party rates the other once a project that ran is completed or cancelled,
+///the rating account makes sure it happens once per party and project.
+///a project closed by cancel_project is rated through the ratings it opened,
+///the account itself is gone or holds a later project of the same parties
+pub fn rate_party(ctx: Context<RatePartyContext>, stars: u8, nonce: u32) -> Result<()> {
+    require!(
+        (1..=Rating::MAX_STARS).contains(&stars),
+        ErrorCode::InvalidRating
+    );
+    let project = ctx.accounts.project_info_account.key();
+    let rater = ctx.accounts.rater.key();
+    let ratee = ctx.accounts.ratee.key();
+
+    let rating = &mut ctx.accounts.rating;
+    match ProjectInfo::load_unless_closed(&ctx.accounts.project_info_account)? {
+        Some(project_info) if project_info.nonce == nonce => {
+            project_info.require_rateable()?;
+            require!(
+                (rater == project_info.client && ratee == project_info.freelancer)
+                    || (rater == project_info.freelancer && ratee == project_info.client),
+                ErrorCode::InvalidRating
+            );
+            rating.open(project, rater, ratee);
+        }
+        //a new account is still zeroed, closing the project didn't open it
+        _ => require!(
+            rating.project != Pubkey::default(),
+            ErrorCode::InvalidRating
+        ),
+    }
+    require!(rating.stars == 0, ErrorCode::AlreadyRated);
+    require!(rating.ratee == ratee, ErrorCode::InvalidRating);
+    rating.stars = stars;
+    Reputation::update(&ctx.accounts.ratee_reputation, |reputation| {
+        reputation.ratings = reputation.ratings.saturating_add(1);
+        reputation.rating_total = reputation.rating_total.saturating_add(stars.into());
+    })?;
+
+    emit!(events::PartyRated {
+        project,
+        actor: rater,
+        ratee,
+        stars,
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct CreateReputationContext<'info> {
+    #[account(
+        init,
+        payer = payer,
+        space = 8 + Reputation::MAX_SIZE,
+        seeds = [b"reputation", wallet.key().as_ref()],
+        bump,
+    )]
+    reputation: Account<'info, Reputation>,
+    /// CHECK: any wallet
+    wallet: AccountInfo<'info>,
+    #[account(mut)]
+    payer: Signer<'info>,
+    system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(stars: u8, nonce: u32)]
+pub struct RatePartyContext<'info> {
+    /// CHECK: loaded unless closed, a closed project is bound to the rating instead
+    project_info_account: UncheckedAccount<'info>,
+    #[account(
+        init_if_needed,
+        payer = rater,
+        space = 8 + Rating::MAX_SIZE,
+        seeds = [
+            b"rating",
+            project_info_account.key().as_ref(),
+            &nonce.to_le_bytes(),
+            rater.key().as_ref(),
+        ],
+        bump,
+    )]
+    rating: Box<Account<'info, Rating>>,
+    #[account(mut)]
+    rater: Signer<'info>,
+    /// CHECK: the other party, checked against the project
+    ratee: AccountInfo<'info>,
+    /// CHECK: updated when the ratee has a reputation account
+    #[account(
+        mut,
+        seeds = [b"reputation", ratee.key().as_ref()],
+        bump
+    )]
+    ratee_reputation: UncheckedAccount<'info>,
+    system_program: Program<'info, System>,
+}
@@ -0,0 +1,383 @@
+use crate::errors::ErrorCode;
+use crate::events;
+use crate::instructions::project::open_project;
+use crate::payout;
+use crate::state::{Bond, Multisig, ProjectInfo, ProjectStatus, Reputation, Retainer, Versioned};
+use anchor_lang::prelude::*;
+use anchor_lang::system_program;
+use anchor_lang::system_program::Transfer;
+
+///open a retainer: a project with one milestone per period, all of them funded upfront.
+///a period unlocks at the end of each interval from `start` while the project runs,
+///the freelancer accepts it and it's run through the multisig like any other
+pub fn initialize_retainer(
+    ctx: Context<InitializeRetainerContext>,
+    period_amount: u64,
+    periods: u8,
+    start: i64,
+    interval: i64,
+    notice_period: i64,
+) -> Result<()> {
+    require!(
+        interval > 0 && notice_period >= 0,
+        ErrorCode::InvalidRetainer
+    );
+    require!(
+        periods > 0 && periods as usize <= ProjectInfo::MAX_MILESTONES,
+        ErrorCode::InvalidMilestones
+    );
+
+    let funding = ctx.accounts.transfer_funds_from_client();
+    open_project(
+        &mut ctx.accounts.project_info_account,
+        &mut ctx.accounts.multisig,
+        [
+            ctx.accounts.client.key(),
+            ctx.accounts.freelancer.key(),
+            ctx.accounts.observer.key(),
+        ],
+        [
+            &mut ctx.accounts.client_reputation,
+            &mut ctx.accounts.freelancer_reputation,
+        ],
+        vec![period_amount; periods as usize],
+        funding,
+        ctx.accounts.client.key(),
+    )?;
+
+    let retainer = &mut ctx.accounts.retainer;
+    retainer.project = ctx.accounts.project_info_account.key();
+    retainer.start = start;
+    retainer.interval = interval;
+    retainer.notice_period = notice_period;
+    retainer.terminates_at = 0;
+    retainer.version = Retainer::VERSION;
+
+    let now = Clock::get()?.unix_timestamp;
+    emit!(events::RetainerInitialized {
+        project: ctx.accounts.project_info_account.key(),
+        actor: ctx.accounts.client.key(),
+        period_amount,
+        periods,
+        start,
+        interval,
+        notice_period,
+        timestamp: now,
+    });
+    Ok(())
+}
+
+///mark the periods whose interval has passed as reached, anyone can crank it.
+///the freelancer then withdraws them with withdraw_milestone_funds
+pub fn unlock_retainer_periods(ctx: Context<UnlockRetainerPeriodsContext>) -> Result<()> {
+    let project_info = &mut ctx.accounts.project_info_account;
+    project_info.require_status(&[ProjectStatus::Running])?;
+
+    let now = Clock::get()?.unix_timestamp;
+    let retainer = &ctx.accounts.retainer;
+    let unlocked = payout::retainer_periods_unlocked(
+        retainer.start,
+        retainer.interval,
+        project_info.milestones,
+        retainer.schedule_time(now, project_info),
+    )?;
+    require!(
+        unlocked > project_info.milestones_reached,
+        ErrorCode::NoPeriodDue
+    );
+    reach_milestones(project_info, unlocked, ctx.accounts.caller.key(), now)
+}
+
+///either party gives notice, the retainer ends once `notice_period` has passed
+///and the periods that haven't unlocked by then go back to the client
+pub fn give_retainer_notice(ctx: Context<GiveRetainerNoticeContext>) -> Result<()> {
+    ctx.accounts
+        .project_info_account
+        .require_status(&[ProjectStatus::Running, ProjectStatus::Paused])?;
+    let retainer = &mut ctx.accounts.retainer;
+    require!(retainer.terminates_at == 0, ErrorCode::NoticeAlreadyGiven);
+
+    let now = Clock::get()?.unix_timestamp;
+    retainer.terminates_at = now
+        .checked_add(retainer.notice_period)
+        .ok_or(ErrorCode::Overflow)?;
+
+    emit!(events::RetainerNoticeGiven {
+        project: ctx.accounts.project_info_account.key(),
+        actor: ctx.accounts.party.key(),
+        terminates_at: retainer.terminates_at,
+        timestamp: now,
+    });
+    Ok(())
+}
+
+///end a retainer whose notice period is over: the periods unlocked by then are kept,
+///the rest is refunded to the client and the retainer account is closed.
+///anyone can settle it, the freelancer withdraws the kept periods as usual
+pub fn settle_retainer(ctx: Context<SettleRetainerContext>) -> Result<()> {
+    let now = Clock::get()?.unix_timestamp;
+    let retainer = &ctx.accounts.retainer;
+    require!(
+        retainer.terminates_at != 0 && now >= retainer.terminates_at,
+        ErrorCode::NoticePeriodRunning
+    );
+    let project_info = &mut ctx.accounts.project_info_account;
+    project_info.require_status(&[ProjectStatus::Running])?;
+
+    let unlocked = payout::retainer_periods_unlocked(
+        retainer.start,
+        retainer.interval,
+        project_info.milestones,
+        retainer.schedule_time(now, project_info),
+    )?;
+    let kept = unlocked.max(project_info.milestones_reached);
+    let mut refund = payout::unused_milestone_funds(
+        &project_info.milestone_amounts,
+        &project_info.milestone_funds_streamed,
+        kept,
+    )?;
+
+    reach_milestones(project_info, kept, ctx.accounts.caller.key(), now)?;
+    let kept_amounts = project_info.milestone_amounts[..kept as usize].to_vec();
+    project_info.milestone_funds_streamed.truncate(kept as usize);
+    project_info.total_project_funds = kept_amounts
+        .iter()
+        .try_fold(0u64, |acc, amount| acc.checked_add(*amount))
+        .ok_or(ErrorCode::Overflow)?;
+    project_info.milestones = kept;
+    project_info.milestone_amounts = kept_amounts;
+    if kept == project_info.milestone_funds_withdrawn {
+        //nothing left for the freelancer, whatever is in the escrow goes back
+        refund = ctx.accounts.token_escrow.lamports();
+        let on_time = Bond::deadline_met(&ctx.accounts.bond, now, project_info)?;
+        project_info.transition(match kept {
+            0 => ProjectStatus::Cancelled,
+            _ => ProjectStatus::Completed,
+        })?;
+        //cancelled retainers are counted once the client closes them
+        if project_info.status == ProjectStatus::Completed {
+            Reputation::record_completion(
+                &ctx.accounts.client_reputation,
+                &ctx.accounts.freelancer_reputation,
+                project_info.total_project_funds,
+                on_time,
+            )?;
+        }
+    }
+
+    if refund > 0 {
+        let bump = *ctx
+            .bumps
+            .get("token_escrow")
+            .ok_or(ErrorCode::BumpNotFound)?;
+        let project_info_key = ctx.accounts.project_info_account.key();
+        let signer_seed: &[&[&[u8]]] =
+            &[&[b"token_escrow", project_info_key.as_ref(), &[bump]]];
+        system_program::transfer(
+            ctx.accounts.refund_client().with_signer(signer_seed),
+            refund,
+        )?;
+    }
+
+    emit!(events::RetainerSettled {
+        project: ctx.accounts.project_info_account.key(),
+        actor: ctx.accounts.caller.key(),
+        periods: kept,
+        refund,
+        client: ctx.accounts.client.key(),
+        timestamp: now,
+    });
+    Ok(())
+}
+
+///mark every milestone before `reached` as reached, one event each
+fn reach_milestones(
+    project_info: &mut Account<ProjectInfo>,
+    reached: u8,
+    actor: Pubkey,
+    now: i64,
+) -> Result<()> {
+    for milestone_index in project_info.milestones_reached..reached {
+        emit!(events::MilestoneReached {
+            project: project_info.key(),
+            actor,
+            milestone_index,
+            amount: project_info.milestone_amounts[milestone_index as usize],
+            timestamp: now,
+        });
+    }
+    project_info.milestones_reached = project_info.milestones_reached.max(reached);
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct InitializeRetainerContext<'info> {
+    #[account(
+        init,
+        payer = client,
+        space = 8 + ProjectInfo::MAX_SIZE,
+        seeds = [
+            b"project_info_account",
+            client.key().as_ref(),
+            freelancer.key().as_ref(),
+        ],
+        bump,
+    )]
+    project_info_account: Box<Account<'info, ProjectInfo>>,
+    #[account(
+        init,
+        payer = client,
+        space = 8 + Multisig::MAX_SIZE,
+        seeds = [b"multisig", project_info_account.key().as_ref()],
+        bump,
+    )]
+    multisig: Box<Account<'info, Multisig>>,
+    #[account(
+        init,
+        payer = client,
+        space = 8 + Retainer::MAX_SIZE,
+        seeds = [b"retainer", project_info_account.key().as_ref()],
+        bump,
+    )]
+    retainer: Box<Account<'info, Retainer>>,
+    /// CHECK:
+    #[account(
+        mut,
+        seeds = [
+            b"token_escrow",
+            project_info_account.key().as_ref()
+        ],
+        bump
+    )]
+    token_escrow: AccountInfo<'info>,
+    /// CHECK:
+    freelancer: AccountInfo<'info>,
+    /// CHECK:
+    observer: AccountInfo<'info>,
+    #[account(mut)]
+    client: Signer<'info>,
+    system_program: Program<'info, System>,
+    #[account(
+        init_if_needed,
+        payer = client,
+        space = 8 + Reputation::MAX_SIZE,
+        seeds = [b"reputation", client.key().as_ref()],
+        bump,
+    )]
+    client_reputation: Box<Account<'info, Reputation>>,
+    #[account(
+        init_if_needed,
+        payer = client,
+        space = 8 + Reputation::MAX_SIZE,
+        seeds = [b"reputation", freelancer.key().as_ref()],
+        bump,
+    )]
+    freelancer_reputation: Box<Account<'info, Reputation>>,
+}
+impl<'info> InitializeRetainerContext<'info> {
+    pub fn transfer_funds_from_client<'a, 'b, 'c>(
+        &self,
+    ) -> CpiContext<'a, 'b, 'c, 'info, Transfer<'info>> {
+        let transfer_acct = Transfer {
+            from: self.client.to_account_info(),
+            to: self.token_escrow.to_account_info(),
+        };
+        CpiContext::new(self.system_program.to_account_info(), transfer_acct)
+    }
+}
+
+#[derive(Accounts)]
+pub struct UnlockRetainerPeriodsContext<'info> {
+    #[account(
+        mut,
+        constraint = project_info_account.is_current() @ ErrorCode::AccountNotMigrated,
+    )]
+    project_info_account: Box<Account<'info, ProjectInfo>>,
+    #[account(
+        seeds = [b"retainer", project_info_account.key().as_ref()],
+        bump,
+        constraint = retainer.is_current() @ ErrorCode::AccountNotMigrated,
+    )]
+    retainer: Account<'info, Retainer>,
+    caller: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct GiveRetainerNoticeContext<'info> {
+    #[account(constraint = project_info_account.is_current() @ ErrorCode::AccountNotMigrated)]
+    project_info_account: Box<Account<'info, ProjectInfo>>,
+    #[account(
+        mut,
+        seeds = [b"retainer", project_info_account.key().as_ref()],
+        bump,
+        constraint = retainer.is_current() @ ErrorCode::AccountNotMigrated,
+    )]
+    retainer: Account<'info, Retainer>,
+    #[account(
+        constraint = party.key() == project_info_account.client
+            || party.key() == project_info_account.freelancer @ ErrorCode::PartiesOnly
+    )]
+    party: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct SettleRetainerContext<'info> {
+    #[account(
+        mut,
+        has_one = client,
+        constraint = project_info_account.is_current() @ ErrorCode::AccountNotMigrated,
+    )]
+    project_info_account: Box<Account<'info, ProjectInfo>>,
+    #[account(
+        mut,
+        close = client,
+        seeds = [b"retainer", project_info_account.key().as_ref()],
+        bump,
+        constraint = retainer.is_current() @ ErrorCode::AccountNotMigrated,
+    )]
+    retainer: Account<'info, Retainer>,
+    /// CHECK:
+    #[account(
+        mut,
+        seeds = [
+            b"token_escrow",
+            project_info_account.key().as_ref()
+        ],
+        bump
+    )]
+    token_escrow: AccountInfo<'info>,
+    /// CHECK: gets the refund and the retainer rent back, checked against the project
+    #[account(mut)]
+    client: AccountInfo<'info>,
+    caller: Signer<'info>,
+    system_program: Program<'info, System>,
+    /// CHECK: only read to check the bond deadline once the project completes
+    #[account(
+        seeds = [b"bond", project_info_account.key().as_ref()],
+        bump
+    )]
+    bond: UncheckedAccount<'info>,
+    /// CHECK: updated once the project completes, when the client has a reputation account
+    #[account(
+        mut,
+        seeds = [b"reputation", project_info_account.client.as_ref()],
+        bump
+    )]
+    client_reputation: UncheckedAccount<'info>,
+    /// CHECK: updated once the project completes, when the freelancer has a reputation account
+    #[account(
+        mut,
+        seeds = [b"reputation", project_info_account.freelancer.as_ref()],
+        bump
+    )]
+    freelancer_reputation: UncheckedAccount<'info>,
+}
+impl<'info> SettleRetainerContext<'info> {
+    pub fn refund_client(&self) -> CpiContext<'_, '_, '_, 'info, Transfer<'info>> {
+        let transfer_acct = Transfer {
+            from: self.token_escrow.to_account_info(),
+            to: self.client.to_account_info(),
+        };
+        CpiContext::new(self.system_program.to_account_info(), transfer_acct)
+    }
+}
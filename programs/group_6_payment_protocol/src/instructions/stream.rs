@@ -0,0 +1,195 @@
+use crate::errors::ErrorCode;
+use crate::events;
+use crate::payout;
+use crate::state::{MilestoneStream, ProjectInfo, ProjectStatus, Versioned};
+use anchor_lang::prelude::*;
+use anchor_lang::system_program;
+use anchor_lang::system_program::Transfer;
+
+// pay a milestone out over time instead of in one go when it's reached
+//The only way this can be invoked
+// is via a recursive call from execute_transaction -> set_milestone_stream.
+// the client has to sign the executing transaction, it pays for the stream account
+pub fn set_milestone_stream(
+    ctx: Context<SetMilestoneStreamContext>,
+    milestone_index: u8,
+    start: i64,
+    cliff: i64,
+    end: i64,
+) -> Result<()> {
+    let project_info = &ctx.accounts.project_info_account;
+    project_info.require_status(&[
+        ProjectStatus::Offered,
+        ProjectStatus::Pending,
+        ProjectStatus::Running,
+    ])?;
+    require!(
+        milestone_index < project_info.milestones,
+        ErrorCode::InvalidMilestones
+    );
+    require!(
+        milestone_index >= project_info.milestones_reached,
+        ErrorCode::MilestoneAlreadyReached
+    );
+    payout::check_stream_schedule(start, cliff, end)?;
+
+    let stream = &mut ctx.accounts.milestone_stream;
+    stream.project = project_info.key();
+    stream.milestone_index = milestone_index;
+    stream.start = start;
+    stream.cliff = cliff;
+    stream.end = end;
+    stream.paused_offset = project_info.paused_duration;
+    stream.version = MilestoneStream::VERSION;
+
+    emit!(events::MilestoneStreamSet {
+        project: project_info.key(),
+        actor: ctx.accounts.client.key(),
+        milestone_index,
+        start,
+        cliff,
+        end,
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+    Ok(())
+}
+
+// withdraw the vested share of a streamed milestone,
+// the rest is paid out when the milestone is withdrawn
+pub fn withdraw_streamed_funds(ctx: Context<WithdrawStreamedFundsContext>) -> Result<()> {
+    let project_info = &ctx.accounts.project_info_account;
+    let stream = &ctx.accounts.milestone_stream;
+    project_info.require_status(&[ProjectStatus::Running])?;
+    require!(
+        ctx.accounts.payee_splits.data_is_empty(),
+        ErrorCode::SplitPayoutsOnly
+    );
+    let index = stream.milestone_index as usize;
+    //once reached, what the stream hasn't paid goes out with the milestone payout
+    require!(
+        stream.milestone_index >= project_info.milestones_reached,
+        ErrorCode::NothingToWithdraw
+    );
+    let (amount, streamed) = project_info
+        .milestone_amounts
+        .get(index)
+        .zip(project_info.milestone_funds_streamed.get(index))
+        .ok_or(ErrorCode::InvalidMilestones)?;
+
+    let now = Clock::get()?.unix_timestamp;
+    let vested = payout::vested_amount(
+        *amount,
+        stream.start,
+        stream.cliff,
+        stream.end,
+        stream.schedule_time(now, project_info.paused_duration),
+    )?;
+    let available = vested.saturating_sub(*streamed);
+    require!(available > 0, ErrorCode::NothingToWithdraw);
+
+    let bump = *ctx
+        .bumps
+        .get("token_escrow")
+        .ok_or(ErrorCode::BumpNotFound)?;
+    let project_info_key = project_info.key();
+    let signer_seed: &[&[&[u8]]] = &[&[b"token_escrow", project_info_key.as_ref(), &[bump]]];
+    system_program::transfer(
+        ctx.accounts
+            .transfer_funds_to_freelancer()
+            .with_signer(signer_seed),
+        available,
+    )?;
+
+    let total_streamed = streamed.checked_add(available).ok_or(ErrorCode::Overflow)?;
+    ctx.accounts.project_info_account.milestone_funds_streamed[index] = total_streamed;
+
+    emit!(events::StreamedFundsWithdrawn {
+        project: project_info_key,
+        actor: ctx.accounts.freelancer.key(),
+        milestone_index: stream.milestone_index,
+        amount: available,
+        total_streamed,
+        timestamp: now,
+    });
+    Ok(())
+}
+
+#[derive(Accounts)]
+#[instruction(milestone_index: u8)]
+pub struct SetMilestoneStreamContext<'info> {
+    #[account(
+        has_one = client,
+        constraint = project_info_account.is_current() @ ErrorCode::AccountNotMigrated,
+    )]
+    project_info_account: Box<Account<'info, ProjectInfo>>,
+    #[account(
+        seeds = [b"multisig", project_info_account.key().as_ref()],
+        bump,
+    )]
+    multisig_signer: Signer<'info>,
+    #[account(
+        init,
+        payer = client,
+        space = 8 + MilestoneStream::MAX_SIZE,
+        seeds = [
+            b"milestone_stream",
+            project_info_account.key().as_ref(),
+            &[milestone_index],
+        ],
+        bump,
+    )]
+    milestone_stream: Account<'info, MilestoneStream>,
+    #[account(mut)]
+    client: Signer<'info>,
+    system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct WithdrawStreamedFundsContext<'info> {
+    #[account(
+        mut,
+        constraint = project_info_account.is_current() @ ErrorCode::AccountNotMigrated,
+    )]
+    project_info_account: Box<Account<'info, ProjectInfo>>,
+    #[account(
+        seeds = [
+            b"milestone_stream",
+            project_info_account.key().as_ref(),
+            &[milestone_stream.milestone_index],
+        ],
+        bump,
+        constraint = milestone_stream.is_current() @ ErrorCode::AccountNotMigrated,
+    )]
+    milestone_stream: Account<'info, MilestoneStream>,
+    /// CHECK:
+    #[account(
+        mut,
+        seeds = [
+            b"token_escrow",
+            project_info_account.key().as_ref()
+        ],
+        bump
+    )]
+    token_escrow: AccountInfo<'info>,
+    #[account(
+        mut,
+        constraint = project_info_account.freelancer == freelancer.key()
+    )]
+    freelancer: Signer<'info>,
+    system_program: Program<'info, System>,
+    /// CHECK: only checked to be empty, a project paying several payees shares every payout
+    #[account(
+        seeds = [b"payee_splits", project_info_account.key().as_ref()],
+        bump
+    )]
+    payee_splits: UncheckedAccount<'info>,
+}
+impl<'info> WithdrawStreamedFundsContext<'info> {
+    pub fn transfer_funds_to_freelancer(&self) -> CpiContext<'_, '_, '_, 'info, Transfer<'info>> {
+        let transfer_acct = Transfer {
+            from: self.token_escrow.to_account_info(),
+            to: self.freelancer.to_account_info(),
+        };
+        CpiContext::new(self.system_program.to_account_info(), transfer_acct)
+    }
+}
@@ -0,0 +1,477 @@
+use crate::errors::ErrorCode;
+use crate::events;
+use crate::instructions::project::open_project;
+use crate::payout;
+use crate::state::{Multisig, ProjectInfo, ProjectStatus, Reputation, Subcontract, Versioned};
+use anchor_lang::prelude::*;
+use anchor_lang::system_program;
+use anchor_lang::system_program::Transfer;
+
+///open a child project for part of the work, the parent's freelancer is its client.
+///the child is funded from the parent's unreached milestones, recorded in
+///`milestone_funds_streamed` so the parent pays that much less when they're reached.
+///executed through the parent multisig, the parent's client signs the executing
+///transaction and pays for the child accounts
+pub fn create_subcontract(
+    ctx: Context<CreateSubcontractContext>,
+    total_project_funds: u64,
+    milestones: u8,
+) -> Result<()> {
+    let parent = &mut ctx.accounts.parent;
+    parent.require_status(&[ProjectStatus::Running])?;
+    require!(
+        ctx.accounts.parent_payee_splits.data_is_empty(),
+        ErrorCode::SplitPayoutsOnly
+    );
+    let streamed = payout::draw_from_milestones(
+        &parent.milestone_amounts,
+        &parent.milestone_funds_streamed,
+        parent.milestones_reached,
+        total_project_funds,
+    )?;
+    let drawn = streamed
+        .iter()
+        .zip(&parent.milestone_funds_streamed)
+        .map(|(after, before)| after - before)
+        .collect();
+    parent.milestone_funds_streamed = streamed;
+
+    let bump = *ctx
+        .bumps
+        .get("parent_escrow")
+        .ok_or(ErrorCode::BumpNotFound)?;
+    let parent_key = ctx.accounts.parent.key();
+    let signer_seed: &[&[&[u8]]] = &[&[b"token_escrow", parent_key.as_ref(), &[bump]]];
+    let funding = ctx
+        .accounts
+        .transfer_funds_from_parent()
+        .with_signer(signer_seed);
+    open_project(
+        &mut ctx.accounts.project_info_account,
+        &mut ctx.accounts.multisig,
+        [
+            ctx.accounts.client.key(),
+            ctx.accounts.freelancer.key(),
+            ctx.accounts.observer.key(),
+        ],
+        [
+            &mut ctx.accounts.client_reputation,
+            &mut ctx.accounts.freelancer_reputation,
+        ],
+        ProjectInfo::split_funds(total_project_funds, milestones)?,
+        funding,
+        ctx.accounts.parent_multisig.key(),
+    )?;
+
+    let subcontract = &mut ctx.accounts.subcontract;
+    subcontract.parent = parent_key;
+    subcontract.parent_client = ctx.accounts.parent.client;
+    subcontract.drawn = drawn;
+    subcontract.frozen = false;
+    subcontract.paused_by_freeze = false;
+    subcontract.version = Subcontract::VERSION;
+
+    emit!(events::SubcontractCreated {
+        project: ctx.accounts.project_info_account.key(),
+        actor: ctx.accounts.parent_multisig.key(),
+        parent: parent_key,
+        amount: total_project_funds,
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+    Ok(())
+}
+
+///freeze a child whose parent is paused or cancelled, anyone can crank it.
+///a running child gets paused, and it can't be resumed until the subcontract is thawed
+pub fn freeze_subcontract(ctx: Context<FreezeSubcontractContext>) -> Result<()> {
+    let parent = ProjectInfo::load_unless_closed(&ctx.accounts.parent)?;
+    //a closed parent was cancelled
+    let parent_status = parent.map_or(ProjectStatus::Cancelled, |parent| parent.status);
+    require!(
+        matches!(
+            parent_status,
+            ProjectStatus::Paused | ProjectStatus::Cancelled
+        ),
+        ErrorCode::ParentNotDisputed
+    );
+    let subcontract = &mut ctx.accounts.subcontract;
+    require!(!subcontract.frozen, ErrorCode::SubcontractFrozen);
+
+    let now = Clock::get()?.unix_timestamp;
+    let project_info = &mut ctx.accounts.project_info_account;
+    project_info.require_status(&[ProjectStatus::Running, ProjectStatus::Paused])?;
+    subcontract.paused_by_freeze = project_info.status == ProjectStatus::Running;
+    if subcontract.paused_by_freeze {
+        project_info.pause(now)?;
+    }
+    subcontract.frozen = true;
+
+    emit!(events::SubcontractFrozen {
+        project: project_info.key(),
+        actor: ctx.accounts.caller.key(),
+        parent: subcontract.parent,
+        timestamp: now,
+    });
+    Ok(())
+}
+
+///thaw a frozen child once its parent runs again or is completed, anyone can crank it.
+///a child the freeze paused is resumed
+pub fn thaw_subcontract(ctx: Context<ThawSubcontractContext>) -> Result<()> {
+    require!(
+        matches!(
+            ctx.accounts.parent.status,
+            ProjectStatus::Running | ProjectStatus::Completed
+        ),
+        ErrorCode::ParentStillDisputed
+    );
+    let subcontract = &mut ctx.accounts.subcontract;
+    require!(subcontract.frozen, ErrorCode::SubcontractNotFrozen);
+
+    let now = Clock::get()?.unix_timestamp;
+    let project_info = &mut ctx.accounts.project_info_account;
+    let mut paused_for = 0;
+    if subcontract.paused_by_freeze && project_info.status == ProjectStatus::Paused {
+        paused_for = project_info.resume(now)?;
+    }
+    subcontract.frozen = false;
+    subcontract.paused_by_freeze = false;
+
+    emit!(events::SubcontractThawed {
+        project: project_info.key(),
+        actor: ctx.accounts.caller.key(),
+        parent: subcontract.parent,
+        paused_for,
+        timestamp: now,
+    });
+    Ok(())
+}
+
+///cancel_project for a child: what's left in its escrow goes back to the parent's
+///unreached milestones it was drawn from, the rest to the parent's freelancer.
+///once the parent is closed everything goes to the parent's client
+pub fn cancel_subcontract(ctx: Context<CancelSubcontractContext>) -> Result<()> {
+    ctx.accounts.project_info_account.require_status(&[
+        ProjectStatus::Offered,
+        ProjectStatus::Pending,
+        ProjectStatus::Cancelled,
+    ])?;
+
+    //children that never started don't count
+    let child = &ctx.accounts.project_info_account;
+    let mut earned = 0;
+    if child.status == ProjectStatus::Cancelled {
+        Reputation::record_cancellation(
+            &ctx.accounts.client_reputation,
+            &ctx.accounts.freelancer_reputation,
+        )?;
+        //milestones reached before the child got stopped still belong to its freelancer
+        earned = payout::earned_funds(
+            &child.milestone_amounts,
+            &child.milestone_funds_streamed,
+            child.milestone_funds_withdrawn,
+            child.milestones_reached,
+        )?;
+        require!(
+            earned == 0 || ctx.accounts.payee_splits.data_is_empty(),
+            ErrorCode::SharesNotWithdrawn
+        );
+    }
+
+    let bump = *ctx
+        .bumps
+        .get("token_escrow")
+        .ok_or(ErrorCode::BumpNotFound)?;
+    let project_info_key = ctx.accounts.project_info_account.key();
+    let signer_seed: &[&[&[u8]]] = &[&[b"token_escrow", project_info_key.as_ref(), &[bump]]];
+    if earned > 0 {
+        let to_freelancer = ctx.accounts.freelancer.to_account_info();
+        system_program::transfer(
+            ctx.accounts
+                .transfer_from_escrow(to_freelancer)
+                .with_signer(signer_seed),
+            earned,
+        )?;
+    }
+
+    let amount = ctx.accounts.token_escrow.lamports();
+    let parent_info = ctx.accounts.parent.to_account_info();
+    let mut returned_to_parent = 0;
+    let recipient = match ProjectInfo::load_unless_closed(&parent_info)? {
+        Some(mut parent) => {
+            let (streamed, returned) = payout::return_to_milestones(
+                &parent.milestone_funds_streamed,
+                &ctx.accounts.subcontract.drawn,
+                parent.milestones_reached,
+                amount,
+            );
+            parent.milestone_funds_streamed = streamed;
+            parent.try_serialize(&mut &mut parent_info.try_borrow_mut_data()?[..])?;
+            returned_to_parent = returned;
+            ctx.accounts.client.to_account_info()
+        }
+        None => ctx.accounts.parent_client.to_account_info(),
+    };
+    let refund = amount - returned_to_parent;
+
+    if returned_to_parent > 0 {
+        let to_parent = ctx.accounts.parent_escrow.to_account_info();
+        system_program::transfer(
+            ctx.accounts
+                .transfer_from_escrow(to_parent)
+                .with_signer(signer_seed),
+            returned_to_parent,
+        )?;
+    }
+    if refund > 0 {
+        system_program::transfer(
+            ctx.accounts
+                .transfer_from_escrow(recipient.clone())
+                .with_signer(signer_seed),
+            refund,
+        )?;
+    }
+
+    emit!(events::SubcontractCancelled {
+        project: project_info_key,
+        actor: ctx.accounts.client.key(),
+        parent: ctx.accounts.subcontract.parent,
+        returned_to_parent,
+        refund,
+        recipient: recipient.key(),
+        freelancer: ctx.accounts.freelancer.key(),
+        earned,
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct CreateSubcontractContext<'info> {
+    #[account(
+        mut,
+        constraint = parent.freelancer == client.key(),
+        constraint = parent.is_current() @ ErrorCode::AccountNotMigrated,
+    )]
+    parent: Box<Account<'info, ProjectInfo>>,
+    /// CHECK:
+    #[account(
+        mut,
+        seeds = [
+            b"token_escrow",
+            parent.key().as_ref()
+        ],
+        bump
+    )]
+    parent_escrow: AccountInfo<'info>,
+    /// CHECK: only read to make sure the parent doesn't share its payouts
+    #[account(
+        seeds = [b"payee_splits", parent.key().as_ref()],
+        bump
+    )]
+    parent_payee_splits: UncheckedAccount<'info>,
+    #[account(
+        seeds = [b"multisig", parent.key().as_ref()],
+        bump,
+    )]
+    parent_multisig: Signer<'info>,
+    ///the parent's funds only leave its escrow with its client's consent
+    #[account(
+        mut,
+        constraint = parent.client == parent_client.key(),
+    )]
+    parent_client: Signer<'info>,
+    #[account(
+        init,
+        payer = parent_client,
+        space = 8 + ProjectInfo::MAX_SIZE,
+        seeds = [
+            b"project_info_account",
+            client.key().as_ref(),
+            freelancer.key().as_ref(),
+        ],
+        bump,
+    )]
+    project_info_account: Box<Account<'info, ProjectInfo>>,
+    #[account(
+        init,
+        payer = parent_client,
+        space = 8 + Multisig::MAX_SIZE,
+        seeds = [b"multisig", project_info_account.key().as_ref()],
+        bump,
+    )]
+    multisig: Box<Account<'info, Multisig>>,
+    #[account(
+        init,
+        payer = parent_client,
+        space = 8 + Subcontract::MAX_SIZE,
+        seeds = [b"subcontract", project_info_account.key().as_ref()],
+        bump,
+    )]
+    subcontract: Box<Account<'info, Subcontract>>,
+    /// CHECK:
+    #[account(
+        mut,
+        seeds = [
+            b"token_escrow",
+            project_info_account.key().as_ref()
+        ],
+        bump
+    )]
+    token_escrow: AccountInfo<'info>,
+    /// CHECK:
+    freelancer: AccountInfo<'info>,
+    /// CHECK:
+    observer: AccountInfo<'info>,
+    /// CHECK: the parent's freelancer
+    client: AccountInfo<'info>,
+    system_program: Program<'info, System>,
+    #[account(
+        init_if_needed,
+        payer = parent_client,
+        space = 8 + Reputation::MAX_SIZE,
+        seeds = [b"reputation", client.key().as_ref()],
+        bump,
+    )]
+    client_reputation: Box<Account<'info, Reputation>>,
+    #[account(
+        init_if_needed,
+        payer = parent_client,
+        space = 8 + Reputation::MAX_SIZE,
+        seeds = [b"reputation", freelancer.key().as_ref()],
+        bump,
+    )]
+    freelancer_reputation: Box<Account<'info, Reputation>>,
+}
+impl<'info> CreateSubcontractContext<'info> {
+    pub fn transfer_funds_from_parent<'a, 'b, 'c>(
+        &self,
+    ) -> CpiContext<'a, 'b, 'c, 'info, Transfer<'info>> {
+        let transfer_acct = Transfer {
+            from: self.parent_escrow.to_account_info(),
+            to: self.token_escrow.to_account_info(),
+        };
+        CpiContext::new(self.system_program.to_account_info(), transfer_acct)
+    }
+}
+
+#[derive(Accounts)]
+pub struct FreezeSubcontractContext<'info> {
+    #[account(mut, constraint = project_info_account.is_current() @ ErrorCode::AccountNotMigrated)]
+    project_info_account: Box<Account<'info, ProjectInfo>>,
+    #[account(
+        mut,
+        seeds = [b"subcontract", project_info_account.key().as_ref()],
+        bump,
+        constraint = subcontract.is_current() @ ErrorCode::AccountNotMigrated,
+    )]
+    subcontract: Account<'info, Subcontract>,
+    /// CHECK: may have been closed by cancel_project, read in the handler
+    #[account(address = subcontract.parent)]
+    parent: UncheckedAccount<'info>,
+    caller: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct ThawSubcontractContext<'info> {
+    #[account(mut, constraint = project_info_account.is_current() @ ErrorCode::AccountNotMigrated)]
+    project_info_account: Box<Account<'info, ProjectInfo>>,
+    #[account(
+        mut,
+        seeds = [b"subcontract", project_info_account.key().as_ref()],
+        bump,
+        constraint = subcontract.is_current() @ ErrorCode::AccountNotMigrated,
+    )]
+    subcontract: Account<'info, Subcontract>,
+    #[account(
+        address = subcontract.parent,
+        constraint = parent.is_current() @ ErrorCode::AccountNotMigrated,
+    )]
+    parent: Box<Account<'info, ProjectInfo>>,
+    caller: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct CancelSubcontractContext<'info> {
+    #[account(
+        mut,
+        close = parent_client,
+        has_one = client @ ErrorCode::ClientOnly,
+        constraint = project_info_account.is_current() @ ErrorCode::AccountNotMigrated,
+    )]
+    project_info_account: Box<Account<'info, ProjectInfo>>,
+    /// CHECK:
+    #[account(
+        mut,
+        seeds = [
+            b"token_escrow",
+            project_info_account.key().as_ref()
+        ],
+        bump
+    )]
+    token_escrow: AccountInfo<'info>,
+    #[account(
+        mut,
+        close = parent_client,
+        seeds = [b"subcontract", project_info_account.key().as_ref()],
+        bump,
+        constraint = subcontract.is_current() @ ErrorCode::AccountNotMigrated,
+    )]
+    subcontract: Account<'info, Subcontract>,
+    /// CHECK: may have been closed by cancel_project, read and written in the handler
+    #[account(mut, address = subcontract.parent)]
+    parent: UncheckedAccount<'info>,
+    /// CHECK:
+    #[account(
+        mut,
+        seeds = [
+            b"token_escrow",
+            subcontract.parent.as_ref()
+        ],
+        bump
+    )]
+    parent_escrow: AccountInfo<'info>,
+    /// CHECK: gets back the rent it paid for the child, and the refund once the parent
+    /// is closed. checked against the subcontract
+    #[account(mut, address = subcontract.parent_client)]
+    parent_client: AccountInfo<'info>,
+    ///the parent's freelancer
+    #[account(mut)]
+    client: Signer<'info>,
+    system_program: Program<'info, System>,
+    /// CHECK: updated when a stopped project is closed, if the client has a reputation account
+    #[account(
+        mut,
+        seeds = [b"reputation", project_info_account.client.as_ref()],
+        bump
+    )]
+    client_reputation: UncheckedAccount<'info>,
+    /// CHECK: updated when a stopped project is closed, if the freelancer has a reputation account
+    #[account(
+        mut,
+        seeds = [b"reputation", project_info_account.freelancer.as_ref()],
+        bump
+    )]
+    freelancer_reputation: UncheckedAccount<'info>,
+    /// CHECK: the child's freelancer, paid the milestones reached before the child got stopped
+    #[account(mut, address = project_info_account.freelancer)]
+    freelancer: AccountInfo<'info>,
+    /// CHECK: only read, payees withdraw their shares of a stopped child themselves
+    #[account(
+        seeds = [b"payee_splits", project_info_account.key().as_ref()],
+        bump
+    )]
+    payee_splits: UncheckedAccount<'info>,
+}
+impl<'info> CancelSubcontractContext<'info> {
+    pub fn transfer_from_escrow(
+        &self,
+        to: AccountInfo<'info>,
+    ) -> CpiContext<'_, '_, '_, 'info, Transfer<'info>> {
+        let transfer_acct = Transfer {
+            from: self.token_escrow.to_account_info(),
+            to,
+        };
+        CpiContext::new(self.system_program.to_account_info(), transfer_acct)
+    }
+}
@@ -0,0 +1,335 @@
+use crate::errors::ErrorCode;
+use crate::events;
+use crate::payout;
+use crate::state::{
+    HourlyTerms, ProjectInfo, ProjectStatus, Timesheet, TimesheetStatus, Versioned,
+};
+use anchor_lang::prelude::*;
+use anchor_lang::system_program;
+use anchor_lang::system_program::Transfer;
+
+// bill the project by the hour, timesheets are paid out of the milestones not reached yet
+//The only way this can be invoked
+// is via a recursive call from execute_transaction -> set_hourly_terms.
+// the client has to sign the executing transaction, it pays for the terms account
+pub fn set_hourly_terms(
+    ctx: Context<SetHourlyTermsContext>,
+    hourly_rate: u64,
+    weekly_cap: u32,
+    approval_window: i64,
+    start: i64,
+) -> Result<()> {
+    let project_info = &ctx.accounts.project_info_account;
+    project_info.require_status(&[
+        ProjectStatus::Offered,
+        ProjectStatus::Pending,
+        ProjectStatus::Running,
+    ])?;
+    require!(
+        hourly_rate > 0 && weekly_cap > 0 && approval_window > 0,
+        ErrorCode::InvalidHourlyTerms
+    );
+
+    let terms = &mut ctx.accounts.hourly_terms;
+    terms.project = project_info.key();
+    terms.hourly_rate = hourly_rate;
+    terms.weekly_cap = weekly_cap;
+    terms.approval_window = approval_window;
+    terms.start = start;
+    terms.version = HourlyTerms::VERSION;
+
+    emit!(events::HourlyTermsSet {
+        project: project_info.key(),
+        actor: ctx.accounts.client.key(),
+        hourly_rate,
+        weekly_cap,
+        approval_window,
+        start,
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+    Ok(())
+}
+
+///bill `hours` worked during `week`, a disputed timesheet can be submitted again
+pub fn submit_timesheet(
+    ctx: Context<SubmitTimesheetContext>,
+    week: u32,
+    hours: u32,
+    memo_hash: [u8; 32],
+) -> Result<()> {
+    let project_info = &ctx.accounts.project_info_account;
+    project_info.require_status(&[ProjectStatus::Running])?;
+    let terms = &ctx.accounts.hourly_terms;
+    let now = Clock::get()?.unix_timestamp;
+    let week_start = terms.week_start(week).ok_or(ErrorCode::Overflow)?;
+    require!(now >= week_start, ErrorCode::WeekNotStarted);
+    require!(hours <= terms.weekly_cap, ErrorCode::WeeklyCapExceeded);
+
+    let timesheet = &mut ctx.accounts.timesheet;
+    //a new account is still zeroed
+    require!(
+        timesheet.project == Pubkey::default() || timesheet.status == TimesheetStatus::Disputed,
+        ErrorCode::TimesheetAlreadySubmitted
+    );
+    timesheet.project = project_info.key();
+    timesheet.week = week;
+    timesheet.hours = hours;
+    timesheet.memo_hash = memo_hash;
+    timesheet.submitted_at = now;
+    timesheet.paused_offset = project_info.paused_duration;
+    timesheet.status = TimesheetStatus::Submitted;
+    timesheet.version = Timesheet::VERSION;
+
+    emit!(events::TimesheetSubmitted {
+        project: project_info.key(),
+        actor: ctx.accounts.freelancer.key(),
+        week,
+        hours,
+        memo_hash,
+        timestamp: now,
+    });
+    Ok(())
+}
+
+pub fn approve_timesheet(ctx: Context<AnswerTimesheetContext>) -> Result<()> {
+    ctx.accounts
+        .project_info_account
+        .require_status(&[ProjectStatus::Running, ProjectStatus::Paused])?;
+    let timesheet = &mut ctx.accounts.timesheet;
+    require!(
+        timesheet.status == TimesheetStatus::Submitted,
+        ErrorCode::TimesheetNotPending
+    );
+    timesheet.status = TimesheetStatus::Approved;
+
+    emit!(events::TimesheetApproved {
+        project: ctx.accounts.project_info_account.key(),
+        actor: ctx.accounts.client.key(),
+        week: timesheet.week,
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+    Ok(())
+}
+
+///reject a timesheet before its approval window is over
+pub fn dispute_timesheet(ctx: Context<AnswerTimesheetContext>) -> Result<()> {
+    let project_info = &ctx.accounts.project_info_account;
+    project_info.require_status(&[ProjectStatus::Running, ProjectStatus::Paused])?;
+    let timesheet = &mut ctx.accounts.timesheet;
+    require!(
+        timesheet.status == TimesheetStatus::Submitted,
+        ErrorCode::TimesheetNotPending
+    );
+    let now = Clock::get()?.unix_timestamp;
+    require!(
+        !timesheet.auto_approved(ctx.accounts.hourly_terms.approval_window, now, project_info),
+        ErrorCode::ApprovalWindowOver
+    );
+    timesheet.status = TimesheetStatus::Disputed;
+
+    emit!(events::TimesheetDisputed {
+        project: project_info.key(),
+        actor: ctx.accounts.client.key(),
+        week: timesheet.week,
+        timestamp: now,
+    });
+    Ok(())
+}
+
+///pay an approved timesheet, or one the client left unanswered past the approval window,
+///at `hourly_rate * hours`
+pub fn withdraw_timesheet_funds(ctx: Context<WithdrawTimesheetFundsContext>) -> Result<()> {
+    let project_info = &ctx.accounts.project_info_account;
+    project_info.require_status(&[ProjectStatus::Running])?;
+    require!(
+        ctx.accounts.payee_splits.data_is_empty(),
+        ErrorCode::SplitPayoutsOnly
+    );
+    let terms = &ctx.accounts.hourly_terms;
+    let timesheet = &ctx.accounts.timesheet;
+    let now = Clock::get()?.unix_timestamp;
+    let auto_approved =
+        timesheet.auto_approved(terms.approval_window, now, project_info);
+    require!(
+        timesheet.status == TimesheetStatus::Approved || auto_approved,
+        ErrorCode::TimesheetNotApproved
+    );
+
+    let amount = terms
+        .hourly_rate
+        .checked_mul(timesheet.hours.into())
+        .ok_or(ErrorCode::Overflow)?;
+    let streamed = payout::draw_from_milestones(
+        &project_info.milestone_amounts,
+        &project_info.milestone_funds_streamed,
+        project_info.milestones_reached,
+        amount,
+    )?;
+
+    let bump = *ctx
+        .bumps
+        .get("token_escrow")
+        .ok_or(ErrorCode::BumpNotFound)?;
+    let project_info_key = project_info.key();
+    let signer_seed: &[&[&[u8]]] = &[&[b"token_escrow", project_info_key.as_ref(), &[bump]]];
+    system_program::transfer(
+        ctx.accounts
+            .transfer_funds_to_freelancer()
+            .with_signer(signer_seed),
+        amount,
+    )?;
+
+    ctx.accounts.project_info_account.milestone_funds_streamed = streamed;
+    let timesheet = &mut ctx.accounts.timesheet;
+    timesheet.status = TimesheetStatus::Paid;
+
+    emit!(events::TimesheetPaid {
+        project: project_info_key,
+        actor: ctx.accounts.freelancer.key(),
+        week: timesheet.week,
+        amount,
+        auto_approved,
+        timestamp: now,
+    });
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct SetHourlyTermsContext<'info> {
+    #[account(
+        has_one = client,
+        constraint = project_info_account.is_current() @ ErrorCode::AccountNotMigrated,
+    )]
+    project_info_account: Box<Account<'info, ProjectInfo>>,
+    #[account(
+        seeds = [b"multisig", project_info_account.key().as_ref()],
+        bump,
+    )]
+    multisig_signer: Signer<'info>,
+    #[account(
+        init,
+        payer = client,
+        space = 8 + HourlyTerms::MAX_SIZE,
+        seeds = [b"hourly_terms", project_info_account.key().as_ref()],
+        bump,
+    )]
+    hourly_terms: Account<'info, HourlyTerms>,
+    #[account(mut)]
+    client: Signer<'info>,
+    system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(week: u32)]
+pub struct SubmitTimesheetContext<'info> {
+    #[account(constraint = project_info_account.is_current() @ ErrorCode::AccountNotMigrated)]
+    project_info_account: Box<Account<'info, ProjectInfo>>,
+    #[account(
+        seeds = [b"hourly_terms", project_info_account.key().as_ref()],
+        bump,
+        constraint = hourly_terms.is_current() @ ErrorCode::AccountNotMigrated,
+    )]
+    hourly_terms: Account<'info, HourlyTerms>,
+    #[account(
+        init_if_needed,
+        payer = freelancer,
+        space = 8 + Timesheet::MAX_SIZE,
+        seeds = [
+            b"timesheet",
+            project_info_account.key().as_ref(),
+            week.to_le_bytes().as_ref(),
+        ],
+        bump,
+    )]
+    timesheet: Account<'info, Timesheet>,
+    #[account(
+        mut,
+        constraint = project_info_account.freelancer == freelancer.key()
+    )]
+    freelancer: Signer<'info>,
+    system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct AnswerTimesheetContext<'info> {
+    #[account(
+        has_one = client,
+        constraint = project_info_account.is_current() @ ErrorCode::AccountNotMigrated,
+    )]
+    project_info_account: Box<Account<'info, ProjectInfo>>,
+    #[account(
+        seeds = [b"hourly_terms", project_info_account.key().as_ref()],
+        bump,
+        constraint = hourly_terms.is_current() @ ErrorCode::AccountNotMigrated,
+    )]
+    hourly_terms: Account<'info, HourlyTerms>,
+    #[account(
+        mut,
+        seeds = [
+            b"timesheet",
+            project_info_account.key().as_ref(),
+            timesheet.week.to_le_bytes().as_ref(),
+        ],
+        bump,
+        constraint = timesheet.is_current() @ ErrorCode::AccountNotMigrated,
+    )]
+    timesheet: Account<'info, Timesheet>,
+    client: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct WithdrawTimesheetFundsContext<'info> {
+    #[account(
+        mut,
+        constraint = project_info_account.is_current() @ ErrorCode::AccountNotMigrated,
+    )]
+    project_info_account: Box<Account<'info, ProjectInfo>>,
+    #[account(
+        seeds = [b"hourly_terms", project_info_account.key().as_ref()],
+        bump,
+        constraint = hourly_terms.is_current() @ ErrorCode::AccountNotMigrated,
+    )]
+    hourly_terms: Account<'info, HourlyTerms>,
+    #[account(
+        mut,
+        seeds = [
+            b"timesheet",
+            project_info_account.key().as_ref(),
+            timesheet.week.to_le_bytes().as_ref(),
+        ],
+        bump,
+        constraint = timesheet.is_current() @ ErrorCode::AccountNotMigrated,
+    )]
+    timesheet: Account<'info, Timesheet>,
+    /// CHECK:
+    #[account(
+        mut,
+        seeds = [
+            b"token_escrow",
+            project_info_account.key().as_ref()
+        ],
+        bump
+    )]
+    token_escrow: AccountInfo<'info>,
+    #[account(
+        mut,
+        constraint = project_info_account.freelancer == freelancer.key()
+    )]
+    freelancer: Signer<'info>,
+    system_program: Program<'info, System>,
+    /// CHECK: only checked to be empty, a project paying several payees shares every payout
+    #[account(
+        seeds = [b"payee_splits", project_info_account.key().as_ref()],
+        bump
+    )]
+    payee_splits: UncheckedAccount<'info>,
+}
+impl<'info> WithdrawTimesheetFundsContext<'info> {
+    pub fn transfer_funds_to_freelancer(&self) -> CpiContext<'_, '_, '_, 'info, Transfer<'info>> {
+        let transfer_acct = Transfer {
+            from: self.token_escrow.to_account_info(),
+            to: self.freelancer.to_account_info(),
+        };
+        CpiContext::new(self.system_program.to_account_info(), transfer_acct)
+    }
+}
@@ -0,0 +1,123 @@
+use crate::errors::ErrorCode;
+use crate::events;
+use crate::payout;
+use crate::state::{ProjectInfo, ProjectStatus, Tips, Versioned};
+use anchor_lang::prelude::*;
+use anchor_lang::system_program;
+use anchor_lang::system_program::Transfer;
+
+///the client pays the freelancer `amount` on top of the project funds, directly or
+///out of what the escrow holds beyond the milestones still to be withdrawn.
+///total_project_funds and the milestone payouts are left untouched
+pub fn tip(ctx: Context<TipContext>, amount: u64, from_escrow: bool) -> Result<()> {
+    let project_info = &ctx.accounts.project_info_account;
+    project_info.require_status(&[
+        ProjectStatus::Running,
+        ProjectStatus::Paused,
+        ProjectStatus::Completed,
+    ])?;
+    require!(amount > 0, ErrorCode::InvalidTip);
+
+    if from_escrow {
+        let owed = payout::unused_milestone_funds(
+            &project_info.milestone_amounts,
+            &project_info.milestone_funds_streamed,
+            project_info.milestone_funds_withdrawn,
+        )?;
+        let leftover = ctx.accounts.token_escrow.lamports().saturating_sub(owed);
+        require!(amount <= leftover, ErrorCode::InvalidTip);
+
+        let bump = *ctx
+            .bumps
+            .get("token_escrow")
+            .ok_or(ErrorCode::BumpNotFound)?;
+        let project_info_key = project_info.key();
+        let signer_seed: &[&[&[u8]]] = &[&[b"token_escrow", project_info_key.as_ref(), &[bump]]];
+        system_program::transfer(
+            ctx.accounts
+                .transfer_to_freelancer(ctx.accounts.token_escrow.to_account_info())
+                .with_signer(signer_seed),
+            amount,
+        )?;
+    } else {
+        system_program::transfer(
+            ctx.accounts
+                .transfer_to_freelancer(ctx.accounts.client.to_account_info()),
+            amount,
+        )?;
+    }
+
+    let tips = &mut ctx.accounts.tips;
+    //a new account is still zeroed
+    tips.project = ctx.accounts.project_info_account.key();
+    tips.version = Tips::VERSION;
+    if from_escrow {
+        tips.from_escrow = tips
+            .from_escrow
+            .checked_add(amount)
+            .ok_or(ErrorCode::Overflow)?;
+    } else {
+        tips.from_client = tips
+            .from_client
+            .checked_add(amount)
+            .ok_or(ErrorCode::Overflow)?;
+    }
+    tips.count = tips.count.checked_add(1).ok_or(ErrorCode::Overflow)?;
+
+    emit!(events::TipPaid {
+        project: tips.project,
+        actor: ctx.accounts.client.key(),
+        freelancer: ctx.accounts.freelancer.key(),
+        amount,
+        from_escrow,
+        total_tips: tips.total(),
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct TipContext<'info> {
+    #[account(
+        has_one = client @ ErrorCode::ClientOnly,
+        has_one = freelancer,
+        constraint = project_info_account.is_current() @ ErrorCode::AccountNotMigrated,
+    )]
+    project_info_account: Box<Account<'info, ProjectInfo>>,
+    #[account(
+        init_if_needed,
+        payer = client,
+        space = 8 + Tips::MAX_SIZE,
+        seeds = [b"tips", project_info_account.key().as_ref()],
+        bump,
+    )]
+    tips: Account<'info, Tips>,
+    /// CHECK:
+    #[account(
+        mut,
+        seeds = [
+            b"token_escrow",
+            project_info_account.key().as_ref()
+        ],
+        bump
+    )]
+    token_escrow: AccountInfo<'info>,
+    /// CHECK: gets the tip, checked against the project
+    #[account(mut)]
+    freelancer: AccountInfo<'info>,
+    #[account(mut)]
+    client: Signer<'info>,
+    system_program: Program<'info, System>,
+}
+impl<'info> TipContext<'info> {
+    pub fn transfer_to_freelancer(
+        &self,
+        from: AccountInfo<'info>,
+    ) -> CpiContext<'_, '_, '_, 'info, Transfer<'info>> {
+        let transfer_acct = Transfer {
+            from,
+            to: self.freelancer.to_account_info(),
+        };
+        CpiContext::new(self.system_program.to_account_info(), transfer_acct)
+    }
+}
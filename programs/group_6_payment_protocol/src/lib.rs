@@ -3,7 +3,9 @@ use anchor_lang::prelude::*;
 declare_id!("Fg6PaFpoGXkYsidMpWTK6W2BeZ7FEfcYkg476zPFsLnS");
 
 pub mod errors;
+pub mod events;
 pub mod instructions;
+pub mod payout;
 pub mod state;
 pub mod utils;
 
@@ -26,13 +28,22 @@ pub mod group_6_payment_protocol {
     // update the state of the project
     //The only way this can be invoked
     // is via a recursive call from execute_transaction -> start_project.
-    pub fn start_project(ctx: Context<MultisigAuth>) -> Result<()> {
+    pub fn start_project(ctx: Context<StartProjectContext>) -> Result<()> {
         instructions::project::start_project(ctx)
     }
-    ///stop the project before it gets started(project status changes to running)
+    ///stop the project before it gets started(project status changes to running),
+    ///or withdraw an offer the freelancer hasn't accepted
     pub fn cancel_project(ctx: Context<StopProjectContext>) -> Result<()> {
         instructions::project::cancel_project(ctx)
     }
+    ///the freelancer accepts the terms of an offered project, which starts it
+    pub fn accept_offer(ctx: Context<AcceptOfferContext>) -> Result<()> {
+        instructions::offer::accept_offer(ctx)
+    }
+    ///the freelancer turns an offered project down, refunding the client
+    pub fn decline_offer(ctx: Context<DeclineOfferContext>) -> Result<()> {
+        instructions::offer::decline_offer(ctx)
+    }
     // withdraw funds for completed milestones
     // so freelancer can withdraw funds for the milestone
     //The only way this can be invoked
@@ -49,9 +60,237 @@ pub mod group_6_payment_protocol {
         instructions::project::mark_current_milestone_completed(ctx)
     }
 
-    ///collective effort to stop the project regardless of the current state
-    pub fn stop_project(ctx: Context<MultisigAuth>) -> Result<()> {
-        instructions::project::stop_project(ctx)
+    // put a running project on hold
+    //The only way this can be invoked
+    // is via a recursive call from execute_transaction -> pause_project.
+    pub fn pause_project(ctx: Context<MultisigAuth>) -> Result<()> {
+        instructions::project::pause_project(ctx)
+    }
+
+    // resume a paused project, deadlines are shifted by the time spent paused
+    //The only way this can be invoked
+    // is via a recursive call from execute_transaction -> resume_project.
+    pub fn resume_project(ctx: Context<ResumeProjectContext>) -> Result<()> {
+        instructions::project::resume_project(ctx)
+    }
+
+    // add funds and/or append or resize milestones
+    //The only way this can be invoked
+    // is via a recursive call from execute_transaction -> change_order.
+    pub fn change_order(
+        ctx: Context<ChangeOrderContext>,
+        additional_funds: u64,
+        milestone_amounts: Vec<u64>,
+    ) -> Result<()> {
+        instructions::project::change_order(ctx, additional_funds, milestone_amounts)
+    }
+
+    ///stream a milestone, from then on the freelancer can withdraw its vested share
+    ///while the project runs, executed through the multisig and signed by the client
+    pub fn set_milestone_stream(
+        ctx: Context<SetMilestoneStreamContext>,
+        milestone_index: u8,
+        start: i64,
+        cliff: i64,
+        end: i64,
+    ) -> Result<()> {
+        instructions::stream::set_milestone_stream(ctx, milestone_index, start, cliff, end)
+    }
+
+    pub fn withdraw_streamed_funds(ctx: Context<WithdrawStreamedFundsContext>) -> Result<()> {
+        instructions::stream::withdraw_streamed_funds(ctx)
+    }
+
+    ///open a project paid in periods, one unlocking at the end of each interval,
+    ///the client funds every period upfront
+    pub fn initialize_retainer(
+        ctx: Context<InitializeRetainerContext>,
+        period_amount: u64,
+        periods: u8,
+        start: i64,
+        interval: i64,
+        notice_period: i64,
+    ) -> Result<()> {
+        instructions::retainer::initialize_retainer(
+            ctx,
+            period_amount,
+            periods,
+            start,
+            interval,
+            notice_period,
+        )
+    }
+
+    pub fn unlock_retainer_periods(ctx: Context<UnlockRetainerPeriodsContext>) -> Result<()> {
+        instructions::retainer::unlock_retainer_periods(ctx)
+    }
+
+    ///the client or the freelancer ends the retainer after its notice period
+    pub fn give_retainer_notice(ctx: Context<GiveRetainerNoticeContext>) -> Result<()> {
+        instructions::retainer::give_retainer_notice(ctx)
+    }
+
+    ///refund the periods that didn't unlock before the notice period ran out
+    pub fn settle_retainer(ctx: Context<SettleRetainerContext>) -> Result<()> {
+        instructions::retainer::settle_retainer(ctx)
+    }
+
+    ///bill the project by the hour, executed through the multisig and signed by the client
+    pub fn set_hourly_terms(
+        ctx: Context<SetHourlyTermsContext>,
+        hourly_rate: u64,
+        weekly_cap: u32,
+        approval_window: i64,
+        start: i64,
+    ) -> Result<()> {
+        instructions::timesheet::set_hourly_terms(ctx, hourly_rate, weekly_cap, approval_window, start)
+    }
+
+    pub fn submit_timesheet(
+        ctx: Context<SubmitTimesheetContext>,
+        week: u32,
+        hours: u32,
+        memo_hash: [u8; 32],
+    ) -> Result<()> {
+        instructions::timesheet::submit_timesheet(ctx, week, hours, memo_hash)
+    }
+
+    pub fn approve_timesheet(ctx: Context<AnswerTimesheetContext>) -> Result<()> {
+        instructions::timesheet::approve_timesheet(ctx)
+    }
+
+    pub fn dispute_timesheet(ctx: Context<AnswerTimesheetContext>) -> Result<()> {
+        instructions::timesheet::dispute_timesheet(ctx)
+    }
+
+    ///pay an approved timesheet, unanswered ones are approved once the window is over
+    pub fn withdraw_timesheet_funds(ctx: Context<WithdrawTimesheetFundsContext>) -> Result<()> {
+        instructions::timesheet::withdraw_timesheet_funds(ctx)
+    }
+
+    ///share the milestone payouts between several payees,
+    ///executed through the multisig and signed by the client
+    pub fn set_payee_splits(
+        ctx: Context<SetPayeeSplitsContext>,
+        payees: Vec<Pubkey>,
+        milestone_splits: Vec<Vec<u16>>,
+    ) -> Result<()> {
+        instructions::payee::set_payee_splits(ctx, payees, milestone_splits)
+    }
+
+    pub fn withdraw_payee_share(ctx: Context<WithdrawPayeeShareContext>) -> Result<()> {
+        instructions::payee::withdraw_payee_share(ctx)
+    }
+
+    ///open a child project funded from the parent's unreached milestones,
+    ///the parent's freelancer is its client
+    pub fn create_subcontract(
+        ctx: Context<CreateSubcontractContext>,
+        total_project_funds: u64,
+        milestones: u8,
+    ) -> Result<()> {
+        instructions::subcontract::create_subcontract(ctx, total_project_funds, milestones)
+    }
+
+    ///pause a child while its parent is paused or cancelled, anyone can call it
+    pub fn freeze_subcontract(ctx: Context<FreezeSubcontractContext>) -> Result<()> {
+        instructions::subcontract::freeze_subcontract(ctx)
+    }
+
+    ///resume a frozen child once its parent is resolved, anyone can call it
+    pub fn thaw_subcontract(ctx: Context<ThawSubcontractContext>) -> Result<()> {
+        instructions::subcontract::thaw_subcontract(ctx)
+    }
+
+    ///refund a child that was never started or got stopped, back to where its funds came from
+    pub fn cancel_subcontract(ctx: Context<CancelSubcontractContext>) -> Result<()> {
+        instructions::subcontract::cancel_subcontract(ctx)
+    }
+
+    ///ask the freelancer for a performance bond, signed by the client before the project starts
+    pub fn require_bond(
+        ctx: Context<RequireBondContext>,
+        amount: u64,
+        deadline: i64,
+    ) -> Result<()> {
+        instructions::bond::require_bond(ctx, amount, deadline)
+    }
+
+    pub fn post_bond(ctx: Context<PostBondContext>) -> Result<()> {
+        instructions::bond::post_bond(ctx)
+    }
+
+    ///return the bond to the freelancer once the project is completed or stopped, anyone can call it
+    pub fn release_bond(ctx: Context<ReleaseBondContext>) -> Result<()> {
+        instructions::bond::release_bond(ctx)
+    }
+
+    // rule against the freelancer, the project is stopped and the bond goes to the client
+    //The only way this can be invoked
+    // is via a recursive call from execute_transaction -> forfeit_bond.
+    pub fn forfeit_bond(ctx: Context<ForfeitBondContext>) -> Result<()> {
+        instructions::bond::forfeit_bond(ctx)
+    }
+
+    ///forfeit the bond to the client once the deadline is missed, anyone can call it
+    pub fn claim_bond(ctx: Context<ClaimBondContext>) -> Result<()> {
+        instructions::bond::claim_bond(ctx)
+    }
+
+    ///publish a job freelancers can bid on
+    pub fn create_job_posting(
+        ctx: Context<CreateJobPostingContext>,
+        job_id: u64,
+        budget_min: u64,
+        budget_max: u64,
+        milestone_template: Vec<u16>,
+        metadata_uri: String,
+    ) -> Result<()> {
+        instructions::job_board::create_job_posting(
+            ctx,
+            job_id,
+            budget_min,
+            budget_max,
+            milestone_template,
+            metadata_uri,
+        )
+    }
+
+    pub fn submit_bid(ctx: Context<SubmitBidContext>, price: u64, timeline: i64) -> Result<()> {
+        instructions::job_board::submit_bid(ctx, price, timeline)
+    }
+
+    ///hire a bidder, creating and funding the project in the same transaction
+    pub fn accept_bid(ctx: Context<AcceptBidContext>) -> Result<()> {
+        instructions::job_board::accept_bid(ctx)
+    }
+
+    ///close a bid that wasn't accepted, the freelancer gets the rent back
+    pub fn close_bid(ctx: Context<CloseBidContext>) -> Result<()> {
+        instructions::job_board::close_bid(ctx)
+    }
+
+    ///start tracking the reputation of a wallet before its first project, anyone can pay for it
+    pub fn create_reputation(ctx: Context<CreateReputationContext>) -> Result<()> {
+        instructions::reputation::create_reputation(ctx)
+    }
+
+    ///rate the other party of a completed or cancelled project that ran, once per party,
+    ///also once it's closed. `nonce` is the project nonce, see `ProjectInfo::nonce`
+    pub fn rate_party(ctx: Context<RatePartyContext>, stars: u8, nonce: u32) -> Result<()> {
+        instructions::reputation::rate_party(ctx, stars, nonce)
+    }
+
+    ///tip the freelancer on top of the project funds, directly or from what's left in the escrow
+    pub fn tip(ctx: Context<TipContext>, amount: u64, from_escrow: bool) -> Result<()> {
+        instructions::tip::tip(ctx, amount, from_escrow)
+    }
+
+    ///collective effort to stop the project at any point before it gets completed,
+    ///`ruling` records who the multisig sided with if the parties were in dispute,
+    ///a ruling against the freelancer forfeits their bond
+    pub fn stop_project(ctx: Context<MultisigRulingAuth>, ruling: Ruling) -> Result<()> {
+        instructions::project::stop_project(ctx, ruling)
     }
 
     pub fn create_transaction(
@@ -63,7 +302,22 @@ pub mod group_6_payment_protocol {
         instructions::multisig::create_transaction(ctx, pid, transaction_accounts, data)
     }
 
+    ///extend the instruction data of a pending proposal past what fits in one transaction,
+    ///only the proposer can, and the approvals collected so far are reset
+    pub fn append_transaction_data(
+        ctx: Context<AppendTransactionData>,
+        data: Vec<u8>,
+    ) -> Result<()> {
+        instructions::multisig::append_transaction_data(ctx, data)
+    }
+
     pub fn approve(ctx: Context<Approve>) -> Result<()> {
         instructions::multisig::approve(ctx)
     }
+
+    ///upgrade a project, multisig or proposal account written with an older layout,
+    ///every other instruction rejects those with `AccountNotMigrated`
+    pub fn migrate_account(ctx: Context<MigrateAccount>) -> Result<()> {
+        instructions::migrate::migrate_account(ctx)
+    }
 }
@@ -0,0 +1,215 @@
+use crate::errors::ErrorCode;
+use anchor_lang::prelude::*;
+
+pub const MAX_BPS: u64 = 10_000;
+
+///what a withdrawal pays the freelancer
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct MilestonePayout {
+    pub amount: u64,
+    ///every milestone got withdrawn, the project is completed
+    pub completed: bool,
+}
+
+///payout for the milestones reached but not withdrawn yet
+///`milestone_amounts` and `milestone_funds_streamed` have one entry per milestone,
+///what a stream already paid out of a milestone is deducted from it.
+///once the last one is reached the freelancer gets whatever is left in the
+///escrow so nothing stays locked in it
+pub fn milestone_payout(
+    milestone_amounts: &[u64],
+    milestone_funds_streamed: &[u64],
+    milestone_funds_withdrawn: u8,
+    milestones_reached: u8,
+    escrow_balance: u64,
+) -> Result<MilestonePayout> {
+    require!(
+        milestones_reached > milestone_funds_withdrawn,
+        ErrorCode::NothingToWithdraw
+    );
+    let amount = earned_funds(
+        milestone_amounts,
+        milestone_funds_streamed,
+        milestone_funds_withdrawn,
+        milestones_reached,
+    )?;
+
+    if milestones_reached as usize == milestone_amounts.len() {
+        return Ok(MilestonePayout {
+            amount: escrow_balance,
+            completed: true,
+        });
+    }
+    Ok(MilestonePayout {
+        amount,
+        completed: false,
+    })
+}
+
+///what the milestones reached but not withdrawn yet are worth to the freelancer,
+///minus what their streams already paid out.
+///unlike `milestone_payout` nothing else in the escrow is added to it
+pub fn earned_funds(
+    milestone_amounts: &[u64],
+    milestone_funds_streamed: &[u64],
+    milestone_funds_withdrawn: u8,
+    milestones_reached: u8,
+) -> Result<u64> {
+    if milestones_reached <= milestone_funds_withdrawn {
+        return Ok(0);
+    }
+    let range = milestone_funds_withdrawn as usize..milestones_reached as usize;
+    let amounts = milestone_amounts
+        .get(range.clone())
+        .ok_or(ErrorCode::InvalidMilestones)?;
+    let streamed = milestone_funds_streamed
+        .get(range)
+        .ok_or(ErrorCode::InvalidMilestones)?;
+    amounts
+        .iter()
+        .zip(streamed)
+        .try_fold(0u64, |acc, (amount, streamed)| {
+            acc.checked_add(amount.checked_sub(*streamed)?)
+        })
+        .ok_or_else(|| ErrorCode::Overflow.into())
+}
+
+///share of `amount` vested at `now` by a stream running from `start` to `end`,
+///nothing vests before `cliff` and everything from `end` on.
+///in between it grows linearly from `start`, rounded down
+pub fn vested_amount(amount: u64, start: i64, cliff: i64, end: i64, now: i64) -> Result<u64> {
+    check_stream_schedule(start, cliff, end)?;
+    if now < cliff {
+        return Ok(0);
+    }
+    if now >= end {
+        return Ok(amount);
+    }
+    //a schedule can span the whole i64 range, which only fits the difference in 128 bits
+    let elapsed = (now as i128 - start as i128) as u128;
+    let duration = (end as i128 - start as i128) as u128;
+    u64::try_from(amount as u128 * elapsed / duration).map_err(|_| ErrorCode::Overflow.into())
+}
+
+pub fn check_stream_schedule(start: i64, cliff: i64, end: i64) -> Result<()> {
+    require!(
+        start <= cliff && cliff <= end && start < end,
+        ErrorCode::InvalidStream
+    );
+    Ok(())
+}
+
+///periods of a retainer unlocked at `schedule_time`, one at the end of each `interval`
+///from `start`, never more than `periods`
+pub fn retainer_periods_unlocked(
+    start: i64,
+    interval: i64,
+    periods: u8,
+    schedule_time: i64,
+) -> Result<u8> {
+    require!(interval > 0, ErrorCode::InvalidRetainer);
+    let elapsed = schedule_time.saturating_sub(start).max(0);
+    Ok((elapsed / interval).min(periods as i64) as u8)
+}
+
+///what ending a project after its first `milestones_kept` milestones gives back to the client,
+///every later milestone minus what its stream already paid out
+pub fn unused_milestone_funds(
+    milestone_amounts: &[u64],
+    milestone_funds_streamed: &[u64],
+    milestones_kept: u8,
+) -> Result<u64> {
+    let range = milestones_kept as usize..milestone_amounts.len();
+    let amounts = milestone_amounts
+        .get(range.clone())
+        .ok_or(ErrorCode::InvalidMilestones)?;
+    let streamed = milestone_funds_streamed
+        .get(range)
+        .ok_or(ErrorCode::InvalidMilestones)?;
+    amounts
+        .iter()
+        .zip(streamed)
+        .try_fold(0u64, |acc, (amount, streamed)| {
+            acc.checked_add(amount.checked_sub(*streamed)?)
+        })
+        .ok_or_else(|| ErrorCode::Overflow.into())
+}
+
+///`milestone_funds_streamed` after paying `amount` out of the milestones from `first` on,
+///each one filled up to its amount before moving to the next.
+///fails with `BudgetExceeded` if they can't cover it
+pub fn draw_from_milestones(
+    milestone_amounts: &[u64],
+    milestone_funds_streamed: &[u64],
+    first: u8,
+    amount: u64,
+) -> Result<Vec<u64>> {
+    require!(
+        milestone_amounts.len() == milestone_funds_streamed.len(),
+        ErrorCode::InvalidMilestones
+    );
+    let mut streamed = milestone_funds_streamed.to_vec();
+    let mut left = amount;
+    for (index, milestone_amount) in milestone_amounts.iter().enumerate().skip(first as usize) {
+        let room = milestone_amount
+            .checked_sub(streamed[index])
+            .ok_or(ErrorCode::Overflow)?;
+        let drawn = room.min(left);
+        streamed[index] += drawn;
+        left -= drawn;
+    }
+    require!(left == 0, ErrorCode::BudgetExceeded);
+    Ok(streamed)
+}
+
+///`milestone_funds_streamed` after giving back up to `amount` of what was `drawn` from
+///the milestones from `first` on, and how much of `amount` went back
+pub fn return_to_milestones(
+    milestone_funds_streamed: &[u64],
+    drawn: &[u64],
+    first: u8,
+    amount: u64,
+) -> (Vec<u64>, u64) {
+    let mut streamed = milestone_funds_streamed.to_vec();
+    let mut left = amount;
+    for (streamed, drawn) in streamed.iter_mut().zip(drawn).skip(first as usize) {
+        let returned = (*drawn).min(*streamed).min(left);
+        *streamed -= returned;
+        left -= returned;
+    }
+    (streamed, amount - left)
+}
+
+///`amount` split between payees by basis points, rounded down,
+///the first payee also gets what rounding leaves so the shares add up to `amount`
+pub fn split_shares(amount: u64, splits_bps: &[u16]) -> Result<Vec<u64>> {
+    let mut shares = splits_bps
+        .iter()
+        .map(|bps| (amount as u128 * *bps as u128 / MAX_BPS as u128) as u64)
+        .collect::<Vec<_>>();
+    let rest = shares
+        .iter()
+        .skip(1)
+        .try_fold(0u64, |acc, share| acc.checked_add(*share))
+        .and_then(|others| amount.checked_sub(others))
+        .ok_or(ErrorCode::InvalidSplits)?;
+    *shares.first_mut().ok_or(ErrorCode::InvalidSplits)? = rest;
+    Ok(shares)
+}
+
+///`milestone_splits` has between 1 and `max_rows` rows of one share per payee
+///and every row adds up to `MAX_BPS`
+pub fn check_splits(payees: usize, milestone_splits: &[Vec<u16>], max_rows: usize) -> Result<()> {
+    require!(
+        !milestone_splits.is_empty() && milestone_splits.len() <= max_rows,
+        ErrorCode::InvalidSplits
+    );
+    for splits in milestone_splits {
+        let total = splits.iter().map(|bps| *bps as u64).sum::<u64>();
+        require!(
+            splits.len() == payees && total == MAX_BPS,
+            ErrorCode::InvalidSplits
+        );
+    }
+    Ok(())
+}
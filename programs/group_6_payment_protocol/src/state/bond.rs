@@ -0,0 +1,108 @@
+use crate::errors::ErrorCode;
+use crate::state::{AccountVersion, ProjectInfo, ProjectStatus, Versioned};
+use crate::utils::close_account;
+use anchor_lang::prelude::*;
+
+///performance bond the freelancer posts before the project starts,
+///`[b"bond", project_info_account]`. the posted lamports sit in this account
+#[account]
+pub struct Bond {
+    pub project: Pubkey,
+    ///gets the bond when it's forfeited and the rent back either way
+    pub client: Pubkey,
+    ///gets the bond back once the project is completed
+    pub freelancer: Pubkey,
+    pub amount: u64,
+    ///unix timestamp the project must be completed by, 0 for none.
+    ///shifted by the time the project spends paused
+    pub deadline: i64,
+    pub posted: bool,
+    ///always last, see `AccountVersion`
+    pub version: AccountVersion,
+}
+
+impl Bond {
+    pub const MAX_SIZE: usize = 32 //project
+    + 32 //client
+    + 32 //freelancer
+    + 8 //amount
+    + 8 //deadline
+    + 1 //posted
+    + AccountVersion::SIZE; //version
+
+    ///fail if the project at `bond` asks for a bond that wasn't posted,
+    ///`bond` is the `[b"bond", project_info_account]` PDA, empty when none was required
+    pub fn require_posted(bond: &AccountInfo) -> Result<()> {
+        if bond.data_is_empty() {
+            return Ok(());
+        }
+        let bond = Bond::try_deserialize(&mut &bond.try_borrow_data()?[..])?;
+        require!(bond.posted, ErrorCode::BondNotPosted);
+        Ok(())
+    }
+
+    ///send the bond posted at `bond` to `client` on a ruling against the freelancer,
+    ///closing the account. `None` when no bond was posted, release_bond closes it then
+    pub fn forfeit<'info>(
+        bond: &AccountInfo<'info>,
+        client: &AccountInfo<'info>,
+    ) -> Result<Option<Bond>> {
+        if bond.data_is_empty() {
+            return Ok(None);
+        }
+        let state = Bond::try_deserialize(&mut &bond.try_borrow_data()?[..])?;
+        if !state.posted {
+            return Ok(None);
+        }
+        close_account(bond, client)?;
+        Ok(Some(state))
+    }
+
+    ///whether the project at `bond` made its deadline, checked as it completes.
+    ///`None` without a bond deadline, or once the bond was claimed for missing it
+    pub fn deadline_met(
+        bond: &AccountInfo,
+        now: i64,
+        project: &ProjectInfo,
+    ) -> Result<Option<bool>> {
+        if bond.data_is_empty() {
+            return Ok(None);
+        }
+        let bond = Bond::try_deserialize(&mut &bond.try_borrow_data()?[..])?;
+        if bond.deadline == 0 {
+            return Ok(None);
+        }
+        Ok(Some(!bond.deadline_missed(now, project)))
+    }
+
+    ///the project is past its deadline while it's underway,
+    ///the clock is stopped while the project is paused.
+    ///a project that hasn't started or was completed or stopped can't miss it
+    pub fn deadline_missed(&self, now: i64, project: &ProjectInfo) -> bool {
+        if self.deadline == 0
+            || !matches!(
+                project.status,
+                ProjectStatus::Running | ProjectStatus::Paused
+            )
+        {
+            return false;
+        }
+        let mut now = now;
+        if project.status == ProjectStatus::Paused {
+            now = now.min(project.paused_at);
+        }
+        now.saturating_sub(project.paused_duration) > self.deadline
+    }
+}
+
+impl Versioned for Bond {
+    const VERSION: AccountVersion = AccountVersion::V1;
+
+    fn version(&self) -> AccountVersion {
+        self.version
+    }
+
+    fn version_mut(&mut self) -> &mut AccountVersion {
+        &mut self.version
+    }
+}
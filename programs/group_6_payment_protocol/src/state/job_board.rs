@@ -0,0 +1,116 @@
+use crate::errors::ErrorCode;
+use crate::payout;
+use crate::state::{AccountVersion, ProjectInfo, Versioned};
+use anchor_lang::prelude::*;
+
+///a client looking for a freelancer, `[b"job_posting", client, job_id]`
+#[account]
+pub struct JobPosting {
+    pub client: Pubkey,
+    ///picked by the client, tells its postings apart
+    pub job_id: u64,
+    ///bids must ask for a price in this range
+    pub budget_min: u64,
+    pub budget_max: u64,
+    ///basis points of the price released by each milestone, adds up to 10000
+    pub milestone_template: Vec<u16>,
+    ///off-chain description of the job
+    pub metadata_uri: String,
+    ///project created from the accepted bid, default while the posting is open
+    pub project: Pubkey,
+    ///unix timestamp the project is due by, the accepted bid's timeline counted
+    ///from its acceptance. 0 while the posting is open
+    pub deadline: i64,
+    ///always last, see `AccountVersion`
+    pub version: AccountVersion,
+}
+
+impl JobPosting {
+    pub const MAX_URI_LEN: usize = 200;
+
+    pub const MAX_SIZE: usize = 32 //client
+    + 8 //job_id
+    + 8 //budget_min
+    + 8 //budget_max
+    + 4 + (2 * ProjectInfo::MAX_MILESTONES) //milestone_template
+    + 4 + JobPosting::MAX_URI_LEN //metadata_uri
+    + 32 //project
+    + 8 //deadline
+    + AccountVersion::SIZE; //version
+
+    pub fn check(
+        budget_min: u64,
+        budget_max: u64,
+        milestone_template: &[u16],
+        metadata_uri: &str,
+    ) -> Result<()> {
+        let template_bps = milestone_template
+            .iter()
+            .map(|bps| *bps as u64)
+            .sum::<u64>();
+        require!(
+            budget_min > 0
+                && budget_min <= budget_max
+                && !milestone_template.is_empty()
+                && milestone_template.len() <= ProjectInfo::MAX_MILESTONES
+                && template_bps == payout::MAX_BPS
+                && metadata_uri.len() <= JobPosting::MAX_URI_LEN,
+            ErrorCode::InvalidJobPosting
+        );
+        Ok(())
+    }
+
+    pub fn is_open(&self) -> bool {
+        self.project == Pubkey::default()
+    }
+
+    ///milestone amounts of a project paying `price`, split along the template
+    pub fn milestone_amounts(&self, price: u64) -> Result<Vec<u64>> {
+        payout::split_shares(price, &self.milestone_template)
+    }
+}
+
+impl Versioned for JobPosting {
+    const VERSION: AccountVersion = AccountVersion::V1;
+
+    fn version(&self) -> AccountVersion {
+        self.version
+    }
+
+    fn version_mut(&mut self) -> &mut AccountVersion {
+        &mut self.version
+    }
+}
+
+///a freelancer's proposal for a job posting, `[b"bid", job_posting, freelancer]`
+#[account]
+pub struct Bid {
+    pub job_posting: Pubkey,
+    pub freelancer: Pubkey,
+    ///lamports the project would be funded with
+    pub price: u64,
+    ///seconds the freelancer needs to deliver
+    pub timeline: i64,
+    ///always last, see `AccountVersion`
+    pub version: AccountVersion,
+}
+
+impl Bid {
+    pub const MAX_SIZE: usize = 32 //job_posting
+    + 32 //freelancer
+    + 8 //price
+    + 8 //timeline
+    + AccountVersion::SIZE; //version
+}
+
+impl Versioned for Bid {
+    const VERSION: AccountVersion = AccountVersion::V1;
+
+    fn version(&self) -> AccountVersion {
+        self.version
+    }
+
+    fn version_mut(&mut self) -> &mut AccountVersion {
+        &mut self.version
+    }
+}
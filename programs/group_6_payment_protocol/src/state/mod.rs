@@ -3,3 +3,33 @@ pub use project_info::*;
 
 pub mod multisig;
 pub use multisig::*;
+
+pub mod stream;
+pub use stream::*;
+
+pub mod version;
+pub use version::*;
+
+pub mod retainer;
+pub use retainer::*;
+
+pub mod timesheet;
+pub use timesheet::*;
+
+pub mod payee_splits;
+pub use payee_splits::*;
+
+pub mod subcontract;
+pub use subcontract::*;
+
+pub mod bond;
+pub use bond::*;
+
+pub mod job_board;
+pub use job_board::*;
+
+pub mod reputation;
+pub use reputation::*;
+
+pub mod tips;
+pub use tips::*;
@@ -4,6 +4,7 @@ use anchor_lang::solana_program::instruction::Instruction;
 
 use crate::utils::assert_unique_owners;
 use crate::errors::ErrorCode;
+use crate::state::{AccountVersion, Versioned};
 
 #[account]
 pub struct Multisig {
@@ -13,17 +14,20 @@ pub struct Multisig {
     /// meaning a transaction can get replaced before execution
     pub seqno: u32,
     pub owners: Vec<Pubkey>,
+    ///always last, see `AccountVersion`
+    pub version: AccountVersion,
 }
 
 impl Multisig {
     pub const MAX_SIZE: usize = 8 + 4  //threshold, seqno
-    + 4 + (32 * 3); //3 owners max
+    + 4 + (32 * 3) //3 owners max
+    + AccountVersion::SIZE; //version
 
     pub fn init(&mut self, owners: Vec<Pubkey>, threshold: u64) -> Result<()> {
         assert_unique_owners(&owners)?;
         //platform multisig should only be created once
         require!(
-            self.owners.len() == 0 && self.threshold == 0,
+            self.owners.is_empty() && self.threshold == 0,
             ErrorCode::MultisigAlreadyInitialized
         );
         
@@ -35,11 +39,24 @@ impl Multisig {
         self.owners = owners;
         self.threshold = threshold;
         self.seqno = 0;
+        self.version = Multisig::VERSION;
 
         Ok(())
     }
 
 }
+impl Versioned for Multisig {
+    const VERSION: AccountVersion = AccountVersion::V1;
+
+    fn version(&self) -> AccountVersion {
+        self.version
+    }
+
+    fn version_mut(&mut self) -> &mut AccountVersion {
+        &mut self.version
+    }
+}
+
 #[account]
 pub struct Transaction {
     pub proposer: Pubkey,
@@ -57,18 +74,43 @@ pub struct Transaction {
     pub signers: Vec<bool>,
     // Instruction data for the transaction.
     pub data: Vec<u8>,
+    // Layout version, always last. See `AccountVersion`.
+    pub version: AccountVersion,
 }
 
 impl Transaction {
-    pub const MAX_SIZE: usize = 32 //proposer
-    + 32 //multisig
-    + 32 //program_id
-    + 1 //did_execute
-    + 4  // seqno
-    + 4 + (2 * TransactionAccount::MAX_SIZE) // 20 accounts max
-    + 4 + (1 * 20) // signers max
-    + 4 + (1 * 1000); // data
+    ///approve passes every account of the proposal along,
+    ///a legacy transaction can't fit many more next to its own accounts
+    pub const MAX_ACCOUNTS: usize = 24;
+    ///largest instruction data the runtime lets a CPI carry
+    pub const MAX_DATA_LEN: usize = 10 * 1024;
+
+    ///space of a proposal with `accounts` accounts, `data_len` bytes of data
+    ///and one signer flag per owner, discriminator excluded
+    pub fn space(accounts: usize, data_len: usize, owners: usize) -> usize {
+        32 //proposer
+        + 32 //multisig
+        + 32 //program_id
+        + 1 //did_execute
+        + 4  // seqno
+        + 4 + (accounts * TransactionAccount::MAX_SIZE) // accounts
+        + 4 + owners // signers
+        + 4 + data_len // data
+        + AccountVersion::SIZE // version
+    }
 }
+impl Versioned for Transaction {
+    const VERSION: AccountVersion = AccountVersion::V1;
+
+    fn version(&self) -> AccountVersion {
+        self.version
+    }
+
+    fn version_mut(&mut self) -> &mut AccountVersion {
+        &mut self.version
+    }
+}
+
 impl From<&Transaction> for Instruction {
     fn from(tx: &Transaction) -> Instruction {
         Instruction {
@@ -78,7 +120,7 @@ impl From<&Transaction> for Instruction {
         }
     }
 }
-#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, PartialEq, Eq)]
 pub struct TransactionAccount {
     pub pubkey: Pubkey,
     pub is_signer: bool,
@@ -0,0 +1,49 @@
+use crate::state::{AccountVersion, ProjectInfo, Versioned};
+use anchor_lang::prelude::*;
+
+///several payees sharing the milestone payouts of a project,
+///`[b"payee_splits", project_info_account]`
+#[account]
+pub struct PayeeSplits {
+    pub project: Pubkey,
+    pub payees: Vec<Pubkey>,
+    ///basis points of each milestone going to each payee, `[milestone][payee]`.
+    ///milestones past the last row, added by a change order, use the last row
+    pub milestone_splits: Vec<Vec<u16>>,
+    ///milestones each payee has withdrawn their share of
+    pub milestones_withdrawn: Vec<u8>,
+    ///lamports each payee has withdrawn
+    pub amounts_withdrawn: Vec<u64>,
+    ///always last, see `AccountVersion`
+    pub version: AccountVersion,
+}
+
+impl PayeeSplits {
+    pub const MAX_PAYEES: usize = 8;
+
+    pub const MAX_SIZE: usize = 32 //project
+    + 4 + (32 * PayeeSplits::MAX_PAYEES) //payees
+    + 4 + ProjectInfo::MAX_MILESTONES * (4 + 2 * PayeeSplits::MAX_PAYEES) //milestone_splits
+    + 4 + PayeeSplits::MAX_PAYEES //milestones_withdrawn
+    + 4 + (8 * PayeeSplits::MAX_PAYEES) //amounts_withdrawn
+    + AccountVersion::SIZE; //version
+
+    ///basis points of `milestone_index` per payee
+    pub fn splits(&self, milestone_index: u8) -> Option<&Vec<u16>> {
+        self.milestone_splits
+            .get(milestone_index as usize)
+            .or_else(|| self.milestone_splits.last())
+    }
+}
+
+impl Versioned for PayeeSplits {
+    const VERSION: AccountVersion = AccountVersion::V1;
+
+    fn version(&self) -> AccountVersion {
+        self.version
+    }
+
+    fn version_mut(&mut self) -> &mut AccountVersion {
+        &mut self.version
+    }
+}
@@ -1,8 +1,12 @@
 use crate::errors::ErrorCode;
+use crate::state::{AccountVersion, Versioned};
 use anchor_lang::prelude::*;
+use anchor_lang::Discriminator;
 
 /// state
-#[account]
+///the account traits `#[account]` would derive are written out below,
+///so accounts in an older layout are rejected before they're decoded
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
 pub struct ProjectInfo {
     pub total_project_funds: u64,
     pub milestones: u8,
@@ -11,7 +15,26 @@ pub struct ProjectInfo {
     pub client: Pubkey,
     pub freelancer: Pubkey,
     pub multisig: Pubkey,
-    pub status: u8,
+    pub status: ProjectStatus,
+    ///amount released by each milestone
+    ///always sums up to total_project_funds
+    pub milestone_amounts: Vec<u64>,
+    ///unix timestamp the project got paused at, 0 when not paused
+    pub paused_at: i64,
+    ///total seconds the project has spent paused
+    ///deadlines and auto-approval windows are shifted by this amount
+    pub paused_duration: i64,
+    ///paid out of each milestone by its stream ahead of the milestone payout,
+    ///one entry per milestone like `milestone_amounts`
+    pub milestone_funds_streamed: Vec<u64>,
+    ///counts the projects opened by the client, set from their reputation.
+    ///tells the ratings of this project apart from those of earlier projects
+    ///between the same parties, which had the same address
+    pub nonce: u32,
+    ///set once the project first runs, withdrawn and declined offers can't be rated
+    pub started: bool,
+    ///always last, see `AccountVersion`
+    pub version: AccountVersion,
 }
 
 impl ProjectInfo {
@@ -22,13 +45,345 @@ impl ProjectInfo {
     + 32 //client
     + 32 //freelancer
     + 32 //multisig
-    + 1; //status
-         //see more at: https://book.anchor-lang.com/anchor_references/space.html
+    + 1 //status
+    + 4 + (8 * ProjectInfo::MAX_MILESTONES) //milestone_amounts
+    + 8 //paused_at
+    + 8 //paused_duration
+    + 4 + (8 * ProjectInfo::MAX_MILESTONES) //milestone_funds_streamed
+    + 4 //nonce
+    + 1 //started
+    + AccountVersion::SIZE; //version
+    //see more at: https://book.anchor-lang.com/anchor_references/space.html
+
+    pub const MAX_MILESTONES: usize = 20;
+
+    ///split the project funds equally between milestones,
+    ///the last milestone takes whatever is left from the division
+    pub fn split_funds(total_project_funds: u64, milestones: u8) -> Result<Vec<u64>> {
+        require!(
+            milestones > 0 && milestones as usize <= ProjectInfo::MAX_MILESTONES,
+            ErrorCode::InvalidMilestones
+        );
+        let amount_per_milestone = total_project_funds
+            .checked_div(milestones.into())
+            .ok_or(ErrorCode::Overflow)?;
+        let mut amounts = vec![amount_per_milestone; milestones as usize - 1];
+        let last_milestone = amount_per_milestone
+            .checked_mul((milestones - 1).into())
+            .and_then(|split| total_project_funds.checked_sub(split))
+            .ok_or(ErrorCode::Overflow)?;
+        amounts.push(last_milestone);
+        Ok(amounts)
+    }
+
+    ///fill a new project paying `milestone_amounts` out, the project funds are their sum.
+    ///it's offered to the freelancer, nothing starts until they accept the terms
+    pub fn init(
+        &mut self,
+        client: Pubkey,
+        freelancer: Pubkey,
+        multisig: Pubkey,
+        nonce: u32,
+        milestone_amounts: Vec<u64>,
+    ) -> Result<()> {
+        require!(
+            !milestone_amounts.is_empty() && milestone_amounts.len() <= ProjectInfo::MAX_MILESTONES,
+            ErrorCode::InvalidMilestones
+        );
+        self.total_project_funds = milestone_amounts
+            .iter()
+            .try_fold(0u64, |acc, amount| acc.checked_add(*amount))
+            .ok_or(ErrorCode::Overflow)?;
+        self.client = client;
+        self.freelancer = freelancer;
+        self.multisig = multisig;
+        self.milestones = milestone_amounts.len() as u8;
+        self.milestone_funds_streamed = vec![0; milestone_amounts.len()];
+        self.milestone_amounts = milestone_amounts;
+        self.milestones_reached = 0;
+        self.milestone_funds_withdrawn = 0;
+        self.nonce = nonce;
+        self.started = false;
+        self.status = ProjectStatus::Offered;
+        self.version = ProjectInfo::VERSION;
+        Ok(())
+    }
+
+    ///move the project to `to`, failing if the transition isn't in
+    ///`ProjectStatus::ALLOWED_TRANSITIONS`
+    pub fn transition(&mut self, to: ProjectStatus) -> Result<()> {
+        self.status.check_transition(to)?;
+        self.status = to;
+        self.started |= to == ProjectStatus::Running;
+        Ok(())
+    }
+
+    ///fail unless the project is currently in one of the `allowed` states
+    pub fn require_status(&self, allowed: &[ProjectStatus]) -> Result<()> {
+        if allowed.contains(&self.status) {
+            return Ok(());
+        }
+        Err(self.status.unavailable_error().into())
+    }
+
+    ///stop the project clocks
+    pub fn pause(&mut self, now: i64) -> Result<()> {
+        self.transition(ProjectStatus::Paused)?;
+        self.paused_at = now;
+        Ok(())
+    }
+
+    ///restart the project clocks,
+    ///every deadline moves forward by the time spent paused
+    ///returns how long the project was paused for
+    pub fn resume(&mut self, now: i64) -> Result<i64> {
+        self.transition(ProjectStatus::Running)?;
+        let paused_for = now.saturating_sub(self.paused_at).max(0);
+        self.paused_duration = self
+            .paused_duration
+            .checked_add(paused_for)
+            .ok_or(ErrorCode::Overflow)?;
+        self.paused_at = 0;
+        Ok(paused_for)
+    }
+
+    ///replace the milestone schedule with `milestone_amounts`
+    ///milestones already reached or streamed from can't be edited and
+    ///the new schedule must add up to `total_project_funds`
+    pub fn apply_change_order(
+        &mut self,
+        total_project_funds: u64,
+        milestone_amounts: Vec<u64>,
+    ) -> Result<()> {
+        let locked = self.locked_milestones();
+        require!(
+            !milestone_amounts.is_empty()
+                && milestone_amounts.len() <= ProjectInfo::MAX_MILESTONES
+                && milestone_amounts.len() >= locked,
+            ErrorCode::InvalidMilestones
+        );
+        require!(
+            milestone_amounts.get(..locked) == self.milestone_amounts.get(..locked),
+            ErrorCode::MilestoneAlreadyReached
+        );
+        let sum = milestone_amounts
+            .iter()
+            .try_fold(0u64, |acc, amount| acc.checked_add(*amount))
+            .ok_or(ErrorCode::Overflow)?;
+        require!(sum == total_project_funds, ErrorCode::FundsMismatch);
+
+        self.total_project_funds = total_project_funds;
+        self.milestones = milestone_amounts.len() as u8;
+        self.milestone_funds_streamed
+            .resize(milestone_amounts.len(), 0);
+        self.milestone_amounts = milestone_amounts;
+        Ok(())
+    }
+
+    ///the parties can rate each other once a project that ran is over
+    pub fn require_rateable(&self) -> Result<()> {
+        self.require_status(&[ProjectStatus::Completed, ProjectStatus::Cancelled])?;
+        require!(self.started, ErrorCode::InvalidRating);
+        Ok(())
+    }
+
+    ///the project at `info`, `None` once cancel_project closed it
+    pub fn load_unless_closed(info: &AccountInfo) -> Result<Option<ProjectInfo>> {
+        if info.owner != &crate::ID || info.lamports() == 0 {
+            return Ok(None);
+        }
+        let project_info = ProjectInfo::try_deserialize(&mut &info.try_borrow_data()?[..])?;
+        require!(project_info.is_current(), ErrorCode::AccountNotMigrated);
+        Ok(Some(project_info))
+    }
+
+    ///number of leading milestones a change order must keep as they are,
+    ///the reached ones and any a stream already paid from
+    pub fn locked_milestones(&self) -> usize {
+        let streamed = self
+            .milestone_funds_streamed
+            .iter()
+            .rposition(|streamed| *streamed > 0)
+            .map_or(0, |index| index + 1);
+        streamed.max(self.milestones_reached as usize)
+    }
+
+    ///read a project account written with any layout,
+    ///the fields added since are zeroed and `version` tells which one it was
+    pub fn decode_any_layout(data: &[u8]) -> Result<ProjectInfo> {
+        require!(
+            data.len() >= 8 && data[..8] == ProjectInfo::discriminator(),
+            anchor_lang::error::ErrorCode::AccountDiscriminatorMismatch
+        );
+        let mut fields = &data[8..];
+        //every layout was allocated with its full size, so the size tells them apart
+        if fields.len() == ProjectInfoV0::SIZE {
+            return ProjectInfoV0::deserialize(&mut fields)?.upgrade();
+        }
+        if fields.len() <= ProjectInfoV1::SIZE {
+            return Ok(ProjectInfoV1::deserialize(&mut fields)?.upgrade());
+        }
+        ProjectInfo::try_deserialize(&mut &data[..])
+    }
+}
+
+impl Versioned for ProjectInfo {
+    const VERSION: AccountVersion = AccountVersion::V2;
+
+    fn version(&self) -> AccountVersion {
+        self.version
+    }
+
+    fn version_mut(&mut self) -> &mut AccountVersion {
+        &mut self.version
+    }
+}
+
+impl Discriminator for ProjectInfo {
+    ///sha256("account:ProjectInfo")[..8], the one `#[account]` derived
+    fn discriminator() -> [u8; 8] {
+        [88, 74, 233, 19, 78, 99, 28, 21]
+    }
 }
-#[derive(PartialEq, Eq)]
+
+impl Owner for ProjectInfo {
+    fn owner() -> Pubkey {
+        crate::ID
+    }
+}
+
+impl AccountSerialize for ProjectInfo {
+    fn try_serialize<W: std::io::Write>(&self, writer: &mut W) -> Result<()> {
+        writer
+            .write_all(&ProjectInfo::discriminator())
+            .map_err(|_| anchor_lang::error::ErrorCode::AccountDidNotSerialize)?;
+        AnchorSerialize::serialize(self, writer)
+            .map_err(|_| anchor_lang::error::ErrorCode::AccountDidNotSerialize)?;
+        Ok(())
+    }
+}
+
+impl AccountDeserialize for ProjectInfo {
+    fn try_deserialize(buf: &mut &[u8]) -> Result<Self> {
+        require!(
+            buf.len() >= 8,
+            anchor_lang::error::ErrorCode::AccountDiscriminatorNotFound
+        );
+        require!(
+            buf[..8] == ProjectInfo::discriminator(),
+            anchor_lang::error::ErrorCode::AccountDiscriminatorMismatch
+        );
+        //older layouts were allocated smaller and don't decode as this one,
+        //so they're caught here rather than by the `is_current` constraint
+        require!(
+            buf.len() - 8 > ProjectInfoV1::SIZE,
+            ErrorCode::AccountNotMigrated
+        );
+        Self::try_deserialize_unchecked(buf)
+    }
+
+    fn try_deserialize_unchecked(buf: &mut &[u8]) -> Result<Self> {
+        let mut data = &buf[8..];
+        AnchorDeserialize::deserialize(&mut data)
+            .map_err(|_| anchor_lang::error::ErrorCode::AccountDidNotDeserialize.into())
+    }
+}
+
+///`ProjectInfo` before streamed milestones were added, also the layout of
+///accounts written before the version byte, only read to migrate old accounts
+#[derive(AnchorDeserialize)]
+pub struct ProjectInfoV1 {
+    pub total_project_funds: u64,
+    pub milestones: u8,
+    pub milestones_reached: u8,
+    pub milestone_funds_withdrawn: u8,
+    pub client: Pubkey,
+    pub freelancer: Pubkey,
+    pub multisig: Pubkey,
+    pub status: ProjectStatus,
+    pub milestone_amounts: Vec<u64>,
+    pub paused_at: i64,
+    pub paused_duration: i64,
+    pub version: AccountVersion,
+}
+
+impl ProjectInfoV1 {
+    pub const SIZE: usize = 8 + 1 + 1 + 1 + 32 + 32 + 32 + 1
+    + 4 + (8 * ProjectInfo::MAX_MILESTONES)
+    + 8 + 8
+    + AccountVersion::SIZE;
+
+    ///same project in the current layout, nothing was streamed yet.
+    ///old projects share nonce 0, they were closed before ratings existed
+    pub fn upgrade(self) -> ProjectInfo {
+        ProjectInfo {
+            total_project_funds: self.total_project_funds,
+            milestones: self.milestones,
+            milestones_reached: self.milestones_reached,
+            milestone_funds_withdrawn: self.milestone_funds_withdrawn,
+            client: self.client,
+            freelancer: self.freelancer,
+            multisig: self.multisig,
+            status: self.status,
+            milestone_funds_streamed: vec![0; self.milestone_amounts.len()],
+            milestone_amounts: self.milestone_amounts,
+            paused_at: self.paused_at,
+            paused_duration: self.paused_duration,
+            nonce: 0,
+            started: self.status.started(),
+            version: self.version,
+        }
+    }
+}
+
+///`ProjectInfo` as it was laid out before milestone amounts and pausing were added,
+///only read to migrate old accounts
+#[derive(AnchorDeserialize)]
+pub struct ProjectInfoV0 {
+    pub total_project_funds: u64,
+    pub milestones: u8,
+    pub milestones_reached: u8,
+    pub milestone_funds_withdrawn: u8,
+    pub client: Pubkey,
+    pub freelancer: Pubkey,
+    pub multisig: Pubkey,
+    pub status: ProjectStatus,
+}
+
+impl ProjectInfoV0 {
+    pub const SIZE: usize = 8 + 1 + 1 + 1 + 32 + 32 + 32 + 1;
+
+    ///same project in the current layout, still `Unversioned`,
+    ///the funds are split between milestones the way v0 did it
+    pub fn upgrade(self) -> Result<ProjectInfo> {
+        let milestone_amounts =
+            ProjectInfo::split_funds(self.total_project_funds, self.milestones)?;
+        Ok(ProjectInfo {
+            total_project_funds: self.total_project_funds,
+            milestones: self.milestones,
+            milestones_reached: self.milestones_reached,
+            milestone_funds_withdrawn: self.milestone_funds_withdrawn,
+            client: self.client,
+            freelancer: self.freelancer,
+            multisig: self.multisig,
+            status: self.status,
+            milestone_funds_streamed: vec![0; milestone_amounts.len()],
+            milestone_amounts,
+            paused_at: 0,
+            paused_duration: 0,
+            nonce: 0,
+            started: self.status.started(),
+            version: AccountVersion::Unversioned,
+        })
+    }
+}
+
+///stored as a single byte (the variant index), so variants
+///must keep their order and new ones go at the end
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq)]
 pub enum ProjectStatus {
     //client can still cancel the project
-    //and withdraw funds
+    //and withdraw funds, only projects created before offers
     Pending,
     //ongoing stage
     Running,
@@ -36,25 +391,50 @@ pub enum ProjectStatus {
     Completed,
     //collective cancellation
     Cancelled,
+    //on hold, clocks are stopped and
+    //submissions/withdrawals are blocked
+    Paused,
+    //waiting for the freelancer to accept the terms,
+    //the client can still withdraw the offer
+    Offered,
 }
 
 impl ProjectStatus {
-    pub fn to_code(&self) -> u8 {
-        match self {
-            ProjectStatus::Pending => 0,
-            ProjectStatus::Running => 1,
-            ProjectStatus::Completed => 2,
-            ProjectStatus::Cancelled => 3,
+    ///every (from, to) pair a project is allowed to go through
+    pub const ALLOWED_TRANSITIONS: [(ProjectStatus, ProjectStatus); 9] = [
+        (ProjectStatus::Offered, ProjectStatus::Running),
+        (ProjectStatus::Offered, ProjectStatus::Cancelled),
+        (ProjectStatus::Pending, ProjectStatus::Running),
+        (ProjectStatus::Pending, ProjectStatus::Cancelled),
+        (ProjectStatus::Running, ProjectStatus::Paused),
+        (ProjectStatus::Running, ProjectStatus::Completed),
+        (ProjectStatus::Running, ProjectStatus::Cancelled),
+        (ProjectStatus::Paused, ProjectStatus::Running),
+        (ProjectStatus::Paused, ProjectStatus::Cancelled),
+    ];
+
+    pub fn check_transition(&self, to: ProjectStatus) -> std::result::Result<(), ErrorCode> {
+        if ProjectStatus::ALLOWED_TRANSITIONS.contains(&(*self, to)) {
+            return Ok(());
         }
+        Err(self.unavailable_error())
     }
 
-    pub fn from(val: u8) -> std::result::Result<ProjectStatus, ErrorCode> {
-        match val {
-            0 => Ok(ProjectStatus::Pending),
-            1 => Ok(ProjectStatus::Running),
-            2 => Ok(ProjectStatus::Completed),
-            3 => Ok(ProjectStatus::Cancelled),
-            _ => Err(ErrorCode::InvalidStatus.into()),
+    ///whether a project in this state ran, for layouts that didn't record it.
+    ///a cancelled project is assumed to have
+    pub fn started(&self) -> bool {
+        !matches!(self, ProjectStatus::Pending | ProjectStatus::Offered)
+    }
+
+    ///error returned when an action isn't allowed in the current state
+    pub fn unavailable_error(&self) -> ErrorCode {
+        match self {
+            ProjectStatus::Pending => ErrorCode::ProjectNotStarted,
+            ProjectStatus::Running => ErrorCode::ProjectAlreadyRunning,
+            ProjectStatus::Completed => ErrorCode::ProjectAlreadyCompleted,
+            ProjectStatus::Cancelled => ErrorCode::ProjectAlreadyCancelled,
+            ProjectStatus::Paused => ErrorCode::ProjectPaused,
+            ProjectStatus::Offered => ErrorCode::OfferNotAccepted,
         }
     }
 }
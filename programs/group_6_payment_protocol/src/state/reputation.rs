@@ -0,0 +1,198 @@
+use crate::errors::ErrorCode;
+use crate::payout;
+use crate::state::{AccountVersion, Versioned};
+use anchor_lang::prelude::*;
+
+///track record of a wallet as a client and as a freelancer, `[b"reputation", wallet]`.
+///opening a project creates it for both parties, anyone can create it before that.
+///the program keeps it up to date from then on
+#[account]
+pub struct Reputation {
+    pub wallet: Pubkey,
+    ///projects opened with the wallet as the client, numbers the next one
+    pub opened_projects: u32,
+    pub completed_projects: u32,
+    ///projects that ended cancelled, counted when they are closed
+    pub cancelled_projects: u32,
+    ///lamports of the completed projects, paid as a client or earned as a freelancer
+    pub total_volume: u64,
+    pub disputes_won: u32,
+    pub disputes_lost: u32,
+    ///projects with a bond deadline delivered before it
+    pub deadlines_met: u32,
+    ///projects whose bond deadline was missed
+    pub deadlines_missed: u32,
+    pub ratings: u32,
+    ///sum of the stars of every rating, divide by `ratings` for the average
+    pub rating_total: u64,
+    ///always last, see `AccountVersion`
+    pub version: AccountVersion,
+}
+
+impl Reputation {
+    pub const MAX_SIZE: usize = 32 //wallet
+    + 4 //opened_projects
+    + 4 //completed_projects
+    + 4 //cancelled_projects
+    + 8 //total_volume
+    + 4 //disputes_won
+    + 4 //disputes_lost
+    + 4 //deadlines_met
+    + 4 //deadlines_missed
+    + 4 //ratings
+    + 8 //rating_total
+    + AccountVersion::SIZE; //version
+
+    ///start tracking `wallet` on a new account, `false` if it already was
+    pub fn open(&mut self, wallet: Pubkey) -> bool {
+        //a new account is still zeroed
+        if self.wallet != Pubkey::default() {
+            return false;
+        }
+        self.wallet = wallet;
+        self.version = Reputation::VERSION;
+        true
+    }
+
+    ///count a project opened by the wallet as the client, returns its nonce
+    pub fn open_project(&mut self) -> u32 {
+        let nonce = self.opened_projects;
+        self.opened_projects = self.opened_projects.wrapping_add(1);
+        nonce
+    }
+
+    ///apply `update` to the reputation at `reputation`, the `[b"reputation", wallet]` PDA.
+    ///wallets without a reputation account are skipped, parties of projects opened
+    ///before opening one created it for them may have none
+    pub fn update(reputation: &AccountInfo, update: impl FnOnce(&mut Reputation)) -> Result<()> {
+        if reputation.data_is_empty() {
+            return Ok(());
+        }
+        let mut state = Reputation::try_deserialize(&mut &reputation.try_borrow_data()?[..])?;
+        require!(state.is_current(), ErrorCode::AccountNotMigrated);
+        update(&mut state);
+        state.try_serialize(&mut &mut reputation.try_borrow_mut_data()?[..])?;
+        Ok(())
+    }
+
+    ///both parties delivered a project worth `volume`, `on_time` is `None` without a deadline
+    pub fn record_completion(
+        client: &AccountInfo,
+        freelancer: &AccountInfo,
+        volume: u64,
+        on_time: Option<bool>,
+    ) -> Result<()> {
+        Reputation::update(client, |reputation| reputation.complete(volume))?;
+        Reputation::update(freelancer, |reputation| {
+            reputation.complete(volume);
+            match on_time {
+                Some(true) => reputation.deadlines_met = reputation.deadlines_met.saturating_add(1),
+                Some(false) => {
+                    reputation.deadlines_missed = reputation.deadlines_missed.saturating_add(1)
+                }
+                None => {}
+            }
+        })
+    }
+
+    pub fn record_cancellation(client: &AccountInfo, freelancer: &AccountInfo) -> Result<()> {
+        Reputation::update(client, |reputation| reputation.cancel())?;
+        Reputation::update(freelancer, |reputation| reputation.cancel())
+    }
+
+    ///the multisig ruled a dispute between the parties in favour of `winner`
+    pub fn record_ruling(winner: &AccountInfo, loser: &AccountInfo) -> Result<()> {
+        Reputation::update(winner, |reputation| {
+            reputation.disputes_won = reputation.disputes_won.saturating_add(1)
+        })?;
+        Reputation::update(loser, |reputation| {
+            reputation.disputes_lost = reputation.disputes_lost.saturating_add(1)
+        })
+    }
+
+    ///share of the deadlines met in basis points, `None` before the first deadline
+    pub fn on_time_bps(&self) -> Option<u64> {
+        let deadlines = self.deadlines_met as u64 + self.deadlines_missed as u64;
+        (deadlines > 0).then(|| self.deadlines_met as u64 * payout::MAX_BPS / deadlines)
+    }
+
+    fn complete(&mut self, volume: u64) {
+        self.completed_projects = self.completed_projects.saturating_add(1);
+        self.total_volume = self.total_volume.saturating_add(volume);
+    }
+
+    fn cancel(&mut self) {
+        self.cancelled_projects = self.cancelled_projects.saturating_add(1);
+    }
+}
+
+impl Versioned for Reputation {
+    const VERSION: AccountVersion = AccountVersion::V1;
+
+    fn version(&self) -> AccountVersion {
+        self.version
+    }
+
+    fn version_mut(&mut self) -> &mut AccountVersion {
+        &mut self.version
+    }
+}
+
+///one party's rating of the other once the project is over,
+///`[b"rating", project_info_account, nonce, rater]` so each party rates a project once,
+///the project nonce as little-endian bytes.
+///closing a project that ran opens both ratings unrated, they stay rateable without it
+#[account]
+pub struct Rating {
+    pub project: Pubkey,
+    pub rater: Pubkey,
+    pub ratee: Pubkey,
+    ///1 to 5, 0 until the rater rates
+    pub stars: u8,
+    ///always last, see `AccountVersion`
+    pub version: AccountVersion,
+}
+
+impl Rating {
+    pub const MAX_STARS: u8 = 5;
+
+    ///bind a new rating account to `rater` rating `ratee` on `project`,
+    ///one that already exists is left as it is
+    pub fn open(&mut self, project: Pubkey, rater: Pubkey, ratee: Pubkey) {
+        //a new account is still zeroed
+        if self.project != Pubkey::default() {
+            return;
+        }
+        self.project = project;
+        self.rater = rater;
+        self.ratee = ratee;
+        self.version = Rating::VERSION;
+    }
+
+    pub const MAX_SIZE: usize = 32 //project
+    + 32 //rater
+    + 32 //ratee
+    + 1 //stars
+    + AccountVersion::SIZE; //version
+}
+
+impl Versioned for Rating {
+    const VERSION: AccountVersion = AccountVersion::V1;
+
+    fn version(&self) -> AccountVersion {
+        self.version
+    }
+
+    fn version_mut(&mut self) -> &mut AccountVersion {
+        &mut self.version
+    }
+}
+
+///who the multisig sides with when it stops a project, recorded on both reputations
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Ruling {
+    ///stopped by agreement, no dispute
+    None,
+    Client,
+    Freelancer,
+}
@@ -0,0 +1,53 @@
+use crate::state::{AccountVersion, ProjectInfo, ProjectStatus, Versioned};
+use anchor_lang::prelude::*;
+
+///schedule of a retainer project, `[b"retainer", project_info_account]`.
+///every period is one milestone of the project, one unlocks at the end of each interval
+#[account]
+pub struct Retainer {
+    pub project: Pubkey,
+    ///unix timestamp the first period starts at
+    pub start: i64,
+    ///length of a period in seconds
+    pub interval: i64,
+    ///seconds between a party giving notice and the retainer ending
+    pub notice_period: i64,
+    ///unix timestamp the retainer ends at, 0 until either party gives notice
+    pub terminates_at: i64,
+    ///always last, see `AccountVersion`
+    pub version: AccountVersion,
+}
+
+impl Retainer {
+    pub const MAX_SIZE: usize = 32 //project
+    + 8 //start
+    + 8 //interval
+    + 8 //notice_period
+    + 8 //terminates_at
+    + AccountVersion::SIZE; //version
+
+    ///time on the retainer schedule, the wall clock stopped at the notice deadline
+    ///and at the pause of a paused project, minus the time the project spent paused
+    pub fn schedule_time(&self, now: i64, project: &ProjectInfo) -> i64 {
+        let mut now = now;
+        if project.status == ProjectStatus::Paused {
+            now = now.min(project.paused_at);
+        }
+        if self.terminates_at != 0 {
+            now = now.min(self.terminates_at);
+        }
+        now.saturating_sub(project.paused_duration)
+    }
+}
+
+impl Versioned for Retainer {
+    const VERSION: AccountVersion = AccountVersion::V1;
+
+    fn version(&self) -> AccountVersion {
+        self.version
+    }
+
+    fn version_mut(&mut self) -> &mut AccountVersion {
+        &mut self.version
+    }
+}
@@ -0,0 +1,48 @@
+use crate::state::{AccountVersion, Versioned};
+use anchor_lang::prelude::*;
+
+///time-vested payout of one milestone,
+///`[b"milestone_stream", project_info_account, milestone_index]`
+#[account]
+pub struct MilestoneStream {
+    pub project: Pubkey,
+    pub milestone_index: u8,
+    ///unix timestamps, vesting is linear from `start` to `end`
+    ///and nothing can be withdrawn before `cliff`
+    pub start: i64,
+    pub cliff: i64,
+    pub end: i64,
+    ///project `paused_duration` when the stream was set up,
+    ///the schedule is shifted by the time the project spends paused after that
+    pub paused_offset: i64,
+    ///always last, see `AccountVersion`
+    pub version: AccountVersion,
+}
+
+impl MilestoneStream {
+    pub const MAX_SIZE: usize = 32 //project
+    + 1 //milestone_index
+    + 8 //start
+    + 8 //cliff
+    + 8 //end
+    + 8 //paused_offset
+    + AccountVersion::SIZE; //version
+
+    ///time on the stream schedule, the wall clock minus the time the project
+    ///spent paused since the stream was set up
+    pub fn schedule_time(&self, now: i64, paused_duration: i64) -> i64 {
+        now.saturating_sub(paused_duration.saturating_sub(self.paused_offset))
+    }
+}
+
+impl Versioned for MilestoneStream {
+    const VERSION: AccountVersion = AccountVersion::V1;
+
+    fn version(&self) -> AccountVersion {
+        self.version
+    }
+
+    fn version_mut(&mut self) -> &mut AccountVersion {
+        &mut self.version
+    }
+}
@@ -0,0 +1,41 @@
+use crate::state::{AccountVersion, ProjectInfo, Versioned};
+use anchor_lang::prelude::*;
+
+///link between a child project and the parent project funding it,
+///`[b"subcontract", project_info_account]` of the child.
+///the child's client is the parent's freelancer
+#[account]
+pub struct Subcontract {
+    pub parent: Pubkey,
+    ///gets the refund of a child whose parent was cancelled and closed
+    pub parent_client: Pubkey,
+    ///drawn out of each parent milestone to fund the child, like `milestone_funds_streamed`
+    pub drawn: Vec<u64>,
+    ///the parent is paused or cancelled, the child can't resume until it's resolved
+    pub frozen: bool,
+    ///the freeze paused the child, so thawing it resumes it
+    pub paused_by_freeze: bool,
+    ///always last, see `AccountVersion`
+    pub version: AccountVersion,
+}
+
+impl Subcontract {
+    pub const MAX_SIZE: usize = 32 //parent
+    + 32 //parent_client
+    + 4 + (8 * ProjectInfo::MAX_MILESTONES) //drawn
+    + 1 //frozen
+    + 1 //paused_by_freeze
+    + AccountVersion::SIZE; //version
+}
+
+impl Versioned for Subcontract {
+    const VERSION: AccountVersion = AccountVersion::V1;
+
+    fn version(&self) -> AccountVersion {
+        self.version
+    }
+
+    fn version_mut(&mut self) -> &mut AccountVersion {
+        &mut self.version
+    }
+}
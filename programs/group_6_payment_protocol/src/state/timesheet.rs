@@ -0,0 +1,115 @@
+use crate::state::{AccountVersion, ProjectInfo, ProjectStatus, Versioned};
+use anchor_lang::prelude::*;
+
+pub const SECONDS_PER_WEEK: i64 = 7 * 24 * 60 * 60;
+
+///time-and-materials terms of a project, `[b"hourly_terms", project_info_account]`
+#[account]
+pub struct HourlyTerms {
+    pub project: Pubkey,
+    ///lamports paid per approved hour
+    pub hourly_rate: u64,
+    ///most hours a single weekly timesheet can bill
+    pub weekly_cap: u32,
+    ///seconds the client has to answer a timesheet before it's approved on its own
+    pub approval_window: i64,
+    ///unix timestamp week 0 starts at
+    pub start: i64,
+    ///always last, see `AccountVersion`
+    pub version: AccountVersion,
+}
+
+impl HourlyTerms {
+    pub const MAX_SIZE: usize = 32 //project
+    + 8 //hourly_rate
+    + 4 //weekly_cap
+    + 8 //approval_window
+    + 8 //start
+    + AccountVersion::SIZE; //version
+
+    ///unix timestamp `week` starts at
+    pub fn week_start(&self, week: u32) -> Option<i64> {
+        (week as i64)
+            .checked_mul(SECONDS_PER_WEEK)
+            .and_then(|offset| self.start.checked_add(offset))
+    }
+}
+
+impl Versioned for HourlyTerms {
+    const VERSION: AccountVersion = AccountVersion::V1;
+
+    fn version(&self) -> AccountVersion {
+        self.version
+    }
+
+    fn version_mut(&mut self) -> &mut AccountVersion {
+        &mut self.version
+    }
+}
+
+///hours billed for one week, `[b"timesheet", project_info_account, week]`
+#[account]
+pub struct Timesheet {
+    pub project: Pubkey,
+    ///week of the hourly terms the hours were worked in, starting at 0
+    pub week: u32,
+    pub hours: u32,
+    ///hash of the off-chain description of the work
+    pub memo_hash: [u8; 32],
+    pub submitted_at: i64,
+    ///project `paused_duration` at submission,
+    ///the approval window is shifted by the time the project spends paused after that
+    pub paused_offset: i64,
+    pub status: TimesheetStatus,
+    ///always last, see `AccountVersion`
+    pub version: AccountVersion,
+}
+
+impl Timesheet {
+    pub const MAX_SIZE: usize = 32 //project
+    + 4 //week
+    + 4 //hours
+    + 32 //memo_hash
+    + 8 //submitted_at
+    + 8 //paused_offset
+    + 1 //status
+    + AccountVersion::SIZE; //version
+
+    ///a submitted timesheet the client didn't answer within `approval_window`,
+    ///the window doesn't run while `project` is paused
+    pub fn auto_approved(&self, approval_window: i64, now: i64, project: &ProjectInfo) -> bool {
+        let mut now = now;
+        if project.status == ProjectStatus::Paused {
+            now = now.min(project.paused_at);
+        }
+        let paused = project.paused_duration.saturating_sub(self.paused_offset);
+        self.status == TimesheetStatus::Submitted
+            && now.saturating_sub(paused) >= self.submitted_at.saturating_add(approval_window)
+    }
+}
+
+impl Versioned for Timesheet {
+    const VERSION: AccountVersion = AccountVersion::V1;
+
+    fn version(&self) -> AccountVersion {
+        self.version
+    }
+
+    fn version_mut(&mut self) -> &mut AccountVersion {
+        &mut self.version
+    }
+}
+
+///stored as a single byte (the variant index), so variants
+///must keep their order and new ones go at the end
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TimesheetStatus {
+    //waiting for the client
+    Submitted,
+    //withdrawable by the freelancer
+    Approved,
+    //rejected by the client, the freelancer can submit it again
+    Disputed,
+    //withdrawn
+    Paid,
+}
@@ -0,0 +1,40 @@
+use crate::state::{AccountVersion, Versioned};
+use anchor_lang::prelude::*;
+
+///tips and bonuses the freelancer got on top of the project funds,
+///`[b"tips", project_info_account]`, created by the first tip
+#[account]
+pub struct Tips {
+    pub project: Pubkey,
+    ///lamports paid directly by the client
+    pub from_client: u64,
+    ///lamports paid out of what the escrow held beyond the milestones
+    pub from_escrow: u64,
+    pub count: u32,
+    ///always last, see `AccountVersion`
+    pub version: AccountVersion,
+}
+
+impl Tips {
+    pub const MAX_SIZE: usize = 32 //project
+    + 8 //from_client
+    + 8 //from_escrow
+    + 4 //count
+    + AccountVersion::SIZE; //version
+
+    pub fn total(&self) -> u64 {
+        self.from_client.saturating_add(self.from_escrow)
+    }
+}
+
+impl Versioned for Tips {
+    const VERSION: AccountVersion = AccountVersion::V1;
+
+    fn version(&self) -> AccountVersion {
+        self.version
+    }
+
+    fn version_mut(&mut self) -> &mut AccountVersion {
+        &mut self.version
+    }
+}
@@ -0,0 +1,56 @@
+use anchor_lang::prelude::*;
+use std::io::{self, Write};
+
+///layout version of `ProjectInfo`, `Multisig` and `Transaction`,
+///serialized after every other field.
+///accounts written before versioning either end before that byte or
+///have zero padding there, both read as `Unversioned`.
+///every account type moves to a new version on its own, see `Versioned::VERSION`
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum AccountVersion {
+    #[default]
+    Unversioned,
+    V1,
+    V2,
+}
+
+impl AccountVersion {
+    pub const SIZE: usize = 1;
+}
+
+///account carrying an `AccountVersion`
+pub trait Versioned {
+    ///version the program writes, every handler but `migrate_account`
+    ///rejects accounts at an older one
+    const VERSION: AccountVersion;
+
+    fn version(&self) -> AccountVersion;
+
+    fn version_mut(&mut self) -> &mut AccountVersion;
+
+    fn is_current(&self) -> bool {
+        self.version() == Self::VERSION
+    }
+}
+
+impl AnchorSerialize for AccountVersion {
+    fn serialize<W: Write>(&self, writer: &mut W) -> io::Result<()> {
+        writer.write_all(&[*self as u8])
+    }
+}
+
+impl AnchorDeserialize for AccountVersion {
+    //never fails, so old accounts still load and handlers can
+    //reject them with `AccountNotMigrated` instead of a decoding error
+    fn deserialize(buf: &mut &[u8]) -> io::Result<AccountVersion> {
+        let version = match buf.first() {
+            Some(1) => AccountVersion::V1,
+            Some(2) => AccountVersion::V2,
+            _ => AccountVersion::Unversioned,
+        };
+        if !buf.is_empty() {
+            *buf = &buf[1..];
+        }
+        Ok(version)
+    }
+}
@@ -1,5 +1,7 @@
 use crate::errors::ErrorCode;
 use anchor_lang::prelude::*;
+use anchor_lang::system_program;
+use anchor_lang::system_program::Transfer;
 
 pub fn assert_unique_owners(owners: &[Pubkey]) -> Result<()> {
     for i in 0..owners.len() {
@@ -9,3 +11,97 @@ pub fn assert_unique_owners(owners: &[Pubkey]) -> Result<()> {
     }
     Ok(())
 }
+
+///realloc `account` to `space` bytes if it's smaller, `payer` tops it up to the new rent.
+///accounts are never shrunk, so a reused one keeps its rent
+pub fn grow_account<'info>(
+    account: &AccountInfo<'info>,
+    payer: &AccountInfo<'info>,
+    system_program: &AccountInfo<'info>,
+    space: usize,
+) -> Result<()> {
+    if account.data_len() >= space {
+        return Ok(());
+    }
+    let rent = Rent::get()?
+        .minimum_balance(space)
+        .saturating_sub(account.lamports());
+    if rent > 0 {
+        let transfer_accounts = Transfer {
+            from: payer.clone(),
+            to: account.clone(),
+        };
+        system_program::transfer(
+            CpiContext::new(system_program.clone(), transfer_accounts),
+            rent,
+        )?;
+    }
+    account.realloc(space, false)?;
+    Ok(())
+}
+
+///close `account` like `close = destination` closes a typed account,
+///`destination` gets all of its lamports
+pub fn close_account<'info>(
+    account: &AccountInfo<'info>,
+    destination: &AccountInfo<'info>,
+) -> Result<()> {
+    **destination.try_borrow_mut_lamports()? = destination
+        .lamports()
+        .checked_add(account.lamports())
+        .ok_or(ErrorCode::Overflow)?;
+    **account.try_borrow_mut_lamports()? = 0;
+    account.assign(&system_program::ID);
+    account.realloc(0, false)?;
+    Ok(())
+}
+
+///create `account`, the PDA signed for by `seeds`, with `space` bytes owned by the program.
+///`payer` pays its rent, an address someone already sent lamports to is taken over like `init` does
+pub fn create_pda_account<'info>(
+    account: &AccountInfo<'info>,
+    payer: &AccountInfo<'info>,
+    system_program: &AccountInfo<'info>,
+    space: usize,
+    seeds: &[&[u8]],
+) -> Result<()> {
+    let rent = Rent::get()?.minimum_balance(space);
+    let signer_seeds = &[seeds];
+    if account.lamports() == 0 {
+        let create_accounts = system_program::CreateAccount {
+            from: payer.clone(),
+            to: account.clone(),
+        };
+        return system_program::create_account(
+            CpiContext::new_with_signer(system_program.clone(), create_accounts, signer_seeds),
+            rent,
+            space as u64,
+            &crate::ID,
+        );
+    }
+    let top_up = rent.saturating_sub(account.lamports());
+    if top_up > 0 {
+        let transfer_accounts = Transfer {
+            from: payer.clone(),
+            to: account.clone(),
+        };
+        system_program::transfer(
+            CpiContext::new(system_program.clone(), transfer_accounts),
+            top_up,
+        )?;
+    }
+    let allocate_accounts = system_program::Allocate {
+        account_to_allocate: account.clone(),
+    };
+    system_program::allocate(
+        CpiContext::new_with_signer(system_program.clone(), allocate_accounts, signer_seeds),
+        space as u64,
+    )?;
+    let assign_accounts = system_program::Assign {
+        account_to_assign: account.clone(),
+    };
+    system_program::assign(
+        CpiContext::new_with_signer(system_program.clone(), assign_accounts, signer_seeds),
+        &crate::ID,
+    )
+}
@@ -0,0 +1,675 @@
+//! Random instruction sequences checked against a ledger of the escrows.
+//!
+//! Shared by `tests/fuzz.rs`, which replays seeded random inputs under
+//! `cargo test`, and the cargo-fuzz target in `fuzz/`, where libFuzzer steers
+//! the inputs. The two projects swap the client and freelancer wallets, so
+//! proposals, signers and withdrawals regularly point at the wrong project.
+//! After every transaction each escrow is compared with what was deposited
+//! into it, paid to the freelancer, refunded to the client and drawn into
+//! subcontracts.
+
+use anchor_lang::prelude::{Pubkey, Rent};
+use anchor_lang::solana_program::instruction::Instruction;
+use anchor_lang::{AccountDeserialize, InstructionData};
+use arbitrary::{Arbitrary, Unstructured};
+use group_6_payment_protocol::state::{Multisig, ProjectInfo, ProjectStatus, Ruling, Transaction};
+use group_6_payment_protocol::{instruction, payout};
+
+use super::{Bank, BankError, Project, PROGRAM_ID};
+
+///1000 SOL per wallet
+const WALLET_LAMPORTS: u64 = 1_000_000_000_000;
+///amounts are drawn in hundredths of a SOL
+const UNIT: u64 = 10_000_000;
+///client, freelancer, observer and outsider of each project, as indexes into the wallets
+const PARTIES: [[usize; 4]; 2] = [[0, 1, 2, 3], [1, 0, 3, 2]];
+const PROJECTS: usize = PARTIES.len();
+
+///who signs, relative to the project the action targets
+#[derive(Arbitrary, Clone, Copy, Debug)]
+pub enum Party {
+    Client,
+    Freelancer,
+    Observer,
+    Outsider,
+}
+
+#[derive(Clone, Debug)]
+pub enum Action {
+    Initialize {
+        project: usize,
+        funds: u64,
+        milestones: u8,
+    },
+    ///propose an instruction of `target` on the multisig of `project`
+    Propose {
+        project: usize,
+        proposer: Party,
+        target: usize,
+        proposal: Proposal,
+    },
+    Approve {
+        project: usize,
+        owner: Party,
+    },
+    Withdraw {
+        project: usize,
+        signer: Party,
+    },
+    Cancel {
+        project: usize,
+        signer: Party,
+    },
+    Warp {
+        seconds: i64,
+    },
+}
+
+#[derive(Clone, Debug)]
+pub enum Proposal {
+    Start,
+    MarkMilestone,
+    Stop {
+        ruling: Ruling,
+    },
+    Pause,
+    Resume,
+    ///keep the reached milestones and split the rest over `milestones`
+    ChangeOrder {
+        additional_funds: u64,
+        milestones: u8,
+    },
+    RawChangeOrder {
+        additional_funds: u64,
+        milestone_amounts: Vec<u64>,
+    },
+    Withdraw,
+    Cancel,
+    ///subcontract the target's outsider with its freelancer as the client
+    Subcontract {
+        funds: u64,
+    },
+}
+
+///index picked with probability proportional to its weight
+fn weighted(u: &mut Unstructured, weights: &[u32]) -> arbitrary::Result<usize> {
+    let mut pick = u.int_in_range(0..=weights.iter().sum::<u32>() - 1)?;
+    for (index, weight) in weights.iter().enumerate() {
+        if pick < *weight {
+            return Ok(index);
+        }
+        pick -= weight;
+    }
+    unreachable!()
+}
+
+fn amount(u: &mut Unstructured) -> arbitrary::Result<u64> {
+    Ok(u64::from(u.arbitrary::<u16>()?) * UNIT)
+}
+
+//weighted towards the proposals and approvals a project needs to make progress,
+//uniform bytes would hardly ever get one past Pending
+impl<'a> Arbitrary<'a> for Action {
+    fn arbitrary(u: &mut Unstructured<'a>) -> arbitrary::Result<Action> {
+        let project = u.int_in_range(0..=PROJECTS - 1)?;
+        Ok(match weighted(u, &[8, 30, 30, 16, 6, 10])? {
+            0 => Action::Initialize {
+                project,
+                funds: amount(u)?,
+                //mostly short schedules, so projects get completed
+                milestones: if u.ratio(4u8, 5)? {
+                    u.int_in_range(1..=3)?
+                } else {
+                    u.int_in_range(0..=ProjectInfo::MAX_MILESTONES as u8 + 1)?
+                },
+            },
+            1 => Action::Propose {
+                project,
+                proposer: u.arbitrary()?,
+                target: if u.ratio(1u8, 10)? {
+                    (project + 1) % PROJECTS
+                } else {
+                    project
+                },
+                proposal: u.arbitrary()?,
+            },
+            2 => Action::Approve {
+                project,
+                owner: u.arbitrary()?,
+            },
+            3 => Action::Withdraw {
+                project,
+                signer: u.arbitrary()?,
+            },
+            4 => Action::Cancel {
+                project,
+                signer: u.arbitrary()?,
+            },
+            _ => Action::Warp {
+                seconds: u.int_in_range(0..=30 * 86_400)?,
+            },
+        })
+    }
+}
+
+impl<'a> Arbitrary<'a> for Proposal {
+    fn arbitrary(u: &mut Unstructured<'a>) -> arbitrary::Result<Proposal> {
+        Ok(match weighted(u, &[15, 30, 5, 8, 8, 8, 3, 5, 5, 6])? {
+            0 => Proposal::Start,
+            1 => Proposal::MarkMilestone,
+            2 => Proposal::Stop {
+                ruling: [Ruling::None, Ruling::Client, Ruling::Freelancer][u.choose_index(3)?],
+            },
+            3 => Proposal::Pause,
+            4 => Proposal::Resume,
+            5 => Proposal::ChangeOrder {
+                additional_funds: amount(u)?,
+                milestones: u.int_in_range(1..=ProjectInfo::MAX_MILESTONES as u8)?,
+            },
+            6 => Proposal::RawChangeOrder {
+                additional_funds: u.arbitrary()?,
+                milestone_amounts: u.arbitrary()?,
+            },
+            7 => Proposal::Withdraw,
+            8 => Proposal::Cancel,
+            //small enough to fit in what the parent hasn't reached yet
+            _ => Proposal::Subcontract {
+                funds: u64::from(u.arbitrary::<u8>()?) * UNIT,
+            },
+        })
+    }
+}
+
+///decode actions until `data` runs out, every byte string is a valid input
+pub fn actions(data: &[u8]) -> Vec<Action> {
+    let mut u = Unstructured::new(data);
+    let mut actions = Vec::new();
+    while !u.is_empty() {
+        match u.arbitrary() {
+            Ok(action) => actions.push(action),
+            Err(_) => break,
+        }
+    }
+    actions
+}
+
+///what a run got through, so callers can tell the inputs reach deep states
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Stats {
+    pub transactions: usize,
+    pub succeeded: usize,
+    pub executed: usize,
+    pub withdrawals: usize,
+    pub completed: usize,
+    pub cancelled: usize,
+    pub subcontracts: usize,
+}
+
+impl Stats {
+    pub fn add(&mut self, other: &Stats) {
+        self.transactions += other.transactions;
+        self.succeeded += other.succeeded;
+        self.executed += other.executed;
+        self.withdrawals += other.withdrawals;
+        self.completed += other.completed;
+        self.cancelled += other.cancelled;
+        self.subcontracts += other.subcontracts;
+    }
+}
+
+///escrow outflow the instruction is allowed to make
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Payout {
+    Milestone,
+    Refund,
+    ///into the escrow of a new subcontract
+    Subcontract(Pubkey),
+}
+
+///lamports that went through the escrow of a project since it was initialized
+#[derive(Clone, Copy, Debug, Default)]
+struct Ledger {
+    deposited: u64,
+    paid: u64,
+    refunded: u64,
+    subcontracted: u64,
+}
+
+struct Observed {
+    escrow: u64,
+    info: Option<ProjectInfo>,
+    multisig: Option<(Vec<Pubkey>, u64)>,
+}
+
+struct Fuzzer {
+    bank: Bank,
+    wallets: Vec<Pubkey>,
+    projects: Vec<Project>,
+    ledgers: [Ledger; PROJECTS],
+    stats: Stats,
+}
+
+///run `actions` against a fresh bank, panicking as soon as an invariant breaks
+pub fn run(actions: &[Action]) -> Stats {
+    let mut bank = Bank::new();
+    let wallets: Vec<Pubkey> = (0..4).map(|_| Pubkey::new_unique()).collect();
+    for wallet in &wallets {
+        bank.airdrop(wallet, WALLET_LAMPORTS);
+    }
+    let projects = PARTIES
+        .iter()
+        .map(|parties| {
+            Project::with_parties(
+                wallets[parties[0]],
+                wallets[parties[1]],
+                wallets[parties[2]],
+            )
+        })
+        .collect();
+    let mut fuzzer = Fuzzer {
+        bank,
+        wallets,
+        projects,
+        ledgers: Default::default(),
+        stats: Stats::default(),
+    };
+    for action in actions {
+        fuzzer.step(action);
+    }
+    fuzzer.stats
+}
+
+impl Fuzzer {
+    fn party(&self, project: usize, party: Party) -> Pubkey {
+        self.wallets[PARTIES[project][party as usize]]
+    }
+
+    fn exists(&self, address: &Pubkey) -> bool {
+        self.bank.account(address).is_some()
+    }
+
+    fn step(&mut self, action: &Action) {
+        let (ix, signers, payout) = match self.transaction(action) {
+            Some(transaction) => transaction,
+            None => return,
+        };
+        let before = self.observe();
+        let balances = self.balances();
+        let lamports = self.bank.total_lamports();
+        let result = self.bank.process(&[ix], &signers);
+        self.stats.transactions += 1;
+        match &result {
+            Ok(()) => self.stats.succeeded += 1,
+            //rejected the way a real cluster would reject it
+            Err(BankError::Program(_))
+            | Err(BankError::MissingSigner(_))
+            | Err(BankError::PrivilegeEscalation(_)) => return,
+            Err(violation) => panic!("{action:?} broke a runtime rule: {violation:?}"),
+        }
+        assert_eq!(
+            self.bank.total_lamports(),
+            lamports,
+            "{action:?} created or destroyed lamports"
+        );
+        if matches!(action, Action::Approve { .. }) {
+            self.stats.executed += 1;
+        }
+        let after = self.observe();
+        for index in 0..PROJECTS {
+            self.check(index, action, payout, &balances, &before, &after);
+        }
+    }
+
+    ///the transaction `action` sends, `None` when it can't even be built
+    fn transaction(
+        &mut self,
+        action: &Action,
+    ) -> Option<(Instruction, Vec<Pubkey>, Option<Payout>)> {
+        match *action {
+            Action::Initialize {
+                project,
+                funds,
+                milestones,
+            } => {
+                let project = &self.projects[project];
+                Some((
+                    project.initialize_ix(funds, milestones),
+                    vec![project.client],
+                    None,
+                ))
+            }
+            Action::Propose {
+                project,
+                proposer,
+                target,
+                ref proposal,
+            } => {
+                if !self.exists(&self.projects[project].multisig) {
+                    return None;
+                }
+                let proposer = self.party(project, proposer);
+                let proposal = self.proposal_ix(target, proposal);
+                Some((
+                    self.projects[project].create_transaction_ix(&self.bank, &proposer, &proposal),
+                    vec![proposer],
+                    None,
+                ))
+            }
+            Action::Approve { project, owner } => {
+                let owner = self.party(project, owner);
+                let project = &self.projects[project];
+                if !self.exists(&project.multisig) {
+                    return None;
+                }
+                let address = project.transaction_address(&self.bank);
+                if !self.exists(&address) {
+                    return None;
+                }
+                let transaction: Transaction = self.bank.get(&address);
+                let payout = if transaction.program_id != PROGRAM_ID {
+                    None
+                } else if transaction.data == (instruction::WithdrawMilestoneFunds {}).data() {
+                    Some(Payout::Milestone)
+                } else if transaction.data == (instruction::CancelProject {}).data() {
+                    Some(Payout::Refund)
+                } else if transaction.data[..8]
+                    == (instruction::CreateSubcontract {
+                        total_project_funds: 0,
+                        milestones: 0,
+                    })
+                    .data()[..8]
+                {
+                    //token_escrow of the child, after the parent's accounts and its project,
+                    //multisig and subcontract
+                    Some(Payout::Subcontract(transaction.accounts[8].pubkey))
+                } else {
+                    None
+                };
+                let ix = project.approve_ix(&self.bank, &owner);
+                //accounts the proposal needs signed co-sign the approval
+                let mut signers = vec![owner];
+                signers.extend(
+                    ix.accounts
+                        .iter()
+                        .filter(|meta| meta.is_signer)
+                        .map(|meta| meta.pubkey),
+                );
+                Some((ix, signers, payout))
+            }
+            Action::Withdraw { project, signer } => {
+                let signer = self.party(project, signer);
+                let project = &self.projects[project];
+                let mut ix = project.withdraw_ix();
+                for meta in ix.accounts.iter_mut() {
+                    if meta.pubkey == project.freelancer {
+                        meta.pubkey = signer;
+                        meta.is_signer = true;
+                    }
+                }
+                Some((ix, vec![signer], Some(Payout::Milestone)))
+            }
+            Action::Cancel { project, signer } => {
+                let signer = self.party(project, signer);
+                Some((
+                    self.projects[project].cancel_ix(&self.bank, &signer),
+                    vec![signer],
+                    Some(Payout::Refund),
+                ))
+            }
+            Action::Warp { seconds } => {
+                self.bank.warp(seconds);
+                None
+            }
+        }
+    }
+
+    fn proposal_ix(&self, target: usize, proposal: &Proposal) -> Instruction {
+        let project = &self.projects[target];
+        match proposal {
+            Proposal::Start => project.start_ix(),
+            Proposal::MarkMilestone => project.mark_milestone_ix(),
+            Proposal::Stop { ruling } => project.stop_ruling_ix(*ruling),
+            Proposal::Pause => project.pause_ix(),
+            Proposal::Resume => project.resume_ix(),
+            Proposal::ChangeOrder {
+                additional_funds,
+                milestones,
+            } => {
+                let milestone_amounts = match self.project_info(target) {
+                    Some(info) => {
+                        let reached = info.milestones_reached as usize;
+                        let mut amounts = info.milestone_amounts[..reached].to_vec();
+                        let left = info
+                            .total_project_funds
+                            .saturating_add(*additional_funds)
+                            .saturating_sub(amounts.iter().sum());
+                        let parts = (*milestones as usize).max(reached + 1) - reached;
+                        amounts.extend((0..parts).map(|_| left / parts as u64));
+                        *amounts.last_mut().unwrap() += left % parts as u64;
+                        amounts
+                    }
+                    None => vec![*additional_funds],
+                };
+                project.change_order_ix(*additional_funds, milestone_amounts)
+            }
+            Proposal::RawChangeOrder {
+                additional_funds,
+                milestone_amounts,
+            } => project.change_order_ix(*additional_funds, milestone_amounts.clone()),
+            Proposal::Withdraw => project.withdraw_ix(),
+            Proposal::Cancel => project.cancel_ix(&self.bank, &project.client),
+            Proposal::Subcontract { funds } => {
+                let child = Project::with_parties(
+                    project.freelancer,
+                    self.party(target, Party::Outsider),
+                    project.observer,
+                );
+                project.create_subcontract_ix(&child, *funds, 2)
+            }
+        }
+    }
+
+    fn project_info(&self, index: usize) -> Option<ProjectInfo> {
+        let account = self
+            .bank
+            .account(&self.projects[index].project_info_account)?;
+        ProjectInfo::try_deserialize(&mut &account.data[..]).ok()
+    }
+
+    fn observe(&self) -> Vec<Observed> {
+        (0..PROJECTS)
+            .map(|index| {
+                let project = &self.projects[index];
+                let multisig = self.bank.account(&project.multisig).map(|account| {
+                    let multisig = Multisig::try_deserialize(&mut &account.data[..])
+                        .expect("multisig doesn't decode");
+                    (multisig.owners, multisig.threshold)
+                });
+                Observed {
+                    escrow: self.bank.balance(&project.token_escrow),
+                    info: self.project_info(index),
+                    multisig,
+                }
+            })
+            .collect()
+    }
+
+    fn balances(&self) -> Vec<u64> {
+        self.wallets
+            .iter()
+            .map(|wallet| self.bank.balance(wallet))
+            .collect()
+    }
+
+    fn check(
+        &mut self,
+        index: usize,
+        action: &Action,
+        payout: Option<Payout>,
+        balances: &[u64],
+        before: &[Observed],
+        after: &[Observed],
+    ) {
+        let (before, after) = (&before[index], &after[index]);
+        let mut ledger = self.ledgers[index];
+
+        if after.escrow > before.escrow {
+            ledger.deposited += after.escrow - before.escrow;
+        } else if after.escrow < before.escrow {
+            let amount = before.escrow - after.escrow;
+            let info = before.info.as_ref().unwrap_or_else(|| {
+                panic!("{action:?} emptied the escrow of project {index} before it was initialized")
+            });
+            //a refund also returns the rent of the project account when it gets closed
+            let closed = if after.info.is_none() {
+                Rent::default().minimum_balance(8 + ProjectInfo::MAX_SIZE)
+            } else {
+                0
+            };
+            //wallets the outflow went to, with what each of them should have received
+            let recipients = match payout {
+                Some(Payout::Milestone) => {
+                    ledger.paid += amount;
+                    self.stats.withdrawals += 1;
+                    vec![(info.freelancer, amount, 0)]
+                }
+                Some(Payout::Refund) => {
+                    //cancelling a stopped project pays the freelancer for the milestones it reached
+                    let earned = if info.status == ProjectStatus::Cancelled {
+                        payout::earned_funds(
+                            &info.milestone_amounts,
+                            &info.milestone_funds_streamed,
+                            info.milestone_funds_withdrawn,
+                            info.milestones_reached,
+                        )
+                        .expect("earned funds don't add up")
+                    } else {
+                        0
+                    };
+                    assert!(
+                        earned <= amount,
+                        "{action:?} moved {amount} lamports out of the escrow of project {index} but the freelancer earned {earned}"
+                    );
+                    ledger.paid += earned;
+                    ledger.refunded += amount - earned;
+                    let mut recipients = vec![(info.client, amount - earned, closed)];
+                    if earned > 0 {
+                        recipients.push((info.freelancer, earned, 0));
+                    }
+                    recipients
+                }
+                Some(Payout::Subcontract(escrow)) => {
+                    ledger.subcontracted += amount;
+                    self.stats.subcontracts += 1;
+                    //the child's escrow is created by the same instruction
+                    assert_eq!(
+                        self.bank.balance(&escrow),
+                        amount,
+                        "{action:?} drew {amount} lamports from project {index} but {escrow} received a different amount"
+                    );
+                    vec![]
+                }
+                None => {
+                    panic!("{action:?} paid {amount} lamports out of the escrow of project {index}")
+                }
+            };
+            for (recipient, expected, closed) in recipients {
+                let wallet = self
+                    .wallets
+                    .iter()
+                    .position(|wallet| *wallet == recipient)
+                    .expect("recipient isn't one of the wallets");
+                let received = self.bank.balance(&recipient) as i128 - balances[wallet] as i128;
+                assert!(
+                    received >= expected as i128 && received <= expected as i128 + closed as i128,
+                    "{action:?} moved {expected} lamports out of the escrow of project {index} but {recipient} received {received}"
+                );
+            }
+        }
+
+        //the escrow never pays out more than was deposited
+        let out = ledger.paid + ledger.refunded + ledger.subcontracted;
+        assert!(
+            out <= ledger.deposited,
+            "escrow of project {index} paid out {out} of {} deposited after {action:?}",
+            ledger.deposited
+        );
+        assert_eq!(
+            after.escrow,
+            ledger.deposited - out,
+            "escrow of project {index} doesn't hold what's left of its deposits after {action:?}"
+        );
+
+        match &after.info {
+            Some(info) => {
+                //the freelancer never receives funds for unreached milestones
+                let reached = info.milestones_reached as usize;
+                let earned: u64 = info.milestone_amounts[..reached].iter().sum();
+                assert!(
+                    ledger.paid <= earned,
+                    "freelancer of project {index} received {} for {reached} milestones worth {earned} after {action:?}",
+                    ledger.paid
+                );
+                assert!(
+                    info.milestone_funds_withdrawn <= info.milestones_reached
+                        && info.milestones_reached <= info.milestones
+                        && info.milestones as usize == info.milestone_amounts.len(),
+                    "inconsistent milestones in project {index} after {action:?}"
+                );
+                assert_eq!(
+                    info.milestone_amounts.iter().sum::<u64>(),
+                    info.total_project_funds,
+                    "milestones of project {index} don't add up to its funds after {action:?}"
+                );
+                if info.status == ProjectStatus::Completed {
+                    assert_eq!(
+                        after.escrow, 0,
+                        "completed project {index} left funds in escrow"
+                    );
+                    assert_eq!(
+                        ledger.paid,
+                        ledger.deposited - ledger.refunded - ledger.subcontracted
+                    );
+                }
+                if let Some(previous) = &before.info {
+                    if previous.status != info.status {
+                        assert!(
+                            ProjectStatus::ALLOWED_TRANSITIONS
+                                .contains(&(previous.status, info.status)),
+                            "project {index} went from {:?} to {:?} on {action:?}",
+                            previous.status,
+                            info.status
+                        );
+                        if info.status == ProjectStatus::Completed {
+                            self.stats.completed += 1;
+                        }
+                    }
+                    assert!(
+                        info.milestones_reached >= previous.milestones_reached,
+                        "project {index} lost reached milestones on {action:?}"
+                    );
+                }
+            }
+            None => {
+                //never initialized or closed by a cancellation, the next
+                //initialization starts a fresh ledger
+                assert_eq!(
+                    after.escrow, 0,
+                    "escrow of closed project {index} isn't empty"
+                );
+                if before.info.is_some() {
+                    self.stats.cancelled += 1;
+                }
+                ledger = Ledger::default();
+            }
+        }
+
+        if let (Some(previous), Some(multisig)) = (&before.multisig, &after.multisig) {
+            assert_eq!(
+                previous, multisig,
+                "owners of project {index} changed on {action:?}"
+            );
+        }
+        self.ledgers[index] = ledger;
+    }
+}
@@ -0,0 +1,1097 @@
+//! In-process bank the integration tests run the program against.
+//!
+//! Instructions are executed natively: accounts get serialized in the layout
+//! the BPF loader hands to a program and passed to the anchor `entry`, the
+//! syscall stubs route `invoke_signed` back into the bank, either to the
+//! system program implemented below or recursively into this program.
+//! After every invocation the runtime rules that matter for an escrow are
+//! checked: only writable accounts change, only the owner debits lamports or
+//! writes data, and lamports are neither created nor destroyed.
+
+#![allow(dead_code)]
+
+pub mod fuzz;
+
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::str::FromStr;
+use std::sync::Once;
+
+use anchor_lang::prelude::{AccountInfo, AccountMeta, Clock, ProgramError, Pubkey, Rent};
+use anchor_lang::solana_program::entrypoint::{
+    self, ProgramResult, MAX_PERMITTED_DATA_INCREASE, SUCCESS,
+};
+use anchor_lang::solana_program::instruction::Instruction;
+use anchor_lang::solana_program::program_stubs::{self, SyscallStubs};
+use anchor_lang::solana_program::program_utils::limited_deserialize;
+use anchor_lang::solana_program::system_instruction::{SystemError, SystemInstruction};
+use anchor_lang::solana_program::{bpf_loader_upgradeable, system_program};
+use anchor_lang::{AccountDeserialize, AnchorDeserialize, Discriminator};
+use group_6_payment_protocol::state::{Multisig, ProjectInfo, Ruling, Transaction};
+use group_6_payment_protocol_client::{instructions, multisig, pda, ProjectAddresses};
+
+pub const PROGRAM_ID: Pubkey = group_6_payment_protocol::ID;
+
+///top-level instruction plus 4 levels of CPI, like the real runtime
+const MAX_STACK_HEIGHT: usize = 5;
+
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Account {
+    pub lamports: u64,
+    pub data: Vec<u8>,
+    pub owner: Pubkey,
+    pub executable: bool,
+}
+
+///why the bank rejected a transaction
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum BankError {
+    ///an instruction failed, anchor errors are `ProgramError::Custom`
+    Program(ProgramError),
+    ///a top-level instruction needs a signature the transaction doesn't carry
+    MissingSigner(Pubkey),
+    ///a CPI asked for a signer or writable account the caller doesn't have
+    PrivilegeEscalation(Pubkey),
+    ReadonlyModified(Pubkey),
+    ///a program wrote the data or spent the lamports of an account it doesn't own
+    ExternalAccountModified(Pubkey),
+    UnbalancedInstruction,
+    CallDepthExceeded,
+    Reentrancy(Pubkey),
+    UnknownProgram(Pubkey),
+}
+
+///state of the transaction being processed, shared with the syscall stubs
+#[derive(Default)]
+struct Runtime {
+    clock: Clock,
+    stack: Vec<Pubkey>,
+    logs: Vec<String>,
+    events: Vec<Vec<u8>>,
+    ///accounts of every executing program as they were when it got invoked,
+    ///updated with the changes of the programs it calls
+    frames: Vec<HashMap<Pubkey, Account>>,
+    ///runtime rule broken inside a CPI, reported instead of the program error
+    violation: Option<BankError>,
+}
+
+thread_local! {
+    static RUNTIME: RefCell<Runtime> = RefCell::new(Runtime::default());
+}
+
+fn log(message: String) {
+    RUNTIME.with(|runtime| runtime.borrow_mut().logs.push(message));
+}
+
+struct Stubs;
+
+impl SyscallStubs for Stubs {
+    fn sol_log(&self, message: &str) {
+        log(format!("Program log: {message}"));
+    }
+
+    fn sol_log_data(&self, fields: &[&[u8]]) {
+        RUNTIME.with(|runtime| {
+            let mut runtime = runtime.borrow_mut();
+            runtime
+                .events
+                .extend(fields.iter().map(|field| field.to_vec()));
+        });
+    }
+
+    fn sol_get_clock_sysvar(&self, var_addr: *mut u8) -> u64 {
+        let clock = RUNTIME.with(|runtime| runtime.borrow().clock.clone());
+        unsafe { std::ptr::write(var_addr as *mut Clock, clock) };
+        SUCCESS
+    }
+
+    fn sol_get_rent_sysvar(&self, var_addr: *mut u8) -> u64 {
+        unsafe { std::ptr::write(var_addr as *mut Rent, Rent::default()) };
+        SUCCESS
+    }
+
+    fn sol_invoke_signed(
+        &self,
+        instruction: &Instruction,
+        account_infos: &[AccountInfo],
+        signers_seeds: &[&[&[u8]]],
+    ) -> ProgramResult {
+        invoke_signed(instruction, account_infos, signers_seeds).map_err(|err| match err {
+            BankError::Program(err) => err,
+            violation => {
+                RUNTIME.with(|runtime| {
+                    runtime.borrow_mut().violation.get_or_insert(violation);
+                });
+                ProgramError::InvalidArgument
+            }
+        })
+    }
+}
+
+static STUBS: Once = Once::new();
+
+pub struct Bank {
+    accounts: HashMap<Pubkey, Account>,
+    clock: Clock,
+    logs: Vec<String>,
+    events: Vec<Vec<u8>>,
+}
+
+impl Default for Bank {
+    fn default() -> Bank {
+        Bank::new()
+    }
+}
+
+impl Bank {
+    pub fn new() -> Bank {
+        STUBS.call_once(|| {
+            program_stubs::set_syscall_stubs(Box::new(Stubs));
+        });
+
+        let mut accounts = HashMap::new();
+        accounts.insert(
+            system_program::ID,
+            Account {
+                lamports: 1,
+                data: b"system_program".to_vec(),
+                owner: Pubkey::from_str("NativeLoader1111111111111111111111111111111").unwrap(),
+                executable: true,
+            },
+        );
+        accounts.insert(
+            PROGRAM_ID,
+            Account {
+                lamports: 1,
+                data: Vec::new(),
+                owner: bpf_loader_upgradeable::ID,
+                executable: true,
+            },
+        );
+        Bank {
+            accounts,
+            clock: Clock {
+                slot: 1,
+                unix_timestamp: 1_660_000_000,
+                ..Clock::default()
+            },
+            logs: Vec::new(),
+            events: Vec::new(),
+        }
+    }
+
+    pub fn airdrop(&mut self, to: &Pubkey, lamports: u64) {
+        self.accounts.entry(*to).or_default().lamports += lamports;
+    }
+
+    pub fn set_account(&mut self, address: &Pubkey, account: Account) {
+        self.accounts.insert(*address, account);
+    }
+
+    pub fn account(&self, address: &Pubkey) -> Option<&Account> {
+        self.accounts.get(address)
+    }
+
+    pub fn balance(&self, address: &Pubkey) -> u64 {
+        self.account(address)
+            .map(|account| account.lamports)
+            .unwrap_or_default()
+    }
+
+    ///decode an anchor account, panicking if it's missing or invalid
+    pub fn get<T: AccountDeserialize>(&self, address: &Pubkey) -> T {
+        let account = self
+            .account(address)
+            .unwrap_or_else(|| panic!("account {address} doesn't exist"));
+        T::try_deserialize(&mut &account.data[..])
+            .unwrap_or_else(|err| panic!("account {address} doesn't decode: {err}"))
+    }
+
+    pub fn now(&self) -> i64 {
+        self.clock.unix_timestamp
+    }
+
+    pub fn warp(&mut self, seconds: i64) {
+        self.clock.unix_timestamp += seconds;
+        self.clock.slot += 1;
+    }
+
+    ///lamports held by every account, constant across transactions
+    pub fn total_lamports(&self) -> u128 {
+        self.accounts
+            .values()
+            .map(|account| account.lamports as u128)
+            .sum()
+    }
+
+    ///run `instructions` atomically, `signers` being the keys that signed the transaction
+    pub fn process(
+        &mut self,
+        instructions: &[Instruction],
+        signers: &[Pubkey],
+    ) -> Result<(), BankError> {
+        let snapshot = self.accounts.clone();
+        RUNTIME.with(|runtime| {
+            *runtime.borrow_mut() = Runtime {
+                clock: self.clock.clone(),
+                ..Runtime::default()
+            }
+        });
+
+        let mut result = Ok(());
+        for ix in instructions {
+            result = match ix
+                .accounts
+                .iter()
+                .find(|meta| meta.is_signer && !signers.contains(&meta.pubkey))
+            {
+                Some(meta) => Err(BankError::MissingSigner(meta.pubkey)),
+                None => {
+                    process_instruction(&ix.program_id, &ix.accounts, &ix.data, &mut self.accounts)
+                }
+            };
+            if result.is_err() {
+                break;
+            }
+        }
+
+        let runtime = RUNTIME.with(|runtime| runtime.take());
+        self.logs = runtime.logs;
+        self.events = runtime.events;
+        if let Some(violation) = runtime.violation {
+            result = Err(violation);
+        }
+        match result {
+            Ok(()) => {
+                //accounts left without lamports are garbage collected
+                self.accounts.retain(|_, account| account.lamports > 0);
+                Ok(())
+            }
+            Err(err) => {
+                self.accounts = snapshot;
+                Err(err)
+            }
+        }
+    }
+
+    ///log messages of the last transaction
+    pub fn logs(&self) -> &[String] {
+        &self.logs
+    }
+
+    ///events of type `T` emitted by the last transaction
+    pub fn events<T: Discriminator + AnchorDeserialize>(&self) -> Vec<T> {
+        self.events
+            .iter()
+            .filter(|data| data.len() >= 8 && data[..8] == T::discriminator())
+            .map(|data| T::try_from_slice(&data[8..]).expect("invalid event data"))
+            .collect()
+    }
+}
+
+fn process_instruction(
+    program_id: &Pubkey,
+    metas: &[AccountMeta],
+    data: &[u8],
+    accounts: &mut HashMap<Pubkey, Account>,
+) -> Result<(), BankError> {
+    let height = RUNTIME.with(|runtime| {
+        let runtime = runtime.borrow();
+        //a program can only call itself directly
+        if runtime.stack.contains(program_id) && runtime.stack.last() != Some(program_id) {
+            return Err(BankError::Reentrancy(*program_id));
+        }
+        Ok(runtime.stack.len() + 1)
+    })?;
+    if height > MAX_STACK_HEIGHT {
+        return Err(BankError::CallDepthExceeded);
+    }
+
+    RUNTIME.with(|runtime| runtime.borrow_mut().stack.push(*program_id));
+    log(format!("Program {program_id} invoke [{height}]"));
+    let result = if *program_id == system_program::ID {
+        process_system_instruction(metas, data, accounts)
+    } else if *program_id == PROGRAM_ID {
+        execute_program(program_id, metas, data, accounts)
+    } else {
+        Err(BankError::UnknownProgram(*program_id))
+    };
+    match &result {
+        Ok(()) => log(format!("Program {program_id} success")),
+        Err(err) => log(format!("Program {program_id} failed: {err:?}")),
+    }
+    RUNTIME.with(|runtime| runtime.borrow_mut().stack.pop());
+    result
+}
+
+fn execute_program(
+    program_id: &Pubkey,
+    metas: &[AccountMeta],
+    data: &[u8],
+    accounts: &mut HashMap<Pubkey, Account>,
+) -> Result<(), BankError> {
+    for meta in metas {
+        accounts.entry(meta.pubkey).or_default();
+    }
+    let pre: HashMap<Pubkey, Account> = metas
+        .iter()
+        .map(|meta| (meta.pubkey, accounts[&meta.pubkey].clone()))
+        .collect();
+    RUNTIME.with(|runtime| runtime.borrow_mut().frames.push(pre));
+
+    let mut input = serialize(program_id, metas, data, accounts);
+    //the account infos point into `input`, which outlives them
+    let (result, post) = unsafe {
+        let (program_id, infos, data) = entrypoint::deserialize(input.as_mut_ptr() as *mut u8);
+        let result = group_6_payment_protocol::entry(program_id, &infos, data);
+        let post: HashMap<Pubkey, Account> = infos
+            .iter()
+            .map(|info| (*info.key, account_from_info(info)))
+            .collect();
+        (result, post)
+    };
+    let pre = RUNTIME.with(|runtime| runtime.borrow_mut().frames.pop().unwrap_or_default());
+    result.map_err(BankError::Program)?;
+
+    verify(program_id, metas, &pre, &post)?;
+    accounts.extend(post);
+    Ok(())
+}
+
+///the input buffer of a BPF program, 8-byte aligned like the loader's
+fn serialize(
+    program_id: &Pubkey,
+    metas: &[AccountMeta],
+    data: &[u8],
+    accounts: &HashMap<Pubkey, Account>,
+) -> Vec<u64> {
+    let mut bytes = Vec::new();
+    bytes.extend_from_slice(&(metas.len() as u64).to_le_bytes());
+    for (position, meta) in metas.iter().enumerate() {
+        if let Some(original) = metas[..position]
+            .iter()
+            .position(|other| other.pubkey == meta.pubkey)
+        {
+            bytes.push(original as u8);
+            bytes.extend_from_slice(&[0; 7]);
+            continue;
+        }
+        //a duplicated account gets the privileges of all its occurrences
+        let is_signer = metas
+            .iter()
+            .any(|other| other.pubkey == meta.pubkey && other.is_signer);
+        let is_writable = metas
+            .iter()
+            .any(|other| other.pubkey == meta.pubkey && other.is_writable);
+        let account = &accounts[&meta.pubkey];
+
+        bytes.push(u8::MAX);
+        bytes.push(is_signer as u8);
+        bytes.push(is_writable as u8);
+        bytes.push(account.executable as u8);
+        bytes.extend_from_slice(&[0; 4]);
+        bytes.extend_from_slice(meta.pubkey.as_ref());
+        bytes.extend_from_slice(account.owner.as_ref());
+        bytes.extend_from_slice(&account.lamports.to_le_bytes());
+        bytes.extend_from_slice(&(account.data.len() as u64).to_le_bytes());
+        bytes.extend_from_slice(&account.data);
+        bytes.resize(bytes.len() + MAX_PERMITTED_DATA_INCREASE, 0);
+        bytes.resize(bytes.len().div_ceil(8) * 8, 0);
+        //rent epoch
+        bytes.extend_from_slice(&0u64.to_le_bytes());
+    }
+    bytes.extend_from_slice(&(data.len() as u64).to_le_bytes());
+    bytes.extend_from_slice(data);
+    bytes.extend_from_slice(program_id.as_ref());
+
+    let mut input = vec![0u64; bytes.len().div_ceil(8)];
+    unsafe {
+        std::ptr::copy_nonoverlapping(bytes.as_ptr(), input.as_mut_ptr() as *mut u8, bytes.len());
+    }
+    input
+}
+
+fn account_from_info(info: &AccountInfo) -> Account {
+    Account {
+        lamports: info.lamports(),
+        data: info.data.borrow().to_vec(),
+        owner: *info.owner,
+        executable: info.executable,
+    }
+}
+
+fn verify(
+    program_id: &Pubkey,
+    metas: &[AccountMeta],
+    pre: &HashMap<Pubkey, Account>,
+    post: &HashMap<Pubkey, Account>,
+) -> Result<(), BankError> {
+    let mut pre_lamports = 0u128;
+    let mut post_lamports = 0u128;
+    for (address, after) in post {
+        let before = &pre[address];
+        pre_lamports += before.lamports as u128;
+        post_lamports += after.lamports as u128;
+        if before == after {
+            continue;
+        }
+        let writable = metas
+            .iter()
+            .any(|meta| meta.pubkey == *address && meta.is_writable);
+        if !writable || before.executable || before.executable != after.executable {
+            return Err(BankError::ReadonlyModified(*address));
+        }
+        if before.owner != *program_id
+            && (before.data != after.data
+                || before.owner != after.owner
+                || after.lamports < before.lamports)
+        {
+            return Err(BankError::ExternalAccountModified(*address));
+        }
+    }
+    if pre_lamports != post_lamports {
+        return Err(BankError::UnbalancedInstruction);
+    }
+    Ok(())
+}
+
+///`invoke_signed` from the program currently executing
+fn invoke_signed(
+    ix: &Instruction,
+    account_infos: &[AccountInfo],
+    signers_seeds: &[&[&[u8]]],
+) -> Result<(), BankError> {
+    let caller = RUNTIME.with(|runtime| {
+        *runtime
+            .borrow()
+            .stack
+            .last()
+            .expect("CPI outside a program")
+    });
+    let pda_signers = signers_seeds
+        .iter()
+        .map(|seeds| Pubkey::create_program_address(seeds, &caller))
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|_| BankError::Program(ProgramError::InvalidSeeds))?;
+
+    let find_info = |address: &Pubkey| {
+        account_infos
+            .iter()
+            .find(|info| info.key == address)
+            .ok_or(BankError::Program(ProgramError::NotEnoughAccountKeys))
+    };
+    let mut accounts: HashMap<Pubkey, Account> = HashMap::new();
+    for meta in &ix.accounts {
+        let info = find_info(&meta.pubkey)?;
+        if (meta.is_signer && !info.is_signer && !pda_signers.contains(&meta.pubkey))
+            || (meta.is_writable && !info.is_writable)
+        {
+            return Err(BankError::PrivilegeEscalation(meta.pubkey));
+        }
+        accounts.insert(meta.pubkey, account_from_info(info));
+    }
+
+    let before = accounts.clone();
+    process_instruction(&ix.program_id, &ix.accounts, &ix.data, &mut accounts)?;
+
+    //the callee's changes are its own, not the caller's
+    RUNTIME.with(|runtime| {
+        let mut runtime = runtime.borrow_mut();
+        let frame = runtime.frames.last_mut().expect("CPI outside a program");
+        for (address, after) in &accounts {
+            let (Some(before), Some(pre)) = (before.get(address), frame.get_mut(address)) else {
+                continue;
+            };
+            pre.lamports =
+                (pre.lamports as i128 + after.lamports as i128 - before.lamports as i128) as u64;
+            if before.data != after.data {
+                pre.data = after.data.clone();
+            }
+            pre.owner = after.owner;
+        }
+    });
+
+    //copy the callee's changes back into the caller's account infos
+    for info in account_infos.iter().filter(|info| info.is_writable) {
+        let account = match accounts.get(info.key) {
+            Some(account) => account,
+            None => continue,
+        };
+        **info.try_borrow_mut_lamports().map_err(BankError::Program)? = account.lamports;
+        info.realloc(account.data.len(), false)
+            .map_err(BankError::Program)?;
+        info.try_borrow_mut_data()
+            .map_err(BankError::Program)?
+            .copy_from_slice(&account.data);
+        if *info.owner != account.owner {
+            info.assign(&account.owner);
+        }
+    }
+    Ok(())
+}
+
+///the subset of the system program anchor and the escrow use
+fn process_system_instruction(
+    metas: &[AccountMeta],
+    data: &[u8],
+    accounts: &mut HashMap<Pubkey, Account>,
+) -> Result<(), BankError> {
+    let program_error = BankError::Program;
+    let instruction: SystemInstruction = limited_deserialize(data, 1232)
+        .map_err(|_| program_error(ProgramError::InvalidInstructionData))?;
+    let signer = |index: usize| -> Result<Pubkey, BankError> {
+        let meta = metas
+            .get(index)
+            .ok_or(program_error(ProgramError::NotEnoughAccountKeys))?;
+        if !meta.is_signer {
+            return Err(program_error(ProgramError::MissingRequiredSignature));
+        }
+        Ok(meta.pubkey)
+    };
+    let system_error = |err: SystemError| program_error(ProgramError::Custom(err as u32));
+
+    match instruction {
+        SystemInstruction::CreateAccount {
+            lamports,
+            space,
+            owner,
+        } => {
+            let (from, to) = (signer(0)?, signer(1)?);
+            let target = accounts.entry(to).or_default();
+            if target.lamports > 0 || !target.data.is_empty() || target.owner != system_program::ID
+            {
+                return Err(system_error(SystemError::AccountAlreadyInUse));
+            }
+            debit(accounts, &from, lamports)?;
+            let target = accounts.entry(to).or_default();
+            target.lamports += lamports;
+            target.data = vec![0; space as usize];
+            target.owner = owner;
+        }
+        SystemInstruction::Transfer { lamports } => {
+            let from = signer(0)?;
+            let to = metas
+                .get(1)
+                .ok_or(program_error(ProgramError::NotEnoughAccountKeys))?
+                .pubkey;
+            debit(accounts, &from, lamports)?;
+            accounts.entry(to).or_default().lamports += lamports;
+        }
+        SystemInstruction::Allocate { space } => {
+            let account = accounts.entry(signer(0)?).or_default();
+            if !account.data.is_empty() || account.owner != system_program::ID {
+                return Err(system_error(SystemError::AccountAlreadyInUse));
+            }
+            account.data = vec![0; space as usize];
+        }
+        SystemInstruction::Assign { owner } => {
+            let account = accounts.entry(signer(0)?).or_default();
+            if account.owner != system_program::ID {
+                return Err(program_error(ProgramError::IncorrectProgramId));
+            }
+            account.owner = owner;
+        }
+        _ => return Err(program_error(ProgramError::InvalidInstructionData)),
+    }
+    Ok(())
+}
+
+///take lamports from a system account
+fn debit(
+    accounts: &mut HashMap<Pubkey, Account>,
+    from: &Pubkey,
+    lamports: u64,
+) -> Result<(), BankError> {
+    let account = accounts.entry(*from).or_default();
+    if !account.data.is_empty() || account.owner != system_program::ID {
+        return Err(BankError::Program(ProgramError::InvalidArgument));
+    }
+    account.lamports = account
+        .lamports
+        .checked_sub(lamports)
+        .ok_or(BankError::Program(ProgramError::Custom(
+            SystemError::ResultWithNegativeLamports as u32,
+        )))?;
+    Ok(())
+}
+
+///anchor error code as the bank reports it
+pub fn anchor_error(code: impl Into<u32>) -> BankError {
+    BankError::Program(ProgramError::Custom(code.into()))
+}
+
+///the three parties of a project and its PDAs
+pub struct Project {
+    pub client: Pubkey,
+    pub freelancer: Pubkey,
+    pub observer: Pubkey,
+    pub project_info_account: Pubkey,
+    pub multisig: Pubkey,
+    pub token_escrow: Pubkey,
+}
+
+impl Project {
+    ///fresh parties, the client funded with `client_lamports`
+    pub fn new(bank: &mut Bank, client_lamports: u64) -> Project {
+        let client = Pubkey::new_unique();
+        let freelancer = Pubkey::new_unique();
+        let observer = Pubkey::new_unique();
+        bank.airdrop(&client, client_lamports);
+        bank.airdrop(&freelancer, 1_000_000_000);
+        bank.airdrop(&observer, 1_000_000_000);
+        Project::with_parties(client, freelancer, observer)
+    }
+
+    ///project between existing wallets, nothing is airdropped
+    pub fn with_parties(client: Pubkey, freelancer: Pubkey, observer: Pubkey) -> Project {
+        let addresses = ProjectAddresses::new(client, freelancer);
+        Project {
+            client,
+            freelancer,
+            observer,
+            project_info_account: addresses.project_info_account,
+            multisig: addresses.multisig,
+            token_escrow: addresses.token_escrow,
+        }
+    }
+
+    pub fn owners(&self) -> [Pubkey; 3] {
+        [self.client, self.freelancer, self.observer]
+    }
+
+    pub fn info(&self, bank: &Bank) -> ProjectInfo {
+        bank.get(&self.project_info_account)
+    }
+
+    pub fn transaction_address(&self, bank: &Bank) -> Pubkey {
+        self.addresses().multisig_transaction(self.seqno(bank))
+    }
+
+    ///the client's addresses of this project, every instruction below is built by the client
+    pub fn addresses(&self) -> ProjectAddresses {
+        ProjectAddresses {
+            client: self.client,
+            freelancer: self.freelancer,
+            project_info_account: self.project_info_account,
+            multisig: self.multisig,
+            token_escrow: self.token_escrow,
+        }
+    }
+
+    pub fn initialize_ix(&self, total_funds_for_project: u64, milestones: u8) -> Instruction {
+        instructions::initialize_project(
+            &self.addresses(),
+            &self.observer,
+            total_funds_for_project,
+            milestones,
+        )
+    }
+
+    pub fn start_ix(&self) -> Instruction {
+        instructions::start_project(&self.addresses())
+    }
+
+    pub fn mark_milestone_ix(&self) -> Instruction {
+        instructions::mark_current_milestone_completed(&self.addresses())
+    }
+
+    pub fn pause_ix(&self) -> Instruction {
+        instructions::pause_project(&self.addresses())
+    }
+
+    pub fn resume_ix(&self) -> Instruction {
+        instructions::resume_project(&self.addresses())
+    }
+
+    ///stop by agreement, without ruling for either party
+    pub fn stop_ix(&self) -> Instruction {
+        self.stop_ruling_ix(Ruling::None)
+    }
+
+    pub fn stop_ruling_ix(&self, ruling: Ruling) -> Instruction {
+        instructions::stop_project(&self.addresses(), ruling)
+    }
+
+    pub fn change_order_ix(
+        &self,
+        additional_funds: u64,
+        milestone_amounts: Vec<u64>,
+    ) -> Instruction {
+        instructions::change_order(&self.addresses(), additional_funds, milestone_amounts)
+    }
+
+    pub fn milestone_stream(&self, milestone_index: u8) -> Pubkey {
+        self.addresses().milestone_stream(milestone_index)
+    }
+
+    pub fn set_milestone_stream_ix(
+        &self,
+        milestone_index: u8,
+        start: i64,
+        cliff: i64,
+        end: i64,
+    ) -> Instruction {
+        instructions::set_milestone_stream(&self.addresses(), milestone_index, start, cliff, end)
+    }
+
+    pub fn withdraw_streamed_funds_ix(&self, milestone_index: u8) -> Instruction {
+        instructions::withdraw_streamed_funds(&self.addresses(), milestone_index)
+    }
+
+    pub fn retainer(&self) -> Pubkey {
+        self.addresses().retainer()
+    }
+
+    pub fn initialize_retainer_ix(
+        &self,
+        period_amount: u64,
+        periods: u8,
+        start: i64,
+        interval: i64,
+        notice_period: i64,
+    ) -> Instruction {
+        instructions::initialize_retainer(
+            &self.addresses(),
+            &self.observer,
+            period_amount,
+            periods,
+            start,
+            interval,
+            notice_period,
+        )
+    }
+
+    pub fn unlock_retainer_periods_ix(&self, caller: &Pubkey) -> Instruction {
+        instructions::unlock_retainer_periods(&self.addresses(), caller)
+    }
+
+    pub fn give_retainer_notice_ix(&self, party: &Pubkey) -> Instruction {
+        instructions::give_retainer_notice(&self.addresses(), party)
+    }
+
+    pub fn settle_retainer_ix(&self, caller: &Pubkey) -> Instruction {
+        instructions::settle_retainer(&self.addresses(), caller)
+    }
+
+    pub fn hourly_terms(&self) -> Pubkey {
+        self.addresses().hourly_terms()
+    }
+
+    pub fn timesheet(&self, week: u32) -> Pubkey {
+        self.addresses().timesheet(week)
+    }
+
+    pub fn set_hourly_terms_ix(
+        &self,
+        hourly_rate: u64,
+        weekly_cap: u32,
+        approval_window: i64,
+        start: i64,
+    ) -> Instruction {
+        instructions::set_hourly_terms(
+            &self.addresses(),
+            hourly_rate,
+            weekly_cap,
+            approval_window,
+            start,
+        )
+    }
+
+    pub fn submit_timesheet_ix(&self, week: u32, hours: u32, memo_hash: [u8; 32]) -> Instruction {
+        instructions::submit_timesheet(&self.addresses(), week, hours, memo_hash)
+    }
+
+    pub fn approve_timesheet_ix(&self, week: u32) -> Instruction {
+        instructions::approve_timesheet(&self.addresses(), week)
+    }
+
+    pub fn dispute_timesheet_ix(&self, week: u32) -> Instruction {
+        instructions::dispute_timesheet(&self.addresses(), week)
+    }
+
+    pub fn withdraw_timesheet_funds_ix(&self, week: u32) -> Instruction {
+        instructions::withdraw_timesheet_funds(&self.addresses(), week)
+    }
+
+    pub fn payee_splits(&self) -> Pubkey {
+        self.addresses().payee_splits()
+    }
+
+    pub fn set_payee_splits_ix(
+        &self,
+        payees: Vec<Pubkey>,
+        milestone_splits: Vec<Vec<u16>>,
+    ) -> Instruction {
+        instructions::set_payee_splits(&self.addresses(), payees, milestone_splits)
+    }
+
+    pub fn withdraw_payee_share_ix(&self, payee: &Pubkey) -> Instruction {
+        instructions::withdraw_payee_share(&self.addresses(), payee)
+    }
+
+    pub fn subcontract(&self) -> Pubkey {
+        self.addresses().subcontract()
+    }
+
+    ///open `child` as a subcontract of this project, `child.client` is this freelancer
+    pub fn create_subcontract_ix(
+        &self,
+        child: &Project,
+        total_project_funds: u64,
+        milestones: u8,
+    ) -> Instruction {
+        instructions::create_subcontract(
+            &self.addresses(),
+            &child.addresses(),
+            &child.observer,
+            total_project_funds,
+            milestones,
+        )
+    }
+
+    pub fn freeze_subcontract_ix(&self, parent: &Project, caller: &Pubkey) -> Instruction {
+        instructions::freeze_subcontract(&self.addresses(), &parent.project_info_account, caller)
+    }
+
+    pub fn thaw_subcontract_ix(&self, parent: &Project, caller: &Pubkey) -> Instruction {
+        instructions::thaw_subcontract(&self.addresses(), &parent.project_info_account, caller)
+    }
+
+    pub fn cancel_subcontract_ix(&self, parent: &Project) -> Instruction {
+        instructions::cancel_subcontract(&self.addresses(), &parent.addresses())
+    }
+
+    pub fn bond(&self) -> Pubkey {
+        self.addresses().bond()
+    }
+
+    pub fn require_bond_ix(&self, amount: u64, deadline: i64) -> Instruction {
+        instructions::require_bond(&self.addresses(), amount, deadline)
+    }
+
+    pub fn post_bond_ix(&self) -> Instruction {
+        instructions::post_bond(&self.addresses())
+    }
+
+    pub fn release_bond_ix(&self, caller: &Pubkey) -> Instruction {
+        instructions::release_bond(&self.addresses(), caller)
+    }
+
+    pub fn forfeit_bond_ix(&self) -> Instruction {
+        instructions::forfeit_bond(&self.addresses())
+    }
+
+    pub fn claim_bond_ix(&self, caller: &Pubkey) -> Instruction {
+        instructions::claim_bond(&self.addresses(), caller)
+    }
+
+    ///posting `job_id` of the client, the freelancer bids on it
+    pub fn job_posting(&self, job_id: u64) -> Pubkey {
+        pda::job_posting(&self.client, job_id).0
+    }
+
+    pub fn bid(&self, job_id: u64) -> Pubkey {
+        pda::bid(&self.job_posting(job_id), &self.freelancer).0
+    }
+
+    pub fn create_job_posting_ix(
+        &self,
+        job_id: u64,
+        budget_min: u64,
+        budget_max: u64,
+        milestone_template: Vec<u16>,
+    ) -> Instruction {
+        instructions::create_job_posting(
+            &self.client,
+            job_id,
+            budget_min,
+            budget_max,
+            milestone_template,
+            "ipfs://job".to_string(),
+        )
+    }
+
+    pub fn submit_bid_ix(&self, job_id: u64, price: u64, timeline: i64) -> Instruction {
+        instructions::submit_bid(&self.job_posting(job_id), &self.freelancer, price, timeline)
+    }
+
+    pub fn accept_bid_ix(&self, job_id: u64) -> Instruction {
+        instructions::accept_bid(&self.job_posting(job_id), &self.addresses(), &self.observer)
+    }
+
+    pub fn close_bid_ix(&self, job_id: u64) -> Instruction {
+        instructions::close_bid(&self.job_posting(job_id), &self.freelancer)
+    }
+
+    ///`nonce` of the project, which counts the projects its client opened before it
+    pub fn rating(&self, nonce: u32, rater: &Pubkey) -> Pubkey {
+        pda::rating(&self.project_info_account, nonce, rater).0
+    }
+
+    ///`ratee` is passed explicitly so tests can rate the wrong party
+    pub fn rate_party_ix(
+        &self,
+        nonce: u32,
+        rater: &Pubkey,
+        ratee: &Pubkey,
+        stars: u8,
+    ) -> Instruction {
+        let ix = instructions::rate_party(&self.addresses(), nonce, rater, stars);
+        let other = if *rater == self.client {
+            self.freelancer
+        } else {
+            self.client
+        };
+        let ix = replace_account(ix, &reputation(&other), &reputation(ratee));
+        replace_account(ix, &other, ratee)
+    }
+
+    pub fn tips(&self) -> Pubkey {
+        self.addresses().tips()
+    }
+
+    pub fn tip_ix(&self, amount: u64, from_escrow: bool) -> Instruction {
+        instructions::tip(&self.addresses(), amount, from_escrow)
+    }
+
+    pub fn withdraw_ix(&self) -> Instruction {
+        instructions::withdraw_milestone_funds(&self.addresses())
+    }
+
+    ///`client` is the account passed as the client, not necessarily the project's.
+    ///the ratings are derived from the nonce of the project in `bank`
+    pub fn cancel_ix(&self, bank: &Bank, client: &Pubkey) -> Instruction {
+        let nonce = match bank.account(&self.project_info_account) {
+            Some(_) => self.info(bank).nonce,
+            None => 0,
+        };
+        replace_account(
+            instructions::cancel_project(&self.addresses(), nonce),
+            &self.client,
+            client,
+        )
+    }
+
+    pub fn accept_offer_ix(&self) -> Instruction {
+        instructions::accept_offer(&self.addresses())
+    }
+
+    pub fn decline_offer_ix(&self) -> Instruction {
+        instructions::decline_offer(&self.addresses())
+    }
+
+    pub fn seqno(&self, bank: &Bank) -> u32 {
+        bank.get::<Multisig>(&self.multisig).seqno
+    }
+
+    pub fn create_transaction_ix(
+        &self,
+        bank: &Bank,
+        proposer: &Pubkey,
+        proposal: &Instruction,
+    ) -> Instruction {
+        instructions::create_transaction(&self.addresses(), proposer, self.seqno(bank), proposal)
+    }
+
+    pub fn append_transaction_data_ix(
+        &self,
+        bank: &Bank,
+        proposer: &Pubkey,
+        data: Vec<u8>,
+    ) -> Instruction {
+        instructions::append_transaction_data(&self.addresses(), proposer, self.seqno(bank), data)
+    }
+
+    ///approve the proposal at the current seqno, passing the accounts it executes with
+    pub fn approve_ix(&self, bank: &Bank, owner: &Pubkey) -> Instruction {
+        let address = self.transaction_address(bank);
+        let transaction: Transaction = bank.get(&address);
+        multisig::approve_transaction(&self.addresses(), owner, &address, &transaction)
+    }
+
+    ///migrate `account` to the current layout, the client pays for the extra space
+    pub fn migrate_ix(&self, account: &Pubkey) -> Instruction {
+        instructions::migrate_account(account, &self.client)
+    }
+
+    pub fn initialize(
+        &self,
+        bank: &mut Bank,
+        total_funds_for_project: u64,
+        milestones: u8,
+    ) -> Result<(), BankError> {
+        bank.process(
+            &[self.initialize_ix(total_funds_for_project, milestones)],
+            &[self.client],
+        )
+    }
+
+    pub fn propose(
+        &self,
+        bank: &mut Bank,
+        proposer: &Pubkey,
+        proposal: &Instruction,
+    ) -> Result<(), BankError> {
+        let ix = self.create_transaction_ix(bank, proposer, proposal);
+        bank.process(&[ix], &[*proposer])
+    }
+
+    ///approve as `owner`, signers of the proposal other than the multisig also sign
+    pub fn approve(&self, bank: &mut Bank, owner: &Pubkey) -> Result<(), BankError> {
+        let ix = self.approve_ix(bank, owner);
+        let mut signers = vec![*owner];
+        signers.extend(
+            ix.accounts
+                .iter()
+                .filter(|meta| meta.is_signer)
+                .map(|meta| meta.pubkey),
+        );
+        bank.process(&[ix], &signers)
+    }
+
+    ///propose as the client and approve as the freelancer, reaching the 2 of 3 threshold
+    pub fn execute(&self, bank: &mut Bank, proposal: &Instruction) -> Result<(), BankError> {
+        self.propose(bank, &self.client, proposal)?;
+        self.approve(bank, &self.freelancer)
+    }
+
+    pub fn withdraw(&self, bank: &mut Bank) -> Result<(), BankError> {
+        bank.process(&[self.withdraw_ix()], &[self.freelancer])
+    }
+
+    pub fn withdraw_streamed_funds(
+        &self,
+        bank: &mut Bank,
+        milestone_index: u8,
+    ) -> Result<(), BankError> {
+        bank.process(
+            &[self.withdraw_streamed_funds_ix(milestone_index)],
+            &[self.freelancer],
+        )
+    }
+
+    pub fn accept_offer(&self, bank: &mut Bank) -> Result<(), BankError> {
+        bank.process(&[self.accept_offer_ix()], &[self.freelancer])
+    }
+
+    pub fn cancel(&self, bank: &mut Bank) -> Result<(), BankError> {
+        bank.process(&[self.cancel_ix(bank, &self.client)], &[self.client])
+    }
+}
+
+///`[b"reputation", wallet]`
+pub fn reputation(wallet: &Pubkey) -> Pubkey {
+    pda::reputation(wallet).0
+}
+
+pub fn create_reputation_ix(wallet: &Pubkey, payer: &Pubkey) -> Instruction {
+    instructions::create_reputation(wallet, payer)
+}
+
+///`ix` with every occurrence of `from` passed as `to` instead
+fn replace_account(mut ix: Instruction, from: &Pubkey, to: &Pubkey) -> Instruction {
+    for meta in ix.accounts.iter_mut().filter(|meta| meta.pubkey == *from) {
+        meta.pubkey = *to;
+    }
+    ix
+}
@@ -0,0 +1,58 @@
+//! Seeded replay of the fuzz target, so `cargo test` exercises the same
+//! invariants as `cargo fuzz run instructions`.
+//!
+//! `FUZZ_SEED` and `FUZZ_RUNS` pick the inputs, a failing seed is printed with
+//! the actions it decoded to so it can be replayed on its own.
+
+mod common;
+
+use std::env;
+use std::panic::{self, AssertUnwindSafe};
+
+use common::fuzz::{actions, run, Stats};
+
+fn env_or(name: &str, default: u64) -> u64 {
+    env::var(name)
+        .ok()
+        .and_then(|value| value.parse().ok())
+        .unwrap_or(default)
+}
+
+///xorshift64*, enough to feed `Unstructured` reproducible bytes
+fn random_bytes(seed: u64) -> Vec<u8> {
+    let mut state = seed.wrapping_mul(0x9e37_79b9_7f4a_7c15) | 1;
+    let mut next = || {
+        state ^= state >> 12;
+        state ^= state << 25;
+        state ^= state >> 27;
+        state.wrapping_mul(0x2545_f491_4f6c_dd1d)
+    };
+    let len = 256 + (next() % 2048) as usize;
+    (0..len).map(|_| next() as u8).collect()
+}
+
+#[test]
+fn random_instruction_sequences_keep_escrow_invariants() {
+    let first = env_or("FUZZ_SEED", 0);
+    let runs = env_or("FUZZ_RUNS", 64);
+    let mut total = Stats::default();
+    for seed in first..first + runs {
+        let actions = actions(&random_bytes(seed));
+        match panic::catch_unwind(AssertUnwindSafe(|| run(&actions))) {
+            Ok(stats) => total.add(&stats),
+            Err(err) => {
+                eprintln!("FUZZ_SEED={seed} failed on {actions:#?}");
+                panic::resume_unwind(err);
+            }
+        }
+    }
+    println!("{total:?}");
+    //the inputs have to get projects through their lifecycle
+    //for the invariants to mean anything
+    if runs >= 64 {
+        assert!(total.withdrawals > 0, "{total:?}");
+        assert!(total.completed > 0, "{total:?}");
+        assert!(total.cancelled > 0, "{total:?}");
+        assert!(total.subcontracts > 0, "{total:?}");
+    }
+}
@@ -0,0 +1,2653 @@
+//! Every instruction of the program, run against the in-process bank in `common`.
+
+mod common;
+
+use anchor_lang::error::ErrorCode as AnchorErrorCode;
+use anchor_lang::prelude::{AccountMeta, ProgramError, Pubkey, Rent};
+use anchor_lang::solana_program::system_instruction;
+use anchor_lang::{AccountDeserialize, AccountSerialize, AnchorSerialize, Discriminator};
+use group_6_payment_protocol::errors::ErrorCode;
+use group_6_payment_protocol::events;
+use group_6_payment_protocol::state::{
+    AccountVersion, Bid, JobPosting, MilestoneStream, Multisig, PayeeSplits, ProjectInfo,
+    ProjectInfoV0, ProjectInfoV1, ProjectStatus, Rating, Reputation, Retainer, Ruling, Subcontract,
+    Timesheet, TimesheetStatus, Tips, Transaction, Versioned,
+};
+
+use group_6_payment_protocol_client::multisig::{self, MultisigAction};
+
+use common::{
+    anchor_error, create_reputation_ix, reputation, Account, Bank, BankError, Project, PROGRAM_ID,
+};
+
+const SOL: u64 = 1_000_000_000;
+
+fn initialized(funds: u64, milestones: u8) -> (Bank, Project) {
+    let mut bank = Bank::new();
+    let project = Project::new(&mut bank, 100 * SOL);
+    project.initialize(&mut bank, funds, milestones).unwrap();
+    (bank, project)
+}
+
+///a project from before offers, left Pending until the multisig starts it
+fn pending(funds: u64, milestones: u8) -> (Bank, Project) {
+    let (mut bank, project) = initialized(funds, milestones);
+    let mut info = project.info(&bank);
+    info.status = ProjectStatus::Pending;
+    let mut data = Vec::new();
+    info.try_serialize(&mut data).unwrap();
+    data.resize(
+        bank.account(&project.project_info_account)
+            .unwrap()
+            .data
+            .len(),
+        0,
+    );
+    overwrite(&mut bank, &project.project_info_account, data);
+    (bank, project)
+}
+
+fn running(funds: u64, milestones: u8) -> (Bank, Project) {
+    let (mut bank, project) = initialized(funds, milestones);
+    project.accept_offer(&mut bank).unwrap();
+    (bank, project)
+}
+
+#[test]
+fn initialize_project_locks_the_funds() {
+    let mut bank = Bank::new();
+    let project = Project::new(&mut bank, 100 * SOL);
+    let total = bank.total_lamports();
+
+    project.initialize(&mut bank, 10 * SOL, 3).unwrap();
+
+    assert_eq!(bank.balance(&project.token_escrow), 10 * SOL);
+    assert_eq!(bank.total_lamports(), total);
+
+    let info = project.info(&bank);
+    assert_eq!(info.client, project.client);
+    assert_eq!(info.freelancer, project.freelancer);
+    assert_eq!(info.multisig, project.multisig);
+    assert_eq!(info.status, ProjectStatus::Offered);
+    assert_eq!(info.total_project_funds, 10 * SOL);
+    assert_eq!(info.milestones, 3);
+    assert_eq!(info.milestone_amounts.iter().sum::<u64>(), 10 * SOL);
+
+    let multisig: Multisig = bank.get(&project.multisig);
+    assert_eq!(multisig.owners, project.owners());
+    assert_eq!(multisig.threshold, 2);
+
+    let initialized = bank.events::<events::ProjectInitialized>();
+    assert_eq!(initialized.len(), 1);
+    assert_eq!(initialized[0].observer, project.observer);
+}
+
+#[test]
+fn initialize_project_twice_fails() {
+    let (mut bank, project) = initialized(SOL, 1);
+    assert!(project.initialize(&mut bank, SOL, 1).is_err());
+    assert_eq!(bank.balance(&project.token_escrow), SOL);
+}
+
+#[test]
+fn initialize_project_rejects_invalid_milestones() {
+    let mut bank = Bank::new();
+    let project = Project::new(&mut bank, 100 * SOL);
+    for milestones in [0, 21] {
+        assert_eq!(
+            project.initialize(&mut bank, SOL, milestones),
+            Err(anchor_error(ErrorCode::InvalidMilestones))
+        );
+    }
+}
+
+#[test]
+fn initialize_project_needs_the_client_funds() {
+    let mut bank = Bank::new();
+    let project = Project::new(&mut bank, SOL);
+    assert!(project.initialize(&mut bank, 2 * SOL, 2).is_err());
+    assert!(bank.account(&project.project_info_account).is_none());
+}
+
+#[test]
+fn offers_start_once_the_freelancer_accepts() {
+    let (mut bank, project) = initialized(SOL, 1);
+
+    //the multisig can't start an offer on the freelancer's behalf
+    project
+        .propose(&mut bank, &project.client, &project.start_ix())
+        .unwrap();
+    assert_eq!(
+        project.approve(&mut bank, &project.observer),
+        Err(anchor_error(ErrorCode::OfferNotAccepted))
+    );
+
+    let stranger = Pubkey::new_unique();
+    let mut ix = project.accept_offer_ix();
+    ix.accounts[1].pubkey = stranger;
+    assert_eq!(
+        bank.process(&[ix], &[stranger]),
+        Err(anchor_error(ErrorCode::FreelancerOnly))
+    );
+
+    project.accept_offer(&mut bank).unwrap();
+    assert_eq!(project.info(&bank).status, ProjectStatus::Running);
+    assert_eq!(bank.events::<events::OfferAccepted>().len(), 1);
+    assert_eq!(
+        project.accept_offer(&mut bank),
+        Err(anchor_error(ErrorCode::ProjectAlreadyRunning))
+    );
+    assert_eq!(
+        bank.process(&[project.decline_offer_ix()], &[project.freelancer]),
+        Err(anchor_error(ErrorCode::ProjectAlreadyRunning))
+    );
+}
+
+#[test]
+fn declined_offers_refund_the_client() {
+    let (mut bank, project) = initialized(10 * SOL, 2);
+    let rent = bank.balance(&project.project_info_account) + bank.balance(&project.multisig);
+    let client_balance = bank.balance(&project.client);
+
+    bank.process(&[project.decline_offer_ix()], &[project.freelancer])
+        .unwrap();
+    assert_eq!(
+        bank.balance(&project.client),
+        client_balance + 10 * SOL + rent
+    );
+    assert_eq!(bank.balance(&project.token_escrow), 0);
+    assert!(bank.account(&project.project_info_account).is_none());
+    assert!(bank.account(&project.multisig).is_none());
+    let declined = bank.events::<events::OfferDeclined>();
+    assert_eq!(declined[0].amount, 10 * SOL);
+    assert_eq!(declined[0].actor, project.freelancer);
+    assert_eq!(declined[0].client, project.client);
+
+    //the client can make the freelancer a new offer
+    project.initialize(&mut bank, 5 * SOL, 1).unwrap();
+    assert_eq!(project.info(&bank).status, ProjectStatus::Offered);
+}
+
+#[test]
+fn start_project_needs_two_approvals() {
+    let (mut bank, project) = pending(SOL, 1);
+
+    project
+        .propose(&mut bank, &project.client, &project.start_ix())
+        .unwrap();
+    assert_eq!(project.info(&bank).status, ProjectStatus::Pending);
+    assert_eq!(bank.events::<events::ProposalCreated>().len(), 1);
+
+    project.approve(&mut bank, &project.observer).unwrap();
+    assert_eq!(project.info(&bank).status, ProjectStatus::Running);
+    assert_eq!(bank.events::<events::ProjectStarted>().len(), 1);
+    assert_eq!(bank.events::<events::ProposalExecuted>().len(), 1);
+
+    let transaction: Transaction = bank.get(&project.transaction_address(&bank));
+    assert!(transaction.did_execute);
+    assert_eq!(transaction.signers, vec![true, false, true]);
+}
+
+#[test]
+fn start_project_requires_the_multisig() {
+    let (mut bank, project) = pending(SOL, 1);
+
+    //the multisig PDA can only sign through approve
+    let mut ix = project.start_ix();
+    ix.accounts[1].is_signer = false;
+    assert_eq!(
+        bank.process(&[ix], &[project.client]),
+        Err(anchor_error(AnchorErrorCode::AccountNotSigner))
+    );
+    assert_eq!(project.info(&bank).status, ProjectStatus::Pending);
+}
+
+#[test]
+fn start_project_twice_fails() {
+    let (mut bank, project) = running(SOL, 1);
+    project
+        .propose(&mut bank, &project.client, &project.start_ix())
+        .unwrap();
+    assert_eq!(
+        project.approve(&mut bank, &project.freelancer),
+        Err(anchor_error(ErrorCode::ProjectAlreadyRunning))
+    );
+}
+
+#[test]
+fn milestones_are_withdrawn_as_they_are_reached() {
+    let (mut bank, project) = running(9 * SOL, 3);
+    let freelancer_balance = bank.balance(&project.freelancer);
+
+    project
+        .execute(&mut bank, &project.mark_milestone_ix())
+        .unwrap();
+    assert_eq!(project.info(&bank).milestones_reached, 1);
+    project.withdraw(&mut bank).unwrap();
+    assert_eq!(
+        bank.balance(&project.freelancer),
+        freelancer_balance + 3 * SOL
+    );
+    assert_eq!(bank.balance(&project.token_escrow), 6 * SOL);
+
+    //two milestones reached at once are paid together
+    project
+        .execute(&mut bank, &project.mark_milestone_ix())
+        .unwrap();
+    project
+        .execute(&mut bank, &project.mark_milestone_ix())
+        .unwrap();
+    project.withdraw(&mut bank).unwrap();
+
+    let withdrawn = bank.events::<events::FundsWithdrawn>();
+    assert_eq!(withdrawn.len(), 1);
+    assert_eq!(withdrawn[0].amount, 6 * SOL);
+    assert_eq!(withdrawn[0].milestone_index, 2);
+    assert!(withdrawn[0].completed);
+
+    assert_eq!(
+        bank.balance(&project.freelancer),
+        freelancer_balance + 9 * SOL
+    );
+    assert_eq!(bank.balance(&project.token_escrow), 0);
+    let info = project.info(&bank);
+    assert_eq!(info.status, ProjectStatus::Completed);
+    assert_eq!(info.milestone_funds_withdrawn, 3);
+}
+
+#[test]
+fn mark_milestone_after_the_last_one_fails() {
+    let (mut bank, project) = running(SOL, 1);
+    project
+        .execute(&mut bank, &project.mark_milestone_ix())
+        .unwrap();
+    assert_eq!(
+        project.execute(&mut bank, &project.mark_milestone_ix()),
+        Err(anchor_error(ErrorCode::AllMilestonesReached))
+    );
+}
+
+#[test]
+fn withdraw_before_any_milestone_fails() {
+    let (mut bank, project) = initialized(2 * SOL, 2);
+    assert_eq!(
+        project.withdraw(&mut bank),
+        Err(anchor_error(ErrorCode::OfferNotAccepted))
+    );
+
+    project.accept_offer(&mut bank).unwrap();
+    assert_eq!(
+        project.withdraw(&mut bank),
+        Err(anchor_error(ErrorCode::NothingToWithdraw))
+    );
+    assert_eq!(bank.balance(&project.token_escrow), 2 * SOL);
+}
+
+#[test]
+fn withdraw_twice_for_the_same_milestone_fails() {
+    let (mut bank, project) = running(2 * SOL, 2);
+    project
+        .execute(&mut bank, &project.mark_milestone_ix())
+        .unwrap();
+    project.withdraw(&mut bank).unwrap();
+    assert_eq!(
+        project.withdraw(&mut bank),
+        Err(anchor_error(ErrorCode::NothingToWithdraw))
+    );
+}
+
+#[test]
+fn withdraw_by_someone_else_fails() {
+    let (mut bank, project) = running(SOL, 1);
+    project
+        .execute(&mut bank, &project.mark_milestone_ix())
+        .unwrap();
+
+    let mut ix = project.withdraw_ix();
+    ix.accounts[2] = AccountMeta::new(project.client, true);
+    assert_eq!(
+        bank.process(&[ix], &[project.client]),
+        Err(anchor_error(AnchorErrorCode::ConstraintRaw))
+    );
+    assert_eq!(bank.balance(&project.token_escrow), SOL);
+}
+
+#[test]
+fn withdraw_overflow_fails() {
+    let (mut bank, project) = running(SOL, 1);
+
+    //change_order never lets the amounts overflow, so write the state directly
+    let info = ProjectInfo {
+        milestones: 3,
+        milestones_reached: 2,
+        milestone_amounts: vec![u64::MAX, 1, 0],
+        milestone_funds_streamed: vec![0; 3],
+        ..project.info(&bank)
+    };
+    let mut data = Vec::new();
+    info.try_serialize(&mut data).unwrap();
+    data.resize(8 + ProjectInfo::MAX_SIZE, 0);
+    let lamports = bank.balance(&project.project_info_account);
+    bank.set_account(
+        &project.project_info_account,
+        Account {
+            lamports,
+            data,
+            owner: PROGRAM_ID,
+            executable: false,
+        },
+    );
+
+    assert_eq!(
+        project.withdraw(&mut bank),
+        Err(anchor_error(ErrorCode::Overflow))
+    );
+    assert_eq!(bank.balance(&project.token_escrow), SOL);
+}
+
+#[test]
+fn pause_and_resume_track_the_time_spent_paused() {
+    let (mut bank, project) = running(2 * SOL, 2);
+    project.execute(&mut bank, &project.pause_ix()).unwrap();
+    assert_eq!(project.info(&bank).status, ProjectStatus::Paused);
+
+    assert_eq!(
+        project.execute(&mut bank, &project.mark_milestone_ix()),
+        Err(anchor_error(ErrorCode::ProjectPaused))
+    );
+    assert_eq!(
+        project.withdraw(&mut bank),
+        Err(anchor_error(ErrorCode::ProjectPaused))
+    );
+
+    bank.warp(3600);
+    project.execute(&mut bank, &project.resume_ix()).unwrap();
+    let resumed = bank.events::<events::ProjectResumed>();
+    assert_eq!(resumed[0].paused_for, 3600);
+
+    let info = project.info(&bank);
+    assert_eq!(info.status, ProjectStatus::Running);
+    assert_eq!(info.paused_duration, 3600);
+    assert_eq!(info.paused_at, 0);
+}
+
+#[test]
+fn only_running_projects_are_paused_and_only_paused_ones_resumed() {
+    let (mut bank, project) = initialized(2 * SOL, 2);
+    assert_eq!(
+        project.execute(&mut bank, &project.pause_ix()),
+        Err(anchor_error(ErrorCode::OfferNotAccepted))
+    );
+    project.accept_offer(&mut bank).unwrap();
+    assert_eq!(
+        project.execute(&mut bank, &project.resume_ix()),
+        Err(anchor_error(ErrorCode::ProjectAlreadyRunning))
+    );
+
+    project.execute(&mut bank, &project.pause_ix()).unwrap();
+    assert_eq!(
+        project.execute(&mut bank, &project.pause_ix()),
+        Err(anchor_error(ErrorCode::ProjectPaused))
+    );
+    project.execute(&mut bank, &project.resume_ix()).unwrap();
+    //every pause is added up
+    project.execute(&mut bank, &project.pause_ix()).unwrap();
+    bank.warp(60);
+    project.execute(&mut bank, &project.resume_ix()).unwrap();
+    project.execute(&mut bank, &project.pause_ix()).unwrap();
+    bank.warp(40);
+    project.execute(&mut bank, &project.resume_ix()).unwrap();
+    assert_eq!(project.info(&bank).paused_duration, 100);
+}
+
+#[test]
+fn reached_milestones_are_withdrawn_once_the_project_resumes() {
+    let (mut bank, project) = running(2 * SOL, 2);
+    project
+        .execute(&mut bank, &project.mark_milestone_ix())
+        .unwrap();
+    project.execute(&mut bank, &project.pause_ix()).unwrap();
+    let freelancer_balance = bank.balance(&project.freelancer);
+    assert_eq!(
+        project.withdraw(&mut bank),
+        Err(anchor_error(ErrorCode::ProjectPaused))
+    );
+    assert_eq!(bank.balance(&project.token_escrow), 2 * SOL);
+
+    project.execute(&mut bank, &project.resume_ix()).unwrap();
+    project.withdraw(&mut bank).unwrap();
+    assert_eq!(bank.balance(&project.freelancer), freelancer_balance + SOL);
+}
+
+#[test]
+fn change_order_adds_funds_and_milestones() {
+    let (mut bank, project) = running(2 * SOL, 2);
+    project
+        .execute(&mut bank, &project.mark_milestone_ix())
+        .unwrap();
+
+    let change_order = project.change_order_ix(SOL, vec![SOL, SOL, SOL]);
+    project
+        .propose(&mut bank, &project.freelancer, &change_order)
+        .unwrap();
+    //the client approves and signs for the additional funds
+    project.approve(&mut bank, &project.client).unwrap();
+
+    let info = project.info(&bank);
+    assert_eq!(info.total_project_funds, 3 * SOL);
+    assert_eq!(info.milestones, 3);
+    assert_eq!(info.milestone_amounts, vec![SOL, SOL, SOL]);
+    assert_eq!(bank.balance(&project.token_escrow), 3 * SOL);
+}
+
+#[test]
+fn change_order_cannot_rewrite_reached_milestones() {
+    let (mut bank, project) = running(2 * SOL, 2);
+    project
+        .execute(&mut bank, &project.mark_milestone_ix())
+        .unwrap();
+
+    let change_order = project.change_order_ix(0, vec![SOL / 2, 3 * SOL / 2]);
+    project
+        .propose(&mut bank, &project.freelancer, &change_order)
+        .unwrap();
+    assert_eq!(
+        project.approve(&mut bank, &project.client),
+        Err(anchor_error(ErrorCode::MilestoneAlreadyReached))
+    );
+}
+
+///stream `milestone_index` from now on, proposed by the freelancer and
+///approved by the client who pays for the stream account
+fn stream_milestone(bank: &mut Bank, project: &Project, milestone_index: u8, cliff: i64, end: i64) {
+    let now = bank.now();
+    let ix = project.set_milestone_stream_ix(milestone_index, now, now + cliff, now + end);
+    project.propose(bank, &project.freelancer, &ix).unwrap();
+    project.approve(bank, &project.client).unwrap();
+}
+
+#[test]
+fn streamed_milestones_vest_over_time() {
+    let (mut bank, project) = running(2 * SOL, 2);
+    stream_milestone(&mut bank, &project, 0, 100, 1000);
+
+    //nothing before the cliff
+    bank.warp(99);
+    assert_eq!(
+        project.withdraw_streamed_funds(&mut bank, 0),
+        Err(anchor_error(ErrorCode::NothingToWithdraw))
+    );
+
+    let freelancer_balance = bank.balance(&project.freelancer);
+    bank.warp(401);
+    project.withdraw_streamed_funds(&mut bank, 0).unwrap();
+    assert_eq!(
+        bank.balance(&project.freelancer),
+        freelancer_balance + SOL / 2
+    );
+    assert_eq!(
+        project.info(&bank).milestone_funds_streamed,
+        vec![SOL / 2, 0]
+    );
+    let withdrawn = bank.events::<events::StreamedFundsWithdrawn>();
+    assert_eq!(withdrawn.len(), 1);
+    assert_eq!(withdrawn[0].total_streamed, SOL / 2);
+    assert_eq!(
+        project.withdraw_streamed_funds(&mut bank, 0),
+        Err(anchor_error(ErrorCode::NothingToWithdraw))
+    );
+
+    //vesting stops while the project is paused
+    project.execute(&mut bank, &project.pause_ix()).unwrap();
+    bank.warp(1000);
+    assert_eq!(
+        project.withdraw_streamed_funds(&mut bank, 0),
+        Err(anchor_error(ErrorCode::ProjectPaused))
+    );
+    project.execute(&mut bank, &project.resume_ix()).unwrap();
+    assert_eq!(
+        project.withdraw_streamed_funds(&mut bank, 0),
+        Err(anchor_error(ErrorCode::NothingToWithdraw))
+    );
+    bank.warp(250);
+    project.withdraw_streamed_funds(&mut bank, 0).unwrap();
+    assert_eq!(
+        project.info(&bank).milestone_funds_streamed,
+        vec![3 * SOL / 4, 0]
+    );
+
+    //reaching the milestone pays what the stream hasn't
+    let freelancer_balance = bank.balance(&project.freelancer);
+    project
+        .execute(&mut bank, &project.mark_milestone_ix())
+        .unwrap();
+    project.withdraw(&mut bank).unwrap();
+    assert_eq!(
+        bank.balance(&project.freelancer),
+        freelancer_balance + SOL / 4
+    );
+    assert_eq!(bank.balance(&project.token_escrow), SOL);
+    assert_eq!(
+        project.withdraw_streamed_funds(&mut bank, 0),
+        Err(anchor_error(ErrorCode::NothingToWithdraw))
+    );
+}
+
+#[test]
+fn streams_are_checked_when_set() {
+    let (mut bank, project) = running(2 * SOL, 2);
+    let now = bank.now();
+
+    let ix = project.set_milestone_stream_ix(1, now, now - 1, now + 10);
+    project
+        .propose(&mut bank, &project.freelancer, &ix)
+        .unwrap();
+    assert_eq!(
+        project.approve(&mut bank, &project.client),
+        Err(anchor_error(ErrorCode::InvalidStream))
+    );
+    let ix = project.set_milestone_stream_ix(2, now, now, now + 10);
+    project
+        .propose(&mut bank, &project.freelancer, &ix)
+        .unwrap();
+    assert_eq!(
+        project.approve(&mut bank, &project.client),
+        Err(anchor_error(ErrorCode::InvalidMilestones))
+    );
+
+    project
+        .execute(&mut bank, &project.mark_milestone_ix())
+        .unwrap();
+    let ix = project.set_milestone_stream_ix(0, now, now, now + 10);
+    project
+        .propose(&mut bank, &project.freelancer, &ix)
+        .unwrap();
+    assert_eq!(
+        project.approve(&mut bank, &project.client),
+        Err(anchor_error(ErrorCode::MilestoneAlreadyReached))
+    );
+}
+
+#[test]
+fn change_order_cannot_rewrite_streamed_milestones() {
+    let (mut bank, project) = running(2 * SOL, 2);
+    stream_milestone(&mut bank, &project, 1, 0, 100);
+    bank.warp(10);
+    project.withdraw_streamed_funds(&mut bank, 1).unwrap();
+
+    let change_order = project.change_order_ix(0, vec![SOL / 2, 3 * SOL / 2]);
+    project
+        .propose(&mut bank, &project.freelancer, &change_order)
+        .unwrap();
+    assert_eq!(
+        project.approve(&mut bank, &project.client),
+        Err(anchor_error(ErrorCode::MilestoneAlreadyReached))
+    );
+
+    //milestones can still be added after it
+    let change_order = project.change_order_ix(SOL, vec![SOL, SOL, SOL]);
+    project
+        .propose(&mut bank, &project.freelancer, &change_order)
+        .unwrap();
+    project.approve(&mut bank, &project.client).unwrap();
+    assert_eq!(
+        project.info(&bank).milestone_funds_streamed,
+        vec![0, SOL / 10, 0]
+    );
+}
+
+///retainer of `periods` periods of 1 SOL, 100 seconds each, offered to the freelancer
+fn offered_retainer(periods: u8, notice_period: i64) -> (Bank, Project) {
+    let mut bank = Bank::new();
+    let project = Project::new(&mut bank, 100 * SOL);
+    let ix = project.initialize_retainer_ix(SOL, periods, bank.now(), 100, notice_period);
+    bank.process(&[ix], &[project.client]).unwrap();
+    (bank, project)
+}
+
+fn running_retainer(periods: u8, notice_period: i64) -> (Bank, Project) {
+    let (mut bank, project) = offered_retainer(periods, notice_period);
+    project.accept_offer(&mut bank).unwrap();
+    (bank, project)
+}
+
+fn unlock_retainer_periods(bank: &mut Bank, project: &Project) -> Result<(), BankError> {
+    let caller = Pubkey::new_unique();
+    bank.process(&[project.unlock_retainer_periods_ix(&caller)], &[caller])
+}
+
+#[test]
+fn initialize_retainer_funds_every_period() {
+    let mut bank = Bank::new();
+    let project = Project::new(&mut bank, 100 * SOL);
+    let now = bank.now();
+
+    let ix = project.initialize_retainer_ix(SOL, 3, now, 0, 0);
+    assert_eq!(
+        bank.process(&[ix], &[project.client]),
+        Err(anchor_error(ErrorCode::InvalidRetainer))
+    );
+    let ix = project.initialize_retainer_ix(SOL, 3, now, 100, 50);
+    bank.process(&[ix], &[project.client]).unwrap();
+
+    assert_eq!(bank.balance(&project.token_escrow), 3 * SOL);
+    let info = project.info(&bank);
+    assert_eq!(info.milestone_amounts, vec![SOL; 3]);
+    assert_eq!(info.total_project_funds, 3 * SOL);
+    assert_eq!(info.status, ProjectStatus::Offered);
+    let retainer: Retainer = bank.get(&project.retainer());
+    assert_eq!(retainer.project, project.project_info_account);
+    assert_eq!(retainer.terminates_at, 0);
+    assert_eq!(bank.events::<events::RetainerInitialized>().len(), 1);
+
+    //periods only unlock once the freelancer accepts
+    bank.warp(200);
+    assert_eq!(
+        unlock_retainer_periods(&mut bank, &project),
+        Err(anchor_error(ErrorCode::OfferNotAccepted))
+    );
+}
+
+#[test]
+fn retainer_periods_unlock_each_interval() {
+    let (mut bank, project) = running_retainer(3, 0);
+    assert_eq!(
+        unlock_retainer_periods(&mut bank, &project),
+        Err(anchor_error(ErrorCode::NoPeriodDue))
+    );
+
+    let freelancer_balance = bank.balance(&project.freelancer);
+    bank.warp(100);
+    unlock_retainer_periods(&mut bank, &project).unwrap();
+    assert_eq!(project.info(&bank).milestones_reached, 1);
+    project.withdraw(&mut bank).unwrap();
+    assert_eq!(bank.balance(&project.freelancer), freelancer_balance + SOL);
+
+    //the retainer clock stops while the project is paused
+    project.execute(&mut bank, &project.pause_ix()).unwrap();
+    bank.warp(500);
+    project.execute(&mut bank, &project.resume_ix()).unwrap();
+    assert_eq!(
+        unlock_retainer_periods(&mut bank, &project),
+        Err(anchor_error(ErrorCode::NoPeriodDue))
+    );
+
+    bank.warp(1000);
+    unlock_retainer_periods(&mut bank, &project).unwrap();
+    let info = project.info(&bank);
+    assert_eq!(info.milestones_reached, 3);
+    assert_eq!(bank.events::<events::MilestoneReached>().len(), 2);
+    project.withdraw(&mut bank).unwrap();
+    assert_eq!(project.info(&bank).status, ProjectStatus::Completed);
+    assert_eq!(bank.balance(&project.token_escrow), 0);
+}
+
+#[test]
+fn retainer_notice_refunds_unused_periods() {
+    let (mut bank, project) = running_retainer(4, 150);
+    bank.warp(120);
+
+    assert_eq!(
+        bank.process(
+            &[project.give_retainer_notice_ix(&project.observer)],
+            &[project.observer]
+        ),
+        Err(anchor_error(ErrorCode::PartiesOnly))
+    );
+    bank.process(
+        &[project.give_retainer_notice_ix(&project.freelancer)],
+        &[project.freelancer],
+    )
+    .unwrap();
+    assert_eq!(
+        bank.process(
+            &[project.give_retainer_notice_ix(&project.client)],
+            &[project.client]
+        ),
+        Err(anchor_error(ErrorCode::NoticeAlreadyGiven))
+    );
+    let settle = || project.settle_retainer_ix(&project.observer);
+    assert_eq!(
+        bank.process(&[settle()], &[project.observer]),
+        Err(anchor_error(ErrorCode::NoticePeriodRunning))
+    );
+
+    //periods ending by the notice deadline are kept, the others refunded
+    bank.warp(500);
+    let client_balance = bank.balance(&project.client);
+    let retainer_rent = bank.balance(&project.retainer());
+    bank.process(&[settle()], &[project.observer]).unwrap();
+    assert_eq!(
+        bank.balance(&project.client),
+        client_balance + 2 * SOL + retainer_rent
+    );
+    assert_eq!(bank.balance(&project.retainer()), 0);
+    let info = project.info(&bank);
+    assert_eq!(info.milestones, 2);
+    assert_eq!(info.milestones_reached, 2);
+    assert_eq!(info.total_project_funds, 2 * SOL);
+    let settled = bank.events::<events::RetainerSettled>();
+    assert_eq!(settled.len(), 1);
+    assert_eq!((settled[0].periods, settled[0].refund), (2, 2 * SOL));
+
+    let freelancer_balance = bank.balance(&project.freelancer);
+    project.withdraw(&mut bank).unwrap();
+    assert_eq!(
+        bank.balance(&project.freelancer),
+        freelancer_balance + 2 * SOL
+    );
+    assert_eq!(project.info(&bank).status, ProjectStatus::Completed);
+}
+
+#[test]
+fn retainer_settled_before_any_period_is_cancelled() {
+    let (mut bank, project) = running_retainer(2, 0);
+    bank.process(
+        &[project.give_retainer_notice_ix(&project.client)],
+        &[project.client],
+    )
+    .unwrap();
+
+    let client_balance = bank.balance(&project.client);
+    bank.process(
+        &[project.settle_retainer_ix(&project.client)],
+        &[project.client],
+    )
+    .unwrap();
+    assert!(bank.balance(&project.client) > client_balance + 2 * SOL);
+    assert_eq!(bank.balance(&project.token_escrow), 0);
+    assert_eq!(project.info(&bank).status, ProjectStatus::Cancelled);
+}
+
+///running project of 2 milestones of 5 SOL billed at 1 SOL an hour,
+///40 hours a week at most and a day to answer timesheets, week 0 starts now
+fn hourly(bank_project: (Bank, Project)) -> (Bank, Project) {
+    let (mut bank, project) = bank_project;
+    let terms = project.set_hourly_terms_ix(SOL, 40, 86_400, bank.now());
+    project.execute(&mut bank, &terms).unwrap();
+    (bank, project)
+}
+
+fn submit_timesheet(
+    bank: &mut Bank,
+    project: &Project,
+    week: u32,
+    hours: u32,
+) -> Result<(), BankError> {
+    bank.process(
+        &[project.submit_timesheet_ix(week, hours, [7; 32])],
+        &[project.freelancer],
+    )
+}
+
+fn withdraw_timesheet_funds(
+    bank: &mut Bank,
+    project: &Project,
+    week: u32,
+) -> Result<(), BankError> {
+    bank.process(
+        &[project.withdraw_timesheet_funds_ix(week)],
+        &[project.freelancer],
+    )
+}
+
+#[test]
+fn approved_timesheets_are_paid_by_the_hour() {
+    let (mut bank, project) = hourly(running(10 * SOL, 2));
+
+    assert_eq!(
+        submit_timesheet(&mut bank, &project, 0, 41),
+        Err(anchor_error(ErrorCode::WeeklyCapExceeded))
+    );
+    assert_eq!(
+        submit_timesheet(&mut bank, &project, 1, 8),
+        Err(anchor_error(ErrorCode::WeekNotStarted))
+    );
+    submit_timesheet(&mut bank, &project, 0, 6).unwrap();
+    assert_eq!(
+        bank.events::<events::TimesheetSubmitted>()[0].memo_hash,
+        [7; 32]
+    );
+    assert_eq!(
+        submit_timesheet(&mut bank, &project, 0, 6),
+        Err(anchor_error(ErrorCode::TimesheetAlreadySubmitted))
+    );
+    assert_eq!(
+        withdraw_timesheet_funds(&mut bank, &project, 0),
+        Err(anchor_error(ErrorCode::TimesheetNotApproved))
+    );
+
+    bank.process(&[project.approve_timesheet_ix(0)], &[project.client])
+        .unwrap();
+    let freelancer_balance = bank.balance(&project.freelancer);
+    withdraw_timesheet_funds(&mut bank, &project, 0).unwrap();
+    assert_eq!(
+        bank.balance(&project.freelancer),
+        freelancer_balance + 6 * SOL
+    );
+    let timesheet: Timesheet = bank.get(&project.timesheet(0));
+    assert_eq!(timesheet.status, TimesheetStatus::Paid);
+    assert_eq!(
+        withdraw_timesheet_funds(&mut bank, &project, 0),
+        Err(anchor_error(ErrorCode::TimesheetNotApproved))
+    );
+
+    //hours are drawn from the milestones not reached yet, in order
+    assert_eq!(
+        project.info(&bank).milestone_funds_streamed,
+        vec![5 * SOL, SOL]
+    );
+    bank.warp(7 * 86_400);
+    submit_timesheet(&mut bank, &project, 1, 5).unwrap();
+    bank.process(&[project.approve_timesheet_ix(1)], &[project.client])
+        .unwrap();
+    assert_eq!(
+        withdraw_timesheet_funds(&mut bank, &project, 1),
+        Err(anchor_error(ErrorCode::BudgetExceeded))
+    );
+}
+
+#[test]
+fn unanswered_timesheets_are_approved_after_the_window() {
+    let (mut bank, project) = hourly(running(10 * SOL, 2));
+    submit_timesheet(&mut bank, &project, 0, 3).unwrap();
+
+    //the window doesn't run while the project is paused
+    project.execute(&mut bank, &project.pause_ix()).unwrap();
+    bank.warp(86_400);
+    project.execute(&mut bank, &project.resume_ix()).unwrap();
+    assert_eq!(
+        withdraw_timesheet_funds(&mut bank, &project, 0),
+        Err(anchor_error(ErrorCode::TimesheetNotApproved))
+    );
+
+    bank.warp(86_400);
+    assert_eq!(
+        bank.process(&[project.dispute_timesheet_ix(0)], &[project.client]),
+        Err(anchor_error(ErrorCode::ApprovalWindowOver))
+    );
+    withdraw_timesheet_funds(&mut bank, &project, 0).unwrap();
+    let paid = bank.events::<events::TimesheetPaid>();
+    assert_eq!(paid.len(), 1);
+    assert_eq!(paid[0].amount, 3 * SOL);
+    assert!(paid[0].auto_approved);
+}
+
+#[test]
+fn paused_projects_can_dispute_past_the_window() {
+    let (mut bank, project) = hourly(running(10 * SOL, 2));
+    submit_timesheet(&mut bank, &project, 0, 3).unwrap();
+    bank.warp(3_600);
+    project.execute(&mut bank, &project.pause_ix()).unwrap();
+
+    //still paused past the window, the client can answer
+    bank.warp(2 * 86_400);
+    bank.process(&[project.dispute_timesheet_ix(0)], &[project.client])
+        .unwrap();
+    let timesheet: Timesheet = bank.get(&project.timesheet(0));
+    assert_eq!(timesheet.status, TimesheetStatus::Disputed);
+}
+
+#[test]
+fn disputed_timesheets_can_be_submitted_again() {
+    let (mut bank, project) = hourly(running(10 * SOL, 2));
+    submit_timesheet(&mut bank, &project, 0, 30).unwrap();
+    bank.process(&[project.dispute_timesheet_ix(0)], &[project.client])
+        .unwrap();
+    assert_eq!(
+        bank.process(&[project.approve_timesheet_ix(0)], &[project.client]),
+        Err(anchor_error(ErrorCode::TimesheetNotPending))
+    );
+
+    //a disputed timesheet is never approved on its own
+    bank.warp(2 * 86_400);
+    assert_eq!(
+        withdraw_timesheet_funds(&mut bank, &project, 0),
+        Err(anchor_error(ErrorCode::TimesheetNotApproved))
+    );
+
+    submit_timesheet(&mut bank, &project, 0, 10).unwrap();
+    bank.process(&[project.approve_timesheet_ix(0)], &[project.client])
+        .unwrap();
+    withdraw_timesheet_funds(&mut bank, &project, 0).unwrap();
+    assert_eq!(bank.balance(&project.token_escrow), 0);
+    assert_eq!(
+        project.info(&bank).milestone_funds_streamed,
+        vec![5 * SOL, 5 * SOL]
+    );
+}
+
+fn withdraw_payee_share(
+    bank: &mut Bank,
+    project: &Project,
+    payee: &Pubkey,
+) -> Result<(), BankError> {
+    bank.process(&[project.withdraw_payee_share_ix(payee)], &[*payee])
+}
+
+#[test]
+fn split_milestones_are_withdrawn_by_each_payee() {
+    let (mut bank, project) = running(10 * SOL, 2);
+    let designer = Pubkey::new_unique();
+    let writer = Pubkey::new_unique();
+    let payees = vec![project.freelancer, designer, writer];
+    let splits =
+        project.set_payee_splits_ix(payees, vec![vec![5000, 2500, 2500], vec![6000, 4000, 0]]);
+    project.execute(&mut bank, &splits).unwrap();
+
+    assert_eq!(
+        withdraw_payee_share(&mut bank, &project, &designer),
+        Err(anchor_error(ErrorCode::NothingToWithdraw))
+    );
+    project
+        .execute(&mut bank, &project.mark_milestone_ix())
+        .unwrap();
+    assert_eq!(
+        project.withdraw(&mut bank),
+        Err(anchor_error(ErrorCode::SplitPayoutsOnly))
+    );
+    let stranger = Pubkey::new_unique();
+    assert_eq!(
+        withdraw_payee_share(&mut bank, &project, &stranger),
+        Err(anchor_error(ErrorCode::PayeeOnly))
+    );
+
+    withdraw_payee_share(&mut bank, &project, &designer).unwrap();
+    assert_eq!(bank.balance(&designer), 5 * SOL / 4);
+    assert_eq!(
+        withdraw_payee_share(&mut bank, &project, &designer),
+        Err(anchor_error(ErrorCode::NothingToWithdraw))
+    );
+    //the milestone only counts as withdrawn once every payee took their share
+    assert_eq!(project.info(&bank).milestone_funds_withdrawn, 0);
+
+    project
+        .execute(&mut bank, &project.mark_milestone_ix())
+        .unwrap();
+    let freelancer_balance = bank.balance(&project.freelancer);
+    withdraw_payee_share(&mut bank, &project, &project.freelancer).unwrap();
+    assert_eq!(
+        bank.balance(&project.freelancer),
+        freelancer_balance + 5 * SOL / 2 + 3 * SOL
+    );
+    withdraw_payee_share(&mut bank, &project, &designer).unwrap();
+    assert_eq!(bank.balance(&designer), 5 * SOL / 4 + 2 * SOL);
+    assert_eq!(project.info(&bank).milestone_funds_withdrawn, 0);
+
+    withdraw_payee_share(&mut bank, &project, &writer).unwrap();
+    assert_eq!(bank.balance(&writer), 5 * SOL / 4);
+    let withdrawn = bank.events::<events::PayeeShareWithdrawn>();
+    assert!(withdrawn[0].completed);
+    assert_eq!(project.info(&bank).status, ProjectStatus::Completed);
+    assert_eq!(bank.balance(&project.token_escrow), 0);
+
+    let splits: PayeeSplits = bank.get(&project.payee_splits());
+    assert_eq!(splits.milestones_withdrawn, vec![2, 2, 2]);
+    assert_eq!(
+        splits.amounts_withdrawn,
+        vec![11 * SOL / 2, 13 * SOL / 4, 5 * SOL / 4]
+    );
+}
+
+#[test]
+fn the_payee_completing_the_project_sweeps_the_escrow() {
+    let (mut bank, project) = running(4 * SOL, 2);
+    let designer = Pubkey::new_unique();
+    let splits = project.set_payee_splits_ix(
+        vec![project.freelancer, designer],
+        vec![vec![5000, 5000]; 2],
+    );
+    project.execute(&mut bank, &splits).unwrap();
+    for _ in 0..2 {
+        project
+            .execute(&mut bank, &project.mark_milestone_ix())
+            .unwrap();
+    }
+    withdraw_payee_share(&mut bank, &project, &designer).unwrap();
+    assert_eq!(bank.balance(&designer), 2 * SOL);
+
+    //lamports sent to the escrow go to whoever completes the project
+    bank.airdrop(&project.token_escrow, SOL);
+    let freelancer_balance = bank.balance(&project.freelancer);
+    withdraw_payee_share(&mut bank, &project, &project.freelancer).unwrap();
+    assert_eq!(
+        bank.balance(&project.freelancer),
+        freelancer_balance + 3 * SOL
+    );
+    assert_eq!(bank.balance(&project.token_escrow), 0);
+    assert_eq!(project.info(&bank).status, ProjectStatus::Completed);
+    let splits: PayeeSplits = bank.get(&project.payee_splits());
+    assert_eq!(splits.amounts_withdrawn, vec![3 * SOL, 2 * SOL]);
+}
+
+#[test]
+fn split_projects_pay_streams_and_timesheets_through_the_shares_only() {
+    let (mut bank, project) = hourly(running(10 * SOL, 2));
+    stream_milestone(&mut bank, &project, 1, 0, 1000);
+    submit_timesheet(&mut bank, &project, 0, 2).unwrap();
+    bank.process(&[project.approve_timesheet_ix(0)], &[project.client])
+        .unwrap();
+    let designer = Pubkey::new_unique();
+    let splits = project.set_payee_splits_ix(
+        vec![project.freelancer, designer],
+        vec![vec![5000, 5000]; 2],
+    );
+    project.execute(&mut bank, &splits).unwrap();
+
+    bank.warp(500);
+    assert_eq!(
+        project.withdraw_streamed_funds(&mut bank, 1),
+        Err(anchor_error(ErrorCode::SplitPayoutsOnly))
+    );
+    assert_eq!(
+        withdraw_timesheet_funds(&mut bank, &project, 0),
+        Err(anchor_error(ErrorCode::SplitPayoutsOnly))
+    );
+    assert_eq!(bank.balance(&project.token_escrow), 10 * SOL);
+}
+
+#[test]
+fn payee_splits_are_checked_when_set() {
+    let (mut bank, project) = running(10 * SOL, 2);
+    let designer = Pubkey::new_unique();
+
+    let ix =
+        project.set_payee_splits_ix(vec![project.freelancer, designer], vec![vec![5000, 4000]]);
+    project
+        .propose(&mut bank, &project.freelancer, &ix)
+        .unwrap();
+    assert_eq!(
+        project.approve(&mut bank, &project.client),
+        Err(anchor_error(ErrorCode::InvalidSplits))
+    );
+    let ix = project.set_payee_splits_ix(vec![designer, designer], vec![vec![5000, 5000]]);
+    project
+        .propose(&mut bank, &project.freelancer, &ix)
+        .unwrap();
+    assert_eq!(
+        project.approve(&mut bank, &project.client),
+        Err(anchor_error(ErrorCode::InvalidSplits))
+    );
+
+    //milestones already paid to the freelancer stay out of the splits
+    project
+        .execute(&mut bank, &project.mark_milestone_ix())
+        .unwrap();
+    project.withdraw(&mut bank).unwrap();
+    let ix =
+        project.set_payee_splits_ix(vec![project.freelancer, designer], vec![vec![5000, 5000]]);
+    project.execute(&mut bank, &ix).unwrap();
+    project
+        .execute(&mut bank, &project.mark_milestone_ix())
+        .unwrap();
+    withdraw_payee_share(&mut bank, &project, &designer).unwrap();
+    assert_eq!(bank.balance(&designer), 5 * SOL / 2);
+}
+
+///open `child` with 2 milestones, proposed by the parent's freelancer and approved
+///by the parent's client who signs the executing transaction
+fn create_subcontract(
+    bank: &mut Bank,
+    parent: &Project,
+    child: &Project,
+    funds: u64,
+) -> Result<(), BankError> {
+    let ix = parent.create_subcontract_ix(child, funds, 2);
+    parent.propose(bank, &parent.freelancer, &ix)?;
+    parent.approve(bank, &parent.client)
+}
+
+///`parent` running with `funds` over two milestones and a child of `child_funds`
+///subcontracted by its freelancer
+fn subcontracted(funds: u64, child_funds: u64) -> (Bank, Project, Project) {
+    let (mut bank, parent) = running(funds, 2);
+    let subcontractor = Pubkey::new_unique();
+    let child = Project::with_parties(parent.freelancer, subcontractor, parent.observer);
+    create_subcontract(&mut bank, &parent, &child, child_funds).unwrap();
+    (bank, parent, child)
+}
+
+fn freeze_subcontract(bank: &mut Bank, parent: &Project, child: &Project) -> Result<(), BankError> {
+    let caller = Pubkey::new_unique();
+    bank.process(&[child.freeze_subcontract_ix(parent, &caller)], &[caller])
+}
+
+fn thaw_subcontract(bank: &mut Bank, parent: &Project, child: &Project) -> Result<(), BankError> {
+    let caller = Pubkey::new_unique();
+    bank.process(&[child.thaw_subcontract_ix(parent, &caller)], &[caller])
+}
+
+#[test]
+fn subcontract_is_funded_from_the_parents_unreached_milestones() {
+    let (mut bank, parent) = initialized(10 * SOL, 2);
+    let child = Project::with_parties(parent.freelancer, Pubkey::new_unique(), parent.observer);
+    assert_eq!(
+        create_subcontract(&mut bank, &parent, &child, 6 * SOL),
+        Err(anchor_error(ErrorCode::OfferNotAccepted))
+    );
+    parent.accept_offer(&mut bank).unwrap();
+    let total = bank.total_lamports();
+    create_subcontract(&mut bank, &parent, &child, 6 * SOL).unwrap();
+
+    assert_eq!(bank.total_lamports(), total);
+    assert_eq!(bank.balance(&parent.token_escrow), 4 * SOL);
+    assert_eq!(bank.balance(&child.token_escrow), 6 * SOL);
+    assert_eq!(
+        parent.info(&bank).milestone_funds_streamed,
+        vec![5 * SOL, SOL]
+    );
+    let info = child.info(&bank);
+    assert_eq!(info.client, parent.freelancer);
+    assert_eq!(info.status, ProjectStatus::Offered);
+    let subcontract: Subcontract = bank.get(&child.subcontract());
+    assert_eq!(subcontract.parent, parent.project_info_account);
+    assert_eq!(subcontract.drawn, vec![5 * SOL, SOL]);
+    let created = bank.events::<events::SubcontractCreated>();
+    assert_eq!(created[0].amount, 6 * SOL);
+    //what's left of the child goes back to the parent, not to its client
+    assert_eq!(
+        bank.process(&[child.decline_offer_ix()], &[child.freelancer]),
+        Err(anchor_error(ErrorCode::SubcontractRefundsToParent))
+    );
+
+    //the parent pays its freelancer what the child didn't take
+    parent
+        .execute(&mut bank, &parent.mark_milestone_ix())
+        .unwrap();
+    parent
+        .execute(&mut bank, &parent.mark_milestone_ix())
+        .unwrap();
+    let freelancer_balance = bank.balance(&parent.freelancer);
+    parent.withdraw(&mut bank).unwrap();
+    assert_eq!(
+        bank.balance(&parent.freelancer),
+        freelancer_balance + 4 * SOL
+    );
+
+    child.accept_offer(&mut bank).unwrap();
+    child
+        .execute(&mut bank, &child.mark_milestone_ix())
+        .unwrap();
+    child.withdraw(&mut bank).unwrap();
+    assert_eq!(bank.balance(&child.freelancer), 3 * SOL);
+}
+
+#[test]
+fn subcontracts_need_the_parents_client() {
+    let (mut bank, parent) = running(10 * SOL, 2);
+    //the parent's freelancer controls every owner of the child
+    let child = Project::with_parties(
+        parent.freelancer,
+        Pubkey::new_unique(),
+        Pubkey::new_unique(),
+    );
+    let ix = parent.create_subcontract_ix(&child, 6 * SOL, 2);
+    assert_eq!(
+        bank.process(std::slice::from_ref(&ix), &[parent.freelancer]),
+        Err(BankError::MissingSigner(parent.multisig))
+    );
+
+    //the parent multisig approving without its client isn't enough either
+    parent.propose(&mut bank, &parent.freelancer, &ix).unwrap();
+    let approve = parent.approve_ix(&bank, &parent.observer);
+    assert_eq!(
+        bank.process(&[approve], &[parent.observer]),
+        Err(BankError::MissingSigner(parent.client))
+    );
+    assert_eq!(bank.balance(&parent.token_escrow), 10 * SOL);
+    assert_eq!(parent.info(&bank).milestone_funds_streamed, vec![0, 0]);
+    assert!(bank.account(&child.project_info_account).is_none());
+
+    let client_balance = bank.balance(&parent.client);
+    parent.approve(&mut bank, &parent.client).unwrap();
+    assert_eq!(bank.balance(&child.token_escrow), 6 * SOL);
+    //the parent's client pays for the child accounts and gets their rent back on cancel,
+    //the reputation opened for the subcontractor stays
+    let rent = bank.balance(&child.project_info_account) + bank.balance(&child.subcontract());
+    assert_eq!(
+        bank.balance(&parent.client),
+        client_balance
+            - rent
+            - bank.balance(&child.multisig)
+            - bank.balance(&reputation(&child.freelancer))
+    );
+    let client_balance = bank.balance(&parent.client);
+    bank.process(&[child.cancel_subcontract_ix(&parent)], &[child.client])
+        .unwrap();
+    assert_eq!(bank.balance(&parent.client), client_balance + rent);
+}
+
+#[test]
+fn subcontract_is_frozen_while_the_parent_is_paused() {
+    let (mut bank, parent, child) = subcontracted(10 * SOL, 4 * SOL);
+    child.accept_offer(&mut bank).unwrap();
+    assert_eq!(
+        freeze_subcontract(&mut bank, &parent, &child),
+        Err(anchor_error(ErrorCode::ParentNotDisputed))
+    );
+
+    parent.execute(&mut bank, &parent.pause_ix()).unwrap();
+    freeze_subcontract(&mut bank, &parent, &child).unwrap();
+    assert_eq!(child.info(&bank).status, ProjectStatus::Paused);
+    assert_eq!(
+        freeze_subcontract(&mut bank, &parent, &child),
+        Err(anchor_error(ErrorCode::SubcontractFrozen))
+    );
+    assert_eq!(
+        child.execute(&mut bank, &child.resume_ix()),
+        Err(anchor_error(ErrorCode::SubcontractFrozen))
+    );
+    assert_eq!(
+        thaw_subcontract(&mut bank, &parent, &child),
+        Err(anchor_error(ErrorCode::ParentStillDisputed))
+    );
+
+    bank.warp(100);
+    parent.execute(&mut bank, &parent.resume_ix()).unwrap();
+    thaw_subcontract(&mut bank, &parent, &child).unwrap();
+    assert_eq!(child.info(&bank).status, ProjectStatus::Running);
+    let thawed = bank.events::<events::SubcontractThawed>();
+    assert_eq!(thawed[0].paused_for, 100);
+    assert!(!bank.get::<Subcontract>(&child.subcontract()).frozen);
+    assert_eq!(
+        thaw_subcontract(&mut bank, &parent, &child),
+        Err(anchor_error(ErrorCode::SubcontractNotFrozen))
+    );
+}
+
+#[test]
+fn cancelled_subcontract_goes_back_to_the_parent() {
+    let (mut bank, parent, child) = subcontracted(10 * SOL, 6 * SOL);
+    assert_eq!(
+        child.cancel(&mut bank),
+        Err(anchor_error(ErrorCode::SubcontractRefundsToParent))
+    );
+    parent
+        .execute(&mut bank, &parent.mark_milestone_ix())
+        .unwrap();
+
+    bank.process(&[child.cancel_subcontract_ix(&parent)], &[child.client])
+        .unwrap();
+    //what was drawn from the unreached milestone goes back to it,
+    //the reached one was already the freelancer's
+    let cancelled = bank.events::<events::SubcontractCancelled>();
+    assert_eq!(cancelled[0].returned_to_parent, SOL);
+    assert_eq!(cancelled[0].refund, 5 * SOL);
+    assert_eq!(cancelled[0].recipient, parent.freelancer);
+    assert_eq!(bank.balance(&parent.token_escrow), 5 * SOL);
+    assert_eq!(
+        parent.info(&bank).milestone_funds_streamed,
+        vec![5 * SOL, 0]
+    );
+    assert!(bank.account(&child.subcontract()).is_none());
+    assert!(bank.account(&child.project_info_account).is_none());
+}
+
+#[test]
+fn stopped_subcontract_pays_its_freelancer_the_reached_milestones() {
+    let (mut bank, parent, child) = subcontracted(10 * SOL, 6 * SOL);
+    child.accept_offer(&mut bank).unwrap();
+    child
+        .execute(&mut bank, &child.mark_milestone_ix())
+        .unwrap();
+    child.execute(&mut bank, &child.stop_ix()).unwrap();
+
+    let freelancer_balance = bank.balance(&child.freelancer);
+    bank.process(&[child.cancel_subcontract_ix(&parent)], &[child.client])
+        .unwrap();
+    //the reached milestone is the child's freelancer's, only the rest goes back
+    assert_eq!(
+        bank.balance(&child.freelancer),
+        freelancer_balance + 3 * SOL
+    );
+    let cancelled = bank.events::<events::SubcontractCancelled>();
+    assert_eq!(cancelled[0].freelancer, child.freelancer);
+    assert_eq!(cancelled[0].earned, 3 * SOL);
+    assert_eq!(cancelled[0].returned_to_parent, 3 * SOL);
+    assert_eq!(cancelled[0].refund, 0);
+    assert_eq!(bank.balance(&parent.token_escrow), 7 * SOL);
+}
+
+#[test]
+fn subcontract_of_a_closed_parent_refunds_the_parents_client() {
+    let (mut bank, parent, child) = subcontracted(10 * SOL, 6 * SOL);
+    child.accept_offer(&mut bank).unwrap();
+    parent.execute(&mut bank, &parent.stop_ix()).unwrap();
+    parent.cancel(&mut bank).unwrap();
+    freeze_subcontract(&mut bank, &parent, &child).unwrap();
+    assert_eq!(child.info(&bank).status, ProjectStatus::Paused);
+
+    child.execute(&mut bank, &child.stop_ix()).unwrap();
+    let client_balance = bank.balance(&parent.client);
+    let rent = bank.balance(&child.project_info_account) + bank.balance(&child.subcontract());
+    bank.process(&[child.cancel_subcontract_ix(&parent)], &[child.client])
+        .unwrap();
+    assert_eq!(
+        bank.balance(&parent.client),
+        client_balance + 6 * SOL + rent
+    );
+    let cancelled = bank.events::<events::SubcontractCancelled>();
+    assert_eq!(cancelled[0].recipient, parent.client);
+    assert_eq!(cancelled[0].returned_to_parent, 0);
+}
+
+fn with_posted_bond(amount: u64, deadline: i64) -> (Bank, Project) {
+    let (mut bank, project) = initialized(10 * SOL, 1);
+    bank.process(
+        &[project.require_bond_ix(amount, deadline)],
+        &[project.client],
+    )
+    .unwrap();
+    bank.process(&[project.post_bond_ix()], &[project.freelancer])
+        .unwrap();
+    project.accept_offer(&mut bank).unwrap();
+    (bank, project)
+}
+
+#[test]
+fn bond_is_posted_before_start_and_returned_on_completion() {
+    let (mut bank, project) = initialized(10 * SOL, 1);
+    assert_eq!(
+        bank.process(&[project.require_bond_ix(0, 0)], &[project.client]),
+        Err(anchor_error(ErrorCode::InvalidBond))
+    );
+    bank.process(&[project.require_bond_ix(SOL / 2, 0)], &[project.client])
+        .unwrap();
+    let bond_rent = bank.balance(&project.bond());
+
+    assert_eq!(
+        project.accept_offer(&mut bank),
+        Err(anchor_error(ErrorCode::BondNotPosted))
+    );
+    let freelancer_balance = bank.balance(&project.freelancer);
+    bank.process(&[project.post_bond_ix()], &[project.freelancer])
+        .unwrap();
+    assert_eq!(
+        bank.balance(&project.freelancer),
+        freelancer_balance - SOL / 2
+    );
+    assert_eq!(bank.balance(&project.bond()), bond_rent + SOL / 2);
+    assert_eq!(
+        bank.process(&[project.post_bond_ix()], &[project.freelancer]),
+        Err(anchor_error(ErrorCode::BondAlreadyPosted))
+    );
+    project.accept_offer(&mut bank).unwrap();
+    assert_eq!(project.info(&bank).status, ProjectStatus::Running);
+
+    let caller = Pubkey::new_unique();
+    assert_eq!(
+        bank.process(&[project.release_bond_ix(&caller)], &[caller]),
+        Err(anchor_error(ErrorCode::ProjectAlreadyRunning))
+    );
+    project
+        .execute(&mut bank, &project.mark_milestone_ix())
+        .unwrap();
+    project.withdraw(&mut bank).unwrap();
+    let freelancer_balance = bank.balance(&project.freelancer);
+    let client_balance = bank.balance(&project.client);
+    bank.process(&[project.release_bond_ix(&caller)], &[caller])
+        .unwrap();
+    assert_eq!(
+        bank.balance(&project.freelancer),
+        freelancer_balance + SOL / 2
+    );
+    assert_eq!(bank.balance(&project.client), client_balance + bond_rent);
+    assert!(bank.account(&project.bond()).is_none());
+    let released = bank.events::<events::BondReleased>();
+    assert_eq!(released[0].amount, SOL / 2);
+}
+
+#[test]
+fn bond_is_forfeited_by_a_ruling_against_the_freelancer() {
+    let (mut bank, project) = with_posted_bond(SOL, 0);
+    let bond_balance = bank.balance(&project.bond());
+
+    //the client and the observer rule against the freelancer
+    project
+        .propose(&mut bank, &project.client, &project.forfeit_bond_ix())
+        .unwrap();
+    let client_balance = bank.balance(&project.client);
+    project.approve(&mut bank, &project.observer).unwrap();
+    assert_eq!(bank.balance(&project.client), client_balance + bond_balance);
+    assert!(bank.account(&project.bond()).is_none());
+    let forfeited = bank.events::<events::BondForfeited>();
+    assert!(!forfeited[0].missed_deadline);
+    assert_eq!(forfeited[0].amount, SOL);
+    //the ruling stops the project
+    assert_eq!(project.info(&bank).status, ProjectStatus::Cancelled);
+    assert_eq!(
+        bank.events::<events::ProjectStopped>()[0].ruling,
+        Ruling::Client
+    );
+}
+
+#[test]
+fn bond_is_only_forfeited_while_the_project_is_underway() {
+    let (mut bank, project) = initialized(10 * SOL, 1);
+    bank.process(&[project.require_bond_ix(SOL, 0)], &[project.client])
+        .unwrap();
+    bank.process(&[project.post_bond_ix()], &[project.freelancer])
+        .unwrap();
+    assert_eq!(
+        project.execute(&mut bank, &project.forfeit_bond_ix()),
+        Err(anchor_error(ErrorCode::OfferNotAccepted))
+    );
+}
+
+#[test]
+fn bond_is_forfeited_once_the_deadline_is_missed() {
+    let deadline = Bank::new().now() + 1000;
+    let (mut bank, project) = with_posted_bond(SOL, deadline);
+    let caller = Pubkey::new_unique();
+    assert_eq!(
+        bank.process(&[project.claim_bond_ix(&caller)], &[caller]),
+        Err(anchor_error(ErrorCode::DeadlineNotMissed))
+    );
+
+    //the deadline clock stops while the project is paused
+    project.execute(&mut bank, &project.pause_ix()).unwrap();
+    bank.warp(2000);
+    assert_eq!(
+        bank.process(&[project.claim_bond_ix(&caller)], &[caller]),
+        Err(anchor_error(ErrorCode::DeadlineNotMissed))
+    );
+    project.execute(&mut bank, &project.resume_ix()).unwrap();
+    bank.warp(1001);
+
+    let bond_balance = bank.balance(&project.bond());
+    let client_balance = bank.balance(&project.client);
+    bank.process(&[project.claim_bond_ix(&caller)], &[caller])
+        .unwrap();
+    assert_eq!(bank.balance(&project.client), client_balance + bond_balance);
+    let forfeited = bank.events::<events::BondForfeited>();
+    assert!(forfeited[0].missed_deadline);
+}
+
+#[test]
+fn stopping_a_project_for_the_client_forfeits_the_bond() {
+    let (mut bank, project) = with_posted_bond(SOL, 0);
+    let bond_balance = bank.balance(&project.bond());
+    project
+        .propose(
+            &mut bank,
+            &project.client,
+            &project.stop_ruling_ix(Ruling::Client),
+        )
+        .unwrap();
+    let client_balance = bank.balance(&project.client);
+    project.approve(&mut bank, &project.freelancer).unwrap();
+    assert_eq!(bank.balance(&project.client), client_balance + bond_balance);
+    assert!(bank.account(&project.bond()).is_none());
+    let forfeited = bank.events::<events::BondForfeited>();
+    assert_eq!(forfeited.len(), 1);
+    assert_eq!(forfeited[0].amount, SOL);
+
+    //nothing is left for anyone to release to the freelancer
+    let caller = Pubkey::new_unique();
+    assert!(bank
+        .process(&[project.release_bond_ix(&caller)], &[caller])
+        .is_err());
+    //and the ruling is only recorded once
+    let freelancer: Reputation = bank.get(&reputation(&project.freelancer));
+    assert_eq!((freelancer.disputes_won, freelancer.disputes_lost), (0, 1));
+}
+
+#[test]
+fn job_postings_take_bids_within_the_budget() {
+    let mut bank = Bank::new();
+    let project = Project::new(&mut bank, 100 * SOL);
+    assert_eq!(
+        bank.process(
+            &[project.create_job_posting_ix(1, 2 * SOL, SOL, vec![10000])],
+            &[project.client]
+        ),
+        Err(anchor_error(ErrorCode::InvalidJobPosting))
+    );
+    assert_eq!(
+        bank.process(
+            &[project.create_job_posting_ix(1, SOL, 2 * SOL, vec![5000, 4000])],
+            &[project.client]
+        ),
+        Err(anchor_error(ErrorCode::InvalidJobPosting))
+    );
+    bank.process(
+        &[project.create_job_posting_ix(1, SOL, 2 * SOL, vec![5000, 5000])],
+        &[project.client],
+    )
+    .unwrap();
+    assert_eq!(bank.events::<events::JobPosted>()[0].milestones, 2);
+
+    assert_eq!(
+        bank.process(
+            &[project.submit_bid_ix(1, 3 * SOL, 100)],
+            &[project.freelancer]
+        ),
+        Err(anchor_error(ErrorCode::InvalidBid))
+    );
+    assert_eq!(
+        bank.process(
+            &[project.submit_bid_ix(1, SOL / 2, 100)],
+            &[project.freelancer]
+        ),
+        Err(anchor_error(ErrorCode::InvalidBid))
+    );
+    assert_eq!(
+        bank.process(&[project.submit_bid_ix(1, SOL, 0)], &[project.freelancer]),
+        Err(anchor_error(ErrorCode::InvalidBid))
+    );
+    bank.process(&[project.submit_bid_ix(1, SOL, 100)], &[project.freelancer])
+        .unwrap();
+    let bid: Bid = bank.get(&project.bid(1));
+    assert_eq!((bid.price, bid.timeline), (SOL, 100));
+    assert_eq!(bank.events::<events::BidSubmitted>()[0].timeline, 100);
+}
+
+#[test]
+fn accepting_a_bid_offers_a_funded_project() {
+    let mut bank = Bank::new();
+    let project = Project::new(&mut bank, 100 * SOL);
+    let rival = Project::with_parties(project.client, Pubkey::new_unique(), project.observer);
+    bank.airdrop(&rival.freelancer, SOL);
+    bank.process(
+        &[project.create_job_posting_ix(7, SOL, 5 * SOL, vec![3000, 7000])],
+        &[project.client],
+    )
+    .unwrap();
+    bank.process(
+        &[project.submit_bid_ix(7, 3 * SOL, 600)],
+        &[project.freelancer],
+    )
+    .unwrap();
+    bank.process(&[rival.submit_bid_ix(7, 2 * SOL, 900)], &[rival.freelancer])
+        .unwrap();
+
+    let bid_rent = bank.balance(&project.bid(7));
+    let freelancer_balance = bank.balance(&project.freelancer);
+    let now = bank.now();
+    bank.process(&[project.accept_bid_ix(7)], &[project.client])
+        .unwrap();
+    let info = project.info(&bank);
+    assert_eq!(info.status, ProjectStatus::Offered);
+    assert_eq!(info.total_project_funds, 3 * SOL);
+    assert_eq!(info.milestone_amounts, vec![9 * SOL / 10, 21 * SOL / 10]);
+    assert_eq!(bank.balance(&project.token_escrow), 3 * SOL);
+    assert!(bank.account(&project.bid(7)).is_none());
+    assert_eq!(
+        bank.balance(&project.freelancer),
+        freelancer_balance + bid_rent
+    );
+    let accepted = bank.events::<events::BidAccepted>();
+    assert_eq!(accepted[0].project, project.project_info_account);
+    assert_eq!(accepted[0].price, 3 * SOL);
+    //the project is due the bid's timeline from its acceptance
+    assert_eq!(accepted[0].timeline, 600);
+    assert_eq!(accepted[0].deadline, now + 600);
+    let posting: JobPosting = bank.get(&project.job_posting(7));
+    assert_eq!(posting.deadline, now + 600);
+
+    //the posting is filled, the rival's bid can only be closed
+    assert_eq!(
+        bank.process(&[rival.accept_bid_ix(7)], &[rival.client]),
+        Err(anchor_error(ErrorCode::JobPostingFilled))
+    );
+    let rival_balance = bank.balance(&rival.freelancer);
+    let rival_rent = bank.balance(&rival.bid(7));
+    bank.process(&[rival.close_bid_ix(7)], &[rival.freelancer])
+        .unwrap();
+    assert_eq!(bank.balance(&rival.freelancer), rival_balance + rival_rent);
+    assert!(bank.account(&rival.bid(7)).is_none());
+
+    project.accept_offer(&mut bank).unwrap();
+    assert_eq!(project.info(&bank).status, ProjectStatus::Running);
+}
+
+#[test]
+fn opening_a_project_creates_both_reputations() {
+    let (mut bank, project) = initialized(10 * SOL, 1);
+    let created = bank.events::<events::ReputationCreated>();
+    assert_eq!(
+        created.iter().map(|e| e.wallet).collect::<Vec<_>>(),
+        vec![project.client, project.freelancer]
+    );
+    assert_eq!(created[0].actor, project.client);
+    let client: Reputation = bank.get(&reputation(&project.client));
+    assert_eq!(client.wallet, project.client);
+    assert_eq!(client.completed_projects, 0);
+    assert!(bank
+        .process(
+            &[create_reputation_ix(&project.client, &project.observer)],
+            &[project.observer]
+        )
+        .is_err());
+
+    //a wallet tracked before its first project keeps its account
+    let freelancer = Pubkey::new_unique();
+    bank.process(
+        &[create_reputation_ix(&freelancer, &project.observer)],
+        &[project.observer],
+    )
+    .unwrap();
+    let next = Project::with_parties(project.client, freelancer, project.observer);
+    next.initialize(&mut bank, SOL, 1).unwrap();
+    assert!(bank.events::<events::ReputationCreated>().is_empty());
+    let reputation: Reputation = bank.get(&reputation(&freelancer));
+    assert_eq!(reputation.wallet, freelancer);
+}
+
+#[test]
+fn completed_projects_build_up_both_reputations() {
+    let (mut bank, project) = initialized(10 * SOL, 1);
+    let deadline = bank.now() + 1000;
+    bank.process(&[project.require_bond_ix(SOL, deadline)], &[project.client])
+        .unwrap();
+    bank.process(&[project.post_bond_ix()], &[project.freelancer])
+        .unwrap();
+    project.accept_offer(&mut bank).unwrap();
+    assert_eq!(
+        bank.process(
+            &[project.rate_party_ix(0, &project.client, &project.freelancer, 5)],
+            &[project.client]
+        ),
+        Err(anchor_error(ErrorCode::ProjectAlreadyRunning))
+    );
+
+    project
+        .execute(&mut bank, &project.mark_milestone_ix())
+        .unwrap();
+    project.withdraw(&mut bank).unwrap();
+    let client: Reputation = bank.get(&reputation(&project.client));
+    assert_eq!(client.completed_projects, 1);
+    assert_eq!(client.total_volume, 10 * SOL);
+    assert_eq!(client.deadlines_met, 0);
+    let freelancer: Reputation = bank.get(&reputation(&project.freelancer));
+    assert_eq!(freelancer.completed_projects, 1);
+    assert_eq!(freelancer.total_volume, 10 * SOL);
+    assert_eq!(freelancer.deadlines_met, 1);
+    assert_eq!(freelancer.on_time_bps(), Some(10000));
+
+    //one rating per party, only about the other party
+    let stranger = Pubkey::new_unique();
+    bank.airdrop(&stranger, SOL);
+    assert_eq!(
+        bank.process(
+            &[project.rate_party_ix(0, &stranger, &project.freelancer, 1)],
+            &[stranger]
+        ),
+        Err(anchor_error(ErrorCode::InvalidRating))
+    );
+    assert_eq!(
+        bank.process(
+            &[project.rate_party_ix(0, &project.client, &project.client, 5)],
+            &[project.client]
+        ),
+        Err(anchor_error(ErrorCode::InvalidRating))
+    );
+    assert_eq!(
+        bank.process(
+            &[project.rate_party_ix(0, &project.client, &project.freelancer, 6)],
+            &[project.client]
+        ),
+        Err(anchor_error(ErrorCode::InvalidRating))
+    );
+    bank.process(
+        &[project.rate_party_ix(0, &project.client, &project.freelancer, 5)],
+        &[project.client],
+    )
+    .unwrap();
+    assert_eq!(bank.events::<events::PartyRated>()[0].stars, 5);
+    assert_eq!(
+        bank.process(
+            &[project.rate_party_ix(0, &project.client, &project.freelancer, 1)],
+            &[project.client]
+        ),
+        Err(anchor_error(ErrorCode::AlreadyRated))
+    );
+    bank.process(
+        &[project.rate_party_ix(0, &project.freelancer, &project.client, 3)],
+        &[project.freelancer],
+    )
+    .unwrap();
+
+    let freelancer: Reputation = bank.get(&reputation(&project.freelancer));
+    assert_eq!((freelancer.ratings, freelancer.rating_total), (1, 5));
+    let client: Reputation = bank.get(&reputation(&project.client));
+    assert_eq!((client.ratings, client.rating_total), (1, 3));
+    let rating: Rating = bank.get(&project.rating(0, &project.client));
+    assert_eq!(rating.ratee, project.freelancer);
+}
+
+#[test]
+fn rulings_and_cancellations_show_in_both_reputations() {
+    let (mut bank, project) = with_posted_bond(SOL, 0);
+
+    project
+        .execute(&mut bank, &project.forfeit_bond_ix())
+        .unwrap();
+    project.cancel(&mut bank).unwrap();
+
+    let client: Reputation = bank.get(&reputation(&project.client));
+    assert_eq!((client.disputes_won, client.disputes_lost), (1, 0));
+    assert_eq!(client.cancelled_projects, 1);
+    let freelancer: Reputation = bank.get(&reputation(&project.freelancer));
+    assert_eq!((freelancer.disputes_won, freelancer.disputes_lost), (0, 1));
+    assert_eq!(freelancer.cancelled_projects, 1);
+    assert_eq!(freelancer.completed_projects, 0);
+
+    //a withdrawn offer isn't held against anyone
+    let offer = Project::with_parties(project.client, Pubkey::new_unique(), project.observer);
+    offer.initialize(&mut bank, SOL, 1).unwrap();
+    offer.cancel(&mut bank).unwrap();
+    let client: Reputation = bank.get(&reputation(&project.client));
+    assert_eq!(client.cancelled_projects, 1);
+}
+
+#[test]
+fn stopping_a_project_over_a_dispute_rules_for_one_party() {
+    let (mut bank, project) = running(2 * SOL, 2);
+    project
+        .execute(&mut bank, &project.stop_ruling_ix(Ruling::Freelancer))
+        .unwrap();
+    assert_eq!(
+        bank.events::<events::ProjectStopped>()[0].ruling,
+        Ruling::Freelancer
+    );
+    let freelancer: Reputation = bank.get(&reputation(&project.freelancer));
+    assert_eq!((freelancer.disputes_won, freelancer.disputes_lost), (1, 0));
+    let client: Reputation = bank.get(&reputation(&project.client));
+    assert_eq!((client.disputes_won, client.disputes_lost), (0, 1));
+
+    //a stop both parties agree on isn't a dispute
+    let (mut bank, project) = running(2 * SOL, 2);
+    project.execute(&mut bank, &project.stop_ix()).unwrap();
+    let client: Reputation = bank.get(&reputation(&project.client));
+    assert_eq!((client.disputes_won, client.disputes_lost), (0, 0));
+}
+
+#[test]
+fn only_projects_that_ran_can_be_rated() {
+    //declined and withdrawn offers leave nothing to rate
+    let (mut bank, project) = initialized(SOL, 1);
+    bank.process(&[project.decline_offer_ix()], &[project.freelancer])
+        .unwrap();
+    assert!(bank.account(&project.rating(0, &project.client)).is_none());
+    assert_eq!(
+        bank.process(
+            &[project.rate_party_ix(0, &project.client, &project.freelancer, 2)],
+            &[project.client]
+        ),
+        Err(anchor_error(ErrorCode::InvalidRating))
+    );
+    let (mut bank, project) = initialized(SOL, 1);
+    project.cancel(&mut bank).unwrap();
+    assert!(bank.account(&project.rating(0, &project.client)).is_none());
+    assert!(bank
+        .account(&project.rating(0, &project.freelancer))
+        .is_none());
+
+    //nor does an offer stopped before the freelancer accepted it, though it's cancelled
+    let (mut bank, project) = initialized(SOL, 1);
+    project.execute(&mut bank, &project.stop_ix()).unwrap();
+    assert_eq!(project.info(&bank).status, ProjectStatus::Cancelled);
+    assert_eq!(
+        bank.process(
+            &[project.rate_party_ix(0, &project.freelancer, &project.client, 2)],
+            &[project.freelancer]
+        ),
+        Err(anchor_error(ErrorCode::InvalidRating))
+    );
+    project.cancel(&mut bank).unwrap();
+    assert!(bank
+        .account(&project.rating(0, &project.freelancer))
+        .is_none());
+}
+
+#[test]
+fn closed_projects_can_still_be_rated() {
+    let (mut bank, project) = running(SOL, 1);
+    project.execute(&mut bank, &project.stop_ix()).unwrap();
+    project.cancel(&mut bank).unwrap();
+    assert!(bank.account(&project.project_info_account).is_none());
+    let rating: Rating = bank.get(&project.rating(0, &project.client));
+    assert_eq!((rating.ratee, rating.stars), (project.freelancer, 0));
+
+    bank.process(
+        &[project.rate_party_ix(0, &project.client, &project.freelancer, 2)],
+        &[project.client],
+    )
+    .unwrap();
+    assert_eq!(
+        bank.process(
+            &[project.rate_party_ix(0, &project.client, &project.freelancer, 2)],
+            &[project.client]
+        ),
+        Err(anchor_error(ErrorCode::AlreadyRated))
+    );
+    //the rating is bound to the other party
+    let stranger = Pubkey::new_unique();
+    assert_eq!(
+        bank.process(
+            &[project.rate_party_ix(0, &project.freelancer, &stranger, 1)],
+            &[project.freelancer]
+        ),
+        Err(anchor_error(ErrorCode::InvalidRating))
+    );
+    let freelancer: Reputation = bank.get(&reputation(&project.freelancer));
+    assert_eq!((freelancer.ratings, freelancer.rating_total), (1, 2));
+
+    //outsiders have no rating to fill once the project is gone
+    bank.airdrop(&stranger, SOL);
+    assert_eq!(
+        bank.process(
+            &[project.rate_party_ix(0, &stranger, &project.client, 1)],
+            &[stranger]
+        ),
+        Err(anchor_error(ErrorCode::InvalidRating))
+    );
+
+    //ratings given before a stopped project is closed stay as they are
+    let (mut bank, project) = running(SOL, 1);
+    project.execute(&mut bank, &project.stop_ix()).unwrap();
+    bank.process(
+        &[project.rate_party_ix(0, &project.freelancer, &project.client, 4)],
+        &[project.freelancer],
+    )
+    .unwrap();
+    project.cancel(&mut bank).unwrap();
+    assert_eq!(
+        bank.get::<Rating>(&project.rating(0, &project.freelancer))
+            .stars,
+        4
+    );
+    bank.process(
+        &[project.rate_party_ix(0, &project.client, &project.freelancer, 5)],
+        &[project.client],
+    )
+    .unwrap();
+    let client: Reputation = bank.get(&reputation(&project.client));
+    assert_eq!((client.ratings, client.rating_total), (1, 4));
+}
+
+#[test]
+fn the_same_parties_rate_each_of_their_projects() {
+    let (mut bank, project) = initialized(SOL, 1);
+    bank.process(&[project.decline_offer_ix()], &[project.freelancer])
+        .unwrap();
+
+    //their next project gets the same address, its ratings are told apart by the nonce
+    project.initialize(&mut bank, SOL, 1).unwrap();
+    assert_eq!(project.info(&bank).nonce, 1);
+    let client: Reputation = bank.get(&reputation(&project.client));
+    assert_eq!(client.opened_projects, 2);
+    project.accept_offer(&mut bank).unwrap();
+    project
+        .execute(&mut bank, &project.mark_milestone_ix())
+        .unwrap();
+    project.withdraw(&mut bank).unwrap();
+    bank.process(
+        &[project.rate_party_ix(1, &project.client, &project.freelancer, 3)],
+        &[project.client],
+    )
+    .unwrap();
+    assert_eq!(
+        bank.process(
+            &[project.rate_party_ix(1, &project.client, &project.freelancer, 3)],
+            &[project.client]
+        ),
+        Err(anchor_error(ErrorCode::AlreadyRated))
+    );
+    bank.process(
+        &[project.rate_party_ix(1, &project.freelancer, &project.client, 4)],
+        &[project.freelancer],
+    )
+    .unwrap();
+
+    //the declined offer before it can't be rated under its own nonce
+    assert_eq!(
+        bank.process(
+            &[project.rate_party_ix(0, &project.client, &project.freelancer, 1)],
+            &[project.client]
+        ),
+        Err(anchor_error(ErrorCode::InvalidRating))
+    );
+    let freelancer: Reputation = bank.get(&reputation(&project.freelancer));
+    assert_eq!((freelancer.ratings, freelancer.rating_total), (1, 3));
+    let client: Reputation = bank.get(&reputation(&project.client));
+    assert_eq!((client.ratings, client.rating_total), (1, 4));
+}
+
+#[test]
+fn missed_deadlines_lower_the_on_time_rate() {
+    let deadline = Bank::new().now() + 1000;
+    let (mut bank, project) = with_posted_bond(SOL, deadline);
+    bank.warp(1001);
+
+    let caller = Pubkey::new_unique();
+    bank.process(&[project.claim_bond_ix(&caller)], &[caller])
+        .unwrap();
+    project
+        .execute(&mut bank, &project.mark_milestone_ix())
+        .unwrap();
+    project.withdraw(&mut bank).unwrap();
+
+    //the claimed bond already counted the miss, completing late adds nothing
+    let freelancer: Reputation = bank.get(&reputation(&project.freelancer));
+    assert_eq!(freelancer.completed_projects, 1);
+    assert_eq!(
+        (freelancer.deadlines_met, freelancer.deadlines_missed),
+        (0, 1)
+    );
+    assert_eq!(freelancer.on_time_bps(), Some(0));
+}
+
+#[test]
+fn tips_from_the_client_are_recorded_against_the_project() {
+    let (mut bank, project) = initialized(2 * SOL, 2);
+    assert_eq!(
+        bank.process(&[project.tip_ix(SOL, false)], &[project.client]),
+        Err(anchor_error(ErrorCode::OfferNotAccepted))
+    );
+    project.accept_offer(&mut bank).unwrap();
+    assert_eq!(
+        bank.process(&[project.tip_ix(0, false)], &[project.client]),
+        Err(anchor_error(ErrorCode::InvalidTip))
+    );
+
+    let freelancer_balance = bank.balance(&project.freelancer);
+    bank.process(&[project.tip_ix(SOL, false)], &[project.client])
+        .unwrap();
+    bank.process(&[project.tip_ix(SOL / 2, false)], &[project.client])
+        .unwrap();
+    assert_eq!(
+        bank.balance(&project.freelancer),
+        freelancer_balance + SOL + SOL / 2
+    );
+    let event = &bank.events::<events::TipPaid>()[0];
+    assert_eq!((event.amount, event.from_escrow), (SOL / 2, false));
+    assert_eq!(event.total_tips, SOL + SOL / 2);
+
+    let tips: Tips = bank.get(&project.tips());
+    assert_eq!(tips.project, project.project_info_account);
+    assert_eq!(
+        (tips.from_client, tips.from_escrow, tips.count),
+        (SOL + SOL / 2, 0, 2)
+    );
+    //the escrow only holds the milestones, so there's nothing to tip from
+    assert_eq!(
+        bank.process(&[project.tip_ix(1, true)], &[project.client]),
+        Err(anchor_error(ErrorCode::InvalidTip))
+    );
+    assert_eq!(project.info(&bank).total_project_funds, 2 * SOL);
+}
+
+#[test]
+fn escrow_tips_leave_the_milestone_funds_alone() {
+    let (mut bank, project) = running(2 * SOL, 2);
+    let escrow_extra = bank.balance(&project.token_escrow) - 2 * SOL;
+    bank.airdrop(&project.token_escrow, SOL);
+    let leftover = escrow_extra + SOL;
+    let freelancer_balance = bank.balance(&project.freelancer);
+
+    assert_eq!(
+        bank.process(&[project.tip_ix(leftover + 1, true)], &[project.client]),
+        Err(anchor_error(ErrorCode::InvalidTip))
+    );
+    bank.process(&[project.tip_ix(leftover, true)], &[project.client])
+        .unwrap();
+    assert_eq!(
+        bank.balance(&project.freelancer),
+        freelancer_balance + leftover
+    );
+    assert!(bank.events::<events::TipPaid>()[0].from_escrow);
+    let tips: Tips = bank.get(&project.tips());
+    assert_eq!((tips.from_client, tips.from_escrow), (0, leftover));
+
+    //both milestones still pay out in full
+    for _ in 0..2 {
+        project
+            .execute(&mut bank, &project.mark_milestone_ix())
+            .unwrap();
+        project.withdraw(&mut bank).unwrap();
+    }
+    assert_eq!(
+        bank.balance(&project.freelancer),
+        freelancer_balance + leftover + 2 * SOL
+    );
+    assert_eq!(project.info(&bank).total_project_funds, 2 * SOL);
+}
+
+///rent of the ratings the parties can still give each other once the project is closed,
+///for the first project of its client
+fn rating_rent(bank: &Bank, project: &Project) -> u64 {
+    bank.balance(&project.rating(0, &project.client))
+        + bank.balance(&project.rating(0, &project.freelancer))
+}
+
+#[test]
+fn stop_project_lets_the_client_cancel() {
+    let (mut bank, project) = running(3 * SOL, 3);
+    project
+        .execute(&mut bank, &project.mark_milestone_ix())
+        .unwrap();
+    project.withdraw(&mut bank).unwrap();
+
+    project.execute(&mut bank, &project.stop_ix()).unwrap();
+    assert_eq!(project.info(&bank).status, ProjectStatus::Cancelled);
+
+    let client_balance = bank.balance(&project.client);
+    let rent = bank.balance(&project.project_info_account);
+    project.cancel(&mut bank).unwrap();
+
+    assert_eq!(bank.events::<events::ProjectCancelled>()[0].amount, 2 * SOL);
+    assert_eq!(
+        bank.balance(&project.client),
+        client_balance + 2 * SOL + rent - rating_rent(&bank, &project)
+    );
+    assert!(bank.account(&project.project_info_account).is_none());
+    assert!(bank.account(&project.token_escrow).is_none());
+}
+
+#[test]
+fn cancelling_a_stopped_project_pays_the_reached_milestones_to_the_freelancer() {
+    let (mut bank, project) = running(3 * SOL, 3);
+    project
+        .execute(&mut bank, &project.mark_milestone_ix())
+        .unwrap();
+    project.withdraw(&mut bank).unwrap();
+    project
+        .execute(&mut bank, &project.mark_milestone_ix())
+        .unwrap();
+    project.execute(&mut bank, &project.stop_ix()).unwrap();
+
+    let client_balance = bank.balance(&project.client);
+    let freelancer_balance = bank.balance(&project.freelancer);
+    let rent = bank.balance(&project.project_info_account);
+    project.cancel(&mut bank).unwrap();
+
+    let cancelled = &bank.events::<events::ProjectCancelled>()[0];
+    assert_eq!((cancelled.amount, cancelled.earned), (SOL, SOL));
+    assert_eq!(cancelled.freelancer, project.freelancer);
+    assert_eq!(bank.balance(&project.freelancer), freelancer_balance + SOL);
+    assert_eq!(
+        bank.balance(&project.client),
+        client_balance + SOL + rent - rating_rent(&bank, &project)
+    );
+}
+
+#[test]
+fn reached_milestones_are_withdrawn_after_a_stop() {
+    let (mut bank, project) = running(3 * SOL, 3);
+    project
+        .execute(&mut bank, &project.mark_milestone_ix())
+        .unwrap();
+    project.execute(&mut bank, &project.stop_ix()).unwrap();
+
+    let freelancer_balance = bank.balance(&project.freelancer);
+    project.withdraw(&mut bank).unwrap();
+    assert_eq!(bank.balance(&project.freelancer), freelancer_balance + SOL);
+    let info = project.info(&bank);
+    assert_eq!(info.status, ProjectStatus::Cancelled);
+    assert_eq!(info.milestone_funds_withdrawn, 1);
+    assert_eq!(
+        project.withdraw(&mut bank),
+        Err(anchor_error(ErrorCode::NothingToWithdraw))
+    );
+
+    let client_balance = bank.balance(&project.client);
+    let rent = bank.balance(&project.project_info_account);
+    project.cancel(&mut bank).unwrap();
+    assert_eq!(bank.events::<events::ProjectCancelled>()[0].earned, 0);
+    assert_eq!(
+        bank.balance(&project.client),
+        client_balance + 2 * SOL + rent - rating_rent(&bank, &project)
+    );
+}
+
+#[test]
+fn payees_withdraw_their_shares_before_a_stopped_project_is_cancelled() {
+    let (mut bank, project) = running(4 * SOL, 2);
+    let designer = Pubkey::new_unique();
+    let splits =
+        project.set_payee_splits_ix(vec![project.freelancer, designer], vec![vec![5000, 5000]]);
+    project.execute(&mut bank, &splits).unwrap();
+    project
+        .execute(&mut bank, &project.mark_milestone_ix())
+        .unwrap();
+    project.execute(&mut bank, &project.stop_ix()).unwrap();
+
+    assert_eq!(
+        project.cancel(&mut bank),
+        Err(anchor_error(ErrorCode::SharesNotWithdrawn))
+    );
+    withdraw_payee_share(&mut bank, &project, &designer).unwrap();
+    let freelancer = project.freelancer;
+    withdraw_payee_share(&mut bank, &project, &freelancer).unwrap();
+    assert_eq!(bank.balance(&designer), SOL);
+    assert_eq!(project.info(&bank).status, ProjectStatus::Cancelled);
+
+    let client_balance = bank.balance(&project.client);
+    let rent = bank.balance(&project.project_info_account);
+    project.cancel(&mut bank).unwrap();
+    assert_eq!(
+        bank.balance(&project.client),
+        client_balance + 2 * SOL + rent - rating_rent(&bank, &project)
+    );
+}
+
+#[test]
+fn cancel_project_refunds_a_pending_project() {
+    let mut bank = Bank::new();
+    let project = Project::new(&mut bank, 100 * SOL);
+    project.initialize(&mut bank, 5 * SOL, 2).unwrap();
+    let multisig_rent = bank.balance(&project.multisig);
+    let reputation_rent =
+        bank.balance(&reputation(&project.client)) + bank.balance(&reputation(&project.freelancer));
+
+    project.cancel(&mut bank).unwrap();
+    assert_eq!(
+        bank.balance(&project.client),
+        100 * SOL - multisig_rent - reputation_rent
+    );
+    assert_eq!(rating_rent(&bank, &project), 0);
+    assert!(bank.account(&project.project_info_account).is_none());
+}
+
+#[test]
+fn cancel_project_while_running_fails() {
+    let (mut bank, project) = running(SOL, 1);
+    assert_eq!(
+        project.cancel(&mut bank),
+        Err(anchor_error(ErrorCode::ProjectAlreadyRunning))
+    );
+}
+
+#[test]
+fn cancel_project_rejects_the_wrong_signer() {
+    let (mut bank, project) = initialized(SOL, 1);
+
+    //the freelancer can't pose as the client, the PDA is derived from the client
+    let ix = project.cancel_ix(&bank, &project.freelancer);
+    assert_eq!(
+        bank.process(&[ix], &[project.freelancer]),
+        Err(anchor_error(AnchorErrorCode::ConstraintSeeds))
+    );
+
+    //and the real client has to sign
+    let mut ix = project.cancel_ix(&bank, &project.client);
+    ix.accounts[3].is_signer = false;
+    assert_eq!(
+        bank.process(&[ix], &[project.freelancer]),
+        Err(anchor_error(AnchorErrorCode::AccountNotSigner))
+    );
+
+    assert_eq!(bank.balance(&project.token_escrow), SOL);
+    assert_eq!(project.info(&bank).status, ProjectStatus::Offered);
+}
+
+#[test]
+fn approve_rejects_non_owners() {
+    let (mut bank, project) = pending(SOL, 1);
+    let stranger = Pubkey::new_unique();
+    bank.airdrop(&stranger, SOL);
+
+    assert_eq!(
+        project.propose(&mut bank, &stranger, &project.start_ix()),
+        Err(anchor_error(ErrorCode::InvalidOwner))
+    );
+
+    project
+        .propose(&mut bank, &project.client, &project.start_ix())
+        .unwrap();
+    assert_eq!(
+        project.approve(&mut bank, &stranger),
+        Err(anchor_error(ErrorCode::InvalidOwner))
+    );
+    assert_eq!(project.info(&bank).status, ProjectStatus::Pending);
+}
+
+#[test]
+fn client_multisig_actions_execute_once_approved() {
+    let (mut bank, project) = running(2 * SOL, 2);
+    let addresses = project.addresses();
+
+    let mark = MultisigAction::MarkCurrentMilestoneCompleted;
+    let (program_id, accounts, data) = mark.payload(&addresses);
+    let propose = multisig::propose(&addresses, &project.client, project.seqno(&bank), &mark);
+    bank.process(&[propose], &[project.client]).unwrap();
+    let proposal: Transaction = bank.get(&project.transaction_address(&bank));
+    assert_eq!(
+        (proposal.program_id, proposal.accounts, proposal.data),
+        (program_id, accounts, data)
+    );
+    let approve =
+        multisig::approve_action(&addresses, &project.observer, project.seqno(&bank), &mark);
+    bank.process(&[approve], &[project.observer]).unwrap();
+    assert_eq!(project.info(&bank).milestones_reached, 1);
+
+    //the client signs the transaction executing a change order
+    let change_order = MultisigAction::ChangeOrder {
+        additional_funds: SOL,
+        milestone_amounts: vec![SOL, 2 * SOL],
+    };
+    //the proposal account at the multisig's seqno is reused
+    let seqno = project.seqno(&bank);
+    let propose = multisig::propose(&addresses, &project.freelancer, seqno, &change_order);
+    bank.process(&[propose], &[project.freelancer]).unwrap();
+    let approve = multisig::approve_action(&addresses, &project.observer, seqno, &change_order);
+    assert_eq!(
+        bank.process(std::slice::from_ref(&approve), &[project.observer]),
+        Err(BankError::MissingSigner(project.client))
+    );
+    bank.process(&[approve], &[project.observer, project.client])
+        .unwrap();
+    assert_eq!(project.info(&bank).milestone_amounts, vec![SOL, 2 * SOL]);
+    assert_eq!(bank.balance(&project.token_escrow), 3 * SOL);
+}
+
+#[test]
+fn approve_rejects_double_execution() {
+    let (mut bank, project) = pending(SOL, 1);
+    project
+        .propose(&mut bank, &project.client, &project.start_ix())
+        .unwrap();
+    project.approve(&mut bank, &project.freelancer).unwrap();
+
+    assert_eq!(
+        project.approve(&mut bank, &project.observer),
+        Err(anchor_error(ErrorCode::AlreadyExecuted))
+    );
+    assert_eq!(
+        project.approve(&mut bank, &project.freelancer),
+        Err(anchor_error(ErrorCode::AlreadyExecuted))
+    );
+}
+
+#[test]
+fn approve_needs_the_executed_accounts() {
+    let (mut bank, project) = pending(SOL, 1);
+    project
+        .propose(&mut bank, &project.client, &project.start_ix())
+        .unwrap();
+
+    let mut ix = project.approve_ix(&bank, &project.freelancer);
+    ix.accounts.truncate(5);
+    assert_eq!(
+        bank.process(&[ix], &[project.freelancer]),
+        Err(BankError::Program(ProgramError::NotEnoughAccountKeys))
+    );
+    assert_eq!(project.info(&bank).status, ProjectStatus::Pending);
+}
+
+#[test]
+fn create_transaction_rejects_oversized_proposals() {
+    let (mut bank, project) = initialized(SOL, 1);
+    let mut proposal = project.start_ix();
+    proposal.data = vec![0; Transaction::MAX_DATA_LEN + 1];
+    assert_eq!(
+        project.propose(&mut bank, &project.client, &proposal),
+        Err(anchor_error(ErrorCode::ParamLength))
+    );
+}
+
+#[test]
+fn create_transaction_rejects_too_many_accounts() {
+    let (mut bank, project) = initialized(SOL, 1);
+    let mut proposal = project.start_ix();
+    proposal.accounts = (0..=Transaction::MAX_ACCOUNTS)
+        .map(|_| AccountMeta::new_readonly(Pubkey::new_unique(), false))
+        .collect();
+    assert_eq!(
+        project.propose(&mut bank, &project.client, &proposal),
+        Err(anchor_error(ErrorCode::ParamLength))
+    );
+}
+
+#[test]
+fn proposals_are_sized_from_their_contents() {
+    let (mut bank, project) = pending(SOL, 1);
+    let proposal_size = |bank: &Bank| {
+        let address = project.transaction_address(bank);
+        bank.account(&address).unwrap().data.len()
+    };
+
+    let start = project.start_ix();
+    project.propose(&mut bank, &project.client, &start).unwrap();
+    assert_eq!(
+        proposal_size(&bank),
+        8 + Transaction::space(start.accounts.len(), start.data.len(), 3)
+    );
+
+    //the next proposal reuses the account and grows it
+    let change_order = project.change_order_ix(SOL, vec![SOL; 6]);
+    project
+        .propose(&mut bank, &project.client, &change_order)
+        .unwrap();
+    let grown = 8 + Transaction::space(change_order.accounts.len(), change_order.data.len(), 3);
+    assert_eq!(proposal_size(&bank), grown);
+    let address = project.transaction_address(&bank);
+    assert_eq!(
+        bank.balance(&address),
+        Rent::default().minimum_balance(grown)
+    );
+
+    //a smaller one leaves it as is
+    project.propose(&mut bank, &project.client, &start).unwrap();
+    assert_eq!(proposal_size(&bank), grown);
+    project.approve(&mut bank, &project.freelancer).unwrap();
+    assert_eq!(project.info(&bank).status, ProjectStatus::Running);
+}
+
+#[test]
+fn append_transaction_data_completes_a_proposal() {
+    let (mut bank, project) = running(2 * SOL, 2);
+    let change_order = project.change_order_ix(SOL, vec![SOL, SOL, SOL]);
+    let (head, tail) = change_order.data.split_at(16);
+    let mut proposal = change_order.clone();
+    proposal.data = head.to_vec();
+    project
+        .propose(&mut bank, &project.freelancer, &proposal)
+        .unwrap();
+
+    //only the proposer can append
+    let ix = project.append_transaction_data_ix(&bank, &project.client, tail.to_vec());
+    assert_eq!(
+        bank.process(&[ix], &[project.client]),
+        Err(anchor_error(AnchorErrorCode::ConstraintHasOne))
+    );
+    let ix = project.append_transaction_data_ix(
+        &bank,
+        &project.freelancer,
+        vec![0; Transaction::MAX_DATA_LEN],
+    );
+    assert_eq!(
+        bank.process(&[ix], &[project.freelancer]),
+        Err(anchor_error(ErrorCode::ParamLength))
+    );
+
+    let ix = project.append_transaction_data_ix(&bank, &project.freelancer, tail.to_vec());
+    bank.process(&[ix], &[project.freelancer]).unwrap();
+    let transaction: Transaction = bank.get(&project.transaction_address(&bank));
+    assert_eq!(transaction.data, change_order.data);
+    assert_eq!(transaction.signers, vec![false, true, false]);
+    let appended = bank.events::<events::ProposalDataAppended>();
+    assert_eq!(appended.len(), 1);
+    assert_eq!(appended[0].data_len as usize, change_order.data.len());
+
+    project.approve(&mut bank, &project.client).unwrap();
+    assert_eq!(project.info(&bank).milestone_amounts, vec![SOL, SOL, SOL]);
+
+    //nothing can be added once it ran
+    let ix = project.append_transaction_data_ix(&bank, &project.freelancer, vec![0]);
+    assert_eq!(
+        bank.process(&[ix], &[project.freelancer]),
+        Err(anchor_error(ErrorCode::AlreadyExecuted))
+    );
+}
+
+#[test]
+fn proposals_cannot_spend_accounts_they_do_not_own() {
+    let (mut bank, project) = initialized(SOL, 1);
+
+    //a proposal can't make the multisig sign for the escrow
+    let mut ix = project.withdraw_ix();
+    ix.accounts[2] = AccountMeta::new(project.multisig, true);
+    project.propose(&mut bank, &project.client, &ix).unwrap();
+    assert_eq!(
+        project.approve(&mut bank, &project.freelancer),
+        Err(anchor_error(AnchorErrorCode::ConstraintRaw))
+    );
+
+    //nor sign for the escrow PDA, only the program can
+    let transfer = system_instruction::transfer(&project.token_escrow, &project.observer, SOL);
+    project
+        .propose(&mut bank, &project.client, &transfer)
+        .unwrap();
+    let mut ix = project.approve_ix(&bank, &project.freelancer);
+    for meta in ix.accounts.iter_mut() {
+        meta.is_signer = meta.pubkey == project.freelancer;
+    }
+    assert_eq!(
+        bank.process(&[ix], &[project.freelancer]),
+        Err(BankError::PrivilegeEscalation(project.token_escrow))
+    );
+    assert_eq!(bank.balance(&project.token_escrow), SOL);
+}
+
+///rewrite `address` with `data`, keeping its lamports
+fn overwrite(bank: &mut Bank, address: &Pubkey, data: Vec<u8>) {
+    let lamports = bank.balance(address);
+    bank.set_account(
+        address,
+        Account {
+            lamports,
+            data,
+            owner: PROGRAM_ID,
+            executable: false,
+        },
+    );
+}
+
+///write the project the way the first version of the program laid it out
+fn write_v0_project(bank: &mut Bank, project: &Project) {
+    let info = project.info(bank);
+    let mut data = ProjectInfo::discriminator().to_vec();
+    data.extend(info.total_project_funds.to_le_bytes());
+    data.extend([
+        info.milestones,
+        info.milestones_reached,
+        info.milestone_funds_withdrawn,
+    ]);
+    data.extend(info.client.as_ref());
+    data.extend(info.freelancer.as_ref());
+    data.extend(info.multisig.as_ref());
+    data.push(info.status as u8);
+    assert_eq!(data.len(), 8 + ProjectInfoV0::SIZE);
+    overwrite(bank, &project.project_info_account, data);
+}
+
+///write the project the way it was laid out before streamed milestones,
+///the account keeps the size that layout was allocated with.
+///accounts from before the version byte have this layout with zero padding there
+fn write_v1_project(bank: &mut Bank, project: &Project, version: AccountVersion) {
+    let info = project.info(bank);
+    let mut data = ProjectInfo::discriminator().to_vec();
+    info.total_project_funds.serialize(&mut data).unwrap();
+    (
+        info.milestones,
+        info.milestones_reached,
+        info.milestone_funds_withdrawn,
+    )
+        .serialize(&mut data)
+        .unwrap();
+    (info.client, info.freelancer, info.multisig, info.status)
+        .serialize(&mut data)
+        .unwrap();
+    (info.milestone_amounts, info.paused_at, info.paused_duration)
+        .serialize(&mut data)
+        .unwrap();
+    version.serialize(&mut data).unwrap();
+    data.resize(8 + ProjectInfoV1::SIZE, 0);
+    overwrite(bank, &project.project_info_account, data);
+}
+
+///clear the version byte, like accounts created before it existed: it follows
+///the serialized fields, the rest of the account is zero padding
+fn write_unversioned<T: AccountDeserialize + AccountSerialize>(bank: &mut Bank, address: &Pubkey) {
+    let mut serialized = Vec::new();
+    bank.get::<T>(address)
+        .try_serialize(&mut serialized)
+        .unwrap();
+    let mut data = bank.account(address).unwrap().data.clone();
+    data[serialized.len() - AccountVersion::SIZE] = 0;
+    overwrite(bank, address, data);
+}
+
+#[test]
+fn migrate_account_upgrades_a_v0_project() {
+    let (mut bank, project) = running(3 * SOL + 2, 3);
+    project
+        .execute(&mut bank, &project.mark_milestone_ix())
+        .unwrap();
+    write_v0_project(&mut bank, &project);
+
+    //v0 accounts don't decode as the current layout, handlers reject them
+    assert_eq!(
+        project.withdraw(&mut bank),
+        Err(anchor_error(ErrorCode::AccountNotMigrated))
+    );
+
+    let client_balance = bank.balance(&project.client);
+    let project_balance = bank.balance(&project.project_info_account);
+    bank.process(
+        &[project.migrate_ix(&project.project_info_account)],
+        &[project.client],
+    )
+    .unwrap();
+
+    let info = project.info(&bank);
+    assert_eq!(info.version, ProjectInfo::VERSION);
+    assert_eq!(info.milestone_funds_streamed, vec![0; 3]);
+    assert_eq!(info.status, ProjectStatus::Running);
+    assert_eq!(info.milestones_reached, 1);
+    assert_eq!(info.milestone_amounts, vec![SOL, SOL, SOL + 2]);
+    assert_eq!(info.paused_duration, 0);
+    let account = bank.account(&project.project_info_account).unwrap();
+    assert_eq!(account.data.len(), 8 + ProjectInfo::MAX_SIZE);
+    //the client topped the account up to the rent of the new size
+    let rent = Rent::default().minimum_balance(8 + ProjectInfo::MAX_SIZE);
+    assert_eq!(account.lamports, rent.max(project_balance));
+    assert_eq!(
+        client_balance - bank.balance(&project.client),
+        account.lamports - project_balance
+    );
+
+    project.withdraw(&mut bank).unwrap();
+    assert_eq!(bank.balance(&project.token_escrow), 2 * SOL + 2);
+}
+
+#[test]
+fn migrate_account_upgrades_a_v1_project() {
+    let (mut bank, project) = running(2 * SOL, 2);
+    project
+        .execute(&mut bank, &project.mark_milestone_ix())
+        .unwrap();
+    write_v1_project(&mut bank, &project, AccountVersion::V1);
+    assert_eq!(
+        project.withdraw(&mut bank),
+        Err(anchor_error(ErrorCode::AccountNotMigrated))
+    );
+
+    bank.process(
+        &[project.migrate_ix(&project.project_info_account)],
+        &[project.client],
+    )
+    .unwrap();
+    let info = project.info(&bank);
+    assert_eq!(info.version, ProjectInfo::VERSION);
+    assert_eq!(info.milestone_amounts, vec![SOL, SOL]);
+    assert_eq!(info.milestone_funds_streamed, vec![0, 0]);
+    assert_eq!(
+        bank.account(&project.project_info_account)
+            .unwrap()
+            .data
+            .len(),
+        8 + ProjectInfo::MAX_SIZE
+    );
+    project.withdraw(&mut bank).unwrap();
+    assert_eq!(bank.balance(&project.token_escrow), SOL);
+}
+
+#[test]
+fn handlers_reject_unversioned_accounts() {
+    let (mut bank, project) = running(SOL, 2);
+    project
+        .execute(&mut bank, &project.mark_milestone_ix())
+        .unwrap();
+
+    write_v1_project(&mut bank, &project, AccountVersion::Unversioned);
+    let data = &bank.account(&project.project_info_account).unwrap().data;
+    assert_eq!(
+        ProjectInfo::decode_any_layout(data).unwrap().version,
+        AccountVersion::Unversioned
+    );
+    assert_eq!(
+        project.withdraw(&mut bank),
+        Err(anchor_error(ErrorCode::AccountNotMigrated))
+    );
+
+    write_unversioned::<Multisig>(&mut bank, &project.multisig);
+    assert_eq!(
+        project.propose(&mut bank, &project.client, &project.mark_milestone_ix()),
+        Err(anchor_error(ErrorCode::AccountNotMigrated))
+    );
+
+    for account in [project.project_info_account, project.multisig] {
+        bank.process(&[project.migrate_ix(&account)], &[project.client])
+            .unwrap();
+    }
+    assert_eq!(
+        bank.get::<Multisig>(&project.multisig).version,
+        Multisig::VERSION
+    );
+    project.withdraw(&mut bank).unwrap();
+    project
+        .execute(&mut bank, &project.mark_milestone_ix())
+        .unwrap();
+    assert_eq!(project.info(&bank).milestones_reached, 2);
+}
+
+#[test]
+fn approve_rejects_an_unversioned_proposal() {
+    let (mut bank, project) = pending(SOL, 1);
+    project
+        .propose(&mut bank, &project.client, &project.start_ix())
+        .unwrap();
+    let transaction = project.transaction_address(&bank);
+    write_unversioned::<Transaction>(&mut bank, &transaction);
+
+    assert_eq!(
+        project.approve(&mut bank, &project.freelancer),
+        Err(anchor_error(ErrorCode::AccountNotMigrated))
+    );
+    //nor can it be replaced by a new proposal before it's migrated
+    assert_eq!(
+        project.propose(&mut bank, &project.client, &project.start_ix()),
+        Err(anchor_error(ErrorCode::AccountNotMigrated))
+    );
+
+    bank.process(&[project.migrate_ix(&transaction)], &[project.client])
+        .unwrap();
+    project.approve(&mut bank, &project.freelancer).unwrap();
+    assert_eq!(project.info(&bank).status, ProjectStatus::Running);
+}
+
+#[test]
+fn withdraw_streamed_funds_rejects_an_unversioned_stream() {
+    let (mut bank, project) = running(2 * SOL, 2);
+    stream_milestone(&mut bank, &project, 0, 0, 100);
+    write_unversioned::<MilestoneStream>(&mut bank, &project.milestone_stream(0));
+
+    bank.warp(50);
+    assert_eq!(
+        project.withdraw_streamed_funds(&mut bank, 0),
+        Err(anchor_error(ErrorCode::AccountNotMigrated))
+    );
+}
+
+#[test]
+fn migrate_account_rejects_current_and_foreign_accounts() {
+    let (mut bank, project) = initialized(SOL, 1);
+    assert_eq!(
+        bank.process(
+            &[project.migrate_ix(&project.project_info_account)],
+            &[project.client]
+        ),
+        Err(anchor_error(ErrorCode::AlreadyMigrated))
+    );
+    assert_eq!(
+        bank.process(
+            &[project.migrate_ix(&project.token_escrow)],
+            &[project.client]
+        ),
+        Err(anchor_error(AnchorErrorCode::ConstraintOwner))
+    );
+}
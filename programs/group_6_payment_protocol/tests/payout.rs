@@ -0,0 +1,403 @@
+//! Property tests for the payout arithmetic: the milestone split, the
+//! withdrawal amounts with the last milestone sweeping the escrow, streamed
+//! milestones, and the cancel refund.
+
+use anchor_lang::prelude::Pubkey;
+use group_6_payment_protocol::payout::{
+    draw_from_milestones, earned_funds, milestone_payout, retainer_periods_unlocked,
+    return_to_milestones, split_shares, unused_milestone_funds, vested_amount, MAX_BPS,
+};
+use group_6_payment_protocol::state::{ProjectInfo, ProjectStatus, Versioned};
+use proptest::prelude::*;
+
+const MAX_MILESTONES: u8 = ProjectInfo::MAX_MILESTONES as u8;
+
+#[derive(Clone, Debug)]
+enum Step {
+    Reach,
+    Withdraw,
+    ///stream `share` of what's left of a milestone not withdrawn yet, in 1/256ths
+    Stream {
+        milestone: u8,
+        share: u8,
+    },
+    ///keep the reached milestones and split the rest over `milestones`
+    ChangeOrder {
+        additional_funds: u64,
+        milestones: u8,
+    },
+    ///lamports sent straight to the escrow PDA while the project runs,
+    ///once it is settled nothing moves them out again
+    Donate(u64),
+    Cancel,
+}
+
+///unix timestamps, from close to now to either end of the i64 range
+fn timestamp() -> impl Strategy<Value = i64> {
+    prop_oneof![
+        2 => -1_000_000i64..1_000_000,
+        2 => any::<i64>(),
+        1 => Just(i64::MIN),
+        1 => Just(i64::MAX),
+    ]
+}
+
+fn step() -> impl Strategy<Value = Step> {
+    prop_oneof![
+        4 => Just(Step::Reach),
+        4 => Just(Step::Withdraw),
+        2 => (0..MAX_MILESTONES, any::<u8>()).prop_map(|(milestone, share)| Step::Stream { milestone, share }),
+        1 => (any::<u64>(), 1..=MAX_MILESTONES).prop_map(|(additional_funds, milestones)| {
+            Step::ChangeOrder { additional_funds, milestones }
+        }),
+        1 => (0..1_000_000_000_000u64).prop_map(Step::Donate),
+        1 => Just(Step::Cancel),
+    ]
+}
+
+///totals cluster around small values, where rounding is most visible
+fn total() -> impl Strategy<Value = u64> {
+    prop_oneof![0..1_000u64, 0..1_000_000_000_000u64, any::<u64>()]
+}
+
+fn running_project(total_project_funds: u64, milestones: u8) -> ProjectInfo {
+    ProjectInfo {
+        total_project_funds,
+        milestones,
+        milestones_reached: 0,
+        milestone_funds_withdrawn: 0,
+        client: Pubkey::new_unique(),
+        freelancer: Pubkey::new_unique(),
+        multisig: Pubkey::new_unique(),
+        status: ProjectStatus::Running,
+        milestone_amounts: ProjectInfo::split_funds(total_project_funds, milestones).unwrap(),
+        paused_at: 0,
+        paused_duration: 0,
+        milestone_funds_streamed: vec![0; milestones as usize],
+        nonce: 0,
+        started: true,
+        version: ProjectInfo::VERSION,
+    }
+}
+
+fn sum(amounts: &[u64]) -> u128 {
+    amounts.iter().map(|amount| *amount as u128).sum()
+}
+
+proptest! {
+    #[test]
+    fn split_funds_adds_up(total in total(), milestones in 1..=MAX_MILESTONES) {
+        let amounts = ProjectInfo::split_funds(total, milestones).unwrap();
+        prop_assert_eq!(amounts.len(), milestones as usize);
+        prop_assert_eq!(sum(&amounts), total as u128);
+        //equal parts, the last one takes the remainder of the division
+        let share = total / milestones as u64;
+        prop_assert!(amounts[..amounts.len() - 1].iter().all(|amount| *amount == share));
+        prop_assert_eq!(amounts[amounts.len() - 1] - share, total % milestones as u64);
+    }
+
+    #[test]
+    fn split_funds_rejects_invalid_milestone_counts(total in total(), milestones in MAX_MILESTONES + 1..) {
+        prop_assert!(ProjectInfo::split_funds(total, milestones).is_err());
+        prop_assert!(ProjectInfo::split_funds(total, 0).is_err());
+    }
+
+    #[test]
+    fn payout_covers_exactly_the_reached_milestones(
+        milestones in prop::collection::vec((any::<u32>(), any::<u32>()), 1..=MAX_MILESTONES as usize),
+        withdrawn in 0..MAX_MILESTONES,
+        reached in 0..=MAX_MILESTONES,
+        extra in any::<u32>(),
+    ) {
+        //a stream never pays more than its milestone
+        let amounts: Vec<u64> = milestones.iter().map(|(amount, _)| *amount as u64).collect();
+        let streamed: Vec<u64> = milestones
+            .iter()
+            .map(|(amount, streamed)| (*streamed).min(*amount) as u64)
+            .collect();
+        let reached = reached.min(amounts.len() as u8);
+        let withdrawn = withdrawn.min(reached);
+        let unwithdrawn = sum(&amounts[withdrawn as usize..]) - sum(&streamed[withdrawn as usize..]);
+        let escrow = unwithdrawn as u64 + extra as u64;
+
+        //what a stopped project owes the freelancer never sweeps the escrow
+        let range = withdrawn as usize..reached as usize;
+        let earned = sum(&amounts[range.clone()]) - sum(&streamed[range]);
+        prop_assert_eq!(
+            earned_funds(&amounts, &streamed, withdrawn, reached).unwrap() as u128,
+            earned
+        );
+
+        match milestone_payout(&amounts, &streamed, withdrawn, reached, escrow) {
+            Ok(payout) => {
+                prop_assert!(reached > withdrawn);
+                prop_assert!(payout.amount <= escrow);
+                prop_assert_eq!(payout.completed, reached as usize == amounts.len());
+                if payout.completed {
+                    //nothing is left behind in the escrow
+                    prop_assert_eq!(payout.amount, escrow);
+                } else {
+                    let range = withdrawn as usize..reached as usize;
+                    prop_assert_eq!(
+                        payout.amount as u128,
+                        sum(&amounts[range.clone()]) - sum(&streamed[range])
+                    );
+                }
+            }
+            Err(_) => prop_assert_eq!(reached, withdrawn),
+        }
+    }
+
+    #[test]
+    fn vesting_grows_from_the_cliff_to_the_whole_amount(
+        amount in any::<u64>(),
+        schedule in [timestamp(), timestamp(), timestamp()],
+        now in timestamp(),
+        later in 0..=i64::MAX,
+    ) {
+        let mut schedule = schedule;
+        schedule.sort_unstable();
+        let [start, cliff, end] = schedule;
+        prop_assume!(start < end);
+        let vested = vested_amount(amount, start, cliff, end, now).unwrap();
+        prop_assert!(vested <= amount);
+        if now < cliff {
+            prop_assert_eq!(vested, 0);
+        }
+        if now >= end {
+            prop_assert_eq!(vested, amount);
+        }
+        //vesting never goes backwards
+        prop_assert!(vested_amount(amount, start, cliff, end, now.saturating_add(later)).unwrap() >= vested);
+        prop_assert!(vested_amount(amount, cliff, start, end, now).is_err() || cliff == start);
+    }
+
+    #[test]
+    fn retainer_unlocks_one_period_per_interval(
+        start in -1_000_000i64..1_000_000,
+        interval in 1i64..100_000,
+        periods in 1..=MAX_MILESTONES,
+        now in -2_000_000i64..4_000_000,
+        later in 0i64..1_000_000,
+    ) {
+        let unlocked = retainer_periods_unlocked(start, interval, periods, now).unwrap();
+        prop_assert!(unlocked <= periods);
+        if now < start + interval {
+            prop_assert_eq!(unlocked, 0);
+        }
+        if now >= start + interval * periods as i64 {
+            prop_assert_eq!(unlocked, periods);
+        }
+        prop_assert!(retainer_periods_unlocked(start, interval, periods, now + later).unwrap() >= unlocked);
+        prop_assert!(retainer_periods_unlocked(start, 0, periods, now).is_err());
+    }
+
+    #[test]
+    fn kept_and_unused_milestones_add_up_to_the_project(
+        total in total(),
+        milestones in 1..=MAX_MILESTONES,
+        kept in 0..=MAX_MILESTONES,
+    ) {
+        let kept = kept.min(milestones);
+        let amounts = ProjectInfo::split_funds(total, milestones).unwrap();
+        let streamed = vec![0; amounts.len()];
+        let unused = unused_milestone_funds(&amounts, &streamed, kept).unwrap();
+        prop_assert_eq!(sum(&amounts[..kept as usize]) + unused as u128, total as u128);
+    }
+
+    #[test]
+    fn hourly_draws_fill_the_unreached_milestones_in_order(
+        total in total(),
+        milestones in 1..=MAX_MILESTONES,
+        first in 0..=MAX_MILESTONES,
+        amount in any::<u64>(),
+    ) {
+        let amounts = ProjectInfo::split_funds(total, milestones).unwrap();
+        let first = first.min(milestones);
+        let streamed = vec![0; amounts.len()];
+        let budget = sum(&amounts[first as usize..]);
+        match draw_from_milestones(&amounts, &streamed, first, amount) {
+            Ok(drawn) => {
+                prop_assert!(amount as u128 <= budget);
+                prop_assert_eq!(sum(&drawn), amount as u128);
+                prop_assert!(drawn[..first as usize].iter().all(|drawn| *drawn == 0));
+                prop_assert!(drawn.iter().zip(&amounts).all(|(drawn, amount)| drawn <= amount));
+                //a milestone is only drawn from once the previous ones are full
+                for index in first as usize + 1..drawn.len() {
+                    prop_assert!(drawn[index] == 0 || drawn[index - 1] == amounts[index - 1]);
+                }
+            }
+            Err(_) => prop_assert!(amount as u128 > budget),
+        }
+    }
+
+    #[test]
+    fn subcontract_refunds_only_go_back_where_they_were_drawn(
+        total in total(),
+        milestones in 1..=MAX_MILESTONES,
+        first in 0..=MAX_MILESTONES,
+        amount in any::<u64>(),
+        reached in 0..=MAX_MILESTONES,
+        refund in any::<u64>(),
+    ) {
+        let amounts = ProjectInfo::split_funds(total, milestones).unwrap();
+        let first = first.min(milestones);
+        let before = vec![0; amounts.len()];
+        let amount = amount % (sum(&amounts[first as usize..]) as u64).saturating_add(1);
+        let streamed = draw_from_milestones(&amounts, &before, first, amount).unwrap();
+        let reached = reached.min(milestones);
+        let refund = refund % amount.saturating_add(1);
+        let (after, returned) = return_to_milestones(&streamed, &streamed, reached, refund);
+        prop_assert!(returned <= refund);
+        prop_assert_eq!(sum(&streamed) - sum(&after), returned as u128);
+        //reached milestones keep what was drawn from them
+        prop_assert_eq!(&after[..reached as usize], &streamed[..reached as usize]);
+        //everything goes back when nothing was reached yet
+        if reached <= first {
+            prop_assert_eq!(returned, refund);
+        }
+    }
+
+    #[test]
+    fn payee_shares_add_up_to_the_milestone(
+        amount in any::<u64>(),
+        cuts in prop::collection::vec(0..=MAX_BPS as u16, 0..8),
+    ) {
+        //turn the cuts into splits adding up to MAX_BPS
+        let mut cuts = cuts;
+        cuts.sort_unstable();
+        let mut splits = Vec::new();
+        let mut previous = 0;
+        for cut in cuts.into_iter().chain([MAX_BPS as u16]) {
+            splits.push(cut - previous);
+            previous = cut;
+        }
+        let shares = split_shares(amount, &splits).unwrap();
+        prop_assert_eq!(sum(&shares), amount as u128);
+        for (share, bps) in shares.iter().zip(&splits).skip(1) {
+            prop_assert_eq!(*share as u128, amount as u128 * *bps as u128 / MAX_BPS as u128);
+        }
+    }
+
+    #[test]
+    fn freelancer_gets_the_deposit_minus_refunds(
+        total in total(),
+        milestones in 1..=MAX_MILESTONES,
+        steps in prop::collection::vec(step(), 0..64),
+    ) {
+        let mut info = running_project(total, milestones);
+        let mut escrow = total as u128;
+        let mut deposited = total as u128;
+        let mut refunded = 0u128;
+        let mut paid = 0u128;
+
+        for step in steps.into_iter().chain([Step::Cancel]) {
+            let running = info.status == ProjectStatus::Running;
+            match step {
+                Step::Reach if running && info.milestones_reached < info.milestones => {
+                    info.milestones_reached += 1;
+                }
+                Step::Withdraw if running => {
+                    let range = info.milestone_funds_withdrawn as usize..info.milestones_reached as usize;
+                    let earned = sum(&info.milestone_amounts[range.clone()])
+                        - sum(&info.milestone_funds_streamed[range]);
+                    let payout = match milestone_payout(
+                        &info.milestone_amounts,
+                        &info.milestone_funds_streamed,
+                        info.milestone_funds_withdrawn,
+                        info.milestones_reached,
+                        u64::try_from(escrow).unwrap(),
+                    ) {
+                        Ok(payout) => payout,
+                        Err(_) => {
+                            prop_assert_eq!(info.milestones_reached, info.milestone_funds_withdrawn);
+                            continue;
+                        }
+                    };
+                    //every withdrawal is within bounds
+                    prop_assert!(payout.amount as u128 <= escrow);
+                    prop_assert!(payout.amount as u128 >= earned);
+                    if !payout.completed {
+                        prop_assert_eq!(payout.amount as u128, earned);
+                    }
+
+                    escrow -= payout.amount as u128;
+                    paid += payout.amount as u128;
+                    info.milestone_funds_withdrawn = info.milestones_reached;
+                    if payout.completed {
+                        info.transition(ProjectStatus::Completed).unwrap();
+                        prop_assert_eq!(escrow, 0);
+                    }
+                }
+                Step::Stream { milestone, share } if running
+                    && milestone < info.milestones
+                    && milestone >= info.milestone_funds_withdrawn =>
+                {
+                    let index = milestone as usize;
+                    let left = info.milestone_amounts[index] - info.milestone_funds_streamed[index];
+                    let amount = (left as u128 * share as u128 / 256) as u64;
+                    escrow -= amount as u128;
+                    paid += amount as u128;
+                    info.milestone_funds_streamed[index] += amount;
+                }
+                Step::ChangeOrder { additional_funds, milestones } if running => {
+                    let reached = info.locked_milestones();
+                    let Some(new_total) = info.total_project_funds.checked_add(additional_funds) else {
+                        continue;
+                    };
+                    if escrow + additional_funds as u128 > u64::MAX as u128 {
+                        continue;
+                    }
+                    let left = new_total - sum(&info.milestone_amounts[..reached]) as u64;
+                    let parts = milestones.max(reached as u8 + 1) - reached as u8;
+                    if reached + parts as usize > ProjectInfo::MAX_MILESTONES {
+                        continue;
+                    }
+                    let mut amounts = info.milestone_amounts[..reached].to_vec();
+                    amounts.extend(ProjectInfo::split_funds(left, parts).unwrap());
+                    info.apply_change_order(new_total, amounts).unwrap();
+                    escrow += additional_funds as u128;
+                    deposited += additional_funds as u128;
+                }
+                //an escrow can't hold more than u64::MAX lamports
+                Step::Donate(lamports)
+                    if running
+                        && escrow + lamports as u128 <= u64::MAX as u128 =>
+                {
+                    escrow += lamports as u128;
+                    deposited += lamports as u128;
+                }
+                Step::Cancel if info.status != ProjectStatus::Completed
+                    && info.status != ProjectStatus::Cancelled =>
+                {
+                    refunded += escrow;
+                    escrow = 0;
+                    info.transition(ProjectStatus::Cancelled).unwrap();
+                }
+                _ => {}
+            }
+            prop_assert_eq!(paid + refunded + escrow, deposited);
+            //the freelancer is never paid ahead of the milestones and streams
+            if info.status == ProjectStatus::Running {
+                let withdrawn = info.milestone_funds_withdrawn as usize;
+                prop_assert!(
+                    paid <= sum(&info.milestone_amounts[..withdrawn])
+                            + sum(&info.milestone_funds_streamed[withdrawn..])
+                );
+            }
+        }
+
+        //every project ends settled, completed or cancelled
+        prop_assert_eq!(escrow, 0);
+        prop_assert_eq!(paid, deposited - refunded);
+    }
+}
+
+#[test]
+fn streams_can_span_the_whole_timestamp_range() {
+    let vested = |now| vested_amount(u64::MAX, i64::MIN, i64::MIN, i64::MAX, now).unwrap();
+    assert_eq!(vested(i64::MIN), 0);
+    assert_eq!(vested(0), 1 << 63);
+    assert_eq!(vested(i64::MAX), u64::MAX);
+    assert!(vested_amount(1, i64::MAX, i64::MAX, i64::MIN, 0).is_err());
+}
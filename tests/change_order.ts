@@ -0,0 +1,162 @@
+import * as anchor from "@project-serum/anchor";
+import { Program } from "@project-serum/anchor";
+import { Group6PaymentProtocol } from "../target/types/group_6_payment_protocol";
+import { SystemProgram, Transaction, LAMPORTS_PER_SOL } from '@solana/web3.js';
+import { expect } from "chai";
+import { getMultisigTransactionPda } from "../utils/utils";
+
+describe("Client tops up the budget through a change order", () => {
+    // Configure the client to use the local cluster.
+    const provider = anchor.AnchorProvider.env();
+    anchor.setProvider(provider);
+
+    const program = anchor.workspace.Group6PaymentProtocol as Program<Group6PaymentProtocol>;
+    let client = anchor.web3.Keypair.generate();
+    let freelancer = anchor.web3.Keypair.generate();
+    let observer = anchor.web3.Keypair.generate();
+
+    let projectInfoAccount: anchor.web3.PublicKey;
+
+    let tokenEscrow: anchor.web3.PublicKey;
+    let multisig: anchor.web3.PublicKey;
+
+    let milestones = 4;
+    let totalFundsForProject = new anchor.BN(LAMPORTS_PER_SOL * 20_000);
+
+    before(async () => {
+        [projectInfoAccount,] = anchor.web3.PublicKey.findProgramAddressSync(
+            [
+                Buffer.from("project_info_account"),
+                client.publicKey.toBuffer(),
+                freelancer.publicKey.toBuffer(),
+            ],
+            program.programId
+        );
+
+        [multisig,] = anchor.web3.PublicKey.findProgramAddressSync(
+            [
+                Buffer.from("multisig"),
+                projectInfoAccount.toBuffer(),
+            ],
+            program.programId
+        );
+
+        [tokenEscrow,] = anchor.web3.PublicKey.findProgramAddressSync(
+            [
+                Buffer.from("token_escrow"),
+                projectInfoAccount.toBuffer(),
+            ],
+            program.programId
+        );
+        const tx = new Transaction().add(
+            SystemProgram.transfer({
+                fromPubkey: provider.wallet.publicKey,
+                toPubkey: client.publicKey,
+                lamports: 24_100 * LAMPORTS_PER_SOL,
+            }),
+        );
+        await provider.sendAndConfirm(tx,)
+
+        await program.methods
+            .initializeProject(totalFundsForProject, milestones)
+            .accounts({
+                client: client.publicKey,
+                freelancer: freelancer.publicKey,
+                observer: observer.publicKey,
+                multisig,
+                projectInfoAccount,
+                tokenEscrow
+            })
+            .signers([client])
+            .rpc()
+    })
+
+    it("splits the project funds equally between milestones", async () => {
+        const projectData = await program.account.projectInfo.fetch(projectInfoAccount)
+        expect(projectData.milestoneAmounts.map(amount => amount.toNumber()))
+            .to.deep.equal(Array(milestones).fill(LAMPORTS_PER_SOL * 5_000));
+    })
+
+    it("freelancer proposes a fifth milestone and the client approves and funds it", async () => {
+        const escrowBalanceBefore = await provider.connection.getBalance(tokenEscrow);
+        let multisigData = await program.account.multisig.fetch(multisig)
+
+        const accounts = [
+            {
+                pubkey: projectInfoAccount,
+                isWritable: true,
+                isSigner: false
+            },
+            {
+                pubkey: multisig,
+                isWritable: false,
+                isSigner: true
+            },
+            {
+                pubkey: tokenEscrow,
+                isWritable: true,
+                isSigner: false
+            },
+            {
+                pubkey: client.publicKey,
+                isWritable: true,
+                isSigner: true
+            },
+            {
+                pubkey: SystemProgram.programId,
+                isWritable: false,
+                isSigner: false
+            }
+        ];
+        const additionalFunds = new anchor.BN(LAMPORTS_PER_SOL * 4_000);
+        const milestoneAmounts = [5_000, 5_000, 5_000, 5_000, 4_000]
+            .map(amount => new anchor.BN(LAMPORTS_PER_SOL * amount));
+        const data = program.coder.instruction.encode("change_order", {
+            additionalFunds,
+            milestoneAmounts
+        })
+
+        const multisigTxPda = await getMultisigTransactionPda(program as anchor.Program, multisig, multisigData.seqno);
+
+        await program.methods.
+            createTransaction(program.programId, accounts, data)
+            .accounts({
+                multisig,
+                projectInfoAccount,
+                proposer: freelancer.publicKey,
+                transaction: multisigTxPda.key
+            })
+            .signers([freelancer])
+            .rpc()
+
+        // the client approves, which also signs for the transfer of the additional funds
+        await program.methods
+            .approve()
+            .accounts({
+                multisig,
+                transaction: multisigTxPda.key,
+                owner: client.publicKey,
+                multisigSigner: multisig,
+                projectInfoAccount
+            })
+            .remainingAccounts(accounts.map(
+                account => account.pubkey.equals(multisig) ?
+                    { ...account, isSigner: false } : account
+            )
+                .concat({
+                    pubkey: program.programId,
+                    isWritable: false,
+                    isSigner: false,
+                }))
+
+            .signers([client])
+            .rpc()
+
+        const projectData = await program.account.projectInfo.fetch(projectInfoAccount)
+        expect(projectData.milestones).to.equal(5);
+        expect(projectData.totalProjectFunds.toNumber()).to.equal(LAMPORTS_PER_SOL * 24_000);
+
+        const escrowBalanceAfter = await provider.connection.getBalance(tokenEscrow);
+        expect(escrowBalanceAfter).to.equal(escrowBalanceBefore + additionalFunds.toNumber());
+    })
+});
@@ -19,6 +19,13 @@ describe("Client goes through with a project", () => {
     let projectInfoAccountBump: number;
 
     let tokenEscrow: anchor.web3.PublicKey;
+    let payeeSplits: anchor.web3.PublicKey;
+    let subcontract: anchor.web3.PublicKey;
+    let bond: anchor.web3.PublicKey;
+    let clientReputation: anchor.web3.PublicKey;
+    let freelancerReputation: anchor.web3.PublicKey;
+    let clientRating: anchor.web3.PublicKey;
+    let freelancerRating: anchor.web3.PublicKey;
     let multisig: anchor.web3.PublicKey;
 
     let milestones = 4;
@@ -50,6 +57,68 @@ describe("Client goes through with a project", () => {
             ],
             program.programId
         );
+
+        [payeeSplits,] = anchor.web3.PublicKey.findProgramAddressSync(
+            [
+                Buffer.from("payee_splits"),
+                projectInfoAccount.toBuffer(),
+            ],
+            program.programId
+        );
+
+        [subcontract,] = anchor.web3.PublicKey.findProgramAddressSync(
+            [
+                Buffer.from("subcontract"),
+                projectInfoAccount.toBuffer(),
+            ],
+            program.programId
+        );
+
+        [bond,] = anchor.web3.PublicKey.findProgramAddressSync(
+            [
+                Buffer.from("bond"),
+                projectInfoAccount.toBuffer(),
+            ],
+            program.programId
+        );
+
+        [clientReputation,] = anchor.web3.PublicKey.findProgramAddressSync(
+            [
+                Buffer.from("reputation"),
+                client.publicKey.toBuffer(),
+            ],
+            program.programId
+        );
+
+        [freelancerReputation,] = anchor.web3.PublicKey.findProgramAddressSync(
+            [
+                Buffer.from("reputation"),
+                freelancer.publicKey.toBuffer(),
+            ],
+            program.programId
+        );
+
+        //the client's first project has nonce 0
+        const nonce = new anchor.BN(0).toArrayLike(Buffer, "le", 4);
+        [clientRating,] = anchor.web3.PublicKey.findProgramAddressSync(
+            [
+                Buffer.from("rating"),
+                projectInfoAccount.toBuffer(),
+                nonce,
+                client.publicKey.toBuffer(),
+            ],
+            program.programId
+        );
+
+        [freelancerRating,] = anchor.web3.PublicKey.findProgramAddressSync(
+            [
+                Buffer.from("rating"),
+                projectInfoAccount.toBuffer(),
+                nonce,
+                freelancer.publicKey.toBuffer(),
+            ],
+            program.programId
+        );
         const tx = new Transaction().add(
             SystemProgram.transfer({
                 fromPubkey: provider.wallet.publicKey,
@@ -85,73 +154,25 @@ describe("Client goes through with a project", () => {
     })
 
 
-    it("at least 2 of three members agrees to start initialized project", async () => {
-
-        let multisigData = await program.account.multisig.fetch(multisig)
-        expect(multisigData.seqno).to.equal(0);
+    it("freelancer accepts the offer, which starts the project", async () => {
 
         let projectData = await program.account.projectInfo.fetch(projectInfoAccount)
-        expect(projectData.status).to.equal(0);
-        //Any of the parties can initialize the start project transaction
-        //of any multisig transaction at that
+        expect(projectData.status).to.deep.equal({ offered: {} });
+        //no multisig proposal is needed to start,
+        //the client made the offer and the freelancer agrees to it
 
-        //create transaction
-        const accounts = [
-            {
-                pubkey: projectInfoAccount,
-                isWritable: true,
-                isSigner: false
-            },
-            {
-                pubkey: multisig,
-                isWritable: false,
-                isSigner: true
-            }
-        ];
-        const data = program.coder.instruction.encode("start_project", {})
-
-        const multisigTxPda = await getMultisigTransactionPda(program as anchor.Program, multisig, multisigData.seqno);
-
-        await program.methods.
-            createTransaction(program.programId, accounts, data)
-            .accounts({
-                multisig,
-                projectInfoAccount,
-                proposer: client.publicKey,
-                transaction: multisigTxPda.key
-            })
-            .signers([client])
-            .rpc()
-
-        // approve -- only needs two signers to approve and execute
         await program.methods
-            .approve()
+            .acceptOffer()
             .accounts({
-                multisig,
-                transaction: multisigTxPda.key,
-                owner: freelancer.publicKey,
-                multisigSigner: multisig,
-                projectInfoAccount
+                projectInfoAccount,
+                freelancer: freelancer.publicKey,
+                bond
             })
-            .remainingAccounts(accounts.map(
-                account => account.pubkey.equals(multisig) ?
-                    { ...account, isSigner: false } : account
-            )
-                .concat({
-                    pubkey: program.programId,
-                    isWritable: false,
-                    isSigner: false,
-                }))
-
             .signers([freelancer])
             .rpc()
-            .catch(e => console.log(e));
-
-        const txData = await program.account.transaction.fetch(multisigTxPda.key)
-        expect(txData.didExecute).to.equal(true);
 
         projectData = await program.account.projectInfo.fetch(projectInfoAccount)
-        expect(projectData.status).to.equal(1);
+        expect(projectData.status).to.deep.equal({ running: {} });
     })
 
     it("client can't cancels the project at this point", async () => {
@@ -162,14 +183,20 @@ describe("Client goes through with a project", () => {
                 client: client.publicKey,
                 freelancer: freelancer.publicKey,
                 projectInfoAccount,
-                tokenEscrow
+                tokenEscrow,
+                subcontract,
+                clientReputation,
+                freelancerReputation,
+                payeeSplits,
+                clientRating,
+                freelancerRating
             })
             .signers([client])
             .rpc()
             .catch(e => e)
 
-        expect(result.error.errorCode.number).to.equal(6013);
-        expect(result.error.errorCode.code).to.equal('InvalidStatus');
+        expect(result.error.errorCode.number).to.equal(6019);
+        expect(result.error.errorCode.code).to.equal('ProjectAlreadyRunning');
 
     })
 
@@ -244,7 +271,11 @@ describe("Client goes through with a project", () => {
             .accounts({
                 freelancer: freelancer.publicKey,
                 projectInfoAccount,
-                tokenEscrow
+                tokenEscrow,
+                payeeSplits,
+                bond,
+                clientReputation,
+                freelancerReputation
             })
             .signers([freelancer])
             .rpc()
@@ -327,7 +358,11 @@ describe("Client goes through with a project", () => {
             .accounts({
                 freelancer: freelancer.publicKey,
                 projectInfoAccount,
-                tokenEscrow
+                tokenEscrow,
+                payeeSplits,
+                bond,
+                clientReputation,
+                freelancerReputation
             })
             .signers([freelancer])
             .rpc()
@@ -409,7 +444,11 @@ describe("Client goes through with a project", () => {
             .accounts({
                 freelancer: freelancer.publicKey,
                 projectInfoAccount,
-                tokenEscrow
+                tokenEscrow,
+                payeeSplits,
+                bond,
+                clientReputation,
+                freelancerReputation
             })
             .signers([freelancer])
             .rpc()
@@ -422,6 +461,6 @@ describe("Client goes through with a project", () => {
 
     it('completes the project', async () => {
         const projectData = await program.account.projectInfo.fetch(projectInfoAccount)
-        expect(projectData.status).to.equal(2);
+        expect(projectData.status).to.deep.equal({ completed: {} });
     })
 });